@@ -43,7 +43,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
         println!("  Description: {}", payment_req.description);
 
         // Create a real payment payload using wallet integration
-        let payment_payload = create_real_payment_payload(&payment_req)?;
+        let payment_payload = create_real_payment_payload(&payment_req).await?;
 
         // Retry request with payment
         println!("\n💳 Retrying request with payment...");
@@ -109,7 +109,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
 /// 1. Load the private key from secure storage (hardware wallet, encrypted file, etc.)
 /// 2. Use environment variables or secure key management services
 /// 3. Implement proper key rotation and security practices
-fn create_real_payment_payload(requirements: &PaymentRequirements) -> Result<PaymentPayload> {
+async fn create_real_payment_payload(requirements: &PaymentRequirements) -> Result<PaymentPayload> {
     // In a real application, you would get the private key from secure storage
     // For demonstration purposes, we'll use a test private key
     // NEVER use hardcoded private keys in production!
@@ -125,7 +125,9 @@ fn create_real_payment_payload(requirements: &PaymentRequirements) -> Result<Pay
     let wallet = WalletFactory::from_private_key(&private_key, &requirements.network)?;
 
     // Create the signed payment payload
-    let payment_payload = wallet.create_signed_payment_payload(requirements, &payer_address)?;
+    let payment_payload = wallet
+        .create_signed_payment_payload(requirements, &payer_address)
+        .await?;
 
     println!("✅ Created real payment payload with EIP-712 signature");
     println!("   Payer: {}", payer_address);
@@ -119,6 +119,8 @@ impl SimpleFacilitator {
             transaction: mock_transaction_hash,
             network: payload.network.clone(),
             payer: Some(payload.payload.authorization.from.clone()),
+            fee_amount: None,
+            net_amount: None,
         })
     }
 }
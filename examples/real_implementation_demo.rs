@@ -50,8 +50,9 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
 
     // Step 5: Create real payment payload
     println!("\n5️⃣ Creating real payment payload...");
-    let payment_payload =
-        wallet.create_signed_payment_payload(&payment_req, &get_payer_address())?;
+    let payment_payload = wallet
+        .create_signed_payment_payload(&payment_req, &get_payer_address())
+        .await?;
     println!("✅ Real payment payload created with EIP-712 signature");
 
     // Step 6: Verify payment with real facilitator
@@ -80,6 +80,7 @@ impl WalletIntegration {
             version: "2".to_string(),
             chain_id: network_config.chain_id,
             verifying_contract: network_config.usdc_contract,
+            salt: None,
         };
 
         let message_hash = create_transfer_with_authorization_hash(
@@ -7,6 +7,56 @@
 //!
 //! - **In-Memory**: Default storage (data lost on restart)
 //! - **Redis**: Persistent storage (enable with `redis` feature)
+//!
+//! ## Settlement History
+//!
+//! `GET /settlements?payer=0x...&network=base&limit=50` returns recent
+//! settlements, newest first. History is kept in memory (capped by
+//! `SETTLEMENT_HISTORY_SIZE`) or in Redis alongside the nonces.
+//!
+//! ## Payment Events
+//!
+//! With the `ws` feature, `GET /events` upgrades to a WebSocket that pushes
+//! verify and settle outcomes as JSON frames. Pass `?network=<name>` to only
+//! receive events for one network.
+//!
+//! ## Webhooks
+//!
+//! Set `WEBHOOK_URL` and `WEBHOOK_SECRET` to POST each settlement to a resource
+//! server, signed as described in [`rust_x402::webhook`].
+//!
+//! ## Tracing
+//!
+//! With the `otel` feature, set `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT` (e.g.
+//! `http://localhost:4318/v1/traces`) to export verify and settle spans over
+//! OTLP; see [`rust_x402::otel`].
+//!
+//! ## Verification
+//!
+//! Payments are verified in-process by [`LocalFacilitator`], which checks the
+//! EIP-712 signature against the authorization's `from` address as well as the
+//! validity window, recipient, amount and nonce.
+//!
+//! With the `solana` feature, `/verify` and `/settle` also accept Solana SPL
+//! transfer authorizations, checked the same way against their ed25519
+//! signature. Solana settlement is always simulated.
+//!
+//! ## Settlement
+//!
+//! Set `RPC_URL_<NETWORK>` (e.g. `RPC_URL_BASE_SEPOLIA`, comma-separated for
+//! fallbacks) to settle through an [`OnChainSettler`] using those endpoints.
+//! When none is set, settlement is simulated by [`MockSettler`].
+//!
+//! Set `ALLOWED_PAY_TO` to a comma-separated list of addresses to only accept
+//! payments to those recipients; others are rejected as `recipient_not_allowed`.
+//!
+//! Set `MIN_PAYMENT_AMOUNT` to a decimal token amount (e.g. `0.001`) to reject
+//! smaller payments as `below_minimum`.
+//!
+//! With the `json-schema` feature, requirements whose `outputSchema` is not a
+//! valid JSON Schema, or whose `extra` does not have the scheme's shape, are
+//! rejected with a 400 `invalid_payment_requirements` before verification;
+//! see `PaymentRequirements::validate_documents`.
 
 use axum::{
     extract::{Query, State},
@@ -15,123 +65,238 @@ use axum::{
     routing::{get, post},
     Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::env;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
 
-use rand::Rng;
 use rust_x402::{
+    blockchain::NetworkRpcRegistry,
+    blockchain_facilitator::{BlockchainFacilitatorClient, BlockchainFacilitatorConfig},
+    error::ErrorResponse,
+    facilitator::{LocalFacilitator, MockSettler, OnChainSettler, Settler},
     facilitator_storage::{InMemoryStorage, NonceStorage},
+    settlement_store::{InMemorySettlementStore, SettlementQuery, SettlementStore},
     types::*,
-    Result, X402Error,
+    webhook, Result, X402Error,
 };
 
 #[cfg(feature = "redis")]
 use rust_x402::facilitator_storage::redis_storage::RedisStorage;
+#[cfg(feature = "redis")]
+use rust_x402::settlement_store::redis_store::RedisSettlementStore;
+#[cfg(feature = "solana")]
+use rust_x402::solana::SolanaPaymentPayload;
+
+/// Number of events buffered for slow event subscribers before they lag
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Verify or settle outcome pushed to event subscribers
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum PaymentEvent {
+    Verify {
+        network: String,
+        response: VerifyResponse,
+    },
+    Settle {
+        network: String,
+        response: SettleResponse,
+    },
+}
 
 /// Facilitator implementation with pluggable storage
-#[derive(Debug, Clone)]
 struct Facilitator<S: NonceStorage> {
-    storage: Arc<S>,
+    local: LocalFacilitator<S>,
+    settlements: Arc<dyn SettlementStore>,
+    events: broadcast::Sender<PaymentEvent>,
+}
+
+// Not derived: the storage is shared, so `S` itself need not be `Clone`
+impl<S: NonceStorage> Clone for Facilitator<S> {
+    fn clone(&self) -> Self {
+        Self {
+            local: self.local.clone(),
+            settlements: Arc::clone(&self.settlements),
+            events: self.events.clone(),
+        }
+    }
 }
 
-impl<S: NonceStorage> Facilitator<S> {
-    /// Create a new facilitator with the given storage backend
+impl<S: NonceStorage + 'static> Facilitator<S> {
+    /// Create a new facilitator with the given storage backend, simulating settlement
+    #[cfg(test)]
     fn new(storage: S) -> Self {
+        Self::with_settler(storage, Arc::new(MockSettler))
+    }
+
+    /// Create a facilitator settling with `settler`
+    fn with_settler(storage: S, settler: Arc<dyn Settler>) -> Self {
         Self {
-            storage: Arc::new(storage),
+            local: LocalFacilitator::with_shared_settler(storage, settler),
+            settlements: Arc::new(InMemorySettlementStore::default()),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    /// Record settlements in `settlements` instead of the default in-memory store
+    fn with_settlement_store(mut self, settlements: impl SettlementStore + 'static) -> Self {
+        self.settlements = Arc::new(settlements);
+        self
+    }
+
+    /// Deliver settle events to `url` as signed webhooks
+    fn spawn_webhooks(&self, url: String, secret: String) {
+        let mut events = self.events.subscribe();
+        tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            loop {
+                let response = match events.recv().await {
+                    Ok(PaymentEvent::Settle { response, .. }) => response,
+                    Ok(PaymentEvent::Verify { .. }) => continue,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Webhook sender lagged, skipped {} events", skipped);
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if let Err(e) =
+                    webhook::send_webhook(&client, &url, &response, secret.as_bytes()).await
+                {
+                    tracing::warn!("Failed to deliver settlement webhook: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Publish an event to any connected subscribers
+    fn publish(&self, event: PaymentEvent) {
+        // Sending only fails when nobody is subscribed
+        let _ = self.events.send(event);
+    }
+
+    /// Set the clock skew tolerated on authorization validity windows
+    fn with_clock_skew(mut self, clock_skew: Duration) -> Self {
+        self.local = self.local.with_clock_skew(clock_skew);
+        self
+    }
+
+    /// Only accept payments to `recipients`, if given
+    fn with_allowed_recipients(mut self, recipients: Option<Vec<String>>) -> Self {
+        if let Some(recipients) = recipients {
+            self.local = self.local.with_allowed_recipients(recipients);
         }
+        self
+    }
+
+    /// Reject payments below `min_amount` whole tokens, if given
+    fn with_min_amount(mut self, min_amount: Option<rust_decimal::Decimal>) -> Self {
+        if let Some(min_amount) = min_amount {
+            self.local = self.local.with_min_amount(min_amount);
+        }
+        self
     }
 
     /// Verify a payment payload
     async fn verify_payment(
         &self,
-        payload: &PaymentPayload,
+        payload: &AnyPaymentPayload,
         requirements: &PaymentRequirements,
     ) -> Result<VerifyResponse> {
-        // Check if nonce has been used before (replay protection)
-        let nonce = &payload.payload.authorization.nonce;
-        if self.storage.has_nonce(nonce).await? {
-            return Ok(VerifyResponse {
-                is_valid: false,
-                invalid_reason: Some("nonce_already_used".to_string()),
-                payer: Some(payload.payload.authorization.from.clone()),
-            });
-        }
+        let response = match payload {
+            AnyPaymentPayload::Evm(payload) => {
+                self.local.verify_payment(payload, requirements).await?
+            }
+            #[cfg(feature = "solana")]
+            AnyPaymentPayload::Solana(payload) => {
+                self.local
+                    .verify_solana_payment(payload, requirements)
+                    .await?
+            }
+        };
+        self.publish(PaymentEvent::Verify {
+            network: payload.network().to_string(),
+            response: response.clone(),
+        });
+        Ok(response)
+    }
 
-        // Verify authorization timing
-        if !payload.payload.authorization.is_valid_now()? {
-            return Ok(VerifyResponse {
-                is_valid: false,
-                invalid_reason: Some("authorization_expired".to_string()),
-                payer: Some(payload.payload.authorization.from.clone()),
-            });
+    /// Settle a payment
+    ///
+    /// The payment is verified first. Only successful settlements are
+    /// recorded and published, so a refused one never reaches history,
+    /// subscribers or webhooks.
+    async fn settle_payment(
+        &self,
+        payload: &AnyPaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        let response = match payload {
+            AnyPaymentPayload::Evm(payload) => {
+                self.local.settle_payment(payload, requirements).await?
+            }
+            #[cfg(feature = "solana")]
+            AnyPaymentPayload::Solana(payload) => {
+                self.local
+                    .settle_solana_payment(payload, requirements)
+                    .await?
+            }
+        };
+        if !response.success {
+            return Ok(response);
         }
-
-        // Verify amount meets requirements
-        let payment_amount: u128 = payload
-            .payload
-            .authorization
-            .value
-            .parse()
-            .map_err(|_| X402Error::invalid_payment_requirements("Invalid payment amount"))?;
-        let required_amount: u128 = requirements
-            .max_amount_required
-            .parse()
-            .map_err(|_| X402Error::invalid_payment_requirements("Invalid required amount"))?;
-
-        if payment_amount < required_amount {
-            return Ok(VerifyResponse {
-                is_valid: false,
-                invalid_reason: Some("insufficient_amount".to_string()),
-                payer: Some(payload.payload.authorization.from.clone()),
-            });
+        // The payment has settled; a history write failure must not report otherwise
+        if let Err(e) = self.settlements.record(payload.nonce(), &response).await {
+            tracing::warn!("Failed to record settlement: {}", e);
         }
+        self.publish(PaymentEvent::Settle {
+            network: payload.network().to_string(),
+            response: response.clone(),
+        });
+        Ok(response)
+    }
+}
 
-        // Verify recipient matches
-        if payload.payload.authorization.to != requirements.pay_to {
-            return Ok(VerifyResponse {
-                is_valid: false,
-                invalid_reason: Some("recipient_mismatch".to_string()),
-                payer: Some(payload.payload.authorization.from.clone()),
-            });
-        }
+/// Payment payload for any chain the facilitator verifies
+///
+/// EVM payloads are tried first; Solana authorizations carry a `mint` and
+/// `amount` instead of a `value`, so they never match the EVM shape.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum AnyPaymentPayload {
+    Evm(PaymentPayload),
+    #[cfg(feature = "solana")]
+    Solana(SolanaPaymentPayload),
+}
 
-        // Mark nonce as processed
-        self.storage.mark_nonce(nonce).await?;
+impl AnyPaymentPayload {
+    /// Network the payment is made on
+    fn network(&self) -> &str {
+        match self {
+            Self::Evm(payload) => &payload.network,
+            #[cfg(feature = "solana")]
+            Self::Solana(payload) => &payload.network,
+        }
+    }
 
-        Ok(VerifyResponse {
-            is_valid: true,
-            invalid_reason: None,
-            payer: Some(payload.payload.authorization.from.clone()),
-        })
+    /// Nonce of the payment's authorization
+    fn nonce(&self) -> &str {
+        match self {
+            Self::Evm(payload) => &payload.payload.authorization.nonce,
+            #[cfg(feature = "solana")]
+            Self::Solana(payload) => &payload.payload.authorization.nonce,
+        }
     }
 
-    /// Settle a verified payment
-    ///
-    /// Note: This is a mock implementation that generates a simulated transaction hash.
-    /// For production use, integrate with BlockchainFacilitatorClient to perform
-    /// real blockchain transactions.
-    async fn settle_payment(
-        &self,
-        payload: &PaymentPayload,
-        _requirements: &PaymentRequirements,
-    ) -> Result<SettleResponse> {
-        // TODO: Integrate with BlockchainFacilitatorClient for real blockchain settlement
-        // 1. Call the blockchain to execute the transfer
-        // 2. Wait for transaction confirmation
-        // 3. Return the transaction hash
-
-        // For now, we'll simulate a successful settlement
-        let mock_transaction_hash = format!("0x{:064x}", rand::thread_rng().gen::<u128>());
-
-        Ok(SettleResponse {
-            success: true,
-            error_reason: None,
-            transaction: mock_transaction_hash,
-            network: payload.network.clone(),
-            payer: Some(payload.payload.authorization.from.clone()),
-        })
+    /// The EVM payload, for tests that tamper with it
+    #[cfg(test)]
+    fn evm_mut(&mut self) -> &mut PaymentPayload {
+        match self {
+            Self::Evm(payload) => payload,
+            #[cfg(feature = "solana")]
+            Self::Solana(_) => panic!("not an EVM payment"),
+        }
     }
 }
 
@@ -145,14 +310,14 @@ type RedisFacilitator = Facilitator<RedisStorage>;
 #[derive(Debug, Deserialize)]
 struct VerifyRequest {
     x402_version: u32,
-    payment_payload: PaymentPayload,
+    payment_payload: AnyPaymentPayload,
     payment_requirements: PaymentRequirements,
 }
 
 #[derive(Debug, Deserialize)]
 struct SettleRequest {
     x402_version: u32,
-    payment_payload: PaymentPayload,
+    payment_payload: AnyPaymentPayload,
     payment_requirements: PaymentRequirements,
 }
 
@@ -166,7 +331,16 @@ struct SupportedQuery {
 
 #[tokio::main]
 async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing
+    // Initialize tracing, exporting spans over OTLP when a collector is configured
+    #[cfg(feature = "otel")]
+    let otel = match env::var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT") {
+        Ok(endpoint) => Some(rust_x402::otel::init_otel(&endpoint)?),
+        Err(_) => {
+            tracing_subscriber::fmt::init();
+            None
+        }
+    };
+    #[cfg(not(feature = "otel"))]
     tracing_subscriber::fmt::init();
 
     // Get bind address from environment or use default
@@ -175,6 +349,53 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     // Get storage backend from environment
     let storage_type = env::var("STORAGE_BACKEND").unwrap_or_else(|_| "memory".to_string());
 
+    // Get clock skew tolerance from environment or use default
+    let clock_skew = parse_env("CLOCK_SKEW_SECONDS")?
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_CLOCK_SKEW);
+
+    // Recipients this facilitator accepts payments for; any when unset
+    let allowed_recipients = env::var("ALLOWED_PAY_TO").ok().map(|list| {
+        list.split(',')
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    });
+
+    // Smallest payment accepted, in whole tokens; any when unset
+    let min_amount = parse_env("MIN_PAYMENT_AMOUNT")?;
+
+    // Per-network RPC endpoints from RPC_URL_<NETWORK> variables
+    let rpc_registry = NetworkRpcRegistry::from_env();
+    if rpc_registry.is_empty() {
+        println!("🧪 No RPC_URL_<NETWORK> set; settlement is simulated");
+    }
+    let mut rpc_networks: Vec<_> = rpc_registry.networks().collect();
+    rpc_networks.sort_unstable();
+    for network in rpc_networks {
+        let urls = rpc_registry.urls(network).unwrap_or_default();
+        println!(
+            "🔗 RPC for {}: {} endpoint(s), primary {}",
+            network,
+            urls.len(),
+            urls[0]
+        );
+    }
+
+    let settler = settler_for(rpc_registry, clock_skew)?;
+
+    // Number of settlements kept by the in-memory history
+    let history_size = env::var("SETTLEMENT_HISTORY_SIZE")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(InMemorySettlementStore::DEFAULT_CAPACITY);
+
+    // Settlement webhooks are only sent when both are configured
+    let webhook_target = env::var("WEBHOOK_URL")
+        .ok()
+        .zip(env::var("WEBHOOK_SECRET").ok());
+
     let app = if storage_type == "redis" {
         #[cfg(not(feature = "redis"))]
         {
@@ -191,26 +412,38 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
 
             println!("🔴 Using Redis storage: {}", redis_url);
             let storage = RedisStorage::new(&redis_url, key_prefix.as_deref()).await?;
-            let facilitator = Facilitator::new(storage);
+            let settlements = RedisSettlementStore::new(&redis_url, None).await?;
+            let facilitator = Facilitator::with_settler(storage, settler)
+                .with_settlement_store(settlements)
+                .with_clock_skew(clock_skew)
+                .with_allowed_recipients(allowed_recipients)
+                .with_min_amount(min_amount);
+            if let Some((url, secret)) = webhook_target {
+                facilitator.spawn_webhooks(url, secret);
+            }
 
-            Router::new()
+            let router = Router::new()
                 .route("/verify", post(verify_handler_redis))
                 .route("/settle", post(settle_handler_redis))
                 .route("/supported", get(supported_handler))
-                .route("/health", get(health_handler))
-                .with_state(facilitator)
+                .route("/settlements", get(settlements_handler::<RedisStorage>))
+                .route("/health", get(health_handler::<RedisStorage>));
+            with_events(router).with_state(facilitator)
         }
     } else {
         println!("💾 Using in-memory storage");
-        let storage = InMemoryStorage::new();
-        let facilitator = Facilitator::new(storage);
-
-        Router::new()
-            .route("/verify", post(verify_handler_memory))
-            .route("/settle", post(settle_handler_memory))
-            .route("/supported", get(supported_handler))
-            .route("/health", get(health_handler))
-            .with_state(facilitator)
+        // Remember nonces as long as the Redis backend does
+        let storage = InMemoryStorage::new().with_ttl(std::time::Duration::from_secs(86400));
+        let facilitator = Facilitator::with_settler(storage, settler)
+            .with_settlement_store(InMemorySettlementStore::new(history_size))
+            .with_clock_skew(clock_skew)
+            .with_allowed_recipients(allowed_recipients)
+            .with_min_amount(min_amount);
+        if let Some((url, secret)) = webhook_target {
+            facilitator.spawn_webhooks(url, secret);
+        }
+
+        with_events(memory_router()).with_state(facilitator)
     };
 
     // Start the server
@@ -223,10 +456,18 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     println!("   POST /verify - Verify payment authorization");
     println!("   POST /settle - Settle verified payment");
     println!("   GET /supported - Get supported payment schemes");
-    println!("   GET /health - Health check endpoint");
+    println!("   GET /settlements - Recent settlements, filter by ?payer=&network=");
+    println!("   GET /health - Health check endpoint (503 when storage is unreachable)");
+    #[cfg(feature = "ws")]
+    println!("   GET /events - WebSocket stream of verify and settle events");
     println!("\nEnvironment variables:");
     println!("   BIND_ADDRESS - Server bind address (default: 0.0.0.0:3000)");
     println!("   STORAGE_BACKEND - Storage backend: 'memory' or 'redis' (default: memory)");
+    println!("   CLOCK_SKEW_SECONDS - Tolerated client clock skew in seconds (default: 5)");
+    println!("   MIN_PAYMENT_AMOUNT - Smallest accepted payment in whole tokens (optional)");
+    println!("   RPC_URL_<NETWORK> - Settle on-chain through these endpoints, comma-separated (e.g. RPC_URL_BASE_SEPOLIA)");
+    println!("   SETTLEMENT_HISTORY_SIZE - Settlements kept in memory (default: 10000)");
+    println!("   WEBHOOK_URL / WEBHOOK_SECRET - Send signed settlement webhooks (optional)");
     #[cfg(feature = "redis")]
     {
         println!("   REDIS_URL - Redis connection URL (default: redis://localhost:6379)");
@@ -235,6 +476,113 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
 
     axum::serve(listener, app).await?;
 
+    #[cfg(feature = "otel")]
+    if let Some(provider) = otel {
+        let _ = provider.shutdown();
+    }
+
+    Ok(())
+}
+
+/// Parse environment variable `name`, if set
+///
+/// A value that does not parse is an error rather than silently ignored, so a
+/// typo cannot disable the setting it was meant to configure.
+fn parse_env<T: std::str::FromStr>(name: &str) -> Result<Option<T>> {
+    parse_var(name, env::var(name).ok())
+}
+
+/// Parse `value` of environment variable `name` as in [`parse_env`]
+fn parse_var<T: std::str::FromStr>(name: &str, value: Option<String>) -> Result<Option<T>> {
+    value
+        .map(|value| {
+            value
+                .trim()
+                .parse()
+                .map_err(|_| X402Error::config(format!("Invalid {}: {:?}", name, value)))
+        })
+        .transpose()
+}
+
+/// Settle on-chain through the registered endpoints, or simulate without any
+fn settler_for(rpc_registry: NetworkRpcRegistry, clock_skew: Duration) -> Result<Arc<dyn Settler>> {
+    if rpc_registry.is_empty() {
+        return Ok(Arc::new(MockSettler));
+    }
+    let client = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+        rpc_registry,
+        clock_skew_tolerance: clock_skew,
+        ..Default::default()
+    })?;
+    Ok(Arc::new(OnChainSettler::new(client)))
+}
+
+/// Routes served with in-memory storage
+fn memory_router() -> Router<InMemoryFacilitator> {
+    Router::new()
+        .route("/verify", post(verify_handler_memory))
+        .route("/settle", post(settle_handler_memory))
+        .route("/supported", get(supported_handler))
+        .route("/settlements", get(settlements_handler::<InMemoryStorage>))
+        .route("/health", get(health_handler::<InMemoryStorage>))
+}
+
+/// Add the `/events` WebSocket route when the `ws` feature is enabled
+fn with_events<S: NonceStorage + 'static>(
+    router: Router<Facilitator<S>>,
+) -> Router<Facilitator<S>> {
+    #[cfg(feature = "ws")]
+    let router = router.route("/events", get(events::events_handler::<S>));
+    router
+}
+
+/// JSON error returned by the facilitator handlers
+type ApiError = (StatusCode, Json<ErrorResponse>);
+
+/// Map an error to its status code and JSON body
+fn error_response(error: &X402Error) -> ApiError {
+    let status =
+        StatusCode::from_u16(error.status_code()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    (status, Json(ErrorResponse::from_x402_error(error)))
+}
+
+/// Reject requests for a protocol version outside the supported range
+fn check_version(x402_version: u32) -> std::result::Result<(), ApiError> {
+    if is_supported_version(x402_version) {
+        return Ok(());
+    }
+
+    let expected = if X402_MIN_VERSION == X402_MAX_VERSION {
+        X402_MAX_VERSION.to_string()
+    } else {
+        format!("{} to {}", X402_MIN_VERSION, X402_MAX_VERSION)
+    };
+    let error = ErrorResponse::new(
+        format!(
+            "Unsupported x402Version {}: expected {}",
+            x402_version, expected
+        ),
+        "unsupported_version",
+        StatusCode::BAD_REQUEST.as_u16(),
+    )
+    .with_details(serde_json::json!({
+        "minVersion": X402_MIN_VERSION,
+        "maxVersion": X402_MAX_VERSION,
+    }));
+    Err((StatusCode::BAD_REQUEST, Json(error)))
+}
+
+/// Reject requirements with a malformed `outputSchema` or `extra`
+#[cfg(feature = "json-schema")]
+fn check_requirements(requirements: &PaymentRequirements) -> std::result::Result<(), ApiError> {
+    requirements
+        .validate_documents()
+        .map_err(|e| error_response(&e))
+}
+
+/// Requirements are passed through unchecked without the `json-schema` feature
+#[cfg(not(feature = "json-schema"))]
+fn check_requirements(_requirements: &PaymentRequirements) -> std::result::Result<(), ApiError> {
     Ok(())
 }
 
@@ -242,10 +590,9 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
 async fn verify_handler_memory(
     State(facilitator): State<InMemoryFacilitator>,
     Json(request): Json<VerifyRequest>,
-) -> std::result::Result<Json<VerifyResponse>, StatusCode> {
-    if request.x402_version != X402_VERSION {
-        return Err(StatusCode::BAD_REQUEST);
-    }
+) -> std::result::Result<Json<VerifyResponse>, ApiError> {
+    check_version(request.x402_version)?;
+    check_requirements(&request.payment_requirements)?;
 
     match facilitator
         .verify_payment(&request.payment_payload, &request.payment_requirements)
@@ -254,7 +601,7 @@ async fn verify_handler_memory(
         Ok(response) => Ok(Json(response)),
         Err(e) => {
             eprintln!("Verification error: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_response(&e))
         }
     }
 }
@@ -263,10 +610,9 @@ async fn verify_handler_memory(
 async fn settle_handler_memory(
     State(facilitator): State<InMemoryFacilitator>,
     Json(request): Json<SettleRequest>,
-) -> std::result::Result<Json<SettleResponse>, StatusCode> {
-    if request.x402_version != X402_VERSION {
-        return Err(StatusCode::BAD_REQUEST);
-    }
+) -> std::result::Result<Json<SettleResponse>, ApiError> {
+    check_version(request.x402_version)?;
+    check_requirements(&request.payment_requirements)?;
 
     match facilitator
         .settle_payment(&request.payment_payload, &request.payment_requirements)
@@ -275,7 +621,7 @@ async fn settle_handler_memory(
         Ok(response) => Ok(Json(response)),
         Err(e) => {
             eprintln!("Settlement error: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_response(&e))
         }
     }
 }
@@ -284,10 +630,9 @@ async fn settle_handler_memory(
 async fn verify_handler_redis(
     State(facilitator): State<RedisFacilitator>,
     Json(request): Json<VerifyRequest>,
-) -> std::result::Result<Json<VerifyResponse>, StatusCode> {
-    if request.x402_version != X402_VERSION {
-        return Err(StatusCode::BAD_REQUEST);
-    }
+) -> std::result::Result<Json<VerifyResponse>, ApiError> {
+    check_version(request.x402_version)?;
+    check_requirements(&request.payment_requirements)?;
 
     match facilitator
         .verify_payment(&request.payment_payload, &request.payment_requirements)
@@ -296,7 +641,7 @@ async fn verify_handler_redis(
         Ok(response) => Ok(Json(response)),
         Err(e) => {
             eprintln!("Verification error: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_response(&e))
         }
     }
 }
@@ -305,10 +650,9 @@ async fn verify_handler_redis(
 async fn settle_handler_redis(
     State(facilitator): State<RedisFacilitator>,
     Json(request): Json<SettleRequest>,
-) -> std::result::Result<Json<SettleResponse>, StatusCode> {
-    if request.x402_version != X402_VERSION {
-        return Err(StatusCode::BAD_REQUEST);
-    }
+) -> std::result::Result<Json<SettleResponse>, ApiError> {
+    check_version(request.x402_version)?;
+    check_requirements(&request.payment_requirements)?;
 
     match facilitator
         .settle_payment(&request.payment_payload, &request.payment_requirements)
@@ -317,48 +661,595 @@ async fn settle_handler_redis(
         Ok(response) => Ok(Json(response)),
         Err(e) => {
             eprintln!("Settlement error: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+            Err(error_response(&e))
         }
     }
 }
 
+/// Solana networks accepted alongside the EVM ones
+#[cfg(feature = "solana")]
+const SOLANA_NETWORKS: &[&str] = &[networks::SOLANA_MAINNET, networks::SOLANA_DEVNET];
+
+#[cfg(not(feature = "solana"))]
+const SOLANA_NETWORKS: &[&str] = &[];
+
 /// Handle supported payment schemes requests
+///
+/// Every supported network is listed once per accepted protocol version.
 async fn supported_handler(Query(_query): Query<SupportedQuery>) -> Json<SupportedKinds> {
+    let networks = [
+        networks::BASE_SEPOLIA,
+        networks::BASE_MAINNET,
+        networks::AVALANCHE_FUJI,
+        networks::AVALANCHE_MAINNET,
+    ];
+
     Json(SupportedKinds {
-        kinds: vec![
-            SupportedKind {
-                x402_version: X402_VERSION,
-                scheme: schemes::EXACT.to_string(),
-                network: networks::BASE_SEPOLIA.to_string(),
-                metadata: None,
-            },
-            SupportedKind {
-                x402_version: X402_VERSION,
-                scheme: schemes::EXACT.to_string(),
-                network: networks::BASE_MAINNET.to_string(),
-                metadata: None,
-            },
-            SupportedKind {
-                x402_version: X402_VERSION,
-                scheme: schemes::EXACT.to_string(),
-                network: networks::AVALANCHE_FUJI.to_string(),
-                metadata: None,
-            },
-            SupportedKind {
-                x402_version: X402_VERSION,
-                scheme: schemes::EXACT.to_string(),
-                network: networks::AVALANCHE_MAINNET.to_string(),
-                metadata: None,
-            },
-        ],
+        kinds: supported_versions()
+            .flat_map(|x402_version| {
+                networks
+                    .iter()
+                    .chain(SOLANA_NETWORKS)
+                    .map(move |network| SupportedKind {
+                        x402_version,
+                        scheme: schemes::EXACT.to_string(),
+                        network: network.to_string(),
+                        metadata: None,
+                    })
+            })
+            .collect(),
     })
 }
 
+/// List recent settlements, newest first
+async fn settlements_handler<S: NonceStorage>(
+    State(facilitator): State<Facilitator<S>>,
+    Query(query): Query<SettlementQuery>,
+) -> std::result::Result<Json<Vec<SettleResponse>>, ApiError> {
+    facilitator
+        .settlements
+        .query(&query)
+        .await
+        .map(Json)
+        .map_err(|e| error_response(&e))
+}
+
 /// Health check endpoint
-async fn health_handler() -> Json<serde_json::Value> {
-    Json(serde_json::json!({
-        "status": "healthy",
-        "version": rust_x402::VERSION,
-        "x402_version": X402_VERSION,
-    }))
+///
+/// Pings each dependency and reports its status under `components`. Any
+/// failing component marks the facilitator as degraded with a 503, so the
+/// endpoint can back a readiness probe.
+async fn health_handler<S: NonceStorage>(
+    State(facilitator): State<Facilitator<S>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let storage = match facilitator.local.storage().ping().await {
+        Ok(()) => serde_json::json!({ "status": "up" }),
+        Err(e) => serde_json::json!({ "status": "down", "error": e.to_string() }),
+    };
+    let healthy = storage["status"] == "up";
+
+    let status = if healthy {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(serde_json::json!({
+            "status": if healthy { "healthy" } else { "degraded" },
+            "version": rust_x402::VERSION,
+            "x402_version": X402_VERSION,
+            "components": {
+                "storage": storage,
+            },
+        })),
+    )
+}
+
+#[cfg(feature = "ws")]
+mod events {
+    use super::*;
+    use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+    use axum::response::Response;
+
+    impl PaymentEvent {
+        /// Network the payment was made on
+        fn network(&self) -> &str {
+            match self {
+                Self::Verify { network, .. } | Self::Settle { network, .. } => network,
+            }
+        }
+    }
+
+    /// Event stream query
+    #[derive(Debug, Deserialize)]
+    pub(super) struct EventsQuery {
+        /// Only forward events for this network
+        network: Option<String>,
+    }
+
+    /// Upgrade to a WebSocket streaming payment events
+    pub(super) async fn events_handler<S: NonceStorage>(
+        ws: WebSocketUpgrade,
+        Query(query): Query<EventsQuery>,
+        State(facilitator): State<Facilitator<S>>,
+    ) -> Response {
+        // Subscribe before upgrading so no event after the handshake is missed
+        let events = facilitator.events.subscribe();
+        ws.on_upgrade(move |socket| stream_events(socket, events, query.network))
+    }
+
+    /// Forward events to the socket until either side closes
+    async fn stream_events(
+        mut socket: WebSocket,
+        mut events: broadcast::Receiver<PaymentEvent>,
+        network: Option<String>,
+    ) {
+        loop {
+            tokio::select! {
+                event = events.recv() => {
+                    let event = match event {
+                        Ok(event) => event,
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            tracing::warn!("Event subscriber lagged, skipped {} events", skipped);
+                            continue;
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    };
+                    if network.as_deref().is_some_and(|n| n != event.network()) {
+                        continue;
+                    }
+                    let Ok(json) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(json.into())).await.is_err() {
+                        break;
+                    }
+                }
+                message = socket.recv() => match message {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {}
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Storage whose backend is unreachable
+    struct UnreachableStorage;
+
+    #[async_trait]
+    impl NonceStorage for UnreachableStorage {
+        async fn has_nonce(&self, _scope: &str, _nonce: &str) -> Result<bool> {
+            Err(X402Error::config("storage unreachable"))
+        }
+
+        async fn mark_nonce(&self, _scope: &str, _nonce: &str) -> Result<()> {
+            Err(X402Error::config("storage unreachable"))
+        }
+
+        async fn remove_nonce(&self, _scope: &str, _nonce: &str) -> Result<()> {
+            Err(X402Error::config("storage unreachable"))
+        }
+
+        async fn ping(&self) -> Result<()> {
+            Err(X402Error::config("storage unreachable"))
+        }
+    }
+
+    async fn settle_request(x402_version: u32) -> SettleRequest {
+        let request = verify_request(x402_version).await;
+        SettleRequest {
+            x402_version,
+            payment_payload: request.payment_payload,
+            payment_requirements: request.payment_requirements,
+        }
+    }
+
+    /// A request carrying a payment signed by a test wallet
+    async fn verify_request(x402_version: u32) -> VerifyRequest {
+        use rust_x402::{signer::LocalSigner, wallet::Wallet};
+
+        let payment_requirements = PaymentRequirements::new(
+            schemes::EXACT,
+            networks::BASE_SEPOLIA,
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693bc6afc0c5328ba36faf03c514ef312287c",
+            "https://example.com/test",
+            "Test payment",
+        );
+        let wallet = Wallet::new(
+            LocalSigner::new("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
+                .unwrap(),
+            networks::BASE_SEPOLIA,
+        );
+        VerifyRequest {
+            x402_version,
+            payment_payload: AnyPaymentPayload::Evm(
+                wallet
+                    .authorize_payment(&payment_requirements)
+                    .await
+                    .unwrap(),
+            ),
+            payment_requirements,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_rejects_unsupported_version() {
+        let facilitator = Facilitator::new(InMemoryStorage::new());
+        let (status, Json(body)) = verify_handler_memory(
+            State(facilitator),
+            Json(verify_request(X402_VERSION + 1).await),
+        )
+        .await
+        .unwrap_err();
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.error_type, "unsupported_version");
+        assert_eq!(body.x402_version, X402_VERSION);
+        assert!(body.error.contains(&format!("expected {}", X402_VERSION)));
+        assert_eq!(body.details.unwrap()["maxVersion"], X402_MAX_VERSION);
+
+        let facilitator = Facilitator::new(InMemoryStorage::new());
+        let (status, Json(body)) = settle_handler_memory(
+            State(facilitator),
+            Json(settle_request(X402_MIN_VERSION - 1).await),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.error_type, "unsupported_version");
+    }
+
+    #[cfg(feature = "json-schema")]
+    #[tokio::test]
+    async fn test_verify_validates_output_schema() {
+        let mut request = verify_request(X402_VERSION).await;
+        request.payment_requirements.output_schema = Some(serde_json::json!({
+            "type": "object",
+            "properties": {"answer": {"type": "string"}},
+        }));
+        let response = verify_handler_memory(
+            State(Facilitator::new(InMemoryStorage::new())),
+            Json(request),
+        )
+        .await
+        .unwrap();
+        assert!(response.is_valid);
+
+        let mut request = verify_request(X402_VERSION).await;
+        request.payment_requirements.output_schema = Some(serde_json::json!({"type": 42}));
+        let (status, Json(body)) = verify_handler_memory(
+            State(Facilitator::new(InMemoryStorage::new())),
+            Json(request),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body.error_type, "invalid_payment_requirements");
+        assert!(body.error.contains("outputSchema"));
+
+        let mut request = settle_request(X402_VERSION).await;
+        request.payment_requirements.extra = Some(serde_json::json!({"version": 2}));
+        let (status, _) = settle_handler_memory(
+            State(Facilitator::new(InMemoryStorage::new())),
+            Json(request),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_verify_checks_signature() {
+        let facilitator = Facilitator::new(InMemoryStorage::new());
+
+        // Claiming to pay from another address does not pass
+        let mut forged = verify_request(X402_VERSION).await;
+        forged.payment_payload.evm_mut().payload.authorization.from =
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string();
+        let Json(response) = verify_handler_memory(State(facilitator.clone()), Json(forged))
+            .await
+            .unwrap();
+        assert!(!response.is_valid);
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("invalid_signature")
+        );
+
+        // Neither does a tampered signature
+        let mut tampered = verify_request(X402_VERSION).await;
+        let signature = &mut tampered.payment_payload.evm_mut().payload.signature;
+        let at = signature.len() - 3;
+        let flipped = if &signature[at..at + 1] == "0" {
+            "1"
+        } else {
+            "0"
+        };
+        signature.replace_range(at..at + 1, flipped);
+        let Json(response) = verify_handler_memory(State(facilitator.clone()), Json(tampered))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("invalid_signature")
+        );
+
+        let Json(response) =
+            verify_handler_memory(State(facilitator), Json(verify_request(X402_VERSION).await))
+                .await
+                .unwrap();
+        assert!(response.is_valid, "{:?}", response.invalid_reason);
+    }
+
+    #[cfg(feature = "solana")]
+    #[tokio::test]
+    async fn test_routes_solana_payments() {
+        use rust_x402::solana::SolanaKeypair;
+
+        let facilitator = Facilitator::new(InMemoryStorage::new());
+        let payment_requirements = PaymentRequirements::new(
+            schemes::EXACT,
+            networks::SOLANA_DEVNET,
+            "1000000",
+            networks::get_usdc_address(networks::SOLANA_DEVNET).unwrap(),
+            "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+            "https://example.com/test",
+            "Test payment",
+        );
+        let payment = SolanaKeypair::from_secret_key(&[7; 32])
+            .authorize_payment(&payment_requirements)
+            .unwrap();
+        let body = serde_json::json!({
+            "x402_version": X402_VERSION,
+            "payment_payload": payment,
+            "payment_requirements": payment_requirements,
+        });
+
+        let request: VerifyRequest = serde_json::from_value(body.clone()).unwrap();
+        assert!(matches!(
+            request.payment_payload,
+            AnyPaymentPayload::Solana(_)
+        ));
+        let Json(response) = verify_handler_memory(State(facilitator.clone()), Json(request))
+            .await
+            .unwrap();
+        assert!(response.is_valid, "{:?}", response.invalid_reason);
+
+        // The nonce was consumed, so the same authorization cannot be replayed
+        let request: VerifyRequest = serde_json::from_value(body).unwrap();
+        let Json(response) = verify_handler_memory(State(facilitator), Json(request))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("nonce_already_used")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_accepts_versions_in_supported_range() {
+        for version in supported_versions() {
+            let facilitator = Facilitator::new(InMemoryStorage::new());
+            assert!(verify_handler_memory(
+                State(facilitator.clone()),
+                Json(verify_request(version).await)
+            )
+            .await
+            .is_ok());
+            assert!(
+                settle_handler_memory(State(facilitator), Json(settle_request(version).await))
+                    .await
+                    .is_ok()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_supported_kinds_cover_version_range() {
+        let Json(supported) = supported_handler(Query(SupportedQuery { format: None })).await;
+        for version in supported_versions() {
+            assert!(
+                supported
+                    .kinds
+                    .iter()
+                    .any(|kind| kind.x402_version == version
+                        && kind.network == networks::BASE_SEPOLIA)
+            );
+        }
+        assert!(supported
+            .kinds
+            .iter()
+            .all(|kind| is_supported_version(kind.x402_version)));
+    }
+
+    #[tokio::test]
+    async fn test_verify_reports_storage_error() {
+        let facilitator = Facilitator::new(UnreachableStorage);
+        let request = verify_request(X402_VERSION).await;
+        let error = facilitator
+            .verify_payment(&request.payment_payload, &request.payment_requirements)
+            .await
+            .unwrap_err();
+        let (status, Json(body)) = error_response(&error);
+
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body.error_type, "configuration_error");
+        assert!(body.error.contains("storage unreachable"));
+    }
+
+    #[tokio::test]
+    async fn test_settlements_queryable_by_payer() {
+        let facilitator = Facilitator::new(InMemoryStorage::new());
+        let mut request = verify_request(X402_VERSION).await;
+        let settlement = facilitator
+            .settle_payment(&request.payment_payload, &request.payment_requirements)
+            .await
+            .unwrap();
+
+        let payer = request
+            .payment_payload
+            .evm_mut()
+            .payload
+            .authorization
+            .from
+            .clone();
+        let Json(found) = settlements_handler(
+            State(facilitator.clone()),
+            Query(
+                SettlementQuery::payer(payer.to_lowercase()).with_network(networks::BASE_SEPOLIA),
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(found, vec![settlement]);
+
+        let Json(found) = settlements_handler(
+            State(facilitator),
+            Query(SettlementQuery::payer(payer).with_network(networks::BASE_MAINNET)),
+        )
+        .await
+        .unwrap();
+        assert!(found.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_forged_settlement_not_recorded() {
+        let facilitator = Facilitator::new(InMemoryStorage::new());
+        let mut events = facilitator.events.subscribe();
+        let mut request = settle_request(X402_VERSION).await;
+        let payer = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+        request.payment_payload.evm_mut().payload.authorization.from = payer.to_string();
+
+        let Json(response) = settle_handler_memory(State(facilitator.clone()), Json(request))
+            .await
+            .unwrap();
+        assert!(!response.success);
+        assert_eq!(response.error_reason.as_deref(), Some("invalid_signature"));
+
+        let Json(found) = settlements_handler(
+            State(facilitator),
+            Query(SettlementQuery::payer(payer.to_string())),
+        )
+        .await
+        .unwrap();
+        assert!(found.is_empty());
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_unparseable_env_rejected() {
+        let parsed: Option<rust_decimal::Decimal> =
+            parse_var("MIN_PAYMENT_AMOUNT", Some(" 0.001 ".to_string())).unwrap();
+        assert_eq!(parsed, Some("0.001".parse().unwrap()));
+
+        let parsed: Option<rust_decimal::Decimal> = parse_var("MIN_PAYMENT_AMOUNT", None).unwrap();
+        assert!(parsed.is_none());
+
+        let error = parse_var::<rust_decimal::Decimal>("MIN_PAYMENT_AMOUNT", Some("0,001".into()))
+            .unwrap_err();
+        assert!(error.to_string().contains("MIN_PAYMENT_AMOUNT"));
+
+        // A negative skew is as invalid as a malformed one
+        assert!(parse_var::<u64>("CLOCK_SKEW_SECONDS", Some("-5".into())).is_err());
+        assert_eq!(
+            parse_var::<u64>("CLOCK_SKEW_SECONDS", Some("30".into())).unwrap(),
+            Some(30)
+        );
+    }
+
+    #[test]
+    fn test_settler_uses_rpc_registry() {
+        let settler = settler_for(NetworkRpcRegistry::new(), DEFAULT_CLOCK_SKEW).unwrap();
+        assert!(format!("{:?}", settler).starts_with("MockSettler"));
+
+        let registry = NetworkRpcRegistry::new().with_network("base", "http://localhost:8545");
+        let settler = settler_for(registry, DEFAULT_CLOCK_SKEW).unwrap();
+        assert!(format!("{:?}", settler).starts_with("OnChainSettler"));
+    }
+
+    #[tokio::test]
+    async fn test_health_ok() {
+        let facilitator = Facilitator::new(InMemoryStorage::new());
+        let (status, Json(body)) = health_handler(State(facilitator)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "healthy");
+        assert_eq!(body["components"]["storage"]["status"], "up");
+    }
+
+    #[tokio::test]
+    async fn test_health_degraded_when_storage_unreachable() {
+        let facilitator = Facilitator::new(UnreachableStorage);
+        let (status, Json(body)) = health_handler(State(facilitator)).await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["components"]["storage"]["status"], "down");
+        assert!(body["components"]["storage"]["error"]
+            .as_str()
+            .unwrap()
+            .contains("storage unreachable"));
+    }
+
+    #[cfg(feature = "ws")]
+    #[tokio::test]
+    async fn test_events_stream_settlements() {
+        use futures_util::StreamExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = with_events(memory_router()).with_state(Facilitator::new(InMemoryStorage::new()));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let connect = |query: &str| {
+            tokio_tungstenite::connect_async(format!("ws://{}/events{}", addr, query))
+        };
+        let (mut subscriber, _) = connect("?network=base-sepolia").await.unwrap();
+        let (mut other_network, _) = connect("?network=base").await.unwrap();
+
+        let request = verify_request(X402_VERSION).await;
+        let settlement: SettleResponse = reqwest::Client::new()
+            .post(format!("http://{}/settle", addr))
+            .json(&serde_json::json!({
+                "x402_version": X402_VERSION,
+                "payment_payload": request.payment_payload,
+                "payment_requirements": request.payment_requirements,
+            }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        let frame = tokio::time::timeout(Duration::from_secs(5), subscriber.next())
+            .await
+            .expect("no event received")
+            .unwrap()
+            .unwrap();
+        let Message::Text(text) = frame else {
+            panic!("unexpected frame: {:?}", frame);
+        };
+        let event: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(event["type"], "settle");
+        assert_eq!(event["network"], networks::BASE_SEPOLIA);
+        assert_eq!(event["response"]["transaction"], settlement.transaction);
+
+        // Subscribers filtering on another network see nothing
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), other_network.next())
+                .await
+                .is_err()
+        );
+    }
 }
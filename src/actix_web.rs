@@ -85,12 +85,11 @@ async fn verify_payment_header(
             "Payment network cannot be empty",
         ));
     }
-    if payload.x402_version != crate::X402_VERSION {
-        return Err(crate::X402Error::invalid_payment_payload(format!(
-            "Unsupported x402 version: {}. Expected version: {}",
-            payload.x402_version,
-            crate::X402_VERSION
-        )));
+    if !crate::types::is_supported_version(payload.x402_version) {
+        return Err(crate::X402Error::UnsupportedVersion {
+            got: payload.x402_version,
+            expected: crate::types::X402_MAX_VERSION,
+        });
     }
 
     // Note: Full signature verification with facilitator is handled in verify_payment_with_facilitator
@@ -252,11 +251,10 @@ fn create_payment_requirements_from_request(
         .and_then(|h| h.to_str().ok())
         .unwrap_or("1000000"); // Default to 1 USDC
 
-    let asset = match network {
-        "base-sepolia" => "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
-        "base" => "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
-        _ => "0x036CbD53842c5426634e7929541eC2318f3dCF7e", // Default to testnet
-    };
+    // Unknown networks default to Base Sepolia
+    let network_type =
+        crate::types::Network::from_name(network).unwrap_or(crate::types::Network::Testnet);
+    let asset = network_type.usdc_address();
 
     let pay_to = req
         .headers()
@@ -269,19 +267,13 @@ fn create_payment_requirements_from_request(
         scheme,
         network,
         amount,
-        asset,
+        &asset,
         pay_to,
         path,
         "Payment required for this resource",
     );
 
     // Set network-specific info
-    let network_type = match network {
-        "base" => crate::types::Network::Mainnet,
-        "base-sepolia" => crate::types::Network::Testnet,
-        _ => crate::types::Network::Testnet, // Default to testnet
-    };
-
     let mut req = requirements;
     req.set_usdc_info(network_type)?;
     Ok(req)
@@ -1,10 +1,53 @@
 //! Axum integration for x402 payments
+//!
+//! # Application state
+//!
+//! The payment layer does not need to be the router's state. Keep your own
+//! state and let [`payment_middleware_handler`] pull the middleware out of it
+//! with [`FromRef`](axum::extract::FromRef):
+//!
+//! ```rust,no_run
+//! use axum::{extract::{FromRef, State}, routing::get, Router};
+//! use rust_decimal::Decimal;
+//! use rust_x402::axum::payment_middleware_handler;
+//! use rust_x402::middleware::PaymentMiddleware;
+//!
+//! #[derive(Clone)]
+//! struct AppState {
+//!     greeting: String,
+//!     payment: PaymentMiddleware,
+//! }
+//!
+//! impl FromRef<AppState> for PaymentMiddleware {
+//!     fn from_ref(state: &AppState) -> Self {
+//!         state.payment.clone()
+//!     }
+//! }
+//!
+//! let state = AppState {
+//!     greeting: "Hello, paying customer".into(),
+//!     payment: PaymentMiddleware::new(
+//!         Decimal::new(1, 4),
+//!         "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+//!     ),
+//! };
+//! let app: Router = Router::new()
+//!     .route("/", get(|State(state): State<AppState>| async move { state.greeting }))
+//!     .layer(axum::middleware::from_fn_with_state(
+//!         state.clone(),
+//!         payment_middleware_handler,
+//!     ))
+//!     .with_state(state);
+//! ```
+//!
+//! Alternatively, install the middleware as an [`Extension`](axum::Extension)
+//! and use [`payment_extension_handler`].
 
 use crate::middleware::{PaymentMiddleware, PaymentMiddlewareConfig};
 use crate::X402Error;
 use axum::{
     extract::{Request, State},
-    http::{HeaderMap, HeaderValue, StatusCode},
+    http::{HeaderValue, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     routing::{delete, get, post, put},
@@ -15,7 +58,7 @@ use std::sync::Arc;
 use tower::ServiceBuilder;
 
 /// Re-export the payment middleware for convenience
-pub use crate::middleware::{create_payment_service, payment_middleware};
+pub use crate::middleware::{create_payment_service, payment_middleware, VerifiedPayment};
 
 /// Create a new Axum router with x402 payment middleware
 pub fn create_payment_router(
@@ -70,21 +113,6 @@ pub fn create_payment_middleware(amount: Decimal, pay_to: impl Into<String>) ->
     PaymentMiddleware::new(amount, pay_to)
 }
 
-/// Check if the request is from a web browser
-fn is_web_browser(headers: &HeaderMap) -> bool {
-    let user_agent = headers
-        .get("User-Agent")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("");
-
-    let accept = headers
-        .get("Accept")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("");
-
-    accept.contains("text/html") && user_agent.contains("Mozilla")
-}
-
 /// Get default paywall HTML
 fn get_default_paywall_html() -> &'static str {
     r#"<!DOCTYPE html>
@@ -108,26 +136,83 @@ fn get_default_paywall_html() -> &'static str {
 }
 
 /// Axum middleware handler for payment processing with settlement
+///
+/// Verified payments are available to handlers as `Extension<VerifiedPayment>`.
+/// Settlement runs after the handler returns; its [`SettleResponse`](crate::types::SettleResponse)
+/// is added to the response extensions and the `X-PAYMENT-RESPONSE` header.
+///
+/// CORS headers configured with `with_cors_headers` are added to every response.
 pub async fn payment_middleware_handler(
     State(middleware): State<PaymentMiddleware>,
     request: Request,
     next: Next,
-) -> impl IntoResponse {
+) -> Response {
+    let config = middleware.config();
+    if let Some(response) = config.preflight_response(&request) {
+        return response;
+    }
+
+    let origin = request.headers().get(http::header::ORIGIN).cloned();
+    let mut response = handle_payment(&middleware, request, next).await;
+    config.apply_cors_headers(origin.as_ref(), response.headers_mut());
+    response
+}
+
+/// [`payment_middleware_handler`] reading the middleware from an [`Extension`](axum::Extension)
+///
+/// The `Extension` layer must wrap this one:
+/// `.layer(from_fn(payment_extension_handler)).layer(Extension(middleware))`.
+pub async fn payment_extension_handler(
+    axum::Extension(middleware): axum::Extension<PaymentMiddleware>,
+    request: Request,
+    next: Next,
+) -> Response {
+    payment_middleware_handler(State(middleware), request, next).await
+}
+
+async fn handle_payment(
+    middleware: &PaymentMiddleware,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if let Some(response) = middleware.config().well_known_response(&request) {
+        return response;
+    }
+
+    // Bypassed paths, unpaid methods and CORS preflight never see the payment flow
+    if !middleware
+        .config()
+        .requires_payment(request.method(), request.uri().path())
+    {
+        return next.run(request).await;
+    }
+
+    if let Some(response) = middleware.limit_body(&mut request) {
+        return response;
+    }
+
+    // Requests under the free quota skip the payment flow entirely
+    if middleware.is_free(&request).await {
+        return next.run(request).await;
+    }
+
+    if let Err(e) = middleware.ensure_supported().await {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": e.to_string(),
+                "x402Version": 1
+            })),
+        )
+            .into_response();
+    }
+
     let config = middleware.config().clone();
     let headers = request.headers().clone();
 
-    // Determine the resource URL
-    let resource = if let Some(ref resource_url) = config.resource {
-        resource_url.clone()
-    } else if let Some(ref root_url) = config.resource_root_url {
-        format!("{}{}", root_url, request.uri().path())
-    } else {
-        request.uri().path().to_string()
-    };
-
-    // Create payment requirements
-    let requirements = match config.create_payment_requirements(&resource) {
-        Ok(req) => req,
+    // Create the payment options; the resource URL is resolved from the request URI
+    let accepts = match config.create_accepts(&request.uri().to_string()) {
+        Ok(accepts) => accepts,
         Err(_) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -141,81 +226,102 @@ pub async fn payment_middleware_handler(
     };
 
     // Check for payment header
-    if let Some(payment_header) = headers.get("X-PAYMENT") {
-        if let Ok(payment_str) = payment_header.to_str() {
-            // Parse the payment payload
-            match crate::types::PaymentPayload::from_base64(payment_str) {
-                Ok(payment_payload) => {
-                    // Verify the payment using the middleware's verify method
-                    match middleware
-                        .verify_with_requirements(&payment_payload, &requirements)
-                        .await
-                    {
-                        Ok(true) => {
-                            // Payment is valid, proceed to next handler
-                            let mut response = next.run(request).await;
-
-                            // After successful response, settle the payment
-                            match middleware
-                                .settle_with_requirements(&payment_payload, &requirements)
-                                .await
-                            {
-                                Ok(settlement_response) => {
-                                    if let Ok(settlement_header) = settlement_response.to_base64() {
-                                        if let Ok(header_value) =
-                                            HeaderValue::from_str(&settlement_header)
-                                        {
-                                            response
-                                                .headers_mut()
-                                                .insert("X-PAYMENT-RESPONSE", header_value);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    // Log settlement error but don't fail the request
-                                    tracing::warn!("Payment settlement failed: {}", e);
-                                }
+    if let Some(payment_header) = headers.get(crate::types::X_PAYMENT_HEADER) {
+        // Parse the payment payload
+        match crate::types::PaymentPayload::from_header_value(payment_header) {
+            Ok(payment_payload) => {
+                // Reject a payload for another scheme or network up front
+                let requirements =
+                    match crate::middleware::requirements_for_payment(&accepts, &payment_payload) {
+                        Ok(requirements) => requirements.clone(),
+                        Err(e) => return payment_required(&config, e.to_string(), accepts),
+                    };
+
+                // Verify the payment using the middleware's verify method
+                match middleware
+                    .verify_payment(&payment_payload, &requirements)
+                    .await
+                {
+                    Ok(verify_response) if verify_response.is_valid => {
+                        // Payment is valid, expose it to the handler and proceed
+                        let verified_payment = crate::middleware::VerifiedPayment::new(
+                            payment_payload.clone(),
+                            requirements.clone(),
+                            &verify_response,
+                        );
+                        if let Some(rate_limiter) = &middleware.rate_limiter {
+                            if !rate_limiter.check(&verified_payment.payer) {
+                                return crate::middleware::RateLimiter::too_many_requests_response(
+                                );
                             }
+                        }
+                        let mut request = request;
+                        request.extensions_mut().insert(verified_payment);
+                        let response = match config.run_handler(request, next).await {
+                            Ok(response) => response,
+                            Err(response) => return response,
+                        };
+                        let mut response =
+                            match middleware.check_response(response, &requirements).await {
+                                Ok(response) => response,
+                                Err(response) => return response,
+                            };
 
+                        // The client only pays for responses it was promised
+                        if !config.settles_status(response.status()) {
                             return response;
                         }
-                        Ok(false) => {
-                            // Payment verification failed
-                            let response_body = serde_json::json!({
-                                "x402Version": 1,
-                                "error": "Payment verification failed",
-                                "accepts": vec![requirements],
-                            });
-                            return (StatusCode::PAYMENT_REQUIRED, Json(response_body))
-                                .into_response();
-                        }
-                        Err(e) => {
-                            // Error during verification
-                            let response_body = serde_json::json!({
-                                "x402Version": 1,
-                                "error": format!("Payment verification error: {}", e),
-                                "accepts": vec![requirements],
-                            });
-                            return (StatusCode::PAYMENT_REQUIRED, Json(response_body))
-                                .into_response();
+
+                        // After successful response, settle the payment. Only the
+                        // response head is touched, so streaming bodies are not buffered
+                        match middleware
+                            .settle_with_requirements(&payment_payload, &requirements)
+                            .await
+                        {
+                            Ok(settlement_response) => {
+                                if let Ok(header_value) = settlement_response.to_header_value() {
+                                    response.headers_mut().insert(
+                                        crate::types::X_PAYMENT_RESPONSE_HEADER,
+                                        header_value,
+                                    );
+                                }
+                                response.extensions_mut().insert(settlement_response);
+                            }
+                            Err(e) => {
+                                // Log settlement error but don't fail the request
+                                tracing::warn!("Payment settlement failed: {}", e);
+                            }
                         }
+
+                        return response;
+                    }
+                    Ok(_) => {
+                        // Payment verification failed
+                        return payment_required(&config, "Payment verification failed", accepts);
+                    }
+                    Err(e) => {
+                        // Error during verification
+                        return payment_required(
+                            &config,
+                            format!("Payment verification error: {}", e),
+                            accepts,
+                        );
                     }
                 }
-                Err(e) => {
-                    // Invalid payment payload
-                    let response_body = serde_json::json!({
-                        "x402Version": 1,
-                        "error": format!("Invalid payment payload: {}", e),
-                        "accepts": vec![requirements],
-                    });
-                    return (StatusCode::PAYMENT_REQUIRED, Json(response_body)).into_response();
-                }
+            }
+            Err(e) => {
+                // Invalid payment payload
+                return payment_required(
+                    &config,
+                    format!("Invalid payment payload: {}", e),
+                    accepts,
+                );
             }
         }
     }
 
     // No valid payment found, check if this is a web browser request
-    if is_web_browser(&headers) {
+    if config.client_type(&headers) == crate::middleware::ClientType::Browser {
         let html = config
             .custom_paywall_html
             .clone()
@@ -223,21 +329,29 @@ pub async fn payment_middleware_handler(
 
         let mut response = Response::new(axum::body::Body::from(html));
         *response.status_mut() = StatusCode::PAYMENT_REQUIRED;
-        response
-            .headers_mut()
-            .insert("Content-Type", HeaderValue::from_static("text/html"));
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            HeaderValue::from_static("text/html; charset=utf-8"),
+        );
+        config.apply_quote_headers(response.headers_mut());
 
         return response.into_response();
     }
 
     // Return JSON response for API clients
-    let response_body = serde_json::json!({
-        "x402Version": 1,
-        "error": "X-PAYMENT header is required",
-        "accepts": vec![requirements],
-    });
+    payment_required(&config, "X-PAYMENT header is required", accepts)
+}
 
-    (StatusCode::PAYMENT_REQUIRED, Json(response_body)).into_response()
+/// JSON 402 response for API clients, carrying the quote's validity window
+fn payment_required(
+    config: &crate::middleware::PaymentMiddlewareConfig,
+    error: impl Into<String>,
+    accepts: Vec<crate::types::PaymentRequirements>,
+) -> Response {
+    let body = config.payment_required_body(error, accepts);
+    let mut response = (StatusCode::PAYMENT_REQUIRED, Json(body)).into_response();
+    config.apply_quote_headers(response.headers_mut());
+    response
 }
 
 /// Axum-specific payment middleware configuration
@@ -339,6 +453,12 @@ impl AxumPaymentConfig {
         self
     }
 
+    /// Serve the accepted payments at `/.well-known/x402` without payment
+    pub fn with_well_known(mut self, enabled: bool) -> Self {
+        self.base_config.serve_well_known = enabled;
+        self
+    }
+
     /// Enable CORS
     pub fn with_cors(mut self, origins: Vec<String>) -> Self {
         self.axum_options.enable_cors = true;
@@ -367,6 +487,13 @@ impl AxumPaymentConfig {
             config: Arc::new(self.base_config),
             facilitator: None,
             template_config: None,
+            rate_limiter: None,
+            free_tier: None,
+            lazy_facilitator: Default::default(),
+            supported_check: None,
+            local_verifier: None,
+            settler: None,
+            in_flight: Default::default(),
         }
     }
 
@@ -384,12 +511,60 @@ pub fn create_payment_app(
     routes: impl FnOnce(Router) -> Router,
 ) -> Router {
     let router = Router::new();
-    let router = routes(router);
+    let mut router = routes(router);
+
+    if config.base_config.serve_well_known {
+        let base_config = config.base_config.clone();
+        router = router.route(
+            crate::types::WELL_KNOWN_X402_PATH,
+            get(move || async move { base_config.well_known_document() }),
+        );
+    }
 
     // Apply service layers
     router.layer(config.create_service())
 }
 
+/// Add payment protection to an existing router
+///
+/// The router keeps its state, layers and fallback; every request it serves,
+/// including the fallback, goes through the payment middleware first.
+pub fn apply_payment_layer<S>(router: Router<S>, config: AxumPaymentConfig) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router.layer(axum::middleware::from_fn_with_state(
+        config.into_middleware(),
+        payment_middleware_handler,
+    ))
+}
+
+/// Protect a group of routes, leaving the rest of the app free
+///
+/// Only `routes` go through the payment middleware, so nest or merge the
+/// result next to unprotected routes, which never reach the facilitator:
+///
+/// ```rust,no_run
+/// use axum::{routing::get, Router};
+/// use rust_decimal::Decimal;
+/// use rust_x402::axum::{protected_routes, AxumPaymentConfig};
+///
+/// let config = AxumPaymentConfig::new(
+///     Decimal::new(1, 4),
+///     "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+/// );
+/// let premium = Router::new().route("/report", get(|| async { "paid content" }));
+/// let app: Router = Router::new()
+///     .route("/public", get(|| async { "free content" }))
+///     .nest("/premium", protected_routes(config, premium));
+/// ```
+pub fn protected_routes<S>(config: AxumPaymentConfig, routes: Router<S>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    apply_payment_layer(routes, config)
+}
+
 /// Helper for creating payment-protected handlers
 pub mod handlers {
     use super::*;
@@ -450,6 +625,7 @@ pub mod examples {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::X_PAYMENT_RESPONSE_HEADER;
     use std::str::FromStr;
 
     #[test]
@@ -471,6 +647,1166 @@ mod tests {
         assert!(config.axum_options.enable_tracing);
     }
 
+    const PAYER: &str = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+
+    async fn mock_facilitator() -> mockito::ServerGuard {
+        let mut facilitator = mockito::Server::new_async().await;
+        facilitator
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(r#"{"isValid":true}"#)
+            .create_async()
+            .await;
+        facilitator
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_body(r#"{"success":true,"transaction":"0xabc","network":"base-sepolia"}"#)
+            .create_async()
+            .await;
+        facilitator
+    }
+
+    fn test_middleware(facilitator: &mockito::ServerGuard) -> PaymentMiddleware {
+        PaymentMiddleware::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_testnet(true)
+        .with_facilitator(
+            crate::facilitator::FacilitatorClient::new(crate::types::FacilitatorConfig::new(
+                facilitator.url(),
+            ))
+            .unwrap(),
+        )
+    }
+
+    fn paid_app(middleware: PaymentMiddleware) -> Router {
+        use axum::Extension;
+
+        Router::new()
+            .route(
+                "/paid",
+                get(|Extension(payment): Extension<VerifiedPayment>| async move { payment.payer }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                middleware,
+                payment_middleware_handler,
+            ))
+    }
+
+    fn paid_request(payer: &str) -> Request {
+        paid_request_on(payer, "base-sepolia")
+    }
+
+    fn paid_request_on(payer: &str, network: &str) -> Request {
+        use crate::types::*;
+
+        let authorization = ExactEvmPayloadAuthorization::new(
+            payer,
+            "0x209693bc6afc0c5328ba36faf03c514ef312287c",
+            "100",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payload = PaymentPayload::new(
+            "exact",
+            network,
+            ExactEvmPayload {
+                signature: "0x00".to_string(),
+                authorization,
+            },
+        );
+
+        Request::builder()
+            .uri("/paid")
+            .header(X_PAYMENT_HEADER, payload.to_header_value().unwrap())
+            .body(axum::body::Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_handler_reads_verified_payment_from_extensions() {
+        use tower::ServiceExt;
+
+        let facilitator = mock_facilitator().await;
+        let app = paid_app(test_middleware(&facilitator));
+        let response = app.oneshot(paid_request(PAYER)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let settlement = response
+            .extensions()
+            .get::<crate::types::SettleResponse>()
+            .unwrap();
+        assert_eq!(settlement.transaction, "0xabc");
+        assert!(response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], PAYER.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_payments_run_handler_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tower::ServiceExt;
+
+        let facilitator = mock_facilitator().await;
+        let runs = Arc::new(AtomicUsize::new(0));
+        let handler_runs = Arc::clone(&runs);
+        let app = Router::new()
+            .route(
+                "/paid",
+                get(move || async move {
+                    handler_runs.fetch_add(1, Ordering::SeqCst);
+                    "paid"
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                test_middleware(&facilitator),
+                payment_middleware_handler,
+            ));
+
+        let responses = futures_util::future::join_all(
+            (0..8).map(|_| app.clone().oneshot(paid_request(PAYER))),
+        )
+        .await;
+
+        let ok = responses
+            .iter()
+            .filter(|response| response.as_ref().unwrap().status() == StatusCode::OK)
+            .count();
+        assert_eq!(ok, 1);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_network_mismatch_rejected_before_verify() {
+        use crate::middleware::PaymentResult;
+        use tower::ServiceExt;
+
+        let mut facilitator = mockito::Server::new_async().await;
+        let verify = facilitator
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(r#"{"isValid":true}"#)
+            .expect(0)
+            .create_async()
+            .await;
+        let middleware = test_middleware(&facilitator);
+
+        // A mainnet payload against base-sepolia requirements
+        let response = paid_app(middleware.clone())
+            .oneshot(paid_request_on(PAYER, "base"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+
+        let app = Router::new().route("/paid", get(|| async { "paid" })).layer(
+            axum::middleware::from_fn_with_state(
+                middleware,
+                |State(middleware): State<PaymentMiddleware>, request: Request, next: Next| async move {
+                    match middleware.process_payment(request, next).await.unwrap() {
+                        PaymentResult::VerificationFailed { response } => response,
+                        other => panic!("unexpected payment result: {:?}", other),
+                    }
+                },
+            ),
+        );
+        let response = app.oneshot(paid_request_on(PAYER, "base")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&body).contains("network 'base'"));
+
+        verify.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_per_payer() {
+        use tower::ServiceExt;
+
+        let facilitator = mock_facilitator().await;
+        let app = paid_app(
+            test_middleware(&facilitator).with_rate_limit(2, std::time::Duration::from_secs(60)),
+        );
+
+        for _ in 0..2 {
+            let response = app.clone().oneshot(paid_request(PAYER)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = app.clone().oneshot(paid_request(PAYER)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // A different payer has its own budget
+        let other = "0x209693Bc6afc0C5328bA36FaF03C514EF312287C";
+        let response = app.oneshot(paid_request(other)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_free_tier_then_paywall() {
+        use tower::ServiceExt;
+
+        let facilitator = mock_facilitator().await;
+        let app = Router::new()
+            .route("/paid", get(|| async { "content" }))
+            .layer(axum::middleware::from_fn_with_state(
+                test_middleware(&facilitator).with_free_tier(2, |request| {
+                    request
+                        .headers()
+                        .get("x-api-key")
+                        .and_then(|v| v.to_str().ok())
+                        .map(str::to_string)
+                }),
+                payment_middleware_handler,
+            ));
+        let unpaid = |key: &str| {
+            Request::builder()
+                .uri("/paid")
+                .header("x-api-key", key)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        for _ in 0..2 {
+            let response = app.clone().oneshot(unpaid("alice")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = app.clone().oneshot(unpaid("alice")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+
+        // Another identity still has its free quota
+        let response = app.oneshot(unpaid("bob")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    fn upload_app(middleware: PaymentMiddleware) -> Router {
+        Router::new()
+            .route(
+                "/paid",
+                axum::routing::post(
+                    |body: axum::body::Bytes| async move { body.len().to_string() },
+                ),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                middleware,
+                payment_middleware_handler,
+            ))
+    }
+
+    fn upload(payer: &str, body: axum::body::Body, content_length: Option<usize>) -> Request {
+        let mut request = paid_request(payer);
+        *request.method_mut() = http::Method::POST;
+        if let Some(length) = content_length {
+            request
+                .headers_mut()
+                .insert(http::header::CONTENT_LENGTH, length.into());
+        }
+        *request.body_mut() = body;
+        request
+    }
+
+    #[tokio::test]
+    async fn test_body_over_limit_rejected_before_payment() {
+        use tower::ServiceExt;
+
+        let mut facilitator = mockito::Server::new_async().await;
+        let verify = facilitator
+            .mock("POST", "/verify")
+            .expect(0)
+            .create_async()
+            .await;
+        let app = upload_app(test_middleware(&facilitator).with_max_body_bytes(16));
+
+        let response = app
+            .clone()
+            .oneshot(upload(PAYER, vec![0u8; 17].into(), Some(17)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        // Without a Content-Length the body is cut off at the limit
+        let chunks = futures_util::stream::iter(
+            (0..4).map(|_| Ok::<_, std::io::Error>(axum::body::Bytes::from(vec![0u8; 8]))),
+        );
+        let request = upload(PAYER, axum::body::Body::from_stream(chunks), None);
+        let response =
+            upload_app(test_middleware(&mock_facilitator().await).with_max_body_bytes(16))
+                .oneshot(request)
+                .await
+                .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        verify.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_body_under_limit_succeeds() {
+        use tower::ServiceExt;
+
+        let facilitator = mock_facilitator().await;
+        let app = upload_app(test_middleware(&facilitator).with_max_body_bytes(16));
+
+        let response = app
+            .oneshot(upload(PAYER, vec![0u8; 16].into(), Some(16)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"16");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_response_is_not_buffered() {
+        use http_body_util::BodyExt;
+        use tower::ServiceExt;
+
+        let facilitator = mock_facilitator().await;
+        let middleware = test_middleware(&facilitator);
+        let (events, receiver) = tokio::sync::mpsc::channel::<&'static str>(1);
+        let receiver = Arc::new(tokio::sync::Mutex::new(Some(receiver)));
+
+        let handler = move || {
+            let receiver = receiver.clone();
+            async move {
+                let receiver = receiver.lock().await.take().unwrap();
+                let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+                    let event = receiver.recv().await?;
+                    let chunk = axum::body::Bytes::from(format!("data: {}\n\n", event));
+                    Some((Ok::<_, std::io::Error>(chunk), receiver))
+                });
+                (
+                    [(http::header::CONTENT_TYPE, "text/event-stream")],
+                    axum::body::Body::from_stream(stream),
+                )
+            }
+        };
+        let app = Router::new().route("/paid", get(handler.clone())).layer(
+            axum::middleware::from_fn_with_state(middleware, payment_middleware_handler),
+        );
+
+        // The response arrives, settled, before the handler has produced any data
+        let response = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            app.oneshot(paid_request(PAYER)),
+        )
+        .await
+        .expect("response was buffered")
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+
+        let mut body = response.into_body();
+        for event in ["one", "two"] {
+            events.send(event).await.unwrap();
+            let frame = tokio::time::timeout(std::time::Duration::from_secs(5), body.frame())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                frame.into_data().unwrap(),
+                format!("data: {}\n\n", event).as_bytes()
+            );
+        }
+        drop(events);
+        assert!(body.frame().await.is_none());
+    }
+
+    async fn process_payment_layer(
+        State(middleware): State<PaymentMiddleware>,
+        request: Request,
+        next: Next,
+    ) -> Response {
+        use crate::middleware::PaymentResult;
+
+        match middleware.process_payment(request, next).await.unwrap() {
+            PaymentResult::Bypassed { response }
+            | PaymentResult::PaymentRequired { response }
+            | PaymentResult::Discovery { response } => response,
+            other => panic!("unexpected payment result: {:?}", other),
+        }
+    }
+
+    /// Layer running `process_payment` for requests that reach the handler
+    async fn process_payment_result(
+        State(middleware): State<PaymentMiddleware>,
+        request: Request,
+        next: Next,
+    ) -> Response {
+        use crate::middleware::PaymentResult;
+
+        match middleware.process_payment(request, next).await.unwrap() {
+            PaymentResult::Success { response, .. }
+            | PaymentResult::InvalidResponse { response }
+            | PaymentResult::Unsettled { response }
+            | PaymentResult::TimedOut { response } => response,
+            other => panic!("unexpected payment result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handler_timeout() {
+        use tower::ServiceExt;
+
+        let mut facilitator = mockito::Server::new_async().await;
+        facilitator
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(r#"{"isValid":true}"#)
+            .create_async()
+            .await;
+        let settle = facilitator
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_body(r#"{"success":true,"transaction":"0xabc","network":"base-sepolia"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let middleware = test_middleware(&facilitator)
+            .with_handler_timeout(std::time::Duration::from_millis(50));
+        let app = |delay: u64, process_payment: bool| {
+            let router = Router::new().route(
+                "/paid",
+                get(move || async move {
+                    tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+                    "done"
+                }),
+            );
+            if process_payment {
+                router.layer(axum::middleware::from_fn_with_state(
+                    middleware.clone(),
+                    process_payment_result,
+                ))
+            } else {
+                router.layer(axum::middleware::from_fn_with_state(
+                    middleware.clone(),
+                    payment_middleware_handler,
+                ))
+            }
+        };
+
+        for process_payment in [false, true] {
+            let response = app(500, process_payment)
+                .oneshot(paid_request(PAYER))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+            assert!(!response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+
+            // A handler within the limit is settled as usual
+            let response = app(0, process_payment)
+                .oneshot(paid_request(PAYER))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        // Only the two timely responses were settled
+        settle.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_settles_only_successful_responses() {
+        use tower::ServiceExt;
+
+        let mut facilitator = mockito::Server::new_async().await;
+        facilitator
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(r#"{"isValid":true}"#)
+            .create_async()
+            .await;
+        let settle = facilitator
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_body(r#"{"success":true,"transaction":"0xabc","network":"base-sepolia"}"#)
+            .expect(3)
+            .create_async()
+            .await;
+
+        let app = |middleware: PaymentMiddleware, status: StatusCode, process_payment: bool| {
+            let router = Router::new().route("/paid", get(move || async move { status }));
+            if process_payment {
+                router.layer(axum::middleware::from_fn_with_state(
+                    middleware,
+                    process_payment_result,
+                ))
+            } else {
+                router.layer(axum::middleware::from_fn_with_state(
+                    middleware,
+                    payment_middleware_handler,
+                ))
+            }
+        };
+        let middleware = test_middleware(&facilitator);
+
+        for process_payment in [false, true] {
+            let response = app(middleware.clone(), StatusCode::OK, process_payment)
+                .oneshot(paid_request(PAYER))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+
+            let response = app(
+                middleware.clone(),
+                StatusCode::INTERNAL_SERVER_ERROR,
+                process_payment,
+            )
+            .oneshot(paid_request(PAYER))
+            .await
+            .unwrap();
+            assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+            assert!(!response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+        }
+
+        // The settled statuses are configurable
+        let middleware = middleware.with_settle_on_statuses(vec![StatusCode::ACCEPTED]);
+        let response = app(middleware.clone(), StatusCode::OK, false)
+            .oneshot(paid_request(PAYER))
+            .await
+            .unwrap();
+        assert!(!response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+        let response = app(middleware, StatusCode::ACCEPTED, false)
+            .oneshot(paid_request(PAYER))
+            .await
+            .unwrap();
+        assert!(response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+
+        settle.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_quote_ttl() {
+        use tower::ServiceExt;
+
+        let facilitator = mock_facilitator().await;
+        let unpaid = || {
+            Request::builder()
+                .uri("/paid")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+        let app = |middleware: PaymentMiddleware| {
+            let routes = || Router::new().route("/paid", get(|| async { "paid content" }));
+            [
+                routes().layer(axum::middleware::from_fn_with_state(
+                    middleware.clone(),
+                    payment_middleware_handler,
+                )),
+                routes().layer(axum::middleware::from_fn_with_state(
+                    middleware,
+                    process_payment_layer,
+                )),
+            ]
+        };
+
+        for app in
+            app(test_middleware(&facilitator).with_quote_ttl(std::time::Duration::from_secs(300)))
+        {
+            let before = chrono::Utc::now().timestamp();
+            let response = app.oneshot(unpaid()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+            assert_eq!(response.headers()["cache-control"], "max-age=300");
+            assert!(response.headers().contains_key("expires"));
+
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: crate::types::PaymentRequirementsResponse =
+                serde_json::from_slice(&body).unwrap();
+            let expires = body.expires.unwrap();
+            assert!((before + 300..=chrono::Utc::now().timestamp() + 300).contains(&expires));
+        }
+
+        // Without a TTL, quotes carry no freshness hints
+        for app in app(test_middleware(&facilitator)) {
+            let response = app.oneshot(unpaid()).await.unwrap();
+            assert!(!response.headers().contains_key("cache-control"));
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert!(body.get("expires").is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_well_known_document() {
+        use tower::ServiceExt;
+
+        let mut facilitator = mockito::Server::new_async().await;
+        let verify = facilitator
+            .mock("POST", "/verify")
+            .expect(0)
+            .create_async()
+            .await;
+        let middleware = test_middleware(&facilitator)
+            .with_resource("https://api.example.com/paid")
+            .with_well_known(true);
+        let json = |response: Response| async move {
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+        };
+        let get_request = |uri: &str| {
+            Request::builder()
+                .uri(uri)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let routes = || Router::new().route("/paid", get(|| async { "paid content" }));
+        let apps = [
+            routes().layer(axum::middleware::from_fn_with_state(
+                middleware.clone(),
+                payment_middleware_handler,
+            )),
+            routes().layer(axum::middleware::from_fn_with_state(
+                middleware.clone(),
+                process_payment_layer,
+            )),
+        ];
+        for app in apps {
+            let response = app.clone().oneshot(get_request("/paid")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+            let quoted = json(response).await;
+
+            let response = app
+                .oneshot(get_request(crate::types::WELL_KNOWN_X402_PATH))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let document = json(response).await;
+            assert_eq!(document["accepts"], quoted["accepts"]);
+            assert_eq!(document["x402Version"], 1);
+        }
+
+        // `create_payment_app` publishes the same document
+        let config = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_resource("https://api.example.com/paid")
+        .with_well_known(true);
+        let app = create_payment_app(config, |router| router);
+        let response = app
+            .oneshot(get_request(crate::types::WELL_KNOWN_X402_PATH))
+            .await
+            .unwrap();
+        assert_eq!(
+            json(response).await,
+            serde_json::to_value(middleware.config().payment_options().unwrap()).unwrap()
+        );
+
+        verify.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_bypass_paths_skip_payment() {
+        use tower::ServiceExt;
+
+        let facilitator = mock_facilitator().await;
+        let middleware = test_middleware(&facilitator)
+            .with_bypass_paths(vec!["/health".to_string(), "/metrics/*".to_string()]);
+        let routes = || {
+            Router::new()
+                .route("/health", get(|| async { "ok" }))
+                .route("/metrics/prometheus", get(|| async { "up 1" }))
+                .route("/api", get(|| async { "paid content" }))
+        };
+        let get_request = |uri: &str| {
+            Request::builder()
+                .uri(uri)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        // Both the axum handler and `process_payment` honour the bypass list
+        let apps = [
+            routes().layer(axum::middleware::from_fn_with_state(
+                middleware.clone(),
+                payment_middleware_handler,
+            )),
+            routes().layer(axum::middleware::from_fn_with_state(
+                middleware,
+                process_payment_layer,
+            )),
+        ];
+        for app in apps {
+            for path in ["/health", "/metrics/prometheus"] {
+                let response = app.clone().oneshot(get_request(path)).await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK, "{}", path);
+                assert!(!response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+            }
+
+            let response = app.oneshot(get_request("/api")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_paid_methods() {
+        use tower::ServiceExt;
+
+        let facilitator = mock_facilitator().await;
+        let app = Router::new()
+            .route(
+                "/items",
+                get(|| async { "list" })
+                    .post(|| async { "created" })
+                    .options(|| async { "preflight" }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                test_middleware(&facilitator).with_paid_methods(vec![http::Method::POST]),
+                payment_middleware_handler,
+            ));
+        let request = |method: http::Method| {
+            Request::builder()
+                .method(method)
+                .uri("/items")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(request(http::Method::GET))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app
+            .clone()
+            .oneshot(request(http::Method::POST))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+
+        let mut paid = paid_request(PAYER);
+        *paid.method_mut() = http::Method::POST;
+        *paid.uri_mut() = "/items".parse().unwrap();
+        let response = app.clone().oneshot(paid).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+
+        let response = app.oneshot(request(http::Method::OPTIONS)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_apply_payment_layer() {
+        use tower::ServiceExt;
+
+        let facilitator = mock_facilitator().await;
+        let router = Router::new()
+            .route(
+                "/paid",
+                get(|State(name): State<&'static str>| async move { name }),
+            )
+            .fallback(|| async { (StatusCode::NOT_FOUND, "no such page") })
+            .layer(axum::middleware::map_response(
+                |mut response: Response| async {
+                    response
+                        .headers_mut()
+                        .insert("X-App", HeaderValue::from_static("existing"));
+                    response
+                },
+            ));
+        let config = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_testnet(true)
+        .with_facilitator_config(crate::types::FacilitatorConfig::new(facilitator.url()));
+        let app = apply_payment_layer(router, config).with_state("my-app");
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/paid")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+
+        // State and existing layers survive
+        let response = app.clone().oneshot(paid_request(PAYER)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["X-App"], "existing");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"my-app");
+
+        // So does the fallback
+        let mut request = paid_request(PAYER);
+        *request.uri_mut() = "/missing".parse().unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()["X-App"], "existing");
+    }
+
+    #[tokio::test]
+    async fn test_protected_routes() {
+        use tower::ServiceExt;
+
+        let mut facilitator = mockito::Server::new_async().await;
+        let verify = facilitator
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(r#"{"isValid":true}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        facilitator
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_body(r#"{"success":true,"transaction":"0xabc","network":"base-sepolia"}"#)
+            .create_async()
+            .await;
+
+        let config = AxumPaymentConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_testnet(true)
+        .with_facilitator_config(crate::types::FacilitatorConfig::new(facilitator.url()));
+        let premium = Router::new().route("/paid", get(|| async { "premium" }));
+        let app = Router::new()
+            .route("/public", get(|| async { "public" }))
+            .nest("/premium", protected_routes(config, premium));
+        let request = |uri: &str| {
+            Request::builder()
+                .uri(uri)
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request("/public")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // A payment header on a public route is ignored, not verified
+        let mut paid = paid_request(PAYER);
+        *paid.uri_mut() = "/public".parse().unwrap();
+        let response = app.clone().oneshot(paid).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(!response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+
+        let response = app.clone().oneshot(request("/premium/paid")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+
+        let mut paid = paid_request(PAYER);
+        *paid.uri_mut() = "/premium/paid".parse().unwrap();
+        let response = app.oneshot(paid).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Only the premium request reached the facilitator
+        verify.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_local_verification() {
+        use crate::{signer::LocalSigner, wallet::Wallet};
+        use tower::ServiceExt;
+
+        let mut facilitator = mockito::Server::new_async().await;
+        let verify = facilitator
+            .mock("POST", "/verify")
+            .expect(0)
+            .create_async()
+            .await;
+        facilitator
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_body(r#"{"success":true,"transaction":"0xabc","network":"base-sepolia"}"#)
+            .create_async()
+            .await;
+
+        let middleware = test_middleware(&facilitator)
+            .with_local_verification(crate::facilitator_storage::InMemoryStorage::new());
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/paid")
+            .unwrap();
+        let wallet = Wallet::new(
+            LocalSigner::new("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
+                .unwrap(),
+            "base-sepolia",
+        );
+        let payment = wallet.authorize_payment(&requirements).await.unwrap();
+        let app = paid_app(middleware);
+        let request = || {
+            Request::builder()
+                .uri("/paid")
+                .header(crate::types::X_PAYMENT_HEADER, payment.to_base64().unwrap())
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+
+        // The nonce was consumed locally, so a replay is refused
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+
+        verify.assert_async().await;
+    }
+
+    #[cfg(feature = "json-schema")]
+    #[tokio::test]
+    async fn test_response_validation() {
+        use tower::ServiceExt;
+
+        let mut facilitator = mockito::Server::new_async().await;
+        facilitator
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(r#"{"isValid":true}"#)
+            .create_async()
+            .await;
+        // Only the two conforming responses are settled
+        let settle = facilitator
+            .mock("POST", "/settle")
+            .with_status(200)
+            .with_body(r#"{"success":true,"transaction":"0xabc","network":"base-sepolia"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let middleware = test_middleware(&facilitator)
+            .with_output_schema(serde_json::json!({
+                "type": "object",
+                "required": ["temperature"],
+                "properties": {"temperature": {"type": "number"}}
+            }))
+            .with_response_validation(true);
+        let app = |body: serde_json::Value, layered_with_process_payment: bool| {
+            let router = Router::new().route("/paid", get(move || async move { Json(body) }));
+            if layered_with_process_payment {
+                router.layer(axum::middleware::from_fn_with_state(
+                    middleware.clone(),
+                    process_payment_result,
+                ))
+            } else {
+                router.layer(axum::middleware::from_fn_with_state(
+                    middleware.clone(),
+                    payment_middleware_handler,
+                ))
+            }
+        };
+
+        for process_payment in [false, true] {
+            let response = app(serde_json::json!({"temperature": 21.5}), process_payment)
+                .oneshot(paid_request(PAYER))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert_eq!(&body[..], br#"{"temperature":21.5}"#);
+
+            let response = app(serde_json::json!({"temperature": "warm"}), process_payment)
+                .oneshot(paid_request(PAYER))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+            assert!(!response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+        }
+
+        settle.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_composite_app_state() {
+        use axum::extract::FromRef;
+        use tower::ServiceExt;
+
+        #[derive(Clone)]
+        struct AppState {
+            greeting: &'static str,
+            payment: PaymentMiddleware,
+        }
+
+        impl FromRef<AppState> for PaymentMiddleware {
+            fn from_ref(state: &AppState) -> Self {
+                state.payment.clone()
+            }
+        }
+
+        let facilitator = mock_facilitator().await;
+        let state = AppState {
+            greeting: "hello",
+            payment: test_middleware(&facilitator),
+        };
+        let app = Router::new()
+            .route(
+                "/paid",
+                get(|State(state): State<AppState>| async move { state.greeting }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                payment_middleware_handler,
+            ))
+            .with_state(state);
+        let unpaid = || {
+            Request::builder()
+                .uri("/paid")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(unpaid()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+        let response = app.oneshot(paid_request(PAYER)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"hello");
+
+        // The same middleware installed as an extension
+        let app = Router::new()
+            .route("/paid", get(|| async { "hello" }))
+            .layer(axum::middleware::from_fn(payment_extension_handler))
+            .layer(axum::Extension(test_middleware(&facilitator)));
+        let response = app.clone().oneshot(unpaid()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+        let response = app.oneshot(paid_request(PAYER)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_cors_headers() {
+        use tower::ServiceExt;
+
+        const ORIGIN: &str = "https://app.example.com";
+        let facilitator = mock_facilitator().await;
+        let app = paid_app(test_middleware(&facilitator).with_cors_headers(vec![ORIGIN.into()]));
+        let with_origin = |mut request: Request, origin: &str| {
+            request
+                .headers_mut()
+                .insert(http::header::ORIGIN, origin.parse().unwrap());
+            request
+        };
+        let unpaid = || {
+            Request::builder()
+                .uri("/paid")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        // The 402 is readable cross-origin
+        let response = app
+            .clone()
+            .oneshot(with_origin(unpaid(), ORIGIN))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+        let headers = response.headers();
+        assert_eq!(headers[http::header::ACCESS_CONTROL_ALLOW_ORIGIN], ORIGIN);
+        assert!(headers[http::header::ACCESS_CONTROL_EXPOSE_HEADERS]
+            .to_str()
+            .unwrap()
+            .contains("X-PAYMENT-RESPONSE"));
+
+        // Preflight allows sending X-PAYMENT
+        let preflight = Request::builder()
+            .method(http::Method::OPTIONS)
+            .uri("/paid")
+            .header(http::header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .header(http::header::ACCESS_CONTROL_REQUEST_HEADERS, "x-payment")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let response = app
+            .clone()
+            .oneshot(with_origin(preflight, ORIGIN))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            response.headers()[http::header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            ORIGIN
+        );
+        assert!(
+            response.headers()[http::header::ACCESS_CONTROL_ALLOW_HEADERS]
+                .to_str()
+                .unwrap()
+                .contains("X-PAYMENT")
+        );
+
+        // The verified response carries them too
+        let response = app
+            .clone()
+            .oneshot(with_origin(paid_request(PAYER), ORIGIN))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[http::header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            ORIGIN
+        );
+
+        // Other origins get no CORS headers
+        let response = app
+            .oneshot(with_origin(unpaid(), "https://evil.example"))
+            .await
+            .unwrap();
+        assert!(!response
+            .headers()
+            .contains_key(http::header::ACCESS_CONTROL_ALLOW_ORIGIN));
+    }
+
+    #[tokio::test]
+    async fn test_supported_check_rejects_unsupported_network() {
+        use tower::ServiceExt;
+
+        let mut facilitator = mock_facilitator().await;
+        let supported = facilitator
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_body(r#"{"kinds":[{"x402Version":1,"scheme":"exact","network":"base"}]}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let unpaid = || {
+            Request::builder()
+                .uri("/paid")
+                .body(axum::body::Body::empty())
+                .unwrap()
+        };
+
+        // The middleware asks for base-sepolia, which the facilitator does not list
+        let strict = paid_app(test_middleware(&facilitator).with_supported_check(true));
+        for _ in 0..2 {
+            let response = strict.clone().oneshot(unpaid()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+
+        // Without strict mode the mismatch is only logged
+        let lenient = paid_app(test_middleware(&facilitator).with_supported_check(false));
+        let response = lenient.oneshot(unpaid()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+
+        // Each middleware fetched the supported kinds once
+        supported.assert_async().await;
+    }
+
     #[test]
     fn test_payment_middleware_creation() {
         let middleware = create_payment_middleware(
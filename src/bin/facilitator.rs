@@ -1,7 +1,8 @@
 //! X402 Facilitator Server
 //!
 //! A standalone facilitator server for verifying and settling x402 micropayments.
-//! This server can be run as a binary for production deployment.
+//! Built as the `simple_facilitator` binary: a minimal in-memory variant of
+//! the `facilitator` binary, without history, events or Redis.
 
 use axum::{
     extract::{Query, State},
@@ -11,121 +12,19 @@ use axum::{
     Router,
 };
 use serde::Deserialize;
-use std::collections::HashMap;
 use std::env;
-use std::sync::Arc;
-use tokio::sync::RwLock;
 
-use rand::Rng;
-use rust_x402::{types::*, Result, X402Error};
-
-/// Simple in-memory facilitator for demonstration
-#[derive(Debug, Clone)]
-struct SimpleFacilitator {
-    /// Track processed nonces to prevent replay attacks
-    processed_nonces: Arc<RwLock<HashMap<String, bool>>>,
-}
-
-impl SimpleFacilitator {
-    fn new() -> Self {
-        Self {
-            processed_nonces: Arc::new(RwLock::new(HashMap::new())),
-        }
-    }
-
-    /// Verify a payment payload
-    async fn verify_payment(
-        &self,
-        payload: &PaymentPayload,
-        requirements: &PaymentRequirements,
-    ) -> Result<VerifyResponse> {
-        // Check if nonce has been used before (replay protection)
-        let nonce = &payload.payload.authorization.nonce;
-        {
-            let nonces = self.processed_nonces.read().await;
-            if nonces.contains_key(nonce) {
-                return Ok(VerifyResponse {
-                    is_valid: false,
-                    invalid_reason: Some("nonce_already_used".to_string()),
-                    payer: Some(payload.payload.authorization.from.clone()),
-                });
-            }
-        }
-
-        // Verify authorization timing
-        if !payload.payload.authorization.is_valid_now()? {
-            return Ok(VerifyResponse {
-                is_valid: false,
-                invalid_reason: Some("authorization_expired".to_string()),
-                payer: Some(payload.payload.authorization.from.clone()),
-            });
-        }
-
-        // Verify amount meets requirements
-        let payment_amount: u128 = payload
-            .payload
-            .authorization
-            .value
-            .parse()
-            .map_err(|_| X402Error::invalid_payment_requirements("Invalid payment amount"))?;
-        let required_amount: u128 = requirements
-            .max_amount_required
-            .parse()
-            .map_err(|_| X402Error::invalid_payment_requirements("Invalid required amount"))?;
-
-        if payment_amount < required_amount {
-            return Ok(VerifyResponse {
-                is_valid: false,
-                invalid_reason: Some("insufficient_amount".to_string()),
-                payer: Some(payload.payload.authorization.from.clone()),
-            });
-        }
-
-        // Verify recipient matches
-        if payload.payload.authorization.to != requirements.pay_to {
-            return Ok(VerifyResponse {
-                is_valid: false,
-                invalid_reason: Some("recipient_mismatch".to_string()),
-                payer: Some(payload.payload.authorization.from.clone()),
-            });
-        }
-
-        // Mark nonce as processed
-        {
-            let mut nonces = self.processed_nonces.write().await;
-            nonces.insert(nonce.clone(), true);
-        }
-
-        Ok(VerifyResponse {
-            is_valid: true,
-            invalid_reason: None,
-            payer: Some(payload.payload.authorization.from.clone()),
-        })
-    }
-
-    /// Settle a verified payment
-    async fn settle_payment(
-        &self,
-        payload: &PaymentPayload,
-        _requirements: &PaymentRequirements,
-    ) -> Result<SettleResponse> {
-        // In a real implementation, this would:
-        // 1. Call the blockchain to execute the transfer
-        // 2. Wait for transaction confirmation
-        // 3. Return the transaction hash
-
-        // For this example, we'll simulate a successful settlement
-        let mock_transaction_hash = format!("0x{:064x}", rand::thread_rng().gen::<u128>());
+use rust_x402::{
+    facilitator::{LocalFacilitator, MockSettler},
+    facilitator_storage::InMemoryStorage,
+    types::*,
+};
 
-        Ok(SettleResponse {
-            success: true,
-            error_reason: None,
-            transaction: mock_transaction_hash,
-            network: payload.network.clone(),
-            payer: Some(payload.payload.authorization.from.clone()),
-        })
-    }
-}
+/// In-memory facilitator for demonstration
+///
+/// Verification checks signatures, timing, amount, recipient and nonces;
+/// settlement is simulated with a random transaction hash.
+type SimpleFacilitator = LocalFacilitator<InMemoryStorage>;
 
 /// Request types for the facilitator API
 #[derive(Debug, Deserialize)]
@@ -159,7 +58,7 @@ async fn main() -> std::result::Result<(), Box<dyn std::error::Error>> {
     let bind_address = env::var("BIND_ADDRESS").unwrap_or_else(|_| "0.0.0.0:3000".to_string());
 
     // Create facilitator instance
-    let facilitator = SimpleFacilitator::new();
+    let facilitator = SimpleFacilitator::new(InMemoryStorage::new(), MockSettler);
 
     // Create the API routes
     let app = Router::new()
@@ -193,7 +92,7 @@ async fn verify_handler(
     State(facilitator): State<SimpleFacilitator>,
     Json(request): Json<VerifyRequest>,
 ) -> std::result::Result<Json<VerifyResponse>, StatusCode> {
-    if request.x402_version != X402_VERSION {
+    if !is_supported_version(request.x402_version) {
         return Err(StatusCode::BAD_REQUEST);
     }
 
@@ -214,7 +113,7 @@ async fn settle_handler(
     State(facilitator): State<SimpleFacilitator>,
     Json(request): Json<SettleRequest>,
 ) -> std::result::Result<Json<SettleResponse>, StatusCode> {
-    if request.x402_version != X402_VERSION {
+    if !is_supported_version(request.x402_version) {
         return Err(StatusCode::BAD_REQUEST);
     }
 
@@ -270,3 +169,67 @@ async fn health_handler() -> Json<serde_json::Value> {
         "x402_version": X402_VERSION,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_x402::{signer::LocalSigner, wallet::Wallet};
+
+    async fn verify_request(x402_version: u32) -> VerifyRequest {
+        let payment_requirements = PaymentRequirements::new(
+            schemes::EXACT,
+            networks::BASE_SEPOLIA,
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693bc6afc0c5328ba36faf03c514ef312287c",
+            "https://example.com/test",
+            "Test payment",
+        );
+        let wallet = Wallet::new(
+            LocalSigner::new("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
+                .unwrap(),
+            networks::BASE_SEPOLIA,
+        );
+        VerifyRequest {
+            x402_version,
+            payment_payload: wallet
+                .authorize_payment(&payment_requirements)
+                .await
+                .unwrap(),
+            payment_requirements,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_then_settle() {
+        let facilitator = SimpleFacilitator::new(InMemoryStorage::new(), MockSettler);
+        let request = verify_request(X402_VERSION).await;
+        let settle = SettleRequest {
+            x402_version: X402_VERSION,
+            payment_payload: request.payment_payload.clone(),
+            payment_requirements: request.payment_requirements.clone(),
+        };
+
+        let Json(verified) = verify_handler(State(facilitator.clone()), Json(request))
+            .await
+            .unwrap();
+        assert!(verified.is_valid, "{:?}", verified.invalid_reason);
+
+        let Json(settled) = settle_handler(State(facilitator), Json(settle))
+            .await
+            .unwrap();
+        assert!(settled.success, "{:?}", settled.error_reason);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unsupported_version() {
+        let facilitator = SimpleFacilitator::new(InMemoryStorage::new(), MockSettler);
+        let status = verify_handler(
+            State(facilitator),
+            Json(verify_request(X402_MAX_VERSION + 1).await),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
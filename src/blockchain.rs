@@ -5,20 +5,77 @@
 //! - Balance checking
 //! - Network status verification
 //! - Gas estimation
+//!
+//! A client may be given several RPC providers. Requests are spread across
+//! them round-robin; on a connection error, a 5xx or a 429 the next provider
+//! is tried. A provider failing [`PROVIDER_FAILURE_THRESHOLD`] times in a row
+//! is skipped for [`PROVIDER_COOLDOWN`] unless no other provider is left.
+//!
+//! Independent reads can share one round trip through [`BlockchainClient::batch`].
 
 use crate::{Result, X402Error};
+use ethereum_types::U256;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Consecutive failures after which a provider is temporarily skipped
+pub const PROVIDER_FAILURE_THRESHOLD: u32 = 3;
+
+/// How long an unhealthy provider is skipped
+pub const PROVIDER_COOLDOWN: Duration = Duration::from_secs(30);
 
 /// Blockchain client for real network interactions
 pub struct BlockchainClient {
-    /// RPC endpoint URL
-    rpc_url: String,
+    /// RPC providers, in configured order
+    providers: Vec<RpcProvider>,
+    /// Round-robin cursor into `providers`
+    next_provider: AtomicUsize,
     /// Network name
     pub network: String,
     /// HTTP client for RPC calls
     client: reqwest::Client,
 }
 
+/// An RPC endpoint and its recent health
+#[derive(Debug)]
+struct RpcProvider {
+    url: String,
+    consecutive_failures: AtomicU32,
+    skip_until: Mutex<Option<Instant>>,
+}
+
+impl RpcProvider {
+    fn new(url: String) -> Self {
+        Self {
+            url,
+            consecutive_failures: AtomicU32::new(0),
+            skip_until: Mutex::new(None),
+        }
+    }
+
+    /// Whether the provider is outside its cooldown
+    fn is_healthy(&self) -> bool {
+        let skip_until = self.skip_until.lock().unwrap_or_else(|e| e.into_inner());
+        skip_until.is_none_or(|until| Instant::now() >= until)
+    }
+
+    fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::Relaxed);
+        *self.skip_until.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn record_failure(&self) {
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if failures >= PROVIDER_FAILURE_THRESHOLD {
+            *self.skip_until.lock().unwrap_or_else(|e| e.into_inner()) =
+                Some(Instant::now() + PROVIDER_COOLDOWN);
+        }
+    }
+}
+
 /// Blockchain transaction status
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TransactionStatus {
@@ -50,6 +107,156 @@ pub struct BalanceInfo {
     pub token_address: Option<String>,
 }
 
+/// Outcome of an `eth_call`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallOutcome {
+    /// The call succeeded with the given hex return data
+    Success(String),
+    /// The call reverted; `data` is the raw hex revert data when the node returns it
+    Reverted {
+        message: String,
+        data: Option<String>,
+    },
+}
+
+/// Error returned by the node for one JSON-RPC call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RPC error {}: {}", self.code, self.message)
+    }
+}
+
+/// JSON-RPC calls sent together as one batch request
+///
+/// Built with [`BlockchainClient::batch`]. Each call succeeds or fails on its
+/// own, so one reverted `eth_call` does not lose the other results.
+pub struct RpcBatch<'a> {
+    client: &'a BlockchainClient,
+    calls: Vec<(String, serde_json::Value)>,
+}
+
+impl std::fmt::Debug for RpcBatch<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RpcBatch")
+            .field("calls", &self.calls)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RpcBatch<'_> {
+    /// Add a call to `method` with `params`
+    pub fn request(mut self, method: impl Into<String>, params: serde_json::Value) -> Self {
+        self.calls.push((method.into(), params));
+        self
+    }
+
+    /// Add `eth_getBalance` of `address` at the latest block
+    pub fn get_balance(self, address: &str) -> Self {
+        self.request("eth_getBalance", serde_json::json!([address, "latest"]))
+    }
+
+    /// Add `eth_call` of `transaction` against the latest block
+    pub fn call(self, transaction: &TransactionRequest) -> Self {
+        self.request("eth_call", serde_json::json!([transaction, "latest"]))
+    }
+
+    /// Add `eth_getTransactionCount` of `address` at the latest block
+    pub fn get_transaction_count(self, address: &str) -> Self {
+        self.request(
+            "eth_getTransactionCount",
+            serde_json::json!([address, "latest"]),
+        )
+    }
+
+    /// Number of calls in the batch
+    pub fn len(&self) -> usize {
+        self.calls.len()
+    }
+
+    /// Whether the batch has no calls
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Send every call in one request, returning their results in call order
+    ///
+    /// The outer error is for the request as a whole (no provider reachable,
+    /// or a response that is not a batch); a call the node rejected is an
+    /// [`RpcError`] in its own slot.
+    pub async fn send(self) -> Result<Vec<std::result::Result<serde_json::Value, RpcError>>> {
+        if self.calls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let body: Vec<_> = self
+            .calls
+            .iter()
+            .enumerate()
+            .map(|(id, (method, params))| {
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": method,
+                    "params": params,
+                    "id": id
+                })
+            })
+            .collect();
+        let response = self.client.send_rpc(&serde_json::json!(body)).await?;
+
+        let responses = response.as_array().ok_or_else(|| {
+            let reason = response
+                .get("error")
+                .and_then(|error| error.get("message"))
+                .and_then(|message| message.as_str())
+                .unwrap_or("response is not an array");
+            X402Error::network_error(format!("RPC batch rejected: {}", reason))
+        })?;
+
+        // Nodes may answer a batch in any order; match responses up by id
+        let mut results: Vec<_> = (0..self.calls.len())
+            .map(|_| {
+                Err(RpcError {
+                    code: -32603,
+                    message: "No response for this call".to_string(),
+                    data: None,
+                })
+            })
+            .collect();
+        for response in responses {
+            let Some(slot) = response
+                .get("id")
+                .and_then(|id| id.as_u64())
+                .and_then(|id| results.get_mut(id as usize))
+            else {
+                continue;
+            };
+            *slot =
+                match response.get("error") {
+                    Some(error) => Err(serde_json::from_value(error.clone()).unwrap_or_else(
+                        |_| RpcError {
+                            code: -32603,
+                            message: error.to_string(),
+                            data: None,
+                        },
+                    )),
+                    None => Ok(response
+                        .get("result")
+                        .cloned()
+                        .unwrap_or(serde_json::Value::Null)),
+                };
+        }
+        Ok(results)
+    }
+}
+
 /// Network information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkInfo {
@@ -63,30 +270,108 @@ impl BlockchainClient {
     /// Create a new blockchain client
     pub fn new(rpc_url: String, network: String) -> Self {
         Self {
-            rpc_url,
+            providers: vec![RpcProvider::new(rpc_url)],
+            next_provider: AtomicUsize::new(0),
             network,
             client: reqwest::Client::new(),
         }
     }
 
+    /// Create a client failing over across several RPC providers
+    pub fn with_providers(rpc_urls: Vec<String>, network: String) -> Result<Self> {
+        if rpc_urls.is_empty() {
+            return Err(X402Error::config(format!(
+                "No RPC providers given for network {}",
+                network
+            )));
+        }
+
+        Ok(Self {
+            providers: rpc_urls.into_iter().map(RpcProvider::new).collect(),
+            next_provider: AtomicUsize::new(0),
+            network,
+            client: reqwest::Client::new(),
+        })
+    }
+
+    /// RPC provider URLs, in configured order
+    pub fn rpc_urls(&self) -> Vec<&str> {
+        self.providers.iter().map(|p| p.url.as_str()).collect()
+    }
+
+    /// Providers to try for the next request
+    ///
+    /// Starts at the round-robin cursor; providers in cooldown go last so they
+    /// are still tried when every other provider has failed.
+    fn provider_order(&self) -> Vec<&RpcProvider> {
+        let start = self.next_provider.fetch_add(1, Ordering::Relaxed) % self.providers.len();
+        let (mut healthy, unhealthy): (Vec<_>, Vec<_>) = self.providers[start..]
+            .iter()
+            .chain(&self.providers[..start])
+            .partition(|provider| provider.is_healthy());
+        healthy.extend(unhealthy);
+        healthy
+    }
+
+    /// Send a JSON-RPC request, failing over between providers
+    async fn rpc_request(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+            "id": 1
+        });
+        self.send_rpc(&body).await
+    }
+
+    /// Start a batch of JSON-RPC calls sent as a single request
+    pub fn batch(&self) -> RpcBatch<'_> {
+        RpcBatch {
+            client: self,
+            calls: Vec::new(),
+        }
+    }
+
+    /// Post a JSON-RPC request or batch, failing over between providers
+    async fn send_rpc(&self, body: &serde_json::Value) -> Result<serde_json::Value> {
+        let mut last_error = None;
+        for provider in self.provider_order() {
+            let response = match self.client.post(&provider.url).json(body).send().await {
+                Ok(response) => response,
+                Err(e) => {
+                    provider.record_failure();
+                    last_error = Some(format!("RPC request failed: {}", e));
+                    continue;
+                }
+            };
+
+            let status = response.status();
+            if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                provider.record_failure();
+                last_error = Some(format!("RPC provider returned {}", status));
+                continue;
+            }
+
+            provider.record_success();
+            return response.json().await.map_err(|e| {
+                X402Error::network_error(format!("Failed to parse RPC response: {}", e))
+            });
+        }
+
+        Err(X402Error::network_error(
+            last_error.unwrap_or_else(|| "No RPC provider available".to_string()),
+        ))
+    }
+
     /// Get transaction status by hash
     pub async fn get_transaction_status(&self, tx_hash: &str) -> Result<TransactionInfo> {
-        let response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_getTransactionByHash",
-                "params": [tx_hash],
-                "id": 1
-            }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let response_json: serde_json::Value = response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
+        let response_json = self
+            .rpc_request("eth_getTransactionByHash", serde_json::json!([tx_hash]))
+            .await?;
 
         if let Some(result) = response_json.get("result") {
             if result.is_null() {
@@ -155,22 +440,9 @@ impl BlockchainClient {
 
     /// Get transaction receipt
     async fn get_transaction_receipt(&self, tx_hash: &str) -> Result<serde_json::Value> {
-        let response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_getTransactionReceipt",
-                "params": [tx_hash],
-                "id": 1
-            }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let response_json: serde_json::Value = response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
+        let response_json = self
+            .rpc_request("eth_getTransactionReceipt", serde_json::json!([tx_hash]))
+            .await?;
 
         response_json
             .get("result")
@@ -180,22 +452,9 @@ impl BlockchainClient {
 
     /// Get balance for an address
     pub async fn get_balance(&self, address: &str) -> Result<BalanceInfo> {
-        let response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_getBalance",
-                "params": [address, "latest"],
-                "id": 1
-            }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let response_json: serde_json::Value = response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
+        let response_json = self
+            .rpc_request("eth_getBalance", serde_json::json!([address, "latest"]))
+            .await?;
 
         let balance = response_json
             .get("result")
@@ -216,25 +475,12 @@ impl BlockchainClient {
         let usdc_contract = self.get_usdc_contract_address()?;
 
         // Call balanceOf function on USDC contract
-        let response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_call",
-                "params": [{
+        let response_json = self
+            .rpc_request("eth_call", serde_json::json!([{
                     "to": usdc_contract,
                     "data": format!("0x70a08231000000000000000000000000{}", address.trim_start_matches("0x"))
-                }, "latest"],
-                "id": 1
-            }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let response_json: serde_json::Value = response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
+                }, "latest"]))
+            .await?;
 
         let token_balance = response_json
             .get("result")
@@ -250,106 +496,76 @@ impl BlockchainClient {
         })
     }
 
-    /// Get network information
-    pub async fn get_network_info(&self) -> Result<NetworkInfo> {
-        // Get chain ID
-        let chain_id_response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_chainId",
-                "params": [],
-                "id": 1
-            }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let chain_id_json: serde_json::Value = chain_id_response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
+    /// Balance of `owner` in the ERC-20 token at `token`, in atomic units
+    ///
+    /// A call returning no data (e.g. `token` is not a contract) reads as zero.
+    pub async fn token_balance(&self, token: &str, owner: &str) -> Result<U256> {
+        let data = format!(
+            "0x70a08231000000000000000000000000{}",
+            owner.trim_start_matches("0x")
+        );
+        let result = self
+            .rpc_request(
+                "eth_call",
+                serde_json::json!([{ "to": token, "data": data }, "latest"]),
+            )
+            .await?;
 
-        let chain_id = chain_id_json
+        if let Some(error) = result.get("error") {
+            let message = error
+                .get("message")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Unknown RPC error");
+            return Err(X402Error::network_error(format!("RPC error: {}", message)));
+        }
+        let hex = result
             .get("result")
             .and_then(|v| v.as_str())
-            .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
-            .unwrap_or(0);
-
-        // Get latest block number
-        let block_response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_blockNumber",
-                "params": [],
-                "id": 1
-            }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let block_json: serde_json::Value = block_response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
+            .ok_or_else(|| X402Error::network_error("No result in RPC response".to_string()))?
+            .trim_start_matches("0x");
+        if hex.is_empty() {
+            return Ok(U256::zero());
+        }
+        U256::from_str_radix(hex, 16)
+            .map_err(|_| X402Error::network_error("Invalid token balance format".to_string()))
+    }
 
-        let latest_block = block_json
-            .get("result")
-            .and_then(|v| v.as_str())
-            .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
-            .unwrap_or(0);
-
-        // Get gas price
-        let gas_response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_gasPrice",
-                "params": [],
-                "id": 1
-            }))
+    /// Get network information
+    ///
+    /// The chain ID, block number and gas price are read in one batch.
+    pub async fn get_network_info(&self) -> Result<NetworkInfo> {
+        let results = self
+            .batch()
+            .request("eth_chainId", serde_json::json!([]))
+            .request("eth_blockNumber", serde_json::json!([]))
+            .request("eth_gasPrice", serde_json::json!([]))
             .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let gas_json: serde_json::Value = gas_response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
-
-        let gas_price = gas_json
-            .get("result")
-            .and_then(|v| v.as_str())
-            .unwrap_or("0x0")
-            .to_string();
+            .await?;
+        let hex = |index: usize| {
+            results
+                .get(index)
+                .and_then(|result| result.as_ref().ok())
+                .and_then(|value| value.as_str())
+        };
+        let number = |index: usize| {
+            hex(index)
+                .and_then(|s| u64::from_str_radix(s.trim_start_matches("0x"), 16).ok())
+                .unwrap_or(0)
+        };
 
         Ok(NetworkInfo {
-            chain_id,
+            chain_id: number(0),
             network_name: self.network.clone(),
-            latest_block,
-            gas_price,
+            latest_block: number(1),
+            gas_price: hex(2).unwrap_or("0x0").to_string(),
         })
     }
 
     /// Estimate gas for a transaction
     pub async fn estimate_gas(&self, transaction: &TransactionRequest) -> Result<u64> {
-        let response = self
-            .client
-            .post(&self.rpc_url)
-            .json(&serde_json::json!({
-                "jsonrpc": "2.0",
-                "method": "eth_estimateGas",
-                "params": [transaction],
-                "id": 1
-            }))
-            .send()
-            .await
-            .map_err(|e| X402Error::network_error(format!("RPC request failed: {}", e)))?;
-
-        let response_json: serde_json::Value = response.json().await.map_err(|e| {
-            X402Error::network_error(format!("Failed to parse RPC response: {}", e))
-        })?;
+        let response_json = self
+            .rpc_request("eth_estimateGas", serde_json::json!([transaction]))
+            .await?;
 
         let gas_hex = response_json
             .get("result")
@@ -360,6 +576,41 @@ impl BlockchainClient {
             .map_err(|_| X402Error::network_error("Invalid gas estimate format".to_string()))
     }
 
+    /// Execute a call against the latest block without broadcasting it
+    ///
+    /// Reverts are reported as [`CallOutcome::Reverted`]; other RPC errors are
+    /// returned as errors.
+    pub async fn call(&self, transaction: &TransactionRequest) -> Result<CallOutcome> {
+        let response_json = self
+            .rpc_request("eth_call", serde_json::json!([transaction, "latest"]))
+            .await?;
+
+        if let Some(error) = response_json.get("error") {
+            let message = error
+                .get("message")
+                .and_then(|v| v.as_str())
+                .unwrap_or("Unknown RPC error")
+                .to_string();
+            // Geth reports reverts with code 3; other nodes only say so in the message
+            let reverted =
+                error.get("code").and_then(|v| v.as_i64()) == Some(3) || message.contains("revert");
+            if !reverted {
+                return Err(X402Error::network_error(format!("RPC error: {}", message)));
+            }
+            let data = error
+                .get("data")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            return Ok(CallOutcome::Reverted { message, data });
+        }
+
+        response_json
+            .get("result")
+            .and_then(|v| v.as_str())
+            .map(|result| CallOutcome::Success(result.to_string()))
+            .ok_or_else(|| X402Error::network_error("No result in RPC response".to_string()))
+    }
+
     /// Get USDC contract address for current network
     pub fn get_usdc_contract_address(&self) -> Result<String> {
         match self.network.as_str() {
@@ -386,6 +637,96 @@ pub struct TransactionRequest {
     pub gas_price: Option<String>,
 }
 
+/// Prefix of environment variables read by [`NetworkRpcRegistry::from_env`]
+pub const RPC_URL_ENV_PREFIX: &str = "RPC_URL_";
+
+/// Mapping from network name to RPC endpoint URLs
+///
+/// The first URL registered for a network is its primary endpoint; later ones
+/// are fallbacks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetworkRpcRegistry {
+    endpoints: HashMap<String, Vec<String>>,
+}
+
+impl NetworkRpcRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `url` for `network`, after any URLs already registered
+    pub fn with_network(mut self, network: impl Into<String>, url: impl Into<String>) -> Self {
+        self.insert(network, url);
+        self
+    }
+
+    /// Register `url` for `network`, after any URLs already registered
+    pub fn insert(&mut self, network: impl Into<String>, url: impl Into<String>) {
+        self.endpoints
+            .entry(network.into())
+            .or_default()
+            .push(url.into());
+    }
+
+    /// Load the registry from `RPC_URL_<NETWORK>` environment variables
+    ///
+    /// The network name is the suffix lowercased with `_` replaced by `-`, so
+    /// `RPC_URL_BASE_SEPOLIA` configures `base-sepolia`. A comma-separated
+    /// value registers fallbacks after the primary URL.
+    pub fn from_env() -> Self {
+        Self::from_vars(std::env::vars())
+    }
+
+    /// Load the registry from `(name, value)` pairs as in [`from_env`](Self::from_env)
+    pub fn from_vars(vars: impl IntoIterator<Item = (String, String)>) -> Self {
+        let mut registry = Self::new();
+        for (name, value) in vars {
+            let Some(suffix) = name.strip_prefix(RPC_URL_ENV_PREFIX) else {
+                continue;
+            };
+            let network = suffix.to_lowercase().replace('_', "-");
+            for url in value.split(',').map(str::trim).filter(|u| !u.is_empty()) {
+                registry.insert(network.clone(), url);
+            }
+        }
+        registry
+    }
+
+    /// All URLs registered for `network`, primary first
+    pub fn urls(&self, network: &str) -> Option<&[String]> {
+        self.endpoints
+            .get(network)
+            .map(Vec::as_slice)
+            .filter(|urls| !urls.is_empty())
+    }
+
+    /// Primary URL for `network`
+    pub fn primary(&self, network: &str) -> Result<&str> {
+        self.urls(network)
+            .map(|urls| urls[0].as_str())
+            .ok_or_else(|| {
+                X402Error::invalid_network(format!(
+                    "No RPC endpoint registered for network {}",
+                    network
+                ))
+            })
+    }
+
+    /// Networks with at least one registered URL
+    pub fn networks(&self) -> impl Iterator<Item = &str> {
+        self.endpoints
+            .iter()
+            .filter(|(_, urls)| !urls.is_empty())
+            .map(|(network, _)| network.as_str())
+    }
+
+    /// Check whether no network is registered
+    pub fn is_empty(&self) -> bool {
+        self.networks().next().is_none()
+    }
+}
+
 /// Blockchain client factory
 pub struct BlockchainClientFactory;
 
@@ -429,6 +770,8 @@ impl BlockchainClientFactory {
 mod tests {
     use super::*;
 
+    const ADDRESS: &str = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+
     #[test]
     fn test_blockchain_client_creation() {
         let client =
@@ -446,6 +789,282 @@ mod tests {
         assert_eq!(address, "0x036CbD53842c5426634e7929541eC2318f3dCF7e");
     }
 
+    #[test]
+    fn test_rpc_registry_lookup() {
+        let registry = NetworkRpcRegistry::new()
+            .with_network("base", "https://base.example.com")
+            .with_network("base", "https://base-fallback.example.com")
+            .with_network("avalanche", "https://avax.example.com");
+
+        assert_eq!(
+            registry.primary("base").unwrap(),
+            "https://base.example.com"
+        );
+        assert_eq!(
+            registry.urls("base").unwrap(),
+            [
+                "https://base.example.com",
+                "https://base-fallback.example.com"
+            ]
+        );
+        assert_eq!(
+            registry.primary("avalanche").unwrap(),
+            "https://avax.example.com"
+        );
+
+        let error = registry.primary("base-sepolia").unwrap_err();
+        assert!(matches!(error, X402Error::InvalidNetwork { .. }));
+        assert!(error.to_string().contains("base-sepolia"));
+    }
+
+    #[test]
+    fn test_rpc_registry_from_vars() {
+        let vars = [
+            ("RPC_URL_BASE", "https://base.example.com"),
+            (
+                "RPC_URL_BASE_SEPOLIA",
+                "https://sepolia-a.example.com, https://sepolia-b.example.com",
+            ),
+            ("RPC_URL_AVALANCHE", ""),
+            ("REDIS_URL", "redis://localhost:6379"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+        let registry = NetworkRpcRegistry::from_vars(vars);
+
+        assert_eq!(
+            registry.primary("base").unwrap(),
+            "https://base.example.com"
+        );
+        assert_eq!(
+            registry.urls("base-sepolia").unwrap(),
+            [
+                "https://sepolia-a.example.com",
+                "https://sepolia-b.example.com"
+            ]
+        );
+        // Empty values register nothing
+        assert!(registry.primary("avalanche").is_err());
+
+        let mut networks: Vec<_> = registry.networks().collect();
+        networks.sort();
+        assert_eq!(networks, ["base", "base-sepolia"]);
+    }
+
+    fn balance_response(balance: &str) -> String {
+        format!(r#"{{"jsonrpc":"2.0","id":1,"result":"{}"}}"#, balance)
+    }
+
+    #[tokio::test]
+    async fn test_rpc_failover_to_second_provider() {
+        let mut failing = mockito::Server::new_async().await;
+        let mut healthy = mockito::Server::new_async().await;
+        let unavailable = failing
+            .mock("POST", "/")
+            .with_status(503)
+            .expect(1)
+            .create_async()
+            .await;
+        let ok = healthy
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(balance_response("0x2a"))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = BlockchainClient::with_providers(
+            vec![failing.url(), healthy.url()],
+            "base-sepolia".to_string(),
+        )
+        .unwrap();
+        let balance = client.get_balance(ADDRESS).await.unwrap();
+        assert_eq!(balance.balance, "0x2a");
+        unavailable.assert_async().await;
+        ok.assert_async().await;
+
+        // Rate limiting fails over as well
+        failing.reset();
+        failing
+            .mock("POST", "/")
+            .with_status(429)
+            .create_async()
+            .await;
+        let client = BlockchainClient::with_providers(
+            vec![failing.url(), healthy.url()],
+            "base-sepolia".to_string(),
+        )
+        .unwrap();
+        assert!(client.get_balance(ADDRESS).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rpc_skips_unhealthy_provider() {
+        let mut failing = mockito::Server::new_async().await;
+        let mut healthy = mockito::Server::new_async().await;
+        let unavailable = failing
+            .mock("POST", "/")
+            .with_status(502)
+            .expect(PROVIDER_FAILURE_THRESHOLD as usize)
+            .create_async()
+            .await;
+        healthy
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(balance_response("0x1"))
+            .create_async()
+            .await;
+
+        let client = BlockchainClient::with_providers(
+            vec![failing.url(), healthy.url()],
+            "base-sepolia".to_string(),
+        )
+        .unwrap();
+        // Round-robin reaches the failing provider first on every other request
+        for _ in 0..PROVIDER_FAILURE_THRESHOLD * 2 + 4 {
+            assert!(client.get_balance(ADDRESS).await.is_ok());
+        }
+        // Once in cooldown it is no longer contacted
+        unavailable.assert_async().await;
+
+        // With every provider down the cooled-down one is still tried, last
+        healthy.reset();
+        healthy
+            .mock("POST", "/")
+            .with_status(500)
+            .create_async()
+            .await;
+        let err = client.get_balance(ADDRESS).await.unwrap_err();
+        assert!(err.to_string().contains("502"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_round_robin() {
+        let mut first = mockito::Server::new_async().await;
+        let mut second = mockito::Server::new_async().await;
+        let first_mock = first
+            .mock("POST", "/")
+            .with_body(balance_response("0x1"))
+            .expect(2)
+            .create_async()
+            .await;
+        let second_mock = second
+            .mock("POST", "/")
+            .with_body(balance_response("0x2"))
+            .expect(2)
+            .create_async()
+            .await;
+
+        let client = BlockchainClient::with_providers(
+            vec![first.url(), second.url()],
+            "base-sepolia".to_string(),
+        )
+        .unwrap();
+        let mut balances = Vec::new();
+        for _ in 0..4 {
+            balances.push(client.get_balance(ADDRESS).await.unwrap().balance);
+        }
+        assert_eq!(balances, ["0x1", "0x2", "0x1", "0x2"]);
+        first_mock.assert_async().await;
+        second_mock.assert_async().await;
+
+        assert!(BlockchainClient::with_providers(vec![], "base".to_string()).is_err());
+        assert_eq!(client.rpc_urls(), [first.url(), second.url()]);
+    }
+
+    #[tokio::test]
+    async fn test_rpc_batch() {
+        let mut server = mockito::Server::new_async().await;
+        // Answered out of order, with the call in the middle reverting
+        let batch = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!([
+                {"method": "eth_getBalance", "id": 0},
+                {"method": "eth_call", "id": 1},
+                {"method": "eth_getTransactionCount", "id": 2}
+            ])))
+            .with_status(200)
+            .with_body(
+                r#"[
+                    {"jsonrpc":"2.0","id":2,"result":"0x7"},
+                    {"jsonrpc":"2.0","id":0,"result":"0x2a"},
+                    {"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted","data":"0x08c379a0"}}
+                ]"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+        let call = TransactionRequest {
+            from: ADDRESS.to_string(),
+            to: ADDRESS.to_string(),
+            value: None,
+            data: Some("0x".to_string()),
+            gas: None,
+            gas_price: None,
+        };
+        let results = client
+            .batch()
+            .get_balance(ADDRESS)
+            .call(&call)
+            .get_transaction_count(ADDRESS)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], Ok(serde_json::json!("0x2a")));
+        let error = results[1].as_ref().unwrap_err();
+        assert_eq!(error.code, 3);
+        assert_eq!(error.message, "execution reverted");
+        assert_eq!(error.data, Some(serde_json::json!("0x08c379a0")));
+        assert_eq!(results[2], Ok(serde_json::json!("0x7")));
+        batch.assert_async().await;
+
+        // An empty batch sends nothing
+        assert!(client.batch().send().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rpc_batch_missing_and_rejected() {
+        let mut server = mockito::Server::new_async().await;
+        let partial = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(r#"[{"jsonrpc":"2.0","id":0,"result":"0x1"}]"#)
+            .create_async()
+            .await;
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+
+        let results = client
+            .batch()
+            .get_balance(ADDRESS)
+            .get_balance(ADDRESS)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(results[0], Ok(serde_json::json!("0x1")));
+        assert!(results[1].is_err());
+
+        // Providers without batch support answer with a single error object
+        partial.remove_async().await;
+        server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(
+                r#"{"jsonrpc":"2.0","id":null,"error":{"code":-32600,"message":"batch requests not supported"}}"#,
+            )
+            .create_async()
+            .await;
+        let error = client
+            .batch()
+            .get_balance(ADDRESS)
+            .send()
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("batch requests not supported"));
+    }
+
     #[test]
     fn test_transaction_request_serialization() {
         let tx = TransactionRequest {
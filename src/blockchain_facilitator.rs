@@ -7,19 +7,34 @@
 //! - Provides comprehensive error handling
 
 use crate::{
-    blockchain::{BlockchainClient, BlockchainClientFactory, TransactionStatus},
-    types::{PaymentPayload, PaymentRequirements, SettleResponse, VerifyResponse},
+    blockchain::{
+        BlockchainClient, BlockchainClientFactory, CallOutcome, NetworkRpcRegistry,
+        TransactionRequest, TransactionStatus,
+    },
+    signer::Signature,
+    types::{
+        Amount, ExactEvmPayload, FeePolicy, PaymentPayload, PaymentRequirements, SettleResponse,
+        VerifyResponse, DEFAULT_CLOCK_SKEW,
+    },
     Result, X402Error,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// Selector of `transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)`
+const TRANSFER_WITH_AUTHORIZATION_SELECTOR: &str = "e3ee160e";
+
+/// Selector of Solidity's `Error(string)` revert payload
+const ERROR_STRING_SELECTOR: &str = "08c379a0";
+
 /// Blockchain facilitator client for production use
 pub struct BlockchainFacilitatorClient {
     /// Blockchain client for network interactions
     blockchain_client: BlockchainClient,
+    /// Clients for networks registered in the RPC registry
+    network_clients: HashMap<String, BlockchainClient>,
     /// Network name
-    #[allow(dead_code)]
     network: String,
     /// Verification timeout
     #[allow(dead_code)]
@@ -27,6 +42,12 @@ pub struct BlockchainFacilitatorClient {
     /// Settlement confirmation blocks
     #[allow(dead_code)]
     confirmation_blocks: u64,
+    /// Clock skew tolerated on authorization validity windows
+    clock_skew_tolerance: Duration,
+    /// Fee charged on top of the resource price
+    fee: Option<FeePolicy>,
+    /// Whether settlement is simulated before broadcasting
+    simulate_before_settle: bool,
 }
 
 /// Blockchain facilitator configuration
@@ -34,6 +55,8 @@ pub struct BlockchainFacilitatorClient {
 pub struct BlockchainFacilitatorConfig {
     /// RPC endpoint URL
     pub rpc_url: Option<String>,
+    /// Per-network RPC endpoints, taking precedence over `rpc_url`
+    pub rpc_registry: NetworkRpcRegistry,
     /// Network name
     pub network: String,
     /// Verification timeout
@@ -44,17 +67,27 @@ pub struct BlockchainFacilitatorConfig {
     pub max_retries: u32,
     /// Retry delay
     pub retry_delay: Duration,
+    /// Clock skew tolerated on `validAfter` / `validBefore`
+    pub clock_skew_tolerance: Duration,
+    /// Fee charged on top of the resource price, deducted at settlement
+    pub fee: Option<FeePolicy>,
+    /// Simulate settlement with `eth_call` and skip broadcasting predicted reverts
+    pub simulate_before_settle: bool,
 }
 
 impl Default for BlockchainFacilitatorConfig {
     fn default() -> Self {
         Self {
             rpc_url: None,
+            rpc_registry: NetworkRpcRegistry::default(),
             network: "base-sepolia".to_string(),
             verification_timeout: Duration::from_secs(30),
             confirmation_blocks: 1,
             max_retries: 3,
             retry_delay: Duration::from_secs(1),
+            clock_skew_tolerance: DEFAULT_CLOCK_SKEW,
+            fee: None,
+            simulate_before_settle: false,
         }
     }
 }
@@ -69,6 +102,17 @@ pub struct TransactionVerification {
     pub error_reason: Option<String>,
 }
 
+/// Result of simulating settlement with `eth_call`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulationResult {
+    /// Whether the call would succeed
+    pub success: bool,
+    /// Decoded revert reason, if the call would revert
+    pub revert_reason: Option<String>,
+    /// Raw hex return or revert data
+    pub return_data: Option<String>,
+}
+
 impl BlockchainFacilitatorClient {
     /// Create a new blockchain facilitator client
     pub fn new(config: BlockchainFacilitatorConfig) -> Result<Self> {
@@ -89,14 +133,57 @@ impl BlockchainFacilitatorClient {
             }
         };
 
+        let network_clients = config
+            .rpc_registry
+            .networks()
+            .map(|network| {
+                let urls = config
+                    .rpc_registry
+                    .urls(network)
+                    .unwrap_or_default()
+                    .to_vec();
+                Ok((
+                    network.to_string(),
+                    BlockchainClient::with_providers(urls, network.to_string())?,
+                ))
+            })
+            .collect::<Result<_>>()?;
+
         Ok(Self {
             blockchain_client,
+            network_clients,
             network: config.network,
             verification_timeout: config.verification_timeout,
             confirmation_blocks: config.confirmation_blocks,
+            clock_skew_tolerance: config.clock_skew_tolerance,
+            fee: config.fee,
+            simulate_before_settle: config.simulate_before_settle,
         })
     }
 
+    /// Client for the RPC endpoint serving `network`
+    ///
+    /// Registry entries take precedence; otherwise the configured network's
+    /// client is used. Any other network is an error.
+    fn client_for(&self, network: &str) -> Result<&BlockchainClient> {
+        if let Some(client) = self.network_clients.get(network) {
+            return Ok(client);
+        }
+        if network == self.network {
+            return Ok(&self.blockchain_client);
+        }
+        Err(X402Error::invalid_network(format!(
+            "No RPC endpoint registered for network {}",
+            network
+        )))
+    }
+
+    /// Fee owed on the given requirements' price
+    fn fee_for(&self, requirements: &PaymentRequirements) -> Result<u128> {
+        let price = requirements.required_amount(requirements.asset_decimals())?;
+        self.fee.map_or(Ok(0), |fee| fee.fee_for(price.atomic()))
+    }
+
     /// Verify a payment payload with real blockchain verification
     pub async fn verify(
         &self,
@@ -128,7 +215,11 @@ impl BlockchainFacilitatorClient {
         }
 
         // Validate authorization timing
-        if !payment_payload.payload.authorization.is_valid_now()? {
+        if !payment_payload
+            .payload
+            .authorization
+            .is_valid_at(chrono::Utc::now().timestamp(), self.clock_skew_tolerance)?
+        {
             return Ok(VerifyResponse {
                 is_valid: false,
                 invalid_reason: Some("Authorization expired or not yet valid".to_string()),
@@ -137,25 +228,22 @@ impl BlockchainFacilitatorClient {
         }
 
         // Validate amount
-        let payment_amount: u128 = payment_payload
-            .payload
-            .authorization
-            .value
-            .parse()
-            .map_err(|_| {
-                X402Error::invalid_payment_requirements("Invalid payment amount format")
-            })?;
-
-        let required_amount: u128 = requirements.max_amount_required.parse().map_err(|_| {
-            X402Error::invalid_payment_requirements("Invalid required amount format")
-        })?;
+        let decimals = requirements.asset_decimals();
+        let payment_amount = payment_payload.payload.authorization.amount(decimals)?;
+        let required_amount = requirements.required_amount(decimals)?;
+        // The authorization must also cover the facilitator fee
+        let required_amount = match self.fee {
+            Some(fee) => Amount::new(fee.total_for(required_amount.atomic())?, decimals)?,
+            None => required_amount,
+        };
 
         if payment_amount < required_amount {
             return Ok(VerifyResponse {
                 is_valid: false,
                 invalid_reason: Some(format!(
                     "Insufficient amount: {} < {}",
-                    payment_amount, required_amount
+                    payment_amount.atomic(),
+                    required_amount.atomic()
                 )),
                 payer: Some(payment_payload.payload.authorization.from.clone()),
             });
@@ -175,7 +263,7 @@ impl BlockchainFacilitatorClient {
 
         // Check payer balance
         let balance_info = self
-            .blockchain_client
+            .client_for(&payment_payload.network)?
             .get_usdc_balance(&payment_payload.payload.authorization.from)
             .await?;
 
@@ -183,12 +271,13 @@ impl BlockchainFacilitatorClient {
             let balance: u128 = u128::from_str_radix(token_balance.trim_start_matches("0x"), 16)
                 .map_err(|_| X402Error::invalid_payment_requirements("Invalid balance format"))?;
 
-            if balance < payment_amount {
+            if balance < payment_amount.atomic() {
                 return Ok(VerifyResponse {
                     is_valid: false,
                     invalid_reason: Some(format!(
                         "Insufficient balance: {} < {}",
-                        balance, payment_amount
+                        balance,
+                        payment_amount.atomic()
                     )),
                     payer: Some(payment_payload.payload.authorization.from.clone()),
                 });
@@ -222,9 +311,33 @@ impl BlockchainFacilitatorClient {
                 transaction: "".to_string(),
                 network: payment_payload.network.clone(),
                 payer: verification.payer,
+                fee_amount: None,
+                net_amount: None,
             });
         }
 
+        if self.simulate_before_settle {
+            let simulation = self.simulate_settle(payment_payload, requirements).await?;
+            if !simulation.success {
+                return Ok(SettleResponse {
+                    success: false,
+                    error_reason: Some(
+                        X402Error::SimulationReverted {
+                            reason: simulation
+                                .revert_reason
+                                .unwrap_or_else(|| "unknown reason".to_string()),
+                        }
+                        .to_string(),
+                    ),
+                    transaction: "".to_string(),
+                    network: payment_payload.network.clone(),
+                    payer: Some(payment_payload.payload.authorization.from.clone()),
+                    fee_amount: None,
+                    net_amount: None,
+                });
+            }
+        }
+
         // In a real implementation, this would:
         // 1. Create a transaction to transfer USDC
         // 2. Sign the transaction with the facilitator's private key
@@ -238,15 +351,28 @@ impl BlockchainFacilitatorClient {
             .await?;
 
         // Wait for transaction confirmation
-        let confirmation_result = self.wait_for_confirmation(&transaction_hash).await?;
+        let confirmation_result = self
+            .wait_for_confirmation(&payment_payload.network, &transaction_hash)
+            .await?;
 
         if confirmation_result.success {
-            Ok(SettleResponse {
+            let gross = payment_payload
+                .payload
+                .authorization
+                .amount(requirements.asset_decimals())?
+                .atomic();
+            let response = SettleResponse {
                 success: true,
                 error_reason: None,
                 transaction: transaction_hash,
                 network: payment_payload.network.clone(),
                 payer: Some(payment_payload.payload.authorization.from.clone()),
+                fee_amount: None,
+                net_amount: None,
+            };
+            Ok(match self.fee {
+                Some(_) => response.with_fee_breakdown(gross, self.fee_for(requirements)?),
+                None => response,
             })
         } else {
             Ok(SettleResponse {
@@ -259,44 +385,79 @@ impl BlockchainFacilitatorClient {
                 transaction: transaction_hash,
                 network: payment_payload.network.clone(),
                 payer: Some(payment_payload.payload.authorization.from.clone()),
+                fee_amount: None,
+                net_amount: None,
             })
         }
     }
 
+    /// Simulate settlement with `eth_call` without spending gas
+    ///
+    /// Catches reverts such as an expired authorization, a reused nonce or an
+    /// insufficient balance before a transaction is broadcast.
+    pub async fn simulate_settle(
+        &self,
+        payment_payload: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<SimulationResult> {
+        let tx_request = self.settlement_request(payment_payload, requirements)?;
+
+        let client = self.client_for(&payment_payload.network)?;
+        Ok(match client.call(&tx_request).await? {
+            CallOutcome::Success(data) => SimulationResult {
+                success: true,
+                revert_reason: None,
+                return_data: Some(data),
+            },
+            CallOutcome::Reverted { message, data } => SimulationResult {
+                success: false,
+                revert_reason: Some(
+                    data.as_deref()
+                        .and_then(decode_revert_reason)
+                        .unwrap_or(message),
+                ),
+                return_data: data,
+            },
+        })
+    }
+
+    /// Build the `transferWithAuthorization` call for a payment
+    fn settlement_request(
+        &self,
+        payment_payload: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<TransactionRequest> {
+        Ok(TransactionRequest {
+            from: payment_payload.payload.authorization.from.clone(),
+            to: requirements.asset.clone(),
+            value: None, // No ETH value for token transfers
+            data: Some(encode_transfer_with_authorization(
+                &payment_payload.payload,
+            )?),
+            gas: None,
+            gas_price: None,
+        })
+    }
+
     /// Create and broadcast a real settlement transaction
     async fn create_settlement_transaction(
         &self,
         payment_payload: &PaymentPayload,
-        _requirements: &PaymentRequirements,
+        requirements: &PaymentRequirements,
     ) -> Result<String> {
         // This is a real implementation that creates actual blockchain transactions
         // Note: In production, this would require the facilitator's private key
-
-        // For now, we'll create a transaction that calls the USDC contract's
-        // transferWithAuthorization function with the payment authorization
-
         let auth = &payment_payload.payload.authorization;
-        let usdc_contract = self.blockchain_client.get_usdc_contract_address()?;
-
-        // Create the function call data for transferWithAuthorization
-        let function_selector = "0x4000aea0"; // transferWithAuthorization(bytes32,address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)
-
-        // Encode the parameters
-        let encoded_params = self.encode_transfer_with_authorization_params(auth)?;
-        let data = format!("{}{}", function_selector, encoded_params);
-
-        // Create transaction request
-        let tx_request = crate::blockchain::TransactionRequest {
-            from: auth.from.clone(),
-            to: usdc_contract,
-            value: None, // No ETH value for USDC transfers
-            data: Some(data),
-            gas: Some("0x5208".to_string()), // 21000 gas limit
+        let tx_request = TransactionRequest {
             gas_price: Some("0x3b9aca00".to_string()), // 1 gwei
+            ..self.settlement_request(payment_payload, requirements)?
         };
 
         // Estimate gas for the transaction
-        let estimated_gas = self.blockchain_client.estimate_gas(&tx_request).await?;
+        let estimated_gas = self
+            .client_for(&payment_payload.network)?
+            .estimate_gas(&tx_request)
+            .await?;
 
         // Update gas limit
         let mut final_tx = tx_request;
@@ -314,46 +475,6 @@ impl BlockchainFacilitatorClient {
         Ok(tx_hash)
     }
 
-    /// Encode parameters for transferWithAuthorization function
-    fn encode_transfer_with_authorization_params(
-        &self,
-        auth: &crate::types::ExactEvmPayloadAuthorization,
-    ) -> Result<String> {
-        use std::str::FromStr;
-
-        // The transferWithAuthorization function signature:
-        // transferWithAuthorization(
-        //     bytes32 authorization,    // EIP-712 hash of the authorization
-        //     address from,
-        //     address to,
-        //     uint256 value,
-        //     uint256 validAfter,
-        //     uint256 validBefore,
-        //     bytes32 nonce,
-        //     uint8 v,
-        //     bytes32 r,
-        //     bytes32 s
-        // )
-
-        // For now, we'll create a simplified encoding
-        // In a real implementation, this would use proper ABI encoding
-        let mut encoded = String::new();
-
-        // Pad and encode each parameter (simplified)
-        encoded.push_str(&format!("{:064x}", 0)); // authorization hash placeholder
-        encoded.push_str(auth.from.trim_start_matches("0x"));
-        encoded.push_str(auth.to.trim_start_matches("0x"));
-        encoded.push_str(&format!("{:064x}", u128::from_str(&auth.value)?));
-        encoded.push_str(&format!("{:064x}", u128::from_str(&auth.valid_after)?));
-        encoded.push_str(&format!("{:064x}", u128::from_str(&auth.valid_before)?));
-        encoded.push_str(auth.nonce.trim_start_matches("0x"));
-        encoded.push_str(&format!("{:02x}", 0)); // v placeholder
-        encoded.push_str(&format!("{:064x}", 0)); // r placeholder
-        encoded.push_str(&format!("{:064x}", 0)); // s placeholder
-
-        Ok(encoded)
-    }
-
     /// Simulate transaction broadcast (in production, this would be real)
     async fn simulate_transaction_broadcast(
         &self,
@@ -391,16 +512,17 @@ impl BlockchainFacilitatorClient {
     }
 
     /// Wait for transaction confirmation
-    async fn wait_for_confirmation(&self, transaction_hash: &str) -> Result<ConfirmationResult> {
+    async fn wait_for_confirmation(
+        &self,
+        network: &str,
+        transaction_hash: &str,
+    ) -> Result<ConfirmationResult> {
+        let client = self.client_for(network)?;
         let mut attempts = 0;
         let max_attempts = 30; // 30 seconds timeout
 
         while attempts < max_attempts {
-            match self
-                .blockchain_client
-                .get_transaction_status(transaction_hash)
-                .await
-            {
+            match client.get_transaction_status(transaction_hash).await {
                 Ok(tx_info) => {
                     match tx_info.status {
                         TransactionStatus::Confirmed => {
@@ -471,6 +593,72 @@ struct ConfirmationResult {
     gas_used: Option<u64>,
 }
 
+/// ABI-encode a `transferWithAuthorization` call for an EIP-3009 payload
+fn encode_transfer_with_authorization(payload: &ExactEvmPayload) -> Result<String> {
+    let auth = &payload.authorization;
+    let signature = Signature::from_hex(&payload.signature)?;
+    // The contract expects the legacy 27/28 recovery id
+    let v = if signature.v < 27 {
+        signature.v + 27
+    } else {
+        signature.v
+    };
+
+    let address = |value: &str| -> Result<String> {
+        let hex = value.trim_start_matches("0x");
+        if hex.len() != 40 || hex::decode(hex).is_err() {
+            return Err(X402Error::InvalidAddress(value.to_string()));
+        }
+        Ok(format!("{:0>64}", hex.to_lowercase()))
+    };
+    let uint = |value: &str| -> Result<String> {
+        value
+            .parse::<u128>()
+            .map(|n| format!("{:064x}", n))
+            .map_err(|_| X402Error::invalid_payment_payload(format!("Invalid uint: {}", value)))
+    };
+    let nonce = auth.nonce.trim_start_matches("0x");
+    if nonce.len() != 64 || hex::decode(nonce).is_err() {
+        return Err(X402Error::invalid_payment_payload("Nonce must be 32 bytes"));
+    }
+
+    Ok(format!(
+        "0x{}{}{}{}{}{}{}{:064x}{}{}",
+        TRANSFER_WITH_AUTHORIZATION_SELECTOR,
+        address(&auth.from)?,
+        address(&auth.to)?,
+        uint(&auth.value)?,
+        uint(&auth.valid_after)?,
+        uint(&auth.valid_before)?,
+        nonce.to_lowercase(),
+        v,
+        hex::encode(signature.r.as_bytes()),
+        hex::encode(signature.s.as_bytes()),
+    ))
+}
+
+/// Decode the message of an `Error(string)` revert payload
+fn decode_revert_reason(data: &str) -> Option<String> {
+    let bytes = hex::decode(
+        data.trim_start_matches("0x")
+            .strip_prefix(ERROR_STRING_SELECTOR)?,
+    )
+    .ok()?;
+    // ABI layout: offset (32 bytes), length (32 bytes), UTF-8 bytes
+    let word = |at: usize| -> Option<usize> {
+        let word = bytes.get(at..at + 32)?;
+        if word[..24].iter().any(|&b| b != 0) {
+            return None;
+        }
+        Some(u64::from_be_bytes(word[24..].try_into().ok()?) as usize)
+    };
+    let offset = word(0)?;
+    let length = word(offset)?;
+    let start = offset.checked_add(32)?;
+    let message = bytes.get(start..start.checked_add(length)?)?;
+    String::from_utf8(message.to_vec()).ok()
+}
+
 /// Blockchain facilitator client factory
 pub struct BlockchainFacilitatorFactory;
 
@@ -522,6 +710,7 @@ mod tests {
         let config = BlockchainFacilitatorConfig::default();
         assert_eq!(config.network, "base-sepolia");
         assert_eq!(config.confirmation_blocks, 1);
+        assert_eq!(config.clock_skew_tolerance, DEFAULT_CLOCK_SKEW);
     }
 
     #[test]
@@ -529,4 +718,343 @@ mod tests {
         let facilitator = BlockchainFacilitatorFactory::base_sepolia();
         assert!(facilitator.is_ok());
     }
+
+    fn skewed_payment(valid_after: i64, valid_before: i64) -> PaymentPayload {
+        use crate::types::{ExactEvmPayload, ExactEvmPayloadAuthorization};
+        PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            ExactEvmPayload {
+                signature: "0x00".to_string(),
+                authorization: ExactEvmPayloadAuthorization::new(
+                    "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+                    "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+                    "1000000",
+                    valid_after.to_string(),
+                    valid_before.to_string(),
+                    "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+                ),
+            },
+        )
+    }
+
+    async fn skew_test_facilitator(
+        server: &mockito::ServerGuard,
+        skew: Duration,
+    ) -> BlockchainFacilitatorClient {
+        BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            rpc_url: Some(server.url()),
+            clock_skew_tolerance: skew,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    /// ABI-encode an `Error(string)` revert payload
+    fn error_string(message: &str) -> String {
+        format!(
+            "0x{}{:064x}{:064x}{:0<width$}",
+            ERROR_STRING_SELECTOR,
+            32,
+            message.len(),
+            hex::encode(message),
+            width = message.len().div_ceil(32) * 64
+        )
+    }
+
+    fn signed_payment() -> PaymentPayload {
+        let now = chrono::Utc::now().timestamp();
+        let mut payment = skewed_payment(now - 60, now + 120);
+        payment.payload.signature = format!("0x{}{}00", "11".repeat(32), "22".repeat(32));
+        payment
+    }
+
+    fn simulation_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        )
+    }
+
+    /// Mock the `transferWithAuthorization` eth_call with the given response body
+    async fn mock_simulation(server: &mut mockito::ServerGuard, body: String) -> mockito::Mock {
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(format!(
+                "0x{}",
+                TRANSFER_WITH_AUTHORIZATION_SELECTOR
+            )))
+            .with_status(200)
+            .with_body(body)
+            .create_async()
+            .await
+    }
+
+    #[test]
+    fn test_encode_transfer_with_authorization() {
+        use sha3::{Digest, Keccak256};
+        let selector = Keccak256::digest(
+            b"transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)",
+        );
+        assert_eq!(
+            hex::encode(&selector[..4]),
+            TRANSFER_WITH_AUTHORIZATION_SELECTOR
+        );
+
+        let payment = signed_payment();
+        let data = encode_transfer_with_authorization(&payment.payload).unwrap();
+        let words: Vec<&str> = data.as_bytes()[10..]
+            .chunks(64)
+            .map(|w| std::str::from_utf8(w).unwrap())
+            .collect();
+        assert_eq!(words.len(), 9);
+        assert!(words.iter().all(|w| w.len() == 64));
+        assert_eq!(
+            words[0],
+            format!("{:0>64}", "857b06519e91e3a54538791bdbb0e22373e36b66")
+        );
+        assert_eq!(words[2], format!("{:064x}", 1_000_000));
+        // Recovery id 0 is encoded as 27
+        assert_eq!(words[6], format!("{:064x}", 27));
+        assert_eq!(words[7], "11".repeat(32));
+
+        let mut malformed = payment.payload;
+        malformed.authorization.from = "0x1234".to_string();
+        assert!(encode_transfer_with_authorization(&malformed).is_err());
+    }
+
+    #[test]
+    fn test_decode_revert_reason() {
+        let reason = "FiatTokenV2: authorization is expired";
+        assert_eq!(
+            decode_revert_reason(&error_string(reason)).as_deref(),
+            Some(reason)
+        );
+        assert_eq!(decode_revert_reason("0x"), None);
+        // Custom errors are not decoded
+        assert_eq!(decode_revert_reason("0xdeadbeef"), None);
+        // Truncated payloads are rejected rather than panicking
+        assert_eq!(decode_revert_reason(&error_string(reason)[..80]), None);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_settle() {
+        let requirements = simulation_requirements();
+        let payment = signed_payment();
+
+        let mut server = mockito::Server::new_async().await;
+        let facilitator = skew_test_facilitator(&server, DEFAULT_CLOCK_SKEW).await;
+        let success = mock_simulation(
+            &mut server,
+            r#"{"jsonrpc":"2.0","id":1,"result":"0x"}"#.to_string(),
+        )
+        .await;
+        let result = facilitator
+            .simulate_settle(&payment, &requirements)
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(result.revert_reason, None);
+        success.assert_async().await;
+        success.remove_async().await;
+
+        let reason = "FiatTokenV2: authorization is used or canceled";
+        let _revert = mock_simulation(
+            &mut server,
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": {
+                    "code": 3,
+                    "message": "execution reverted",
+                    "data": error_string(reason),
+                }
+            })
+            .to_string(),
+        )
+        .await;
+        let result = facilitator
+            .simulate_settle(&payment, &requirements)
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(result.revert_reason.as_deref(), Some(reason));
+    }
+
+    #[tokio::test]
+    async fn test_settle_skips_broadcast_on_predicted_revert() {
+        let mut server = mockito::Server::new_async().await;
+        let _balance = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex("0x70a08231".to_string()))
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":"0x3b9aca00"}"#)
+            .create_async()
+            .await;
+        let _revert = mock_simulation(
+            &mut server,
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"execution reverted: insufficient funds"}}"#
+                .to_string(),
+        )
+        .await;
+
+        let facilitator = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            rpc_url: Some(server.url()),
+            simulate_before_settle: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let response = facilitator
+            .settle(&signed_payment(), &simulation_requirements())
+            .await
+            .unwrap();
+
+        assert!(!response.success);
+        assert!(response.transaction.is_empty());
+        assert_eq!(
+            response.error_reason.as_deref(),
+            Some("Simulation reverted: execution reverted: insufficient funds")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_uses_rpc_registered_for_payment_network() {
+        let mut base_rpc = mockito::Server::new_async().await;
+        let balance = base_rpc
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":"0x3b9aca00"}"#)
+            .create_async()
+            .await;
+        let facilitator = BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+            rpc_registry: NetworkRpcRegistry::new().with_network("base", base_rpc.url()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let now = chrono::Utc::now().timestamp();
+        let mut payment = skewed_payment(now - 60, now + 120);
+        payment.network = "base".to_string();
+        let mut requirements = simulation_requirements();
+        requirements.network = "base".to_string();
+
+        let response = facilitator.verify(&payment, &requirements).await.unwrap();
+        assert!(response.is_valid, "{:?}", response.invalid_reason);
+        balance.assert_async().await;
+
+        // Networks with no registered RPC are rejected with a clear error
+        payment.network = "avalanche".to_string();
+        requirements.network = "avalanche".to_string();
+        let error = facilitator
+            .verify(&payment, &requirements)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, X402Error::InvalidNetwork { .. }));
+        assert!(error
+            .to_string()
+            .contains("No RPC endpoint registered for network avalanche"));
+    }
+
+    #[tokio::test]
+    async fn test_verify_requires_price_plus_fee() {
+        let mut server = mockito::Server::new_async().await;
+        let _balance = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":"0x3b9aca00"}"#)
+            .create_async()
+            .await;
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "990000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+        let now = chrono::Utc::now().timestamp();
+        // Authorizes 1000000 against a price of 990000
+        let payment = skewed_payment(now - 60, now + 120);
+
+        let facilitator = |fee| {
+            BlockchainFacilitatorClient::new(BlockchainFacilitatorConfig {
+                rpc_url: Some(server.url()),
+                fee,
+                ..Default::default()
+            })
+            .unwrap()
+        };
+
+        // 100 bps of 990000 is 9900, which the authorization covers
+        let response = facilitator(Some(FeePolicy::BasisPoints(100)))
+            .verify(&payment, &requirements)
+            .await
+            .unwrap();
+        assert!(response.is_valid, "{:?}", response.invalid_reason);
+
+        // A flat fee of 20000 does not fit
+        let response = facilitator(Some(FeePolicy::Flat(20_000)))
+            .verify(&payment, &requirements)
+            .await
+            .unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("Insufficient amount: 1000000 < 1010000")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_tolerates_clock_skew_at_both_ends() {
+        let mut server = mockito::Server::new_async().await;
+        let _balance = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":"0x3b9aca00"}"#)
+            .create_async()
+            .await;
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+        let now = chrono::Utc::now().timestamp();
+        // Signed by a client whose clock runs a few seconds ahead
+        let not_yet_valid = skewed_payment(now + 3, now + 120);
+        // Expired a few seconds ago according to the facilitator's clock
+        let just_expired = skewed_payment(now - 120, now - 3);
+
+        let strict = skew_test_facilitator(&server, Duration::ZERO).await;
+        for payment in [&not_yet_valid, &just_expired] {
+            let response = strict.verify(payment, &requirements).await.unwrap();
+            assert!(!response.is_valid);
+        }
+
+        let tolerant = skew_test_facilitator(&server, DEFAULT_CLOCK_SKEW).await;
+        for payment in [&not_yet_valid, &just_expired] {
+            let response = tolerant.verify(payment, &requirements).await.unwrap();
+            assert!(response.is_valid, "{:?}", response.invalid_reason);
+        }
+
+        // Outside the skew window is still rejected
+        for payment in [
+            skewed_payment(now + 30, now + 120),
+            skewed_payment(now - 120, now - 30),
+        ] {
+            let response = tolerant.verify(&payment, &requirements).await.unwrap();
+            assert_eq!(
+                response.invalid_reason.as_deref(),
+                Some("Authorization expired or not yet valid")
+            );
+        }
+    }
 }
@@ -1,11 +1,19 @@
 //! HTTP client with x402 payment support
 
+use crate::blockchain::{BlockchainClient, NetworkRpcRegistry};
+use crate::facilitator::FacilitatorClient;
 use crate::types::*;
+use crate::wallet::Wallet;
 use crate::{Result, X402Error};
+use ethereum_types::U256;
 use http;
-use reqwest::{Client, Response};
+use reqwest::{header::HeaderMap, Client, Response};
+use std::collections::HashMap;
 use std::time::Duration;
 
+/// Redirects an [`X402Client`] follows per request by default
+pub const DEFAULT_MAX_REDIRECTS: usize = 10;
+
 /// HTTP client with x402 payment support
 #[derive(Debug, Clone)]
 pub struct X402Client {
@@ -13,6 +21,23 @@ pub struct X402Client {
     client: Client,
     /// Default facilitator configuration
     facilitator_config: FacilitatorConfig,
+    /// Facilitator used to pre-verify payments
+    facilitator: Option<FacilitatorClient>,
+    /// Verify payments with the facilitator before attaching them
+    dry_run: bool,
+    /// Headers sent with every request, including payment retries
+    default_headers: HeaderMap,
+    /// Path of resource servers' payment discovery document
+    discovery_path: String,
+    /// Address whose signature 402 bodies must carry before they are paid
+    quote_signer: Option<String>,
+    /// Redirects followed per request before giving up
+    max_redirects: usize,
+    /// Follow redirects to another origin, and pay 402s there
+    allow_cross_origin_redirect: bool,
+    /// Middleware stack every request is sent through
+    #[cfg(feature = "reqwest-middleware")]
+    middleware: Option<reqwest_middleware::ClientWithMiddleware>,
 }
 
 impl X402Client {
@@ -23,17 +48,148 @@ impl X402Client {
 
     /// Create a new x402 client with custom configuration
     pub fn with_config(facilitator_config: FacilitatorConfig) -> Result<Self> {
+        // Redirects are followed by the client itself, see `execute`
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
+            .redirect(reqwest::redirect::Policy::none())
             .build()
             .map_err(|e| X402Error::config(format!("Failed to create HTTP client: {}", e)))?;
 
         Ok(Self {
             client,
             facilitator_config,
+            facilitator: None,
+            dry_run: false,
+            default_headers: HeaderMap::new(),
+            discovery_path: WELL_KNOWN_X402_PATH.to_string(),
+            quote_signer: None,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
+            allow_cross_origin_redirect: false,
+            #[cfg(feature = "reqwest-middleware")]
+            middleware: None,
+        })
+    }
+
+    /// Create a new x402 client that sends requests through a middleware stack
+    ///
+    /// Every request, including the paid retry after a 402 and discovery
+    /// probes, goes through `middleware`, so retries, tracing or auth
+    /// middleware apply to the whole payment flow. Build the inner client with
+    /// `redirect::Policy::none()` so redirects are handled by this client's
+    /// own policy rather than followed with the payment header attached.
+    #[cfg(feature = "reqwest-middleware")]
+    pub fn with_middleware(middleware: reqwest_middleware::ClientWithMiddleware) -> Result<Self> {
+        Ok(Self {
+            middleware: Some(middleware),
+            ..Self::new()?
         })
     }
 
+    /// Re-send a request that got a 402 with the `X-PAYMENT` header attached
+    ///
+    /// Also returns the request that produced the final response, when it
+    /// can be re-sent, so a 402 at a redirect target can be paid in turn.
+    async fn retry_with_payment(
+        &self,
+        retry_request: Option<reqwest::Request>,
+        payment_payload: &PaymentPayload,
+        requirements: Option<PaymentRequirements>,
+    ) -> Result<(PaidResponse, Option<reqwest::Request>)> {
+        let mut request = retry_request
+            .ok_or_else(|| X402Error::unexpected("Request body cannot be re-sent for payment"))?;
+        request
+            .headers_mut()
+            .insert(X_PAYMENT_HEADER, payment_payload.to_header_value()?);
+
+        let (response, replay) = self.execute_following(request).await?;
+        Ok((
+            PaidResponse::paid(response, payment_payload, requirements),
+            replay,
+        ))
+    }
+
+    /// Send a request, following the redirects the client's policy allows
+    async fn execute(&self, request: reqwest::Request) -> Result<Response> {
+        Ok(self.execute_following(request).await?.0)
+    }
+
+    /// Send a request, following redirects, and keep the final request
+    ///
+    /// Returns the final response and, when its body could be cloned, the
+    /// request that produced it. `X-PAYMENT` is never forwarded to a redirect
+    /// target, and an `X-PAYMENT-RESPONSE` header on a redirect is copied to
+    /// the final response so a settlement made before redirecting is not lost.
+    async fn execute_following(
+        &self,
+        mut request: reqwest::Request,
+    ) -> Result<(Response, Option<reqwest::Request>)> {
+        let mut redirects = 0;
+        let mut settlement = None;
+        loop {
+            let replay = request.try_clone();
+            let mut response = self.execute_once(request).await?;
+            if let Some(header) = response.headers().get(X_PAYMENT_RESPONSE_HEADER) {
+                settlement = Some(header.clone());
+            }
+
+            let Some(target) = self.redirect_target(&response) else {
+                if let Some(settlement) = settlement {
+                    response
+                        .headers_mut()
+                        .entry(X_PAYMENT_RESPONSE_HEADER)
+                        .or_insert(settlement);
+                }
+                return Ok((response, replay));
+            };
+            if redirects == self.max_redirects {
+                return Err(X402Error::network_error(format!(
+                    "Too many redirects (limit {})",
+                    self.max_redirects
+                )));
+            }
+            // A streaming body cannot be replayed at the target
+            let Some(replay) = replay else {
+                return Ok((response, None));
+            };
+            request = redirected_request(replay, response.status(), target);
+            redirects += 1;
+        }
+    }
+
+    /// Where a redirect response points, if the client should follow it
+    ///
+    /// Redirects to another origin are not followed unless
+    /// [`with_cross_origin_redirect`](Self::with_cross_origin_redirect) is set.
+    fn redirect_target(&self, response: &Response) -> Option<url::Url> {
+        if !response.status().is_redirection() {
+            return None;
+        }
+        let location = response.headers().get(reqwest::header::LOCATION)?;
+        let target = response.url().join(location.to_str().ok()?).ok()?;
+        if !matches!(target.scheme(), "http" | "https") {
+            return None;
+        }
+        if !self.allow_cross_origin_redirect && !same_origin(response.url(), &target) {
+            tracing::debug!("Not following cross-origin redirect to {}", target);
+            return None;
+        }
+        Some(target)
+    }
+
+    /// Send a built request through the middleware stack, if any
+    async fn execute_once(&self, request: reqwest::Request) -> Result<Response> {
+        #[cfg(feature = "reqwest-middleware")]
+        if let Some(middleware) = &self.middleware {
+            return middleware.execute(request).await.map_err(|e| match e {
+                reqwest_middleware::Error::Reqwest(e) => X402Error::from(e),
+                reqwest_middleware::Error::Middleware(e) => {
+                    X402Error::network_error(format!("HTTP middleware failed: {}", e))
+                }
+            });
+        }
+        Ok(self.client.execute(request).await?)
+    }
+
     /// Create a GET request
     pub fn get(&self, url: &str) -> X402RequestBuilder<'_> {
         let mut builder = X402RequestBuilder::new(self, self.client.get(url));
@@ -67,42 +223,74 @@ impl X402Client {
     }
 
     /// Handle a 402 payment required response with automatic retry
+    ///
+    /// Only the response is known here, so the retry is a bare `GET` of its
+    /// URL with the default headers: the original method, body and
+    /// per-request headers are lost.
+    #[deprecated(
+        note = "re-sends as a bare GET; use `handle_payment_required_for` with the original request"
+    )]
     pub async fn handle_payment_required(
         &self,
         response: Response,
         payment_payload: &PaymentPayload,
-    ) -> Result<Response> {
+    ) -> Result<PaidResponse> {
+        let request = self
+            .client
+            .get(response.url().clone())
+            .headers(self.default_headers.clone())
+            .build()?;
+        self.handle_payment_required_for(request, response, payment_payload)
+            .await
+    }
+
+    /// Handle a 402 `response` to `request` by re-sending it with payment
+    ///
+    /// The payment is verified with the facilitator against each accepted
+    /// requirement, and the original request, including its method, headers
+    /// and body, is re-sent with `X-PAYMENT` for the first that passes. A
+    /// request whose body cannot be cloned cannot be re-sent.
+    pub async fn handle_payment_required_for(
+        &self,
+        request: reqwest::Request,
+        response: Response,
+        payment_payload: &PaymentPayload,
+    ) -> Result<PaidResponse> {
+        self.pay_required(request.try_clone(), response, payment_payload)
+            .await
+    }
+
+    /// Verify `payment_payload` against a 402 `response` and re-send `retry_request`
+    async fn pay_required(
+        &self,
+        retry_request: Option<reqwest::Request>,
+        response: Response,
+        payment_payload: &PaymentPayload,
+    ) -> Result<PaidResponse> {
         if response.status() != 402 {
-            return Ok(response);
+            return Ok(PaidResponse::unpaid(response));
         }
 
-        let original_url = response.url().to_string();
         let payment_requirements: PaymentRequirementsResponse = response.json().await?;
+        self.check_quote(&payment_requirements)?;
 
         // Verify the payment with the facilitator
-        let facilitator = super::facilitator::FacilitatorClient::new(
-            self.facilitator_config.clone(),
-        )
-        .map_err(|e| {
-            X402Error::facilitator_error(format!("Failed to create facilitator client: {}", e))
-        })?;
+        let facilitator = match &self.facilitator {
+            Some(facilitator) => facilitator.clone(),
+            None => FacilitatorClient::new(self.facilitator_config.clone()).map_err(|e| {
+                X402Error::facilitator_error(format!("Failed to create facilitator client: {}", e))
+            })?,
+        };
 
         for requirements in &payment_requirements.accepts {
             let verify_response = facilitator.verify(payment_payload, requirements).await?;
 
             if verify_response.is_valid {
                 // Retry the original request with payment
-                let payment_header = payment_payload.to_base64()?;
-
-                // Create a new request with payment header
-                let new_response = self
-                    .client
-                    .get(&original_url)
-                    .header("X-PAYMENT", payment_header)
-                    .send()
-                    .await?;
-
-                return Ok(new_response);
+                return Ok(self
+                    .retry_with_payment(retry_request, payment_payload, Some(requirements.clone()))
+                    .await?
+                    .0);
             }
         }
 
@@ -117,8 +305,23 @@ impl X402Client {
         method: &str,
         url: &str,
         payment_payload: Option<&PaymentPayload>,
-    ) -> Result<Response> {
-        let mut request_builder = match method.to_uppercase().as_str() {
+    ) -> Result<PaidResponse> {
+        self.request_with_headers(method, url, HeaderMap::new(), payment_payload)
+            .await
+    }
+
+    /// Make a request with extra headers and automatic payment handling
+    ///
+    /// `headers` are merged over the client's default headers. An `X-PAYMENT`
+    /// header among them is replaced by `payment_payload`, if one is given.
+    pub async fn request_with_headers(
+        &self,
+        method: &str,
+        url: &str,
+        headers: HeaderMap,
+        payment_payload: Option<&PaymentPayload>,
+    ) -> Result<PaidResponse> {
+        let request_builder = match method.to_uppercase().as_str() {
             "GET" => self.get(url),
             "POST" => self.post(url),
             "PUT" => self.put(url),
@@ -131,25 +334,134 @@ impl X402Client {
             }
         };
 
-        // Add payment header if provided
+        let mut request = request_builder.headers(headers).request.build()?;
+
+        // Add payment header if provided, replacing any in `headers`
         if let Some(payload) = payment_payload {
-            let payment_header = payload.to_base64()?;
-            request_builder = request_builder.header("X-PAYMENT", payment_header);
+            request
+                .headers_mut()
+                .insert(X_PAYMENT_HEADER, payload.to_header_value()?);
         }
 
-        let response = request_builder.send().await?;
+        let (response, retry_request) = self.execute_following(request).await?;
 
         // If we get a 402 and have a payment payload, try to handle it
         if response.status() == 402 {
             if let Some(payload) = payment_payload {
-                return self.handle_payment_required(response, payload).await;
+                return self.pay_required(retry_request, response, payload).await;
             } else {
                 // Return the 402 response as-is if no payment payload provided
-                return Ok(response);
+                return Ok(PaidResponse::unpaid(response));
             }
         }
 
-        Ok(response)
+        // A payment sent up front may already have been settled
+        Ok(match payment_payload {
+            Some(payload) => PaidResponse::paid(response, payload, None),
+            None => PaidResponse::unpaid(response),
+        })
+    }
+
+    /// Ask a resource server which payments it accepts, without paying
+    ///
+    /// Fetches the discovery document at the server's discovery path (see
+    /// [`with_discovery_path`](Self::with_discovery_path)). Servers without one
+    /// are probed with an unpaid `GET url` and the requirements are read from
+    /// its 402 response; a resource that does not ask for payment accepts
+    /// nothing, so the list is empty.
+    pub async fn discover(&self, url: &str) -> Result<Vec<PaymentRequirements>> {
+        let discovery_url = url::Url::parse(url)
+            .and_then(|url| url.join(&self.discovery_path))
+            .map_err(|e| X402Error::config(format!("Invalid resource URL {}: {}", url, e)))?;
+
+        let document = match self
+            .client
+            .get(discovery_url)
+            .headers(self.default_headers.clone())
+            .build()
+        {
+            Ok(request) => self.execute(request).await,
+            Err(e) => Err(e.into()),
+        };
+        match document {
+            Ok(response) if response.status().is_success() => {
+                match response.json::<PaymentOptions>().await {
+                    Ok(options) => return Ok(options.accepts),
+                    Err(e) => tracing::debug!("Ignoring invalid discovery document: {}", e),
+                }
+            }
+            Ok(response) => tracing::debug!("No discovery document: {}", response.status()),
+            Err(e) => tracing::debug!("Discovery document request failed: {}", e),
+        }
+
+        let request = self
+            .client
+            .get(url)
+            .headers(self.default_headers.clone())
+            .build()?;
+        let response = self.execute(request).await?;
+        let status = response.status();
+        if status == 402 {
+            let body: PaymentRequirementsResponse = response.json().await?;
+            self.check_quote(&body)?;
+            Ok(body.accepts)
+        } else if status.is_success() {
+            Ok(Vec::new())
+        } else {
+            Err(X402Error::unexpected(format!(
+                "Discovery of {} failed with status: {}",
+                url, status
+            )))
+        }
+    }
+
+    /// Set the path of resource servers' payment discovery document
+    ///
+    /// Defaults to [`WELL_KNOWN_X402_PATH`].
+    pub fn with_discovery_path(mut self, path: impl Into<String>) -> Self {
+        self.discovery_path = path.into();
+        self
+    }
+
+    /// Only pay 402 responses signed by the account at `signer`
+    ///
+    /// Requirements whose signature is missing or does not match (e.g. a
+    /// price or recipient altered in transit) fail with
+    /// [`X402Error::InvalidSignature`] before anything is signed. See
+    /// [`PaymentRequirementsResponse::verify_signature`].
+    pub fn with_quote_signer(mut self, signer: impl Into<String>) -> Self {
+        self.quote_signer = Some(signer.into());
+        self
+    }
+
+    /// Set how many redirects a request follows before failing
+    ///
+    /// Defaults to [`DEFAULT_MAX_REDIRECTS`]. The limit applies to each
+    /// request sent, including the paid retry after a 402.
+    pub fn with_max_redirects(mut self, max_redirects: usize) -> Self {
+        self.max_redirects = max_redirects;
+        self
+    }
+
+    /// Follow redirects to another origin
+    ///
+    /// Off by default: a redirect to another scheme, host or port is returned
+    /// as the response instead. Paying methods pay a 402 found at a redirect
+    /// target, so enabling this lets the paid server send the client to any
+    /// server and have it pay there, subject only to the quote signer and the
+    /// wallet's own checks. Credentials in `Authorization` and `Cookie`
+    /// headers are never forwarded to another origin.
+    pub fn with_cross_origin_redirect(mut self, allow: bool) -> Self {
+        self.allow_cross_origin_redirect = allow;
+        self
+    }
+
+    /// Check a 402 body against the quote signer, if one is set
+    fn check_quote(&self, body: &PaymentRequirementsResponse) -> Result<()> {
+        match &self.quote_signer {
+            Some(signer) => body.verify_signature(signer),
+            None => Ok(()),
+        }
     }
 
     /// Get the facilitator configuration
@@ -162,6 +474,130 @@ impl X402Client {
         self.facilitator_config = config;
         self
     }
+
+    /// Set headers sent with every request
+    ///
+    /// Useful for API keys or `Authorization` bearer tokens. The headers are
+    /// also sent on the automatic retry after a 402. Any `X-PAYMENT` header is
+    /// dropped, since the client manages that header itself.
+    pub fn with_default_headers(mut self, mut headers: HeaderMap) -> Self {
+        headers.remove(X_PAYMENT_HEADER);
+        self.default_headers = headers;
+        self
+    }
+
+    /// Get the headers sent with every request
+    pub fn default_headers(&self) -> &HeaderMap {
+        &self.default_headers
+    }
+
+    /// Set the facilitator used to pre-verify payments
+    pub fn with_facilitator(mut self, facilitator: FacilitatorClient) -> Self {
+        self.facilitator = Some(facilitator);
+        self
+    }
+
+    /// Get the facilitator used to pre-verify payments
+    pub fn facilitator(&self) -> Option<&FacilitatorClient> {
+        self.facilitator.as_ref()
+    }
+
+    /// Enable or disable dry-run verification
+    ///
+    /// In dry-run mode every payment is checked with the facilitator's
+    /// `verify` endpoint before `X-PAYMENT` is attached, so invalid payments
+    /// (expired authorizations, insufficient balance, ...) fail locally
+    /// instead of costing a round-trip to the resource server.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Check if dry-run verification is enabled
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Verify a payment with the facilitator if dry-run mode is enabled
+    ///
+    /// Returns the facilitator's `invalid_reason` as a
+    /// [`X402Error::PaymentVerificationFailed`] error when the payment is invalid.
+    pub async fn preverify(
+        &self,
+        payment_payload: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<()> {
+        if !self.dry_run {
+            return Ok(());
+        }
+
+        let facilitator = self
+            .facilitator
+            .as_ref()
+            .ok_or_else(|| X402Error::config("Dry-run verification requires a facilitator"))?;
+
+        let verify_response = facilitator.verify(payment_payload, requirements).await?;
+        if verify_response.is_valid {
+            Ok(())
+        } else {
+            Err(X402Error::payment_verification_failed(
+                verify_response
+                    .invalid_reason
+                    .unwrap_or_else(|| "Payment verification failed".to_string()),
+            ))
+        }
+    }
+}
+
+/// Whether a redirect from `from` to `to` stays on the same origin
+///
+/// An upgrade from `http` to `https` on the same host counts as the same
+/// origin when the target is on the default `https` port or the port the
+/// request came from; anything else must match scheme, host and port.
+fn same_origin(from: &url::Url, to: &url::Url) -> bool {
+    if from.host_str() != to.host_str() {
+        return false;
+    }
+    let (from_port, to_port) = (from.port_or_known_default(), to.port_or_known_default());
+    match (from.scheme(), to.scheme()) {
+        ("http", "https") => to_port == Some(443) || to_port == from_port,
+        (a, b) => a == b && from_port == to_port,
+    }
+}
+
+/// Turn the request that got a redirect into the request for its target
+///
+/// Like browsers, a 303, or a 301 or 302 answering a `POST`, becomes a
+/// bodiless `GET`. `X-PAYMENT` is always dropped: a payment authorizes the
+/// resource that asked for it, and the target asks for its own. Credentials
+/// are dropped when the target is on another origin.
+fn redirected_request(
+    mut request: reqwest::Request,
+    status: reqwest::StatusCode,
+    target: url::Url,
+) -> reqwest::Request {
+    use reqwest::{header, Method, StatusCode};
+
+    let to_get = status == StatusCode::SEE_OTHER
+        || (matches!(status, StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND)
+            && *request.method() == Method::POST);
+    if to_get && *request.method() != Method::HEAD {
+        *request.method_mut() = Method::GET;
+        *request.body_mut() = None;
+        let headers = request.headers_mut();
+        headers.remove(header::CONTENT_TYPE);
+        headers.remove(header::CONTENT_LENGTH);
+    }
+
+    request.headers_mut().remove(X_PAYMENT_HEADER);
+    if !same_origin(request.url(), &target) {
+        let headers = request.headers_mut();
+        headers.remove(header::AUTHORIZATION);
+        headers.remove(header::COOKIE);
+        headers.remove(header::PROXY_AUTHORIZATION);
+    }
+    *request.url_mut() = target;
+    request
 }
 
 impl Default for X402Client {
@@ -171,11 +607,221 @@ impl Default for X402Client {
             Self {
                 client: Client::new(),
                 facilitator_config: FacilitatorConfig::default(),
+                facilitator: None,
+                dry_run: false,
+                default_headers: HeaderMap::new(),
+                discovery_path: WELL_KNOWN_X402_PATH.to_string(),
+                quote_signer: None,
+                max_redirects: DEFAULT_MAX_REDIRECTS,
+                allow_cross_origin_redirect: false,
+                #[cfg(feature = "reqwest-middleware")]
+                middleware: None,
             }
         })
     }
 }
 
+/// What a paid request spent, read from the server's `X-PAYMENT-RESPONSE` header
+#[derive(Debug, Clone)]
+pub struct PaymentReceipt {
+    /// Address that paid
+    pub payer: String,
+    /// Amount authorized, in atomic units
+    pub amount: String,
+    /// Network the payment settled on
+    pub network: String,
+    /// Settlement transaction hash
+    pub tx_hash: String,
+    /// Server requirements the payment was made against, when known
+    pub requirement: Option<PaymentRequirements>,
+}
+
+impl PaymentReceipt {
+    /// Read the receipt for `payment` from a response
+    ///
+    /// `None` when the server sent no `X-PAYMENT-RESPONSE` header or reported a
+    /// failed settlement. A malformed header is logged rather than failing a
+    /// request that was already paid for.
+    pub fn from_response(
+        response: &Response,
+        payment: &PaymentPayload,
+        requirement: Option<PaymentRequirements>,
+    ) -> Option<Self> {
+        let header = response.headers().get(X_PAYMENT_RESPONSE_HEADER)?;
+        let settlement = match SettleResponse::from_header_value(header) {
+            Ok(settlement) => settlement,
+            Err(e) => {
+                tracing::warn!(
+                    "Ignoring invalid {} header: {}",
+                    X_PAYMENT_RESPONSE_HEADER,
+                    e
+                );
+                return None;
+            }
+        };
+        if !settlement.success {
+            return None;
+        }
+
+        let authorization = &payment.payload.authorization;
+        Some(Self {
+            payer: settlement
+                .payer
+                .unwrap_or_else(|| authorization.from.clone()),
+            amount: authorization.value.clone(),
+            network: settlement.network,
+            tx_hash: settlement.transaction,
+            requirement,
+        })
+    }
+}
+
+/// Response to a request that may have been paid for
+#[derive(Debug)]
+pub struct PaidResponse {
+    /// Final HTTP response
+    pub response: Response,
+    /// Receipt for the payment, if one was made and settled
+    ///
+    /// When several payments were made, this is the last one's.
+    pub receipt: Option<PaymentReceipt>,
+    /// Receipts of every settled payment, in the order they were made
+    ///
+    /// More than one only when a paid request was redirected to another
+    /// resource that was paid as well (see [`X402RequestBuilder::send_with_wallet`]).
+    pub receipts: Vec<PaymentReceipt>,
+}
+
+impl PaidResponse {
+    /// Wrap a response for which no payment was made
+    fn unpaid(response: Response) -> Self {
+        Self {
+            response,
+            receipt: None,
+            receipts: Vec::new(),
+        }
+    }
+
+    /// Wrap a response to a request carrying `payment`
+    fn paid(
+        response: Response,
+        payment: &PaymentPayload,
+        requirement: Option<PaymentRequirements>,
+    ) -> Self {
+        let receipt = PaymentReceipt::from_response(&response, payment, requirement);
+        Self {
+            response,
+            receipts: receipt.iter().cloned().collect(),
+            receipt,
+        }
+    }
+
+    /// HTTP status of the response
+    pub fn status(&self) -> reqwest::StatusCode {
+        self.response.status()
+    }
+
+    /// Unwrap the HTTP response
+    pub fn into_response(self) -> Response {
+        self.response
+    }
+}
+
+/// Chooses which of a 402's payment options to pay, by where the payer has funds
+///
+/// Options are considered in the server's order. The first one on a network
+/// the selector has an RPC client for, where the payer's balance of the
+/// option's asset covers the amount, is chosen.
+pub struct PaymentSelector {
+    /// Address whose balances are checked
+    payer: String,
+    /// RPC clients by network name
+    clients: HashMap<String, BlockchainClient>,
+}
+
+impl PaymentSelector {
+    /// Create a selector for `payer`, with no networks yet
+    pub fn new(payer: impl Into<String>) -> Self {
+        Self {
+            payer: payer.into(),
+            clients: HashMap::new(),
+        }
+    }
+
+    /// Create a selector for `payer` over every network in `registry`
+    pub fn from_registry(payer: impl Into<String>, registry: &NetworkRpcRegistry) -> Result<Self> {
+        let mut selector = Self::new(payer);
+        for network in registry.networks() {
+            let urls = registry.urls(network).unwrap_or_default().to_vec();
+            selector =
+                selector.with_client(BlockchainClient::with_providers(urls, network.to_string())?);
+        }
+        Ok(selector)
+    }
+
+    /// Check balances on `client`'s network with `client`
+    pub fn with_client(mut self, client: BlockchainClient) -> Self {
+        self.clients.insert(client.network.clone(), client);
+        self
+    }
+
+    /// The first option the payer can afford
+    ///
+    /// Options on networks without a client, or whose balance cannot be read,
+    /// are skipped. Fails with [`X402Error::InsufficientFunds`] when none is left.
+    pub async fn select<'a>(
+        &self,
+        accepts: &'a [PaymentRequirements],
+    ) -> Result<&'a PaymentRequirements> {
+        for requirements in accepts {
+            let Some(client) = self.clients.get(&requirements.network) else {
+                tracing::debug!("No RPC client for {}", requirements.network);
+                continue;
+            };
+            let Ok(amount) = U256::from_dec_str(&requirements.max_amount_required) else {
+                continue;
+            };
+            match self.balance(client, requirements).await {
+                Ok(balance) if balance >= amount => return Ok(requirements),
+                Ok(balance) => tracing::debug!(
+                    "Balance {} on {} does not cover {}",
+                    balance,
+                    requirements.network,
+                    amount
+                ),
+                Err(e) => {
+                    tracing::debug!("Failed to read balance on {}: {}", requirements.network, e)
+                }
+            }
+        }
+        Err(X402Error::InsufficientFunds)
+    }
+
+    /// Payer's balance of the option's asset
+    async fn balance(
+        &self,
+        client: &BlockchainClient,
+        requirements: &PaymentRequirements,
+    ) -> Result<U256> {
+        if requirements.is_native() {
+            let balance = client.get_balance(&self.payer).await?.balance;
+            U256::from_str_radix(balance.trim_start_matches("0x"), 16)
+                .map_err(|_| X402Error::network_error("Invalid balance format"))
+        } else {
+            client.token_balance(&requirements.asset, &self.payer).await
+        }
+    }
+}
+
+impl std::fmt::Debug for PaymentSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaymentSelector")
+            .field("payer", &self.payer)
+            .field("networks", &self.clients.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
 /// Request builder for x402 client
 #[derive(Debug)]
 pub struct X402RequestBuilder<'a> {
@@ -191,7 +837,7 @@ impl<'a> X402RequestBuilder<'a> {
     fn new(client: &'a X402Client, request: reqwest::RequestBuilder) -> Self {
         Self {
             client,
-            request,
+            request: request.headers(client.default_headers.clone()),
             method: String::new(),
             url: String::new(),
             _headers: std::collections::HashMap::new(),
@@ -264,41 +910,109 @@ impl<'a> X402RequestBuilder<'a> {
     /// Add a payment header to the request
     pub fn payment(self, payment_payload: &PaymentPayload) -> Result<Self> {
         let payment_header = payment_payload.to_base64()?;
-        Ok(self.header("X-PAYMENT", &payment_header))
+        Ok(self.header(X_PAYMENT_HEADER, &payment_header))
     }
 
     /// Send the request
     pub async fn send(self) -> Result<Response> {
-        self.request.send().await.map_err(X402Error::from)
+        let request = self.request.build()?;
+        self.client.execute(request).await
     }
 
     /// Send the request and handle x402 payments automatically
-    pub async fn send_with_payment(self, payment_payload: &PaymentPayload) -> Result<Response> {
-        // Save values before consuming self
-        let original_url = self.url.clone();
-        let client = self.client.clone();
-
-        let response = self.send().await?;
+    ///
+    /// On a 402 the original request, including its method, headers and body,
+    /// is re-sent with the `X-PAYMENT` header. A user-supplied `X-PAYMENT`
+    /// header is replaced rather than duplicated. The receipt is read from the
+    /// paid response's `X-PAYMENT-RESPONSE` header.
+    ///
+    /// A 402 reached through a redirect is paid at the redirect target. If the
+    /// paid request is itself redirected to a resource that wants payment, that
+    /// 402 is returned: `payment_payload` cannot be spent twice. Use
+    /// [`send_with_wallet`](Self::send_with_wallet) to pay it as well.
+    pub async fn send_with_payment(self, payment_payload: &PaymentPayload) -> Result<PaidResponse> {
+        let client = self.client;
+        let (response, retry_request) = client.execute_following(self.request.build()?).await?;
 
         if response.status() == 402 {
             // Parse payment requirements from 402 response
-            let _payment_requirements: PaymentRequirementsResponse = response.json().await?;
+            let payment_requirements: PaymentRequirementsResponse = response.json().await?;
+            client.check_quote(&payment_requirements)?;
+
+            let matching = payment_requirements.accepts.iter().find(|r| {
+                r.scheme == payment_payload.scheme && r.network == payment_payload.network
+            });
+
+            // Pre-verify against the requirements matching the payment
+            if client.is_dry_run() {
+                let requirements = matching.ok_or_else(|| {
+                    X402Error::payment_verification_failed(
+                        "No payment requirements match the payment payload",
+                    )
+                })?;
+                client.preverify(payment_payload, requirements).await?;
+            }
 
-            // Create a new request with payment header
-            let payment_header = payment_payload.to_base64()?;
+            Ok(client
+                .retry_with_payment(retry_request, payment_payload, matching.cloned())
+                .await?
+                .0)
+        } else {
+            Ok(PaidResponse::unpaid(response))
+        }
+    }
 
-            // Create a new request with payment header
-            let new_response = client
-                .client
-                .get(&original_url)
-                .header("X-PAYMENT", &payment_header)
-                .send()
-                .await?;
+    /// Send the request, paying a 402 from `wallet` on the option `selector` picks
+    ///
+    /// The wallet signs a fresh authorization for the first option the payer
+    /// can afford (see [`PaymentSelector::select`]), and the original request
+    /// is re-sent with it as in [`send_with_payment`](Self::send_with_payment).
+    ///
+    /// If the paid request is redirected to another resource that answers 402,
+    /// that resource is paid too, with a new authorization, up to the client's
+    /// redirect limit. Each URL is paid at most once. See
+    /// [`X402Client::with_cross_origin_redirect`] before allowing redirects
+    /// to other origins.
+    pub async fn send_with_wallet(
+        self,
+        wallet: &Wallet,
+        selector: &PaymentSelector,
+    ) -> Result<PaidResponse> {
+        let client = self.client;
+        let (mut response, mut retry_request) =
+            client.execute_following(self.request.build()?).await?;
+
+        let mut paid_urls = std::collections::HashSet::new();
+        let mut paid = None;
+        let mut receipts = Vec::new();
+        while response.status() == 402 && paid_urls.len() <= client.max_redirects {
+            // A 402 where we already paid is a rejected payment, not a new price
+            if !paid_urls.insert(response.url().clone()) {
+                break;
+            }
 
-            Ok(new_response)
-        } else {
-            Ok(response)
+            let payment_requirements: PaymentRequirementsResponse = response.json().await?;
+            client.check_quote(&payment_requirements)?;
+            let requirements = selector.select(&payment_requirements.accepts).await?;
+            let payment_payload = wallet.authorize_payment(requirements).await?;
+            client.preverify(&payment_payload, requirements).await?;
+
+            let (paid_response, replay) = client
+                .retry_with_payment(retry_request, &payment_payload, Some(requirements.clone()))
+                .await?;
+            (response, retry_request) = (paid_response.response, replay);
+            receipts.extend(paid_response.receipts);
+            paid = Some(paid_response.receipt);
         }
+
+        Ok(match paid {
+            Some(receipt) => PaidResponse {
+                response,
+                receipt,
+                receipts,
+            },
+            None => PaidResponse::unpaid(response),
+        })
     }
 
     /// Send the request and return the response as text
@@ -349,15 +1063,7 @@ impl DiscoveryClient {
         let mut request = self.client.get(format!("{}/resources", self.url));
 
         if let Some(filters) = filters {
-            if let Some(resource_type) = filters.resource_type {
-                request = request.query(&[("type", resource_type)]);
-            }
-            if let Some(limit) = filters.limit {
-                request = request.query(&[("limit", limit.to_string())]);
-            }
-            if let Some(offset) = filters.offset {
-                request = request.query(&[("offset", offset.to_string())]);
-            }
+            request = request.query(&filters.query_params());
         }
 
         let response = request.send().await?;
@@ -380,11 +1086,9 @@ impl DiscoveryClient {
 
     /// Get resources by type
     pub async fn get_resources_by_type(&self, resource_type: &str) -> Result<DiscoveryResponse> {
-        self.discover_resources(Some(DiscoveryFilters {
-            resource_type: Some(resource_type.to_string()),
-            limit: None,
-            offset: None,
-        }))
+        self.discover_resources(Some(
+            DiscoveryFilters::new().with_resource_type(resource_type),
+        ))
         .await
     }
 
@@ -403,6 +1107,12 @@ pub struct DiscoveryFilters {
     pub limit: Option<u32>,
     /// Number of results to skip
     pub offset: Option<u32>,
+    /// Filter by network
+    pub network: Option<String>,
+    /// Filter by payment scheme
+    pub scheme: Option<String>,
+    /// Free-text search over resource metadata
+    pub search: Option<String>,
 }
 
 impl DiscoveryFilters {
@@ -412,6 +1122,9 @@ impl DiscoveryFilters {
             resource_type: None,
             limit: None,
             offset: None,
+            network: None,
+            scheme: None,
+            search: None,
         }
     }
 
@@ -432,6 +1145,48 @@ impl DiscoveryFilters {
         self.offset = Some(offset);
         self
     }
+
+    /// Set network filter
+    pub fn with_network(mut self, network: impl Into<String>) -> Self {
+        self.network = Some(network.into());
+        self
+    }
+
+    /// Set payment scheme filter
+    pub fn with_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.scheme = Some(scheme.into());
+        self
+    }
+
+    /// Set free-text search query
+    pub fn with_search(mut self, search: impl Into<String>) -> Self {
+        self.search = Some(search.into());
+        self
+    }
+
+    /// Query string parameters for these filters
+    pub(crate) fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(resource_type) = &self.resource_type {
+            params.push(("type", resource_type.clone()));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit", limit.to_string()));
+        }
+        if let Some(offset) = self.offset {
+            params.push(("offset", offset.to_string()));
+        }
+        if let Some(network) = &self.network {
+            params.push(("network", network.clone()));
+        }
+        if let Some(scheme) = &self.scheme {
+            params.push(("scheme", scheme.clone()));
+        }
+        if let Some(search) = &self.search {
+            params.push(("q", search.clone()));
+        }
+        params
+    }
 }
 
 impl Default for DiscoveryFilters {
@@ -498,6 +1253,543 @@ mod tests {
         assert_eq!(delete_request.method, "DELETE");
     }
 
+    fn payment_required_body() -> String {
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+        serde_json::to_string(&PaymentRequirementsResponse::new(
+            "X-PAYMENT header is required",
+            vec![requirements],
+        ))
+        .unwrap()
+    }
+
+    fn test_payment_payload() -> PaymentPayload {
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            ExactEvmPayload {
+                signature: "0x00".to_string(),
+                authorization,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn test_discover_from_well_known_document() {
+        let mut server = mockito::Server::new_async().await;
+        let requirements: PaymentRequirementsResponse =
+            serde_json::from_str(&payment_required_body()).unwrap();
+        server
+            .mock("GET", "/.well-known/x402")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::to_string(&PaymentOptions::new(requirements.accepts)).unwrap())
+            .create_async()
+            .await;
+        let resource = server
+            .mock("GET", "/weather")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let accepts = X402Client::new()
+            .unwrap()
+            .discover(&format!("{}/weather?city=Paris", server.url()))
+            .await
+            .unwrap();
+        assert_eq!(accepts.len(), 1);
+        assert_eq!(accepts[0].max_amount_required, "1000000");
+        assert_eq!(accepts[0].network, "base-sepolia");
+
+        // The resource itself is never requested
+        resource.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_discover_falls_back_to_402() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/.well-known/x402")
+            .with_status(404)
+            .create_async()
+            .await;
+        let resource = server
+            .mock("GET", "/weather")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Missing)
+            .with_status(402)
+            .with_header("content-type", "application/json")
+            .with_body(payment_required_body())
+            .expect(1)
+            .create_async()
+            .await;
+        server
+            .mock("GET", "/free")
+            .with_status(200)
+            .with_body("free content")
+            .create_async()
+            .await;
+
+        let client = X402Client::new().unwrap();
+        let accepts = client
+            .discover(&format!("{}/weather", server.url()))
+            .await
+            .unwrap();
+        assert_eq!(accepts.len(), 1);
+        assert_eq!(accepts[0].resource, "https://example.com/test");
+        resource.assert_async().await;
+
+        // Free resources accept nothing
+        let accepts = client
+            .discover(&format!("{}/free", server.url()))
+            .await
+            .unwrap();
+        assert!(accepts.is_empty());
+    }
+
+    fn dry_run_client(facilitator: &mockito::ServerGuard) -> X402Client {
+        let facilitator =
+            FacilitatorClient::new(FacilitatorConfig::new(facilitator.url())).unwrap();
+        X402Client::new()
+            .unwrap()
+            .with_facilitator(facilitator)
+            .with_dry_run(true)
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_attaches_payment_when_valid() {
+        let mut facilitator = mockito::Server::new_async().await;
+        let verify = facilitator
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(r#"{"isValid":true,"payer":"0x857b06519E91e3A54538791bDbb0E22373e36b66"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut resource = mockito::Server::new_async().await;
+        let _unpaid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Missing)
+            .with_status(402)
+            .with_body(payment_required_body())
+            .create_async()
+            .await;
+        let paid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Any)
+            .with_status(200)
+            .with_body("paid content")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = dry_run_client(&facilitator);
+        let response = client
+            .get(&format!("{}/premium", resource.url()))
+            .send_with_payment(&test_payment_payload())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        verify.assert_async().await;
+        paid.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_surfaces_invalid_reason() {
+        let mut facilitator = mockito::Server::new_async().await;
+        let _verify = facilitator
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(r#"{"isValid":false,"invalidReason":"insufficient_funds"}"#)
+            .create_async()
+            .await;
+
+        let mut resource = mockito::Server::new_async().await;
+        let _unpaid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Missing)
+            .with_status(402)
+            .with_body(payment_required_body())
+            .create_async()
+            .await;
+        let paid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let client = dry_run_client(&facilitator);
+        let result = client
+            .get(&format!("{}/premium", resource.url()))
+            .send_with_payment(&test_payment_payload())
+            .await;
+
+        match result {
+            Err(X402Error::PaymentVerificationFailed { reason }) => {
+                assert_eq!(reason, "insufficient_funds")
+            }
+            other => panic!("expected verification failure, got {:?}", other),
+        }
+        paid.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_requires_facilitator() {
+        let client = X402Client::new().unwrap().with_dry_run(true);
+        let requirements: PaymentRequirementsResponse =
+            serde_json::from_str(&payment_required_body()).unwrap();
+
+        let result = client
+            .preverify(&test_payment_payload(), &requirements.accepts[0])
+            .await;
+        assert!(matches!(result, Err(X402Error::Config { .. })));
+
+        // Without dry-run no facilitator is consulted
+        let client = X402Client::new().unwrap();
+        assert!(client
+            .preverify(&test_payment_payload(), &requirements.accepts[0])
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_custom_headers_survive_payment_retry() {
+        let payment_payload = test_payment_payload();
+        let mut resource = mockito::Server::new_async().await;
+        let unpaid = resource
+            .mock("POST", "/premium")
+            .match_header("x-api-key", "secret")
+            .match_header("authorization", "Bearer token")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Missing)
+            .with_status(402)
+            .with_body(payment_required_body())
+            .expect(1)
+            .create_async()
+            .await;
+        let paid = resource
+            .mock("POST", "/premium")
+            .match_header("x-api-key", "secret")
+            .match_header("authorization", "Bearer token")
+            .match_header(
+                X_PAYMENT_HEADER,
+                payment_payload.to_base64().unwrap().as_str(),
+            )
+            .match_body("request body")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert("x-api-key", "secret".parse().unwrap());
+        let client = X402Client::new()
+            .unwrap()
+            .with_default_headers(default_headers);
+
+        let response = client
+            .post(&format!("{}/premium", resource.url()))
+            .header("authorization", "Bearer token")
+            .body("request body")
+            .send_with_payment(&payment_payload)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        unpaid.assert_async().await;
+        paid.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_handled_payment_keeps_original_request() {
+        let payment_payload = test_payment_payload();
+        let mut facilitator = mockito::Server::new_async().await;
+        let _verify = facilitator
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_body(r#"{"isValid":true}"#)
+            .create_async()
+            .await;
+
+        let mut resource = mockito::Server::new_async().await;
+        let _unpaid = resource
+            .mock("PUT", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Missing)
+            .with_status(402)
+            .with_body(payment_required_body())
+            .create_async()
+            .await;
+        let paid = resource
+            .mock("PUT", "/premium")
+            .match_header("x-request-id", "42")
+            .match_header(
+                X_PAYMENT_HEADER,
+                payment_payload.to_base64().unwrap().as_str(),
+            )
+            .match_body("request body")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = X402Client::new().unwrap().with_facilitator(
+            FacilitatorClient::new(FacilitatorConfig::new(facilitator.url())).unwrap(),
+        );
+        let request = reqwest::Client::new()
+            .put(format!("{}/premium", resource.url()))
+            .header("x-request-id", "42")
+            .body("request body")
+            .build()
+            .unwrap();
+        let response = reqwest::Client::new()
+            .execute(request.try_clone().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 402);
+
+        let response = client
+            .handle_payment_required_for(request, response, &payment_payload)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+        paid.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_request_with_headers_sends_one_payment_header() {
+        let payment_payload = test_payment_payload();
+        let mut resource = mockito::Server::new_async().await;
+        let premium = resource
+            .mock("GET", "/premium")
+            .with_status(200)
+            .with_body_from_request(|request| {
+                request.header(X_PAYMENT_HEADER).len().to_string().into()
+            })
+            .expect(1)
+            .create_async()
+            .await;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(X_PAYMENT_HEADER, "stale".parse().unwrap());
+        let paid = X402Client::new()
+            .unwrap()
+            .request_with_headers(
+                "GET",
+                &format!("{}/premium", resource.url()),
+                headers,
+                Some(&payment_payload),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(paid.into_response().text().await.unwrap(), "1");
+        premium.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_receipt_from_settlement_header() {
+        let payment_payload = test_payment_payload();
+        let settlement = SettleResponse {
+            success: true,
+            error_reason: None,
+            payer: None,
+            transaction: "0x8f9c2a6b1d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f90"
+                .to_string(),
+            network: "base-sepolia".to_string(),
+            fee_amount: None,
+            net_amount: None,
+        };
+        let mut resource = mockito::Server::new_async().await;
+        let _unpaid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Missing)
+            .with_status(402)
+            .with_body(payment_required_body())
+            .create_async()
+            .await;
+        let _paid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Any)
+            .with_status(200)
+            .with_header(X_PAYMENT_RESPONSE_HEADER, &settlement.to_base64().unwrap())
+            .create_async()
+            .await;
+
+        let paid = X402Client::new()
+            .unwrap()
+            .get(&format!("{}/premium", resource.url()))
+            .send_with_payment(&payment_payload)
+            .await
+            .unwrap();
+
+        assert_eq!(paid.status(), 200);
+        let receipt = paid.receipt.expect("settled payment has a receipt");
+        assert_eq!(receipt.tx_hash, settlement.transaction);
+        assert_eq!(receipt.payer, payment_payload.payload.authorization.from);
+        assert_eq!(receipt.amount, "1000000");
+        assert_eq!(receipt.network, "base-sepolia");
+        assert_eq!(
+            receipt.requirement.unwrap().pay_to,
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tampered_quote_rejected() {
+        let signer = crate::signer::LocalSigner::new(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let address = format!("{:?}", crate::signer::Signer::address(&signer));
+        let mut tampered: PaymentRequirementsResponse =
+            serde_json::from_str(&payment_required_body()).unwrap();
+        tampered = tampered.signed(&signer).unwrap();
+        tampered.accepts[0].pay_to = "0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string();
+
+        let mut resource = mockito::Server::new_async().await;
+        let _unpaid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Missing)
+            .with_status(402)
+            .with_body(serde_json::to_string(&tampered).unwrap())
+            .create_async()
+            .await;
+        let paid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Any)
+            .expect(0)
+            .create_async()
+            .await;
+
+        let result = X402Client::new()
+            .unwrap()
+            .with_quote_signer(address)
+            .get(&format!("{}/premium", resource.url()))
+            .send_with_payment(&test_payment_payload())
+            .await;
+
+        assert!(matches!(result, Err(X402Error::InvalidSignature { .. })));
+        paid.assert_async().await;
+    }
+
+    #[cfg(feature = "reqwest-middleware")]
+    #[tokio::test]
+    async fn test_middleware_sees_payment_retry() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        struct Counting(Arc<AtomicUsize>);
+
+        #[async_trait::async_trait]
+        impl reqwest_middleware::Middleware for Counting {
+            async fn handle(
+                &self,
+                req: reqwest::Request,
+                extensions: &mut http::Extensions,
+                next: reqwest_middleware::Next<'_>,
+            ) -> reqwest_middleware::Result<Response> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                next.run(req, extensions).await
+            }
+        }
+
+        let payment_payload = test_payment_payload();
+        let mut resource = mockito::Server::new_async().await;
+        let unpaid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Missing)
+            .with_status(402)
+            .with_body(payment_required_body())
+            .expect(1)
+            .create_async()
+            .await;
+        let paid = resource
+            .mock("GET", "/premium")
+            .match_header(
+                X_PAYMENT_HEADER,
+                payment_payload.to_base64().unwrap().as_str(),
+            )
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let middleware = reqwest_middleware::ClientBuilder::new(Client::new())
+            .with(Counting(requests.clone()))
+            .build();
+        let client = X402Client::with_middleware(middleware).unwrap();
+
+        let response = client
+            .get(&format!("{}/premium", resource.url()))
+            .send_with_payment(&payment_payload)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        unpaid.assert_async().await;
+        paid.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_user_payment_header_does_not_collide() {
+        let payment_payload = test_payment_payload();
+        let mut resource = mockito::Server::new_async().await;
+        let _unpaid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, "stale")
+            .with_status(402)
+            .with_body(payment_required_body())
+            .create_async()
+            .await;
+        let paid = resource
+            .mock("GET", "/premium")
+            .match_header(
+                X_PAYMENT_HEADER,
+                payment_payload.to_base64().unwrap().as_str(),
+            )
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = X402Client::new().unwrap();
+        let response = client
+            .get(&format!("{}/premium", resource.url()))
+            .header(X_PAYMENT_HEADER, "stale")
+            .send_with_payment(&payment_payload)
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        paid.assert_async().await;
+
+        // Default headers never carry a payment
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert(X_PAYMENT_HEADER, "stale".parse().unwrap());
+        let client = X402Client::new()
+            .unwrap()
+            .with_default_headers(default_headers);
+        assert!(client.default_headers().get(X_PAYMENT_HEADER).is_none());
+    }
+
     #[test]
     fn test_discovery_filters_builder() {
         let filters = DiscoveryFilters::new()
@@ -509,4 +1801,214 @@ mod tests {
         assert_eq!(filters.limit, Some(10));
         assert_eq!(filters.offset, Some(5));
     }
+
+    const SETTLEMENT_TX: &str =
+        "0x8f9c2a6b1d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f90";
+
+    fn settlement_header() -> String {
+        settlement_header_for(SETTLEMENT_TX)
+    }
+
+    fn settlement_header_for(transaction: &str) -> String {
+        SettleResponse {
+            success: true,
+            error_reason: None,
+            payer: None,
+            transaction: transaction.to_string(),
+            network: "base-sepolia".to_string(),
+            fee_amount: None,
+            net_amount: None,
+        }
+        .to_base64()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_paid_redirect_to_free_resource() {
+        let mut resource = mockito::Server::new_async().await;
+        let _unpaid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Missing)
+            .with_status(402)
+            .with_body(payment_required_body())
+            .create_async()
+            .await;
+        let _paid = resource
+            .mock("GET", "/premium")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Any)
+            .with_status(302)
+            .with_header("location", "/free")
+            .with_header(X_PAYMENT_RESPONSE_HEADER, &settlement_header())
+            .create_async()
+            .await;
+        // The payment is not forwarded to the redirect target
+        let free = resource
+            .mock("GET", "/free")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Missing)
+            .with_status(200)
+            .with_body("free content")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let paid = X402Client::new()
+            .unwrap()
+            .get(&format!("{}/premium", resource.url()))
+            .send_with_payment(&test_payment_payload())
+            .await
+            .unwrap();
+
+        assert_eq!(paid.status(), 200);
+        assert!(paid.receipt.is_some(), "settlement on the redirect is kept");
+        assert_eq!(paid.into_response().text().await.unwrap(), "free content");
+        free.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_paid_redirect_to_paid_resource() {
+        const FIRST_TX: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+        let mut rpc = mockito::Server::new_async().await;
+        let _balance = rpc
+            .mock("POST", "/")
+            .with_body(
+                serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{:064x}", 5_000_000u64)})
+                    .to_string(),
+            )
+            .create_async()
+            .await;
+        let mut resource = mockito::Server::new_async().await;
+        for path in ["/first", "/second"] {
+            resource
+                .mock("GET", path)
+                .match_header(X_PAYMENT_HEADER, mockito::Matcher::Missing)
+                .with_status(402)
+                .with_body(payment_required_body())
+                .expect(1)
+                .create_async()
+                .await;
+        }
+        let first = resource
+            .mock("GET", "/first")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Any)
+            .with_status(307)
+            .with_header("location", "/second")
+            .with_header(X_PAYMENT_RESPONSE_HEADER, &settlement_header_for(FIRST_TX))
+            .expect(1)
+            .create_async()
+            .await;
+        let second = resource
+            .mock("GET", "/second")
+            .match_header(X_PAYMENT_HEADER, mockito::Matcher::Any)
+            .with_status(200)
+            .with_header(X_PAYMENT_RESPONSE_HEADER, &settlement_header())
+            .with_body("second content")
+            .expect(1)
+            .create_async()
+            .await;
+
+        let wallet = Wallet::new(
+            crate::signer::LocalSigner::new(
+                "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+            )
+            .unwrap(),
+            "base-sepolia",
+        );
+        let selector = PaymentSelector::new(format!("{:?}", wallet.address()))
+            .with_client(BlockchainClient::new(rpc.url(), "base-sepolia".to_string()));
+
+        let paid = X402Client::new()
+            .unwrap()
+            .get(&format!("{}/first", resource.url()))
+            .send_with_wallet(&wallet, &selector)
+            .await
+            .unwrap();
+
+        assert_eq!(paid.status(), 200);
+        assert_eq!(paid.receipt.as_ref().unwrap().tx_hash, SETTLEMENT_TX);
+        // The first resource's settlement is not lost to the second's
+        let tx_hashes: Vec<_> = paid.receipts.iter().map(|r| r.tx_hash.as_str()).collect();
+        assert_eq!(tx_hashes, [FIRST_TX, SETTLEMENT_TX]);
+        assert_eq!(paid.into_response().text().await.unwrap(), "second content");
+        first.assert_async().await;
+        second.assert_async().await;
+    }
+
+    #[test]
+    fn test_same_origin() {
+        let origin = |from: &str, to: &str| {
+            same_origin(
+                &url::Url::parse(from).unwrap(),
+                &url::Url::parse(to).unwrap(),
+            )
+        };
+        assert!(origin(
+            "http://api.example.com/a",
+            "http://api.example.com:80/b"
+        ));
+        assert!(origin(
+            "http://api.example.com/a",
+            "https://api.example.com/b"
+        ));
+        assert!(origin(
+            "http://api.example.com:8080/a",
+            "https://api.example.com:8080/b"
+        ));
+
+        // An upgrade must not move to an arbitrary port
+        assert!(!origin(
+            "http://api.example.com/a",
+            "https://api.example.com:8443/b"
+        ));
+        assert!(!origin(
+            "https://api.example.com/a",
+            "http://api.example.com/b"
+        ));
+        assert!(!origin(
+            "http://api.example.com/a",
+            "http://other.example.com/b"
+        ));
+        assert!(!origin(
+            "http://api.example.com:8080/a",
+            "http://api.example.com:9090/b"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_policy() {
+        let mut other = mockito::Server::new_async().await;
+        let elsewhere = other
+            .mock("GET", "/")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+        let mut resource = mockito::Server::new_async().await;
+        resource
+            .mock("GET", "/away")
+            .with_status(302)
+            .with_header("location", &other.url())
+            .create_async()
+            .await;
+        resource
+            .mock("GET", "/loop")
+            .with_status(302)
+            .with_header("location", "/loop")
+            .create_async()
+            .await;
+
+        // Another port is another origin
+        let client = X402Client::new().unwrap();
+        let away = format!("{}/away", resource.url());
+        assert_eq!(client.get(&away).send().await.unwrap().status(), 302);
+        let client = client.with_cross_origin_redirect(true);
+        assert_eq!(client.get(&away).send().await.unwrap().status(), 200);
+        elsewhere.assert_async().await;
+
+        let result = client
+            .with_max_redirects(3)
+            .get(&format!("{}/loop", resource.url()))
+            .send()
+            .await;
+        assert!(result.is_err());
+    }
 }
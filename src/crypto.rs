@@ -131,6 +131,24 @@ pub mod eip712 {
         pub version: String,
         pub chain_id: u64,
         pub verifying_contract: Address,
+        /// Disambiguating salt, part of the separator only when set
+        pub salt: Option<H256>,
+    }
+
+    impl Domain {
+        /// The domain as it appears in `eth_signTypedData_v4` typed data
+        pub fn to_json(&self) -> serde_json::Value {
+            let mut domain = json!({
+                "name": self.name,
+                "version": self.version,
+                "chainId": self.chain_id,
+                "verifyingContract": format!("{:?}", self.verifying_contract),
+            });
+            if let Some(salt) = self.salt {
+                domain["salt"] = json!(format!("{:?}", salt));
+            }
+            domain
+        }
     }
 
     /// EIP-712 typed data structure
@@ -152,42 +170,156 @@ pub mod eip712 {
         valid_before: U256,
         nonce: H256,
     ) -> Result<H256> {
-        let types = json!({
-            "EIP712Domain": [
-                {"name": "name", "type": "string"},
-                {"name": "version", "type": "string"},
-                {"name": "chainId", "type": "uint256"},
-                {"name": "verifyingContract", "type": "address"}
-            ],
-            "TransferWithAuthorization": [
-                {"name": "from", "type": "address"},
-                {"name": "to", "type": "address"},
-                {"name": "value", "type": "uint256"},
-                {"name": "validAfter", "type": "uint256"},
-                {"name": "validBefore", "type": "uint256"},
-                {"name": "nonce", "type": "bytes32"}
-            ]
-        });
+        authorization_hash(
+            "TransferWithAuthorization",
+            domain,
+            from,
+            to,
+            value,
+            valid_after,
+            valid_before,
+            nonce,
+        )
+    }
+
+    /// Domain name of USDC, assumed when the requirements name no token domain
+    pub const DEFAULT_TOKEN_NAME: &str = "USD Coin";
+
+    /// Domain version of USDC, assumed when the requirements name no token domain
+    pub const DEFAULT_TOKEN_VERSION: &str = "2";
 
+    /// EIP-712 domain of the token at `verifying_contract` on `chain_id`
+    ///
+    /// `extra` is the payment requirements' `extra`: its `name` and `version`
+    /// replace USDC's, and a hex `salt` is added, so tokens signed under
+    /// another domain verify too.
+    pub fn token_domain(
+        chain_id: u64,
+        verifying_contract: Address,
+        extra: Option<&serde_json::Value>,
+    ) -> Domain {
+        let field = |key: &str, default: &str| {
+            extra
+                .and_then(|extra| extra.get(key))
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or(default)
+                .to_string()
+        };
+        Domain {
+            name: field("name", DEFAULT_TOKEN_NAME),
+            version: field("version", DEFAULT_TOKEN_VERSION),
+            chain_id,
+            verifying_contract,
+            salt: extra
+                .and_then(|extra| extra.get("salt"))
+                .and_then(serde_json::Value::as_str)
+                .and_then(|salt| H256::from_str(salt).ok()),
+        }
+    }
+
+    /// Domain name for native gas-token payment authorizations
+    pub const NATIVE_DOMAIN_NAME: &str = "x402 Native Payment";
+
+    /// EIP-712 domain for native payments on `chain_id`
+    ///
+    /// There is no token contract, so the verifying contract is the zero address.
+    pub fn native_payment_domain(chain_id: u64) -> Domain {
+        Domain {
+            name: NATIVE_DOMAIN_NAME.to_string(),
+            version: "1".to_string(),
+            chain_id,
+            verifying_contract: Address::zero(),
+            salt: None,
+        }
+    }
+
+    /// Create EIP-712 hash authorizing a native gas-token transfer
+    ///
+    /// The message mirrors `TransferWithAuthorization` so the same
+    /// authorization fields cover ERC-20 and native payments.
+    pub fn create_native_transfer_authorization_hash(
+        domain: &Domain,
+        from: Address,
+        to: Address,
+        value: U256,
+        valid_after: U256,
+        valid_before: U256,
+        nonce: H256,
+    ) -> Result<H256> {
+        authorization_hash(
+            "NativeTransferAuthorization",
+            domain,
+            from,
+            to,
+            value,
+            valid_after,
+            valid_before,
+            nonce,
+        )
+    }
+
+    /// Hash an authorization message with the given primary type
+    #[allow(clippy::too_many_arguments)]
+    fn authorization_hash(
+        primary_type: &str,
+        domain: &Domain,
+        from: Address,
+        to: Address,
+        value: U256,
+        valid_after: U256,
+        valid_before: U256,
+        nonce: H256,
+    ) -> Result<H256> {
         let message = json!({
             "from": format!("{:?}", from),
             "to": format!("{:?}", to),
-            "value": format!("0x{:x}", value),
-            "validAfter": format!("0x{:x}", valid_after),
-            "validBefore": format!("0x{:x}", valid_before),
+            "value": uint256_hex(value),
+            "validAfter": uint256_hex(valid_after),
+            "validBefore": uint256_hex(valid_before),
             "nonce": format!("{:?}", nonce)
         });
 
         let typed_data = TypedData {
             domain: domain.clone(),
-            primary_type: "TransferWithAuthorization".to_string(),
-            types,
+            primary_type: primary_type.to_string(),
+            types: authorization_types(primary_type, domain),
             message,
         };
 
         hash_typed_data(&typed_data)
     }
 
+    /// EIP-712 `types` of an authorization message with the given primary type
+    pub fn authorization_types(primary_type: &str, domain: &Domain) -> serde_json::Value {
+        let mut domain_fields = vec![
+            json!({"name": "name", "type": "string"}),
+            json!({"name": "version", "type": "string"}),
+            json!({"name": "chainId", "type": "uint256"}),
+            json!({"name": "verifyingContract", "type": "address"}),
+        ];
+        if domain.salt.is_some() {
+            domain_fields.push(json!({"name": "salt", "type": "bytes32"}));
+        }
+        json!({
+            "EIP712Domain": domain_fields,
+            primary_type: [
+                {"name": "from", "type": "address"},
+                {"name": "to", "type": "address"},
+                {"name": "value", "type": "uint256"},
+                {"name": "validAfter", "type": "uint256"},
+                {"name": "validBefore", "type": "uint256"},
+                {"name": "nonce", "type": "bytes32"}
+            ]
+        })
+    }
+
+    /// Encode a uint256 as fixed-width (32-byte) hex
+    fn uint256_hex(value: U256) -> String {
+        let mut bytes = [0u8; 32];
+        value.to_big_endian(&mut bytes);
+        format!("0x{}", hex::encode(bytes))
+    }
+
     /// Hash EIP-712 typed data
     pub fn hash_typed_data(typed_data: &TypedData) -> Result<H256> {
         // Full EIP-712 implementation following the specification
@@ -209,10 +341,19 @@ pub mod eip712 {
     }
 
     /// Hash the domain separator
-    fn hash_domain(domain: &Domain) -> Result<H256> {
-        let domain_type_hash = keccak256(
-            b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
-        );
+    ///
+    /// `salt` is appended to the domain type and its encoding only when set,
+    /// so unsalted domains hash as they always have.
+    pub(crate) fn hash_domain(domain: &Domain) -> Result<H256> {
+        let domain_type_hash = if domain.salt.is_some() {
+            keccak256(
+                b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract,bytes32 salt)",
+            )
+        } else {
+            keccak256(
+                b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+            )
+        };
 
         let name_hash = keccak256(domain.name.as_bytes());
         let version_hash = keccak256(domain.version.as_bytes());
@@ -225,6 +366,9 @@ pub mod eip712 {
         data.extend_from_slice(&version_hash);
         data.extend_from_slice(&chain_id_hash);
         data.extend_from_slice(&verifying_contract_hash);
+        if let Some(salt) = domain.salt {
+            data.extend_from_slice(salt.as_bytes());
+        }
 
         Ok(H256::from_slice(&keccak256(&data)))
     }
@@ -365,6 +509,11 @@ pub mod signature {
         message_hash: H256,
         expected_address: Address,
     ) -> Result<bool> {
+        Ok(recover_signer(signature, message_hash)? == expected_address)
+    }
+
+    /// Recover the address that signed `message_hash`
+    pub fn recover_signer(signature: &str, message_hash: H256) -> Result<Address> {
         let sig_bytes = hex::decode(signature.trim_start_matches("0x"))
             .map_err(|_| X402Error::invalid_signature("Invalid hex signature"))?;
 
@@ -374,7 +523,11 @@ pub mod signature {
 
         let r = H256::from_slice(&sig_bytes[0..32]);
         let s = H256::from_slice(&sig_bytes[32..64]);
-        let v = sig_bytes[64];
+        // Browser wallets sign with the legacy 27/28 recovery id
+        let v = match sig_bytes[64] {
+            v @ 27.. => v - 27,
+            v => v,
+        };
 
         let recovery_id = RecoveryId::try_from(v)
             .map_err(|_| X402Error::invalid_signature("Invalid recovery ID"))?;
@@ -393,9 +546,104 @@ pub mod signature {
                 .map_err(|_| X402Error::invalid_signature("Failed to recover public key"))?;
 
         // Convert to Ethereum address
-        let recovered_address = ethereum_address_from_pubkey(&verifying_key)?;
+        ethereum_address_from_pubkey(&verifying_key)
+    }
 
-        Ok(recovered_address == expected_address)
+    /// Memoizes signer recovery by signature and message hash
+    ///
+    /// A retried payload carries the same signature over the same message, so
+    /// its signer is looked up instead of being recovered again. Holds at most
+    /// `capacity` signers, evicting the oldest first.
+    #[derive(Debug)]
+    pub struct RecoveryCache {
+        capacity: usize,
+        signers: std::sync::Mutex<RecoveredSigners>,
+        recoveries: std::sync::atomic::AtomicU64,
+    }
+
+    #[derive(Debug, Default)]
+    struct RecoveredSigners {
+        by_key: std::collections::HashMap<(String, H256), Address>,
+        order: std::collections::VecDeque<(String, H256)>,
+    }
+
+    impl RecoveryCache {
+        /// Remember up to `capacity` recovered signers
+        pub fn new(capacity: usize) -> Self {
+            Self {
+                capacity: capacity.max(1),
+                signers: std::sync::Mutex::default(),
+                recoveries: std::sync::atomic::AtomicU64::new(0),
+            }
+        }
+
+        /// Recover the signer of `message_hash`, reusing an earlier recovery
+        pub fn recover(&self, signature: &str, message_hash: H256) -> Result<Address> {
+            let key = (signature.to_ascii_lowercase(), message_hash);
+            if let Some(signer) = self.lock().by_key.get(&key) {
+                return Ok(*signer);
+            }
+
+            // Recovered outside the lock; failures are not remembered
+            let signer = recover_signer(signature, message_hash)?;
+            self.recoveries
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+            let mut signers = self.lock();
+            if signers.by_key.insert(key.clone(), signer).is_none() {
+                signers.order.push_back(key);
+            }
+            while signers.order.len() > self.capacity {
+                if let Some(oldest) = signers.order.pop_front() {
+                    signers.by_key.remove(&oldest);
+                }
+            }
+            Ok(signer)
+        }
+
+        /// Signatures actually recovered, as opposed to looked up
+        pub fn recoveries(&self) -> u64 {
+            self.recoveries.load(std::sync::atomic::Ordering::Relaxed)
+        }
+
+        /// [`verify_payment_payload_with_extra`], recovering through this cache
+        pub fn verify_payment_payload(
+            &self,
+            payload: &crate::types::ExactEvmPayload,
+            expected_from: &str,
+            network: &str,
+            extra: Option<&serde_json::Value>,
+        ) -> Result<bool> {
+            verify_authorization(
+                payload,
+                expected_from,
+                &token_domain_for(network, extra)?,
+                eip712::create_transfer_with_authorization_hash,
+                Some(self),
+            )
+        }
+
+        /// [`verify_native_payment_payload`], recovering through this cache
+        pub fn verify_native_payment_payload(
+            &self,
+            payload: &crate::types::ExactEvmPayload,
+            expected_from: &str,
+            network: &str,
+        ) -> Result<bool> {
+            verify_authorization(
+                payload,
+                expected_from,
+                &native_domain_for(network)?,
+                eip712::create_native_transfer_authorization_hash,
+                Some(self),
+            )
+        }
+
+        fn lock(&self) -> std::sync::MutexGuard<'_, RecoveredSigners> {
+            self.signers
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+        }
     }
 
     /// Sign a message hash with a private key
@@ -430,9 +678,21 @@ pub mod signature {
         Ok(format!("0x{}", hex::encode(sig_bytes)))
     }
 
+    /// Derive the Ethereum address controlled by a private key
+    pub fn address_from_private_key(private_key: &str) -> Result<Address> {
+        let private_key_bytes = hex::decode(private_key.trim_start_matches("0x"))
+            .map_err(|_| X402Error::invalid_signature("Invalid hex private key"))?;
+
+        let signing_key = k256::ecdsa::SigningKey::from_slice(&private_key_bytes)
+            .map_err(|_| X402Error::invalid_signature("Invalid private key"))?;
+
+        ethereum_address_from_pubkey(signing_key.verifying_key())
+    }
+
     /// Convert a public key to an Ethereum address
     fn ethereum_address_from_pubkey(pubkey: &k256::ecdsa::VerifyingKey) -> Result<Address> {
-        let pubkey_bytes = pubkey.to_sec1_bytes();
+        let encoded = pubkey.to_encoded_point(false);
+        let pubkey_bytes = encoded.as_bytes();
         if pubkey_bytes.len() != 65 {
             return Err(X402Error::invalid_signature("Invalid public key length"));
         }
@@ -485,43 +745,181 @@ pub mod signature {
     }
 
     /// Keccak-256 hash function
-    fn keccak256(data: &[u8]) -> [u8; 32] {
+    pub fn keccak256(data: &[u8]) -> [u8; 32] {
         use sha3::{Digest, Keccak256};
         Keccak256::digest(data).into()
     }
 
     /// Generate a random nonce for EIP-3009 authorization
     pub fn generate_nonce() -> H256 {
-        use rand::RngCore;
+        generate_nonce_with(&mut rand::thread_rng())
+    }
+
+    /// Generate an EIP-3009 nonce from the given RNG
+    pub fn generate_nonce_with<R: rand::RngCore + ?Sized>(rng: &mut R) -> H256 {
         let mut bytes = [0u8; 32];
-        rand::thread_rng().fill_bytes(&mut bytes);
+        rng.fill_bytes(&mut bytes);
         H256::from_slice(&bytes)
     }
 
+    /// Source of EIP-3009 authorization nonces
+    ///
+    /// Production code should keep [`RandomNonceSource`]; the other sources
+    /// exist for reproducible tests and deterministic agents.
+    pub trait NonceSource: Send + Sync {
+        /// Produce the next nonce
+        fn next_nonce(&self) -> H256;
+    }
+
+    /// Cryptographically secure random nonces from the thread-local RNG
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct RandomNonceSource;
+
+    impl NonceSource for RandomNonceSource {
+        fn next_nonce(&self) -> H256 {
+            generate_nonce()
+        }
+    }
+
+    /// Nonces drawn from a caller-supplied RNG
+    #[derive(Debug)]
+    pub struct RngNonceSource<R> {
+        rng: std::sync::Mutex<R>,
+    }
+
+    impl<R: rand::RngCore + Send> RngNonceSource<R> {
+        /// Draw nonces from `rng`
+        pub fn new(rng: R) -> Self {
+            Self {
+                rng: std::sync::Mutex::new(rng),
+            }
+        }
+    }
+
+    impl RngNonceSource<rand::rngs::StdRng> {
+        /// Draw nonces from a `StdRng` seeded with `seed`
+        ///
+        /// Not suitable for production: anyone who knows the seed can predict nonces.
+        pub fn seeded(seed: u64) -> Self {
+            use rand::SeedableRng;
+            Self::new(rand::rngs::StdRng::seed_from_u64(seed))
+        }
+    }
+
+    impl<R: rand::RngCore + Send> NonceSource for RngNonceSource<R> {
+        fn next_nonce(&self) -> H256 {
+            let mut rng = self.rng.lock().unwrap_or_else(|e| e.into_inner());
+            generate_nonce_with(&mut *rng)
+        }
+    }
+
+    /// Sequential nonces: the counter as a big-endian 256-bit integer
+    #[derive(Debug, Default)]
+    pub struct CounterNonceSource {
+        next: std::sync::atomic::AtomicU64,
+    }
+
+    impl CounterNonceSource {
+        /// Count up from `start`
+        pub fn new(start: u64) -> Self {
+            Self {
+                next: std::sync::atomic::AtomicU64::new(start),
+            }
+        }
+    }
+
+    impl NonceSource for CounterNonceSource {
+        fn next_nonce(&self) -> H256 {
+            let n = self.next.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            H256::from_low_u64_be(n)
+        }
+    }
+
     /// Verify a payment payload signature
     pub fn verify_payment_payload(
         payload: &crate::types::ExactEvmPayload,
         expected_from: &str,
         network: &str,
     ) -> Result<bool> {
-        let from_addr = Address::from_str(expected_from)
-            .map_err(|_| X402Error::invalid_signature("Invalid from address"))?;
+        verify_payment_payload_with_extra(payload, expected_from, network, None)
+    }
 
-        // Create the message hash from authorization
-        let auth = &payload.authorization;
+    /// Verify a payment payload signature under the token domain in `extra`
+    ///
+    /// `extra` is the payment requirements' `extra`; without a `name` or
+    /// `version` there, USDC's domain is assumed as in [`verify_payment_payload`].
+    pub fn verify_payment_payload_with_extra(
+        payload: &crate::types::ExactEvmPayload,
+        expected_from: &str,
+        network: &str,
+        extra: Option<&serde_json::Value>,
+    ) -> Result<bool> {
+        verify_authorization(
+            payload,
+            expected_from,
+            &token_domain_for(network, extra)?,
+            eip712::create_transfer_with_authorization_hash,
+            None,
+        )
+    }
+
+    /// Verify the signature of a native gas-token payment payload
+    pub fn verify_native_payment_payload(
+        payload: &crate::types::ExactEvmPayload,
+        expected_from: &str,
+        network: &str,
+    ) -> Result<bool> {
+        verify_authorization(
+            payload,
+            expected_from,
+            &native_domain_for(network)?,
+            eip712::create_native_transfer_authorization_hash,
+            None,
+        )
+    }
 
+    /// Domain of the network's USDC, adjusted by the requirements' `extra`
+    fn token_domain_for(
+        network: &str,
+        extra: Option<&serde_json::Value>,
+    ) -> Result<eip712::Domain> {
         // Get network configuration based on the payment network
         let network_config = crate::types::NetworkConfig::from_name(network)
             .ok_or_else(|| X402Error::invalid_signature("Unsupported network"))?;
 
-        let message_hash = eip712::create_transfer_with_authorization_hash(
-            &eip712::Domain {
-                name: "USD Coin".to_string(),
-                version: "2".to_string(),
-                chain_id: network_config.chain_id,
-                verifying_contract: Address::from_str(&network_config.usdc_contract)
-                    .map_err(|_| X402Error::invalid_signature("Invalid verifying contract"))?,
-            },
+        Ok(eip712::token_domain(
+            network_config.chain_id,
+            Address::from_str(&network_config.usdc_contract)
+                .map_err(|_| X402Error::invalid_signature("Invalid verifying contract"))?,
+            extra,
+        ))
+    }
+
+    /// Domain of native payments on the network
+    fn native_domain_for(network: &str) -> Result<eip712::Domain> {
+        let network_config = crate::types::NetworkConfig::from_name(network)
+            .ok_or_else(|| X402Error::invalid_signature("Unsupported network"))?;
+
+        Ok(eip712::native_payment_domain(network_config.chain_id))
+    }
+
+    /// Hash the payload's authorization with `hash_fn` and check the signer,
+    /// recovering through `cache` when given one
+    fn verify_authorization(
+        payload: &crate::types::ExactEvmPayload,
+        expected_from: &str,
+        domain: &eip712::Domain,
+        hash_fn: fn(&eip712::Domain, Address, Address, U256, U256, U256, H256) -> Result<H256>,
+        cache: Option<&RecoveryCache>,
+    ) -> Result<bool> {
+        let from_addr = Address::from_str(expected_from)
+            .map_err(|_| X402Error::invalid_signature("Invalid from address"))?;
+
+        // Create the message hash from authorization
+        let auth = &payload.authorization;
+
+        let message_hash = hash_fn(
+            domain,
             Address::from_str(&auth.from)
                 .map_err(|_| X402Error::invalid_signature("Invalid from address"))?,
             Address::from_str(&auth.to)
@@ -536,7 +934,11 @@ pub mod signature {
                 .map_err(|_| X402Error::invalid_signature("Invalid nonce"))?,
         )?;
 
-        verify_eip712_signature(&payload.signature, message_hash, from_addr)
+        let signer = match cache {
+            Some(cache) => cache.recover(&payload.signature, message_hash)?,
+            None => recover_signer(&payload.signature, message_hash)?,
+        };
+        Ok(signer == from_addr)
     }
 }
 
@@ -565,6 +967,7 @@ mod tests {
             chain_id: 8453,
             verifying_contract: Address::from_str("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913")
                 .unwrap(),
+            salt: None,
         };
 
         assert_eq!(domain.name, "USD Coin");
@@ -572,6 +975,69 @@ mod tests {
         assert_eq!(domain.chain_id, 8453);
     }
 
+    #[test]
+    fn test_salted_domain() {
+        let private_key = "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef";
+        let signer = crate::signer::LocalSigner::new(private_key).unwrap();
+        let from = crate::signer::Signer::address(&signer);
+        let domain = eip712::token_domain(
+            8453,
+            Address::from_str("0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913").unwrap(),
+            None,
+        );
+        let salted = eip712::token_domain(
+            domain.chain_id,
+            domain.verifying_contract,
+            Some(&json!({
+                "salt": "0x0000000000000000000000000000000000000000000000000000000000002105"
+            })),
+        );
+        assert_eq!(salted.salt, Some(H256::from_low_u64_be(8453)));
+
+        // Unsalted domains keep their separator; a salt changes it
+        assert_eq!(
+            eip712::hash_domain(&domain).unwrap(),
+            H256::from_str("0xeef4b0afe01530ae75370777e40b62aa5dad32ad71a3166449ed7126091007c3")
+                .unwrap()
+        );
+        assert_ne!(
+            eip712::hash_domain(&salted).unwrap(),
+            eip712::hash_domain(&domain).unwrap()
+        );
+
+        let hash = |domain: &eip712::Domain| {
+            eip712::create_transfer_with_authorization_hash(
+                domain,
+                from,
+                Address::from_str("0x209693Bc6afc0C5328bA36FaF03C514EF312287C").unwrap(),
+                U256::from(1000),
+                U256::from(1745323800),
+                U256::from(1745323985),
+                H256::from_low_u64_be(1),
+            )
+            .unwrap()
+        };
+        let signature = signature::sign_message_hash(hash(&salted), private_key).unwrap();
+        assert!(signature::verify_eip712_signature(&signature, hash(&salted), from).unwrap());
+        assert!(!signature::verify_eip712_signature(&signature, hash(&domain), from).unwrap());
+    }
+
+    #[test]
+    fn test_nonce_sources() {
+        use signature::{CounterNonceSource, NonceSource, RngNonceSource};
+
+        let counter = CounterNonceSource::new(7);
+        assert_eq!(counter.next_nonce(), H256::from_low_u64_be(7));
+        assert_eq!(counter.next_nonce(), H256::from_low_u64_be(8));
+
+        // The same seed yields the same sequence
+        let (a, b) = (RngNonceSource::seeded(42), RngNonceSource::seeded(42));
+        let first = a.next_nonce();
+        assert_eq!(first, b.next_nonce());
+        assert_eq!(a.next_nonce(), b.next_nonce());
+        assert_ne!(first, RngNonceSource::seeded(43).next_nonce());
+    }
+
     #[test]
     fn test_nonce_generation() {
         let nonce1 = signature::generate_nonce();
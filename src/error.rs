@@ -91,6 +91,26 @@ pub enum X402Error {
     #[error("Recipient mismatch: expected {expected}, got {got}")]
     RecipientMismatch { expected: String, got: String },
 
+    /// Requested payment is above the configured limit (atomic units)
+    #[error("Payment of {requested} exceeds the limit of {limit}")]
+    PaymentExceedsLimit { limit: String, requested: String },
+
+    /// Payer balance cannot cover the payment (atomic units)
+    #[error("Insufficient balance: need {needed}, have {available}")]
+    InsufficientBalance { needed: String, available: String },
+
+    /// Settlement simulation predicted a revert
+    #[error("Simulation reverted: {reason}")]
+    SimulationReverted { reason: String },
+
+    /// Protocol version outside the supported range
+    #[error("Unsupported x402 version {got}: expected {expected}")]
+    UnsupportedVersion { got: u32, expected: u32 },
+
+    /// Malformed address
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+
     /// Unexpected error
     #[error("Unexpected error: {message}")]
     Unexpected { message: String },
@@ -241,6 +261,11 @@ impl X402Error {
             Self::AuthorizationNotYetValid => 401,
             Self::InvalidAmount { .. } => 400,
             Self::RecipientMismatch { .. } => 400,
+            Self::PaymentExceedsLimit { .. } => 400,
+            Self::InsufficientBalance { .. } => 402,
+            Self::SimulationReverted { .. } => 402,
+            Self::UnsupportedVersion { .. } => 400,
+            Self::InvalidAddress(_) => 400,
             Self::Unexpected { .. } => 500,
             Self::Config { .. } => 500,
             Self::Timeout => 408,
@@ -283,6 +308,11 @@ impl X402Error {
             Self::AuthorizationNotYetValid => "authorization_not_yet_valid",
             Self::InvalidAmount { .. } => "invalid_amount",
             Self::RecipientMismatch { .. } => "recipient_mismatch",
+            Self::PaymentExceedsLimit { .. } => "payment_exceeds_limit",
+            Self::InsufficientBalance { .. } => "insufficient_balance",
+            Self::SimulationReverted { .. } => "simulation_reverted",
+            Self::UnsupportedVersion { .. } => "unsupported_version",
+            Self::InvalidAddress(_) => "invalid_address",
             Self::Unexpected { .. } => "unexpected_error",
             Self::Config { .. } => "configuration_error",
             Self::Timeout => "timeout",
@@ -305,6 +335,28 @@ impl X402Error {
             Self::H3(_) => "h3_error",
         }
     }
+
+    /// Structured fields of this error for API responses, if it has any
+    pub fn details(&self) -> Option<serde_json::Value> {
+        let details = match self {
+            Self::InvalidAmount { expected, got } | Self::RecipientMismatch { expected, got } => {
+                serde_json::json!({ "expected": expected, "got": got })
+            }
+            Self::PaymentExceedsLimit { limit, requested } => {
+                serde_json::json!({ "limit": limit, "requested": requested })
+            }
+            Self::InsufficientBalance { needed, available } => {
+                serde_json::json!({ "needed": needed, "available": available })
+            }
+            Self::SimulationReverted { reason } => serde_json::json!({ "reason": reason }),
+            Self::UnsupportedVersion { got, expected } => {
+                serde_json::json!({ "got": got, "expected": expected })
+            }
+            Self::InvalidAddress(address) => serde_json::json!({ "address": address }),
+            _ => return None,
+        };
+        Some(details)
+    }
 }
 
 /// Unified error response structure
@@ -333,7 +385,7 @@ impl ErrorResponse {
             error_type: error.error_type().to_string(),
             status_code: error.status_code(),
             x402_version: 1,
-            details: None,
+            details: error.details(),
         }
     }
 
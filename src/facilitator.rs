@@ -1,8 +1,15 @@
 //! Facilitator client for payment verification and settlement
 
+pub mod local;
+pub mod settler;
+
+pub use local::{LocalFacilitator, LocalVerifier};
+pub use settler::{FacilitatorSettler, MockSettler, OnChainSettler, Settler};
+
 use crate::client::DiscoveryFilters;
 use crate::types::*;
 use crate::{Result, X402Error};
+use futures_util::{stream, Stream, TryStreamExt};
 use reqwest::Client;
 use serde_json::json;
 use std::collections::HashMap;
@@ -19,6 +26,19 @@ pub struct FacilitatorClient {
     client: Client,
     /// Configuration for authentication headers
     auth_config: Option<crate::types::AuthHeadersFnArc>,
+    /// Endpoint paths appended to the base URL
+    endpoints: FacilitatorEndpoints,
+    /// Per-request timeout for verify calls
+    verify_timeout: Option<std::time::Duration>,
+    /// Per-request timeout for settle calls
+    settle_timeout: Option<std::time::Duration>,
+    /// Whether signatures and nonces are masked in logs
+    redact_logs: bool,
+    /// How long a `/supported` response is reused
+    supported_cache_ttl: Option<std::time::Duration>,
+    /// Last `/supported` response and when it was fetched, shared by clones
+    supported_cache:
+        std::sync::Arc<tokio::sync::Mutex<Option<(std::time::Instant, SupportedKinds)>>>,
 }
 
 impl std::fmt::Debug for FacilitatorClient {
@@ -26,6 +46,11 @@ impl std::fmt::Debug for FacilitatorClient {
         f.debug_struct("FacilitatorClient")
             .field("url", &self.url)
             .field("auth_config", &"<function>")
+            .field("endpoints", &self.endpoints)
+            .field("verify_timeout", &self.verify_timeout)
+            .field("settle_timeout", &self.settle_timeout)
+            .field("redact_logs", &self.redact_logs)
+            .field("supported_cache_ttl", &self.supported_cache_ttl)
             .finish()
     }
 }
@@ -36,24 +61,62 @@ impl FacilitatorClient {
         // Validate configuration first
         config.validate()?;
 
-        let mut client_builder = Client::builder();
+        let client = match config.http_client {
+            Some(client) => client,
+            None => {
+                let mut client_builder = Client::builder();
 
-        if let Some(timeout) = config.timeout {
-            client_builder = client_builder.timeout(timeout);
-        }
+                if let Some(timeout) = config.timeout {
+                    client_builder = client_builder.timeout(timeout);
+                }
 
-        let client = client_builder
-            .build()
-            .map_err(|e| X402Error::config(format!("Failed to create HTTP client: {}", e)))?;
+                client_builder.build().map_err(|e| {
+                    X402Error::config(format!("Failed to create HTTP client: {}", e))
+                })?
+            }
+        };
 
         Ok(Self {
             url: config.url,
             client,
             auth_config: config.create_auth_headers,
+            endpoints: config.endpoints,
+            verify_timeout: config.verify_timeout,
+            settle_timeout: config.settle_timeout,
+            redact_logs: true,
+            supported_cache_ttl: config.supported_cache_ttl,
+            supported_cache: Default::default(),
         })
     }
 
+    /// Enable or disable masking of signatures and nonces in logs
+    ///
+    /// Redaction is on by default; disable it only when debugging locally.
+    pub fn with_log_redaction(mut self, redact: bool) -> Self {
+        self.redact_logs = redact;
+        self
+    }
+
+    /// Serialize a value for logging, masking authorization material
+    fn loggable<T: serde::Serialize>(&self, value: &T) -> serde_json::Value {
+        let mut value = serde_json::to_value(value).unwrap_or_default();
+        if self.redact_logs {
+            redact_value(&mut value);
+        }
+        value
+    }
+
     /// Verify a payment without executing the transaction
+    #[tracing::instrument(
+        name = "x402.verify",
+        skip_all,
+        fields(
+            x402.network = %payment_payload.network,
+            x402.scheme = %payment_payload.scheme,
+            x402.payer = %payment_payload.payload.authorization.from,
+            x402.amount = %payment_payload.payload.authorization.value,
+        )
+    )]
     pub async fn verify(
         &self,
         payment_payload: &PaymentPayload,
@@ -61,7 +124,7 @@ impl FacilitatorClient {
     ) -> Result<VerifyResponse> {
         tracing::debug!(
             "Payment payload: {}",
-            serde_json::to_string_pretty(payment_payload).unwrap_or_default()
+            serde_json::to_string_pretty(&self.loggable(payment_payload)).unwrap_or_default()
         );
         tracing::debug!(
             "Payment requirements: {}",
@@ -75,14 +138,15 @@ impl FacilitatorClient {
 
         tracing::debug!(
             "Facilitator verify request body: {}",
-            serde_json::to_string_pretty(&request_body).unwrap_or_default()
+            serde_json::to_string_pretty(&self.loggable(&request_body)).unwrap_or_default()
         );
-        tracing::debug!("Sending request to: {}/verify", self.url);
+        let url = self.endpoint_url(&self.endpoints.verify);
+        tracing::debug!("Sending request to: {}", url);
 
-        let mut request = self
-            .client
-            .post(format!("{}/verify", self.url))
-            .json(&request_body);
+        let mut request = self.client.post(url).json(&request_body);
+        if let Some(timeout) = self.verify_timeout {
+            request = request.timeout(timeout);
+        }
 
         // Add authentication headers if available
         if let Some(auth_config) = &self.auth_config {
@@ -105,22 +169,33 @@ impl FacilitatorClient {
             tracing::error!(
                 "Facilitator verify failed with status: {}. Request body: {}. Response body: {}",
                 status,
-                serde_json::to_string_pretty(&request_body).unwrap_or_default(),
+                serde_json::to_string_pretty(&self.loggable(&request_body)).unwrap_or_default(),
                 response_body
             );
             return Err(X402Error::facilitator_error(format!(
                 "Verification failed with status: {}. Response: {}. Request: {}",
                 status,
                 response_body,
-                serde_json::to_string(&request_body).unwrap_or_default()
+                self.loggable(&request_body)
             )));
         }
 
         let verify_response: VerifyResponse = response.json().await?;
-        Ok(verify_response)
+        Ok(verify_response.fill_payer(payment_payload))
     }
 
     /// Settle a verified payment by executing the transaction
+    #[tracing::instrument(
+        name = "x402.settle",
+        skip_all,
+        fields(
+            x402.network = %payment_payload.network,
+            x402.scheme = %payment_payload.scheme,
+            x402.payer = %payment_payload.payload.authorization.from,
+            x402.amount = %payment_payload.payload.authorization.value,
+            x402.tx_hash = tracing::field::Empty,
+        )
+    )]
     pub async fn settle(
         &self,
         payment_payload: &PaymentPayload,
@@ -133,8 +208,11 @@ impl FacilitatorClient {
 
         let mut request = self
             .client
-            .post(format!("{}/settle", self.url))
+            .post(self.endpoint_url(&self.endpoints.settle))
             .json(&request_body);
+        if let Some(timeout) = self.settle_timeout {
+            request = request.timeout(timeout);
+        }
 
         // Add authentication headers if available
         if let Some(auth_config) = &self.auth_config {
@@ -156,12 +234,58 @@ impl FacilitatorClient {
         }
 
         let settle_response: SettleResponse = response.json().await?;
+        tracing::Span::current().record("x402.tx_hash", settle_response.transaction.as_str());
         Ok(settle_response)
     }
 
     /// Get supported payment schemes and networks
+    ///
+    /// With a [`supported_cache_ttl`](FacilitatorConfig::with_supported_cache_ttl),
+    /// a response younger than the TTL is returned without a request.
     pub async fn supported(&self) -> Result<SupportedKinds> {
-        let mut request = self.client.get(format!("{}/supported", self.url));
+        let Some(ttl) = self.supported_cache_ttl else {
+            return self.fetch_supported().await;
+        };
+
+        // Held across the fetch, so concurrent callers share one request
+        let mut cache = self.supported_cache.lock().await;
+        if let Some((fetched_at, kinds)) = cache.as_ref() {
+            if fetched_at.elapsed() < ttl {
+                return Ok(kinds.clone());
+            }
+        }
+        let kinds = self.fetch_supported().await?;
+        *cache = Some((std::time::Instant::now(), kinds.clone()));
+        Ok(kinds)
+    }
+
+    /// Whether the facilitator supports `scheme` on `network`
+    pub async fn supported_for(&self, scheme: &str, network: &str) -> Result<bool> {
+        Ok(self
+            .supported()
+            .await?
+            .kinds
+            .iter()
+            .any(|kind| kind.scheme == scheme && kind.network == network))
+    }
+
+    /// Networks on which the facilitator supports `scheme`, in response order
+    pub async fn networks_for_scheme(&self, scheme: &str) -> Result<Vec<String>> {
+        let mut networks: Vec<String> = Vec::new();
+        for kind in self.supported().await?.kinds {
+            // Kinds repeat a network once per protocol version
+            if kind.scheme == scheme && !networks.contains(&kind.network) {
+                networks.push(kind.network);
+            }
+        }
+        Ok(networks)
+    }
+
+    /// Request the supported kinds from the facilitator
+    async fn fetch_supported(&self) -> Result<SupportedKinds> {
+        let mut request = self
+            .client
+            .get(self.endpoint_url(&self.endpoints.supported));
 
         // Add authentication headers if available
         if let Some(auth_config) = &self.auth_config {
@@ -191,6 +315,16 @@ impl FacilitatorClient {
         &self.url
     }
 
+    /// Get the endpoint paths used by this facilitator
+    pub fn endpoints(&self) -> &FacilitatorEndpoints {
+        &self.endpoints
+    }
+
+    /// Build the full URL for an endpoint path
+    fn endpoint_url(&self, path: &str) -> String {
+        format!("{}{}", self.url, path)
+    }
+
     /// Create a facilitator client for a specific network
     pub fn for_network(_network: &str, config: FacilitatorConfig) -> Result<Self> {
         // For now, use the provided config as-is
@@ -239,19 +373,13 @@ impl FacilitatorClient {
     /// This method hits the `/discovery/resources` endpoint and forwards any auth headers,
     /// similar to TypeScript's `useFacilitator().list()` and Python's `FacilitatorClient.list()`
     pub async fn list(&self, filters: Option<DiscoveryFilters>) -> Result<DiscoveryResponse> {
-        let mut request = self.client.get(format!("{}/discovery/resources", self.url));
+        let mut request = self
+            .client
+            .get(self.endpoint_url(&self.endpoints.discovery));
 
         // Add query parameters if filters are provided
         if let Some(filters) = filters {
-            if let Some(resource_type) = filters.resource_type {
-                request = request.query(&[("type", resource_type)]);
-            }
-            if let Some(limit) = filters.limit {
-                request = request.query(&[("limit", limit.to_string())]);
-            }
-            if let Some(offset) = filters.offset {
-                request = request.query(&[("offset", offset.to_string())]);
-            }
+            request = request.query(&filters.query_params());
         }
 
         // Add authentication headers if available
@@ -287,6 +415,46 @@ impl FacilitatorClient {
         let filters = DiscoveryFilters::new().with_resource_type(resource_type);
         self.list(Some(filters)).await
     }
+
+    /// Stream all discovery resources, following pagination
+    ///
+    /// Pages are fetched lazily as the stream is polled, starting at the
+    /// filters' offset and using their limit as the page size. The stream ends
+    /// once `pagination.total` is reached or a page comes back short.
+    pub fn list_stream(
+        &self,
+        filters: Option<DiscoveryFilters>,
+    ) -> impl Stream<Item = Result<DiscoveryResource>> + '_ {
+        let filters = filters.unwrap_or_default();
+        let start = filters.offset.unwrap_or(0);
+
+        stream::try_unfold(Some(start), move |offset| {
+            self.next_discovery_page(filters.clone(), offset)
+        })
+        .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Fetch one discovery page for [`list_stream`](Self::list_stream)
+    ///
+    /// Returns the page's items and the offset of the next page, if any.
+    async fn next_discovery_page(
+        &self,
+        filters: DiscoveryFilters,
+        offset: Option<u32>,
+    ) -> Result<Option<(Vec<DiscoveryResource>, Option<u32>)>> {
+        let Some(offset) = offset else {
+            return Ok(None);
+        };
+
+        let page = self.list(Some(filters.with_offset(offset))).await?;
+        let fetched = page.items.len() as u32;
+        let next_offset = offset.saturating_add(fetched);
+        let exhausted =
+            fetched == 0 || fetched < page.pagination.limit || next_offset >= page.pagination.total;
+
+        Ok(Some((page.items, (!exhausted).then_some(next_offset))))
+    }
 }
 
 impl Default for FacilitatorClient {
@@ -297,11 +465,59 @@ impl Default for FacilitatorClient {
                 url: "https://x402.org/facilitator".to_string(),
                 client: Client::new(),
                 auth_config: None,
+                endpoints: FacilitatorEndpoints::new(),
+                verify_timeout: None,
+                settle_timeout: None,
+                redact_logs: true,
+                supported_cache_ttl: None,
+                supported_cache: Default::default(),
             }
         })
     }
 }
 
+/// Mask signatures and nonces anywhere in a JSON value
+///
+/// Signatures keep their first and last 6 characters; nonces keep their first 10.
+fn redact_value(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match (key.as_str(), value.as_str()) {
+                    ("signature", Some(signature)) => {
+                        *value = json!(mask_middle(signature, 6));
+                    }
+                    ("nonce", Some(nonce)) => {
+                        *value = json!(truncate(nonce, 10));
+                    }
+                    _ => redact_value(value),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// Keep `keep` characters at each end of `s`
+fn mask_middle(s: &str, keep: usize) -> String {
+    if !s.is_ascii() || s.len() <= keep * 2 {
+        return "***".to_string();
+    }
+    format!("{}...{}", &s[..keep], &s[s.len() - keep..])
+}
+
+/// Keep the first `keep` characters of `s`
+fn truncate(s: &str, keep: usize) -> String {
+    if !s.is_ascii() {
+        return "***".to_string();
+    }
+    if s.len() <= keep {
+        return s.to_string();
+    }
+    format!("{}...", &s[..keep])
+}
+
 /// Coinbase facilitator integration
 pub mod coinbase {
     use super::*;
@@ -382,11 +598,9 @@ pub mod coinbase {
 
     /// Create a facilitator config for Coinbase
     pub fn create_facilitator_config(api_key_id: &str, api_key_secret: &str) -> FacilitatorConfig {
-        FacilitatorConfig::new(format!(
-            "{}{}",
-            COINBASE_FACILITATOR_BASE_URL, COINBASE_FACILITATOR_V2_ROUTE
-        ))
-        .with_auth_headers(Box::new(create_auth_headers(api_key_id, api_key_secret)))
+        FacilitatorConfig::new(COINBASE_FACILITATOR_BASE_URL)
+            .with_endpoints(FacilitatorEndpoints::new().with_prefix(COINBASE_FACILITATOR_V2_ROUTE))
+            .with_auth_headers(Box::new(create_auth_headers(api_key_id, api_key_secret)))
     }
 
     /// Create correlation header for requests
@@ -482,6 +696,30 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_facilitator_verify_derives_missing_payer() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"x402Version": 1, "isValid": true}).to_string())
+            .create();
+
+        let client = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let payment_payload = create_test_payment_payload();
+
+        let response = client
+            .verify(&payment_payload, &create_test_payment_requirements())
+            .await
+            .unwrap();
+        assert!(response.is_valid);
+        assert_eq!(
+            response.payer.as_deref(),
+            Some(payment_payload.payload.authorization.from.as_str())
+        );
+    }
+
     #[tokio::test]
     async fn test_facilitator_verify_failure() {
         let mut server = Server::new_async().await;
@@ -918,6 +1156,147 @@ mod tests {
         assert_eq!(discovery_response.pagination.limit, 5);
     }
 
+    #[tokio::test]
+    async fn test_facilitator_discovery_with_metadata_filters() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/discovery/resources")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("network".to_string(), "base-sepolia".to_string()),
+                Matcher::UrlEncoded("scheme".to_string(), "exact".to_string()),
+                Matcher::UrlEncoded("q".to_string(), "weather api".to_string()),
+                Matcher::UrlEncoded("limit".to_string(), "10".to_string()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "items": [],
+                    "pagination": {
+                        "total": 0,
+                        "limit": 10,
+                        "offset": 0
+                    }
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let client = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let filters = DiscoveryFilters::new()
+            .with_network("base-sepolia")
+            .with_scheme("exact")
+            .with_search("weather api")
+            .with_limit(10);
+
+        let response = client.list(Some(filters)).await.unwrap();
+        assert!(response.items.is_empty());
+        mock.assert();
+    }
+
+    fn discovery_page(resources: &[&str], total: u32, limit: u32, offset: u32) -> String {
+        let items: Vec<_> = resources
+            .iter()
+            .map(|resource| {
+                json!({
+                    "resource": resource,
+                    "type": "http",
+                    "x402Version": 1,
+                    "accepts": [],
+                    "lastUpdated": 1745323800
+                })
+            })
+            .collect();
+        json!({
+            "x402Version": 1,
+            "items": items,
+            "pagination": { "total": total, "limit": limit, "offset": offset }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_list_stream_follows_pagination() {
+        let mut server = Server::new_async().await;
+        let pages = [
+            (0, vec!["https://a.example", "https://b.example"]),
+            (2, vec!["https://c.example", "https://d.example"]),
+            (4, vec!["https://e.example"]),
+        ];
+        let mut mocks = Vec::new();
+        for (offset, resources) in &pages {
+            mocks.push(
+                server
+                    .mock("GET", "/discovery/resources")
+                    .match_query(Matcher::AllOf(vec![
+                        Matcher::UrlEncoded("limit".to_string(), "2".to_string()),
+                        Matcher::UrlEncoded("offset".to_string(), offset.to_string()),
+                    ]))
+                    .with_status(200)
+                    .with_body(discovery_page(resources, 5, 2, *offset))
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
+
+        let client = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let resources: Vec<String> = client
+            .list_stream(Some(DiscoveryFilters::new().with_limit(2)))
+            .map_ok(|item| item.resource)
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            resources,
+            vec![
+                "https://a.example",
+                "https://b.example",
+                "https://c.example",
+                "https://d.example",
+                "https://e.example",
+            ]
+        );
+        for mock in mocks {
+            mock.assert_async().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_list_stream_stops_on_empty_or_short_page() {
+        let mut server = Server::new_async().await;
+        let empty = server
+            .mock("GET", "/discovery/resources")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(discovery_page(&[], 0, 10, 0))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let client = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let items: Vec<_> = client.list_stream(None).try_collect().await.unwrap();
+        assert!(items.is_empty());
+        empty.assert_async().await;
+
+        // A short page ends the stream even if `total` claims more
+        empty.remove_async().await;
+        let short = server
+            .mock("GET", "/discovery/resources")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_body(discovery_page(&["https://a.example"], 50, 10, 0))
+            .expect(1)
+            .create_async()
+            .await;
+        let items: Vec<_> = client.list_stream(None).try_collect().await.unwrap();
+        assert_eq!(items.len(), 1);
+        short.assert_async().await;
+    }
+
     #[tokio::test]
     async fn test_facilitator_discovery_by_type() {
         let mut server = Server::new_async().await;
@@ -981,6 +1360,308 @@ mod tests {
             .contains("Discovery failed with status: 500"));
     }
 
+    #[tokio::test]
+    async fn test_facilitator_custom_endpoints() {
+        let mut server = Server::new_async().await;
+        let verify_mock = server
+            .mock("POST", "/api/v1/check")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"x402Version": 1, "isValid": true}).to_string())
+            .create();
+        let settle_mock = server
+            .mock("POST", "/api/v1/settle")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "success": true,
+                    "transaction": "0xabc",
+                    "network": "base-sepolia"
+                })
+                .to_string(),
+            )
+            .create();
+        let supported_mock = server
+            .mock("GET", "/api/v1/kinds")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"kinds": []}).to_string())
+            .create();
+        let discovery_mock = server
+            .mock("GET", "/api/v1/discovery/resources")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({
+                    "x402Version": 1,
+                    "items": [],
+                    "pagination": {"total": 0, "limit": 10, "offset": 0}
+                })
+                .to_string(),
+            )
+            .create();
+
+        let endpoints = FacilitatorEndpoints::new()
+            .with_verify("/check")
+            .with_supported("/kinds")
+            .with_prefix("/api/v1/");
+        assert_eq!(endpoints.settle, "/api/v1/settle");
+
+        let config = FacilitatorConfig::new(server.url()).with_endpoints(endpoints);
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payload = create_test_payment_payload();
+        let requirements = create_test_payment_requirements();
+        assert!(
+            client
+                .verify(&payload, &requirements)
+                .await
+                .unwrap()
+                .is_valid
+        );
+        assert!(
+            client
+                .settle(&payload, &requirements)
+                .await
+                .unwrap()
+                .success
+        );
+        assert!(client.supported().await.unwrap().kinds.is_empty());
+        assert!(client.list_all().await.unwrap().items.is_empty());
+
+        verify_mock.assert();
+        settle_mock.assert();
+        supported_mock.assert();
+        discovery_mock.assert();
+    }
+
+    /// Mock endpoint that responds with `body` after `delay`
+    fn slow_mock(
+        server: &mut mockito::ServerGuard,
+        path: &str,
+        body: serde_json::Value,
+        delay: Duration,
+    ) -> mockito::Mock {
+        let body = body.to_string();
+        server
+            .mock("POST", path)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_chunked_body(move |w| {
+                std::thread::sleep(delay);
+                w.write_all(body.as_bytes())
+            })
+            .create()
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_per_operation_timeouts() {
+        let mut server = Server::new_async().await;
+        let delay = Duration::from_millis(300);
+        let _verify = slow_mock(
+            &mut server,
+            "/verify",
+            json!({"x402Version": 1, "isValid": true}),
+            delay,
+        );
+        let _settle = slow_mock(
+            &mut server,
+            "/settle",
+            json!({"success": true, "transaction": "0xabc", "network": "base-sepolia"}),
+            delay,
+        );
+
+        let config = FacilitatorConfig::new(server.url())
+            .with_verify_timeout(Duration::from_millis(50))
+            .with_settle_timeout(Duration::from_secs(5));
+        let client = FacilitatorClient::new(config).unwrap();
+
+        let payload = create_test_payment_payload();
+        let requirements = create_test_payment_requirements();
+
+        let error = client.verify(&payload, &requirements).await.unwrap_err();
+        assert!(matches!(error, X402Error::Http(ref e) if e.is_timeout()));
+        assert!(
+            client
+                .settle(&payload, &requirements)
+                .await
+                .unwrap()
+                .success
+        );
+
+        // Without an override, settle falls back to the client timeout
+        let config = FacilitatorConfig::new(server.url())
+            .with_timeout(Duration::from_millis(50))
+            .with_verify_timeout(Duration::from_secs(5));
+        let client = FacilitatorClient::new(config).unwrap();
+
+        assert!(
+            client
+                .verify(&payload, &requirements)
+                .await
+                .unwrap()
+                .is_valid
+        );
+        let error = client.settle(&payload, &requirements).await.unwrap_err();
+        assert!(matches!(error, X402Error::Http(ref e) if e.is_timeout()));
+    }
+
+    /// Writer capturing formatted log output
+    #[derive(Clone, Default)]
+    struct LogBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Run verify with debug logging captured and return the log output
+    async fn captured_verify_logs(client: &FacilitatorClient) -> String {
+        let buffer = LogBuffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(move || writer.clone())
+            .finish();
+
+        let _guard = tracing::subscriber::set_default(subscriber);
+        let payload = create_test_payment_payload();
+        let requirements = create_test_payment_requirements();
+        client.verify(&payload, &requirements).await.unwrap();
+
+        let logs = buffer.0.lock().unwrap().clone();
+        String::from_utf8(logs).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_redacts_logs() {
+        let mut server = Server::new_async().await;
+        let _mock = server
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"x402Version": 1, "isValid": true}).to_string())
+            .expect(2)
+            .create();
+
+        let payload = create_test_payment_payload();
+        let signature = payload.payload.signature.clone();
+        let nonce = payload.payload.authorization.nonce.clone();
+
+        let client = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        let logs = captured_verify_logs(&client).await;
+        assert!(logs.contains("Facilitator verify request body"));
+        assert!(logs.contains(&signature[..6]));
+        assert!(!logs.contains(&signature));
+        assert!(!logs.contains(&nonce));
+
+        let client = client.with_log_redaction(false);
+        let logs = captured_verify_logs(&client).await;
+        assert!(logs.contains(&signature));
+    }
+
+    #[tokio::test]
+    async fn test_facilitator_uses_shared_http_client() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/supported")
+            .match_header("x-shared-client", "1")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"kinds": []}).to_string())
+            .expect(2)
+            .create();
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-shared-client", "1".parse().unwrap());
+        let shared = Client::builder().default_headers(headers).build().unwrap();
+
+        let config = FacilitatorConfig::new(server.url()).with_http_client(shared);
+        // Both clients send through the injected client rather than a new one
+        let first = FacilitatorClient::new(config.clone()).unwrap();
+        let second = FacilitatorClient::new(config).unwrap();
+        first.supported().await.unwrap();
+        second.supported().await.unwrap();
+
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_supported_filters() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(
+                json!({"kinds": [
+                    {"x402Version": 1, "scheme": "exact", "network": "base-sepolia"},
+                    {"x402Version": 2, "scheme": "exact", "network": "base-sepolia"},
+                    {"x402Version": 1, "scheme": "exact", "network": "base"},
+                    {"x402Version": 1, "scheme": "upto", "network": "avalanche"}
+                ]})
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+
+        let config = FacilitatorConfig::new(server.url())
+            .with_supported_cache_ttl(std::time::Duration::from_secs(60));
+        let client = FacilitatorClient::new(config).unwrap();
+
+        assert!(client.supported_for("exact", "base").await.unwrap());
+        assert!(client.supported_for("upto", "avalanche").await.unwrap());
+        assert!(!client.supported_for("exact", "avalanche").await.unwrap());
+        assert!(!client.supported_for("upto", "base").await.unwrap());
+        assert_eq!(
+            client.networks_for_scheme("exact").await.unwrap(),
+            ["base-sepolia", "base"]
+        );
+        assert!(client
+            .networks_for_scheme("streaming")
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Every lookup, on the client or a clone, was served from one response
+        client.clone().supported().await.unwrap();
+        mock.assert();
+    }
+
+    #[tokio::test]
+    async fn test_supported_uncached_by_default() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(json!({"kinds": []}).to_string())
+            .expect(2)
+            .create();
+
+        let client = FacilitatorClient::new(FacilitatorConfig::new(server.url())).unwrap();
+        assert!(!client.supported_for("exact", "base").await.unwrap());
+        assert!(!client.supported_for("exact", "base").await.unwrap());
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_coinbase_config_endpoints() {
+        let config = coinbase::default_coinbase_config();
+        assert_eq!(config.url, coinbase::COINBASE_FACILITATOR_BASE_URL);
+        assert_eq!(config.endpoints.verify, "/platform/v2/x402/verify");
+        assert_eq!(config.endpoints.settle, "/platform/v2/x402/settle");
+    }
+
     #[tokio::test]
     async fn test_facilitator_supported_with_auth_headers() {
         let mut server = Server::new_async().await;
@@ -1019,7 +1700,12 @@ mod tests {
         let config = FacilitatorConfig {
             url: server.url(),
             timeout: None,
+            verify_timeout: None,
+            settle_timeout: None,
             create_auth_headers: Some(std::sync::Arc::new(auth_config)),
+            endpoints: FacilitatorEndpoints::default(),
+            http_client: None,
+            supported_cache_ttl: None,
         };
         let client = FacilitatorClient::new(config).unwrap();
 
@@ -1083,7 +1769,12 @@ mod tests {
         let config = FacilitatorConfig {
             url: "invalid-url".to_string(),
             timeout: None,
+            verify_timeout: None,
+            settle_timeout: None,
             create_auth_headers: None,
+            endpoints: FacilitatorEndpoints::default(),
+            http_client: None,
+            supported_cache_ttl: None,
         };
 
         let result = FacilitatorClient::new(config);
@@ -1100,7 +1791,12 @@ mod tests {
         let config = FacilitatorConfig {
             url: "https://example.com/facilitator".to_string(),
             timeout: Some(std::time::Duration::from_secs(30)),
+            verify_timeout: None,
+            settle_timeout: None,
             create_auth_headers: None,
+            endpoints: FacilitatorEndpoints::default(),
+            http_client: None,
+            supported_cache_ttl: None,
         };
 
         let result = FacilitatorClient::new(config);
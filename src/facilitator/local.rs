@@ -0,0 +1,1141 @@
+//! In-process payment verification
+//!
+//! [`LocalVerifier`] performs the checks a facilitator's `/verify` endpoint
+//! would — signature, validity window, recipient, amount and nonce replay —
+//! without a network round trip. Only settlement still needs a facilitator
+//! (or a [`BlockchainFacilitatorClient`](crate::BlockchainFacilitatorClient)).
+//!
+//! [`LocalFacilitator`] pairs the verifier with a [`Settler`] to implement a
+//! whole facilitator in-process; the facilitator binaries are built on it.
+
+use super::settler::Settler;
+use crate::crypto::signature::{
+    verify_native_payment_payload, verify_payment_payload_with_extra, RecoveryCache,
+};
+use crate::facilitator_storage::{canonical_nonce, nonce_scope, NonceStorage};
+#[cfg(feature = "solana")]
+use crate::solana::{settle_solana_payment, verify_solana_payment, SolanaPaymentPayload};
+use crate::types::{
+    Amount, NetworkConfig, PaymentPayload, PaymentRequirements, SettleResponse, VerifyResponse,
+    DEFAULT_CLOCK_SKEW,
+};
+use crate::{Result, X402Error};
+use futures_util::stream::{self, StreamExt, TryStreamExt};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Verifies payments in-process, tracking used nonces in a [`NonceStorage`]
+#[derive(Clone)]
+pub struct LocalVerifier {
+    storage: Arc<dyn NonceStorage>,
+    clock_skew: Duration,
+    allowed_recipients: Option<Vec<String>>,
+    min_amount: Option<Decimal>,
+    batch_parallelism: usize,
+    recovery_cache: Option<Arc<RecoveryCache>>,
+}
+
+impl LocalVerifier {
+    /// Create a verifier recording nonces in `storage`
+    pub fn new(storage: impl NonceStorage + 'static) -> Self {
+        Self::with_shared_storage(Arc::new(storage))
+    }
+
+    /// Create a verifier recording nonces in storage shared with other users
+    pub fn with_shared_storage(storage: Arc<dyn NonceStorage>) -> Self {
+        Self {
+            storage,
+            clock_skew: DEFAULT_CLOCK_SKEW,
+            allowed_recipients: None,
+            min_amount: None,
+            batch_parallelism: std::thread::available_parallelism().map_or(4, usize::from),
+            recovery_cache: None,
+        }
+    }
+
+    /// Set the clock skew tolerated on authorization validity windows
+    pub fn with_clock_skew(mut self, clock_skew: Duration) -> Self {
+        self.clock_skew = clock_skew;
+        self
+    }
+
+    /// Only accept payments to these recipients
+    ///
+    /// Applies on top of the requirements' `pay_to` check, so a facilitator
+    /// serving many merchants cannot be used to move funds to arbitrary
+    /// addresses. Addresses are compared case-insensitively.
+    pub fn with_allowed_recipients<I, A>(mut self, recipients: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<String>,
+    {
+        self.allowed_recipients = Some(recipients.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Reject payments smaller than `min_amount` whole tokens as `below_minimum`
+    ///
+    /// Keeps out dust payments worth less than the gas to settle them. The
+    /// floor is converted to atomic units with the asset's decimals, rounding
+    /// up, and applies whatever the requirements ask for.
+    pub fn with_min_amount(mut self, min_amount: Decimal) -> Self {
+        self.min_amount = Some(min_amount);
+        self
+    }
+
+    /// Check at most `parallelism` signatures of a batch at once
+    ///
+    /// Defaults to the number of available cores.
+    pub fn with_batch_parallelism(mut self, parallelism: usize) -> Self {
+        self.batch_parallelism = parallelism.max(1);
+        self
+    }
+
+    /// Recover signers through `cache`, so retried payloads are not recovered again
+    pub fn with_recovery_cache(mut self, cache: Arc<RecoveryCache>) -> Self {
+        self.recovery_cache = Some(cache);
+        self
+    }
+
+    fn allows_recipient(&self, recipient: &str) -> bool {
+        self.allowed_recipients.as_ref().is_none_or(|allowed| {
+            allowed
+                .iter()
+                .any(|address| address.eq_ignore_ascii_case(recipient))
+        })
+    }
+
+    /// Verify a payment against the requirements and consume its nonce
+    ///
+    /// Returns an invalid response with a reason rather than an error when the
+    /// payment does not satisfy the requirements. The nonce is only recorded
+    /// once every other check has passed.
+    #[tracing::instrument(
+        name = "x402.verify",
+        skip_all,
+        fields(
+            x402.network = %payment.network,
+            x402.scheme = %payment.scheme,
+            x402.payer = %payment.payload.authorization.from,
+            x402.amount = %payment.payload.authorization.value,
+        )
+    )]
+    pub async fn verify(
+        &self,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        match self.check(payment, requirements)? {
+            Some(invalid) => Ok(invalid),
+            None => self.claim(payment, requirements).await,
+        }
+    }
+
+    /// Verify a batch of payments, consuming the nonces of the valid ones
+    ///
+    /// Signatures are recovered on blocking threads, at most
+    /// [`with_batch_parallelism`](Self::with_batch_parallelism) at once, so a
+    /// large batch neither stalls the async runtime nor takes every core.
+    /// Nonces are then claimed in batch order, so a payment repeated within
+    /// the batch is only accepted the first time. Responses are in batch order.
+    pub async fn verify_batch(
+        &self,
+        batch: &[(PaymentPayload, PaymentRequirements)],
+    ) -> Result<Vec<VerifyResponse>> {
+        let checks: Vec<_> = stream::iter(batch.iter().cloned())
+            .map(|(payment, requirements)| {
+                let verifier = self.clone();
+                tokio::task::spawn_blocking(move || verifier.check(&payment, &requirements))
+            })
+            .buffered(self.batch_parallelism)
+            .map(|joined| {
+                joined.map_err(|e| {
+                    X402Error::unexpected(format!("Verification task failed: {}", e))
+                })?
+            })
+            .try_collect()
+            .await?;
+
+        let mut responses = Vec::with_capacity(batch.len());
+        for ((payment, requirements), check) in batch.iter().zip(checks) {
+            responses.push(match check {
+                Some(invalid) => invalid,
+                None => self.claim(payment, requirements).await?,
+            });
+        }
+        Ok(responses)
+    }
+
+    /// Check a payment about to be settled and claim it for settlement
+    ///
+    /// Runs every [`verify`](Self::verify) check, so a forged payment never
+    /// reaches the settler. A payment already verified through `verify` can
+    /// still be settled, but only once: settlement claims the nonce in a
+    /// scope of its own. A payment settled without verifying first also
+    /// consumes its verify nonce.
+    pub async fn verify_settlement(
+        &self,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        if let Some(invalid) = self.check(payment, requirements)? {
+            return Ok(invalid);
+        }
+        let authorization = &payment.payload.authorization;
+        let scope = nonce_scope(&payment.network, &requirements.asset);
+        self.claim_settlement(&scope, &authorization.nonce, &authorization.from)
+            .await
+    }
+
+    /// Verify a Solana payment against the requirements and consume its nonce
+    ///
+    /// Runs the checks of [`verify_solana_payment`] plus this verifier's
+    /// recipient allowlist and minimum amount. Nonces are scoped by cluster
+    /// and mint, as EVM nonces are by network and asset.
+    #[cfg(feature = "solana")]
+    pub async fn verify_solana(
+        &self,
+        payment: &SolanaPaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        if let Some(invalid) = self.check_solana(payment, requirements)? {
+            return Ok(invalid);
+        }
+        let authorization = &payment.payload.authorization;
+        let scope = nonce_scope(&payment.network, &authorization.mint);
+        self.claim_in(&scope, &authorization.nonce, &authorization.from)
+            .await
+    }
+
+    /// Check a Solana payment about to be settled and claim it for settlement
+    ///
+    /// The Solana counterpart of [`verify_settlement`](Self::verify_settlement).
+    #[cfg(feature = "solana")]
+    pub async fn verify_solana_settlement(
+        &self,
+        payment: &SolanaPaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        if let Some(invalid) = self.check_solana(payment, requirements)? {
+            return Ok(invalid);
+        }
+        let authorization = &payment.payload.authorization;
+        let scope = nonce_scope(&payment.network, &authorization.mint);
+        self.claim_settlement(&scope, &authorization.nonce, &authorization.from)
+            .await
+    }
+
+    /// Every Solana check but the nonce's, returning the invalid response on failure
+    #[cfg(feature = "solana")]
+    fn check_solana(
+        &self,
+        payment: &SolanaPaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<Option<VerifyResponse>> {
+        let response = verify_solana_payment(payment, requirements)?;
+        if !response.is_valid {
+            return Ok(Some(response));
+        }
+
+        let authorization = &payment.payload.authorization;
+        let invalid = |reason: &str| {
+            Some(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some(reason.to_string()),
+                payer: Some(authorization.from.clone()),
+            })
+        };
+        if !self.allows_recipient(&authorization.to) {
+            return Ok(invalid("recipient_not_allowed"));
+        }
+        if let Some(min_amount) = self.min_amount {
+            let decimals = requirements.asset_decimals();
+            let amount = Amount::from_atomic_str(&authorization.amount, decimals)?;
+            if amount < Amount::from_decimal_ceil(min_amount, decimals)? {
+                return Ok(invalid("below_minimum"));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Claim `nonce` for settlement within `scope`, and for verification too
+    async fn claim_settlement(
+        &self,
+        scope: &str,
+        nonce: &str,
+        payer: &str,
+    ) -> Result<VerifyResponse> {
+        let response = self
+            .claim_in(&settlement_scope(scope), nonce, payer)
+            .await?;
+        if response.is_valid {
+            // Whether or not it was verified before, it cannot be verified again
+            self.claim_in(scope, nonce, payer).await?;
+        }
+        Ok(response)
+    }
+
+    /// Release a settlement claim after the settler failed, so it can be retried
+    async fn release_settlement(&self, scope: &str, nonce: &str) -> Result<()> {
+        match canonical_nonce(nonce) {
+            Some(nonce) => {
+                self.storage
+                    .remove_nonce(&settlement_scope(scope), &nonce)
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Every check but the nonce's, returning the invalid response on failure
+    fn check(
+        &self,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<Option<VerifyResponse>> {
+        let authorization = &payment.payload.authorization;
+        let invalid = |reason: &str| {
+            Some(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some(reason.to_string()),
+                payer: Some(authorization.from.clone()),
+            })
+        };
+
+        if payment.scheme != requirements.scheme {
+            return Ok(invalid("scheme_mismatch"));
+        }
+
+        let network = match NetworkConfig::from_name(&payment.network) {
+            Some(network) if payment.network == requirements.network => network,
+            _ => return Ok(invalid("network_mismatch")),
+        };
+
+        // Token signatures are checked against the network's USDC domain, so
+        // that must be the contract the requirement asks to be paid in
+        if !requirements.is_native()
+            && !requirements
+                .asset
+                .eq_ignore_ascii_case(&network.usdc_contract)
+        {
+            return Ok(invalid("wrong_asset"));
+        }
+
+        // Amounts only compare in one unit
+        if !requirements.decimals_match(&network) {
+            return Ok(invalid("asset_mismatch"));
+        }
+
+        if !authorization.is_valid_at(chrono::Utc::now().timestamp(), self.clock_skew)? {
+            return Ok(invalid("authorization_expired"));
+        }
+
+        if !authorization.to.eq_ignore_ascii_case(&requirements.pay_to) {
+            return Ok(invalid("recipient_mismatch"));
+        }
+
+        if !self.allows_recipient(&authorization.to) {
+            return Ok(invalid("recipient_not_allowed"));
+        }
+
+        let decimals = requirements.asset_decimals();
+        let amount = authorization.amount(decimals)?;
+        if amount < requirements.required_amount(decimals)? {
+            return Ok(invalid("insufficient_amount"));
+        }
+        if let Some(min_amount) = self.min_amount {
+            if amount < Amount::from_decimal_ceil(min_amount, decimals)? {
+                return Ok(invalid("below_minimum"));
+            }
+        }
+
+        // Cheap checks first; signature recovery is the expensive one
+        let (payload, from, network) = (&payment.payload, &authorization.from, &payment.network);
+        let extra = requirements.extra.as_ref();
+        let signature_valid = match (&self.recovery_cache, requirements.is_native()) {
+            (Some(cache), true) => cache.verify_native_payment_payload(payload, from, network),
+            (Some(cache), false) => cache.verify_payment_payload(payload, from, network, extra),
+            (None, true) => verify_native_payment_payload(payload, from, network),
+            (None, false) => verify_payment_payload_with_extra(payload, from, network, extra),
+        };
+        // A malformed signature is as invalid as a wrong one
+        if !signature_valid.unwrap_or(false) {
+            return Ok(invalid("invalid_signature"));
+        }
+
+        Ok(None)
+    }
+
+    /// Consume the nonce of a payment that passed [`check`](Self::check)
+    async fn claim(
+        &self,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        let authorization = &payment.payload.authorization;
+        let scope = nonce_scope(&payment.network, &requirements.asset);
+        self.claim_in(&scope, &authorization.nonce, &authorization.from)
+            .await
+    }
+
+    /// Consume `nonce`, signed by `payer`, within `scope`
+    async fn claim_in(&self, scope: &str, nonce: &str, payer: &str) -> Result<VerifyResponse> {
+        let invalid = |reason: &str| VerifyResponse {
+            is_valid: false,
+            invalid_reason: Some(reason.to_string()),
+            payer: Some(payer.to_string()),
+        };
+
+        // One key per nonce however it is spelled, so respelling cannot replay it
+        let Some(nonce) = canonical_nonce(nonce) else {
+            return Ok(invalid("invalid_nonce"));
+        };
+
+        // Claimed atomically, so concurrent replays cannot both pass
+        if !self.storage.claim_nonce(scope, &nonce).await? {
+            return Ok(invalid("nonce_already_used"));
+        }
+
+        Ok(VerifyResponse {
+            is_valid: true,
+            invalid_reason: None,
+            payer: Some(payer.to_string()),
+        })
+    }
+}
+
+/// Nonce scope recording which payments of `scope` have been settled
+fn settlement_scope(scope: &str) -> String {
+    format!("settled:{}", scope)
+}
+
+/// In-process facilitator: [`LocalVerifier`] checks plus pluggable settlement
+pub struct LocalFacilitator<S: NonceStorage> {
+    storage: Arc<S>,
+    verifier: LocalVerifier,
+    settler: Arc<dyn Settler>,
+}
+
+// Not derived: the storage is shared, so `S` itself need not be `Clone`
+impl<S: NonceStorage> Clone for LocalFacilitator<S> {
+    fn clone(&self) -> Self {
+        Self {
+            storage: Arc::clone(&self.storage),
+            verifier: self.verifier.clone(),
+            settler: Arc::clone(&self.settler),
+        }
+    }
+}
+
+impl<S: NonceStorage + 'static> LocalFacilitator<S> {
+    /// Create a facilitator recording nonces in `storage` and settling with `settler`
+    pub fn new(storage: S, settler: impl Settler + 'static) -> Self {
+        Self::with_shared_settler(storage, Arc::new(settler))
+    }
+
+    /// Create a facilitator settling with a settler shared with other users
+    pub fn with_shared_settler(storage: S, settler: Arc<dyn Settler>) -> Self {
+        let storage = Arc::new(storage);
+        Self {
+            verifier: LocalVerifier::with_shared_storage(storage.clone()),
+            storage,
+            settler,
+        }
+    }
+}
+
+impl<S: NonceStorage> LocalFacilitator<S> {
+    /// Set the clock skew tolerated on authorization validity windows
+    pub fn with_clock_skew(mut self, clock_skew: Duration) -> Self {
+        self.verifier = self.verifier.with_clock_skew(clock_skew);
+        self
+    }
+
+    /// Only accept payments to these recipients
+    pub fn with_allowed_recipients<I, A>(mut self, recipients: I) -> Self
+    where
+        I: IntoIterator<Item = A>,
+        A: Into<String>,
+    {
+        self.verifier = self.verifier.with_allowed_recipients(recipients);
+        self
+    }
+
+    /// Reject payments smaller than `min_amount` whole tokens as `below_minimum`
+    pub fn with_min_amount(mut self, min_amount: Decimal) -> Self {
+        self.verifier = self.verifier.with_min_amount(min_amount);
+        self
+    }
+
+    /// Check at most `parallelism` signatures of a batch at once
+    pub fn with_batch_parallelism(mut self, parallelism: usize) -> Self {
+        self.verifier = self.verifier.with_batch_parallelism(parallelism);
+        self
+    }
+
+    /// Recover signers through `cache`
+    pub fn with_recovery_cache(mut self, cache: Arc<RecoveryCache>) -> Self {
+        self.verifier = self.verifier.with_recovery_cache(cache);
+        self
+    }
+
+    /// The nonce storage backend
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Verify a payment, including its signature, and consume its nonce
+    pub async fn verify_payment(
+        &self,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        self.verifier.verify(payment, requirements).await
+    }
+
+    /// Verify a batch of payments in parallel; see [`LocalVerifier::verify_batch`]
+    pub async fn verify_payments(
+        &self,
+        batch: &[(PaymentPayload, PaymentRequirements)],
+    ) -> Result<Vec<VerifyResponse>> {
+        self.verifier.verify_batch(batch).await
+    }
+
+    /// Settle a payment with the configured [`Settler`]
+    ///
+    /// The payment is checked with [`LocalVerifier::verify_settlement`] first;
+    /// one that fails is answered with an unsuccessful response and never
+    /// reaches the settler.
+    #[tracing::instrument(
+        name = "x402.settle",
+        skip_all,
+        fields(
+            x402.network = %payment.network,
+            x402.scheme = %payment.scheme,
+            x402.payer = %payment.payload.authorization.from,
+            x402.amount = %payment.payload.authorization.value,
+            x402.tx_hash = tracing::field::Empty,
+        )
+    )]
+    pub async fn settle_payment(
+        &self,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        let verified = self
+            .verifier
+            .verify_settlement(payment, requirements)
+            .await?;
+        if !verified.is_valid {
+            return Ok(SettleResponse {
+                success: false,
+                error_reason: verified.invalid_reason,
+                payer: verified.payer,
+                transaction: String::new(),
+                network: payment.network.clone(),
+                fee_amount: None,
+                net_amount: None,
+            });
+        }
+
+        let response = match self.settler.settle(payment, requirements).await {
+            Ok(response) => response,
+            Err(e) => {
+                let scope = nonce_scope(&payment.network, &requirements.asset);
+                self.verifier
+                    .release_settlement(&scope, &payment.payload.authorization.nonce)
+                    .await?;
+                return Err(e);
+            }
+        };
+        tracing::Span::current().record("x402.tx_hash", response.transaction.as_str());
+        Ok(response)
+    }
+
+    /// Verify a Solana payment and consume its nonce
+    #[cfg(feature = "solana")]
+    pub async fn verify_solana_payment(
+        &self,
+        payment: &SolanaPaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<VerifyResponse> {
+        self.verifier.verify_solana(payment, requirements).await
+    }
+
+    /// Settle a Solana payment, checking it first as [`settle_payment`](Self::settle_payment) does
+    ///
+    /// Settlement is simulated; see [`settle_solana_payment`].
+    #[cfg(feature = "solana")]
+    pub async fn settle_solana_payment(
+        &self,
+        payment: &SolanaPaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        let verified = self
+            .verifier
+            .verify_solana_settlement(payment, requirements)
+            .await?;
+        if !verified.is_valid {
+            return Ok(SettleResponse {
+                success: false,
+                error_reason: verified.invalid_reason,
+                payer: verified.payer,
+                transaction: String::new(),
+                network: payment.network.clone(),
+                fee_amount: None,
+                net_amount: None,
+            });
+        }
+        settle_solana_payment(payment)
+    }
+}
+
+impl std::fmt::Debug for LocalVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalVerifier")
+            .field("storage", &"<storage>")
+            .field("clock_skew", &self.clock_skew)
+            .field("allowed_recipients", &self.allowed_recipients)
+            .field("min_amount", &self.min_amount)
+            .field("batch_parallelism", &self.batch_parallelism)
+            .field("recovery_cache", &self.recovery_cache)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::facilitator::MockSettler;
+    use crate::facilitator_storage::InMemoryStorage;
+    use crate::{signer::LocalSigner, wallet::Wallet};
+
+    const PAY_TO: &str = "0x209693bc6afc0c5328ba36faf03c514ef312287c";
+
+    fn wallet() -> Wallet {
+        Wallet::new(
+            LocalSigner::new("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
+                .unwrap(),
+            "base-sepolia",
+        )
+    }
+
+    fn requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            PAY_TO,
+            "https://example.com/resource",
+            "Test resource",
+        )
+    }
+
+    async fn reason(
+        verifier: &LocalVerifier,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Option<String> {
+        verifier
+            .verify(payment, requirements)
+            .await
+            .unwrap()
+            .invalid_reason
+    }
+
+    #[tokio::test]
+    async fn test_valid_payment_consumes_nonce() {
+        let verifier = LocalVerifier::new(InMemoryStorage::new());
+        let requirements = requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        let response = verifier.verify(&payment, &requirements).await.unwrap();
+        assert!(response.is_valid, "{:?}", response.invalid_reason);
+        assert_eq!(
+            response.payer,
+            Some(payment.payload.authorization.from.clone())
+        );
+
+        assert_eq!(
+            reason(&verifier, &payment, &requirements).await.as_deref(),
+            Some("nonce_already_used")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_legacy_recovery_id_accepted() {
+        let verifier = LocalVerifier::new(InMemoryStorage::new());
+        let requirements = requirements();
+        let mut payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        // eth_signTypedData_v4 returns v as 27/28 rather than 0/1
+        let mut signature =
+            hex::decode(payment.payload.signature.trim_start_matches("0x")).unwrap();
+        if signature[64] < 27 {
+            signature[64] += 27;
+        }
+        payment.payload.signature = format!("0x{}", hex::encode(signature));
+
+        let response = verifier.verify(&payment, &requirements).await.unwrap();
+        assert!(response.is_valid, "{:?}", response.invalid_reason);
+        assert_eq!(
+            response.payer,
+            Some(payment.payload.authorization.from.clone())
+        );
+    }
+
+    #[cfg(feature = "solana")]
+    #[tokio::test]
+    async fn test_solana_payment_not_replayable() {
+        use crate::solana::SolanaKeypair;
+        use crate::types::networks;
+
+        let facilitator = LocalFacilitator::new(InMemoryStorage::new(), MockSettler);
+        let keypair = SolanaKeypair::from_secret_key(&[7; 32]);
+        let requirements = PaymentRequirements::new(
+            "exact",
+            networks::SOLANA_DEVNET,
+            "1000000",
+            networks::get_usdc_address(networks::SOLANA_DEVNET).unwrap(),
+            "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+            "https://example.com/resource",
+            "Test resource",
+        );
+        let payment = keypair.authorize_payment(&requirements).unwrap();
+
+        let response = facilitator
+            .verify_solana_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert!(response.is_valid, "{:?}", response.invalid_reason);
+        assert_eq!(response.payer, Some(keypair.pubkey()));
+
+        let replayed = facilitator
+            .verify_solana_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert_eq!(
+            replayed.invalid_reason.as_deref(),
+            Some("nonce_already_used")
+        );
+
+        // The verified payment settles once
+        let settlement = facilitator
+            .settle_solana_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert!(settlement.success, "{:?}", settlement.error_reason);
+        let settlement = facilitator
+            .settle_solana_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert_eq!(
+            settlement.error_reason.as_deref(),
+            Some("nonce_already_used")
+        );
+
+        // Forged payments never settle
+        let mut forged = keypair.authorize_payment(&requirements).unwrap();
+        forged.payload.authorization.amount = "2000000".to_string();
+        let settlement = facilitator
+            .settle_solana_payment(&forged, &requirements)
+            .await
+            .unwrap();
+        assert_eq!(
+            settlement.error_reason.as_deref(),
+            Some("invalid_signature")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_respelled_nonce_not_replayable() {
+        let verifier = LocalVerifier::new(InMemoryStorage::new());
+        let requirements = requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+        assert_eq!(reason(&verifier, &payment, &requirements).await, None);
+
+        // Same signed authorization, nonce in uppercase and without the prefix
+        let nonce = &payment.payload.authorization.nonce;
+        for respelled in [
+            format!("0x{}", nonce[2..].to_uppercase()),
+            nonce[2..].to_string(),
+        ] {
+            let mut replay = payment.clone();
+            replay.payload.authorization.nonce = respelled;
+            assert_eq!(
+                reason(&verifier, &replay, &requirements).await.as_deref(),
+                Some("nonce_already_used")
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_replay_accepted_once() {
+        let verifier = LocalVerifier::new(InMemoryStorage::new());
+        let requirements = requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        let attempts: Vec<_> = (0..8)
+            .map(|_| {
+                let (verifier, payment, requirements) =
+                    (verifier.clone(), payment.clone(), requirements.clone());
+                tokio::spawn(async move { verifier.verify(&payment, &requirements).await })
+            })
+            .collect();
+        let mut accepted = 0;
+        for attempt in attempts {
+            let response = attempt.await.unwrap().unwrap();
+            if response.is_valid {
+                accepted += 1;
+            } else {
+                assert_eq!(
+                    response.invalid_reason.as_deref(),
+                    Some("nonce_already_used")
+                );
+            }
+        }
+        assert_eq!(accepted, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejection_reasons() {
+        let verifier = LocalVerifier::new(InMemoryStorage::new());
+        let requirements = requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        let mut other = requirements.clone();
+        other.scheme = "upto".to_string();
+        assert_eq!(
+            reason(&verifier, &payment, &other).await.as_deref(),
+            Some("scheme_mismatch")
+        );
+
+        let mut other = requirements.clone();
+        other.network = "base".to_string();
+        assert_eq!(
+            reason(&verifier, &payment, &other).await.as_deref(),
+            Some("network_mismatch")
+        );
+
+        let mut tampered = payment.clone();
+        tampered.payload.authorization.value = "1000000".to_string();
+        assert_eq!(
+            reason(&verifier, &tampered, &requirements).await.as_deref(),
+            Some("invalid_signature")
+        );
+        tampered.payload.signature = "0x00".to_string();
+        assert_eq!(
+            reason(&verifier, &tampered, &requirements).await.as_deref(),
+            Some("invalid_signature")
+        );
+
+        let mut other = requirements.clone();
+        other.pay_to = "0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string();
+        assert_eq!(
+            reason(&verifier, &payment, &other).await.as_deref(),
+            Some("recipient_mismatch")
+        );
+
+        let mut other = requirements.clone();
+        other.max_amount_required = "1001".to_string();
+        assert_eq!(
+            reason(&verifier, &payment, &other).await.as_deref(),
+            Some("insufficient_amount")
+        );
+
+        // None of the rejections consumed the nonce
+        assert!(
+            verifier
+                .verify(&payment, &requirements)
+                .await
+                .unwrap()
+                .is_valid
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_verify_batch() {
+        let requirements = requirements();
+        let wallet = wallet();
+        let mut batch = Vec::new();
+        for _ in 0..8 {
+            let payment = wallet.authorize_payment(&requirements).await.unwrap();
+            batch.push((payment, requirements.clone()));
+        }
+        // A forged payer, a wrong recipient and a replay within the batch
+        batch[2].0.payload.authorization.from =
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string();
+        batch[5].1.pay_to = "0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string();
+        batch.push(batch[0].clone());
+
+        let verifier = LocalVerifier::new(InMemoryStorage::new()).with_batch_parallelism(3);
+        let reasons: Vec<_> = verifier
+            .verify_batch(&batch)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|response| response.invalid_reason)
+            .collect();
+
+        let mut expected = vec![None; 9];
+        expected[2] = Some("invalid_signature".to_string());
+        expected[5] = Some("recipient_mismatch".to_string());
+        expected[8] = Some("nonce_already_used".to_string());
+        assert_eq!(reasons, expected);
+
+        // Accepted nonces were consumed
+        for (payment, requirements) in &batch[..2] {
+            assert_eq!(
+                reason(&verifier, payment, requirements).await.as_deref(),
+                Some("nonce_already_used")
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expired_authorization() {
+        let verifier = LocalVerifier::new(InMemoryStorage::new());
+        let requirements = requirements();
+        let mut payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        // The window is checked before the signature it would invalidate
+        payment.payload.authorization.valid_before = "1700000000".to_string();
+        assert_eq!(
+            reason(&verifier, &payment, &requirements).await.as_deref(),
+            Some("authorization_expired")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_asset_decimals() {
+        let verifier = LocalVerifier::new(InMemoryStorage::new());
+        let requirements = requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        // Priced as an 18-decimal token, the USDC amount means something else
+        let mut other = requirements.clone();
+        other.extra = Some(serde_json::json!({ "decimals": 18 }));
+        assert_eq!(
+            reason(&verifier, &payment, &other).await.as_deref(),
+            Some("asset_mismatch")
+        );
+
+        // USDC's own decimals are consistent
+        let mut other = requirements.clone();
+        other.extra = Some(serde_json::json!({ "decimals": 6 }));
+        assert_eq!(reason(&verifier, &payment, &other).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_asset_contract() {
+        let verifier = LocalVerifier::new(InMemoryStorage::new());
+        let requirements = requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        // Another token, or USDC on another network, is not what was signed for
+        for asset in [
+            "0x1111111111111111111111111111111111111111",
+            "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
+        ] {
+            let mut other = requirements.clone();
+            other.asset = asset.to_string();
+            assert_eq!(
+                reason(&verifier, &payment, &other).await.as_deref(),
+                Some("wrong_asset")
+            );
+        }
+
+        // Address case does not matter
+        let mut other = requirements.clone();
+        other.asset = other.asset.to_lowercase();
+        assert_eq!(reason(&verifier, &payment, &other).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_token_domain_from_extra() {
+        let mut requirements = requirements();
+        requirements.extra = Some(serde_json::json!({"name": "Custom Token", "version": "1"}));
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+        let from = &payment.payload.authorization.from;
+
+        // Signed under the custom domain, not USDC's
+        assert!(verify_payment_payload_with_extra(
+            &payment.payload,
+            from,
+            &payment.network,
+            requirements.extra.as_ref()
+        )
+        .unwrap());
+        assert!(
+            !verify_payment_payload_with_extra(&payment.payload, from, &payment.network, None)
+                .unwrap()
+        );
+
+        let verifier = LocalVerifier::new(InMemoryStorage::new());
+        let mut defaults = requirements.clone();
+        defaults.extra = None;
+        assert_eq!(
+            reason(&verifier, &payment, &defaults).await.as_deref(),
+            Some("invalid_signature")
+        );
+        assert_eq!(reason(&verifier, &payment, &requirements).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_signer_recovered_once_per_payment() {
+        let cache = Arc::new(RecoveryCache::new(16));
+        let facilitator = LocalFacilitator::new(InMemoryStorage::new(), MockSettler)
+            .with_recovery_cache(cache.clone());
+        let requirements = requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        let verification = facilitator
+            .verify_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert!(verification.is_valid);
+        let settlement = facilitator
+            .settle_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert_eq!(settlement.payer, verification.payer);
+        assert_eq!(cache.recoveries(), 1);
+
+        // A retry is refused on its nonce without recovering the signer again
+        let retry = facilitator
+            .verify_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert_eq!(retry.invalid_reason.as_deref(), Some("nonce_already_used"));
+        assert_eq!(cache.recoveries(), 1);
+
+        // A different payment is recovered
+        let other = wallet().authorize_payment(&requirements).await.unwrap();
+        assert!(
+            facilitator
+                .verify_payment(&other, &requirements)
+                .await
+                .unwrap()
+                .is_valid
+        );
+        assert_eq!(cache.recoveries(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_recipient_allowlist() {
+        let requirements = requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        let verifier = LocalVerifier::new(InMemoryStorage::new())
+            .with_allowed_recipients(["0x857b06519E91e3A54538791bDbb0E22373e36b66"]);
+        assert_eq!(
+            reason(&verifier, &payment, &requirements).await.as_deref(),
+            Some("recipient_not_allowed")
+        );
+
+        let verifier = LocalVerifier::new(InMemoryStorage::new())
+            .with_allowed_recipients(["0x209693Bc6afc0C5328bA36FaF03C514EF312287C"]);
+        assert_eq!(reason(&verifier, &payment, &requirements).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_min_amount() {
+        use std::str::FromStr;
+
+        // 1000 atomic units of 6-decimal USDC
+        let requirements = requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        let verifier = LocalVerifier::new(InMemoryStorage::new())
+            .with_min_amount(Decimal::from_str("0.0011").unwrap());
+        assert_eq!(
+            reason(&verifier, &payment, &requirements).await.as_deref(),
+            Some("below_minimum")
+        );
+
+        let verifier = LocalVerifier::new(InMemoryStorage::new())
+            .with_min_amount(Decimal::from_str("0.001").unwrap());
+        assert_eq!(reason(&verifier, &payment, &requirements).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_local_facilitator_checks_signature() {
+        let facilitator = LocalFacilitator::new(InMemoryStorage::new(), MockSettler);
+        let requirements = requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        // A payment claiming to come from someone else is refused
+        let mut forged = payment.clone();
+        forged.payload.authorization.from =
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string();
+        let response = facilitator
+            .verify_payment(&forged, &requirements)
+            .await
+            .unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("invalid_signature")
+        );
+
+        let response = facilitator
+            .verify_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert!(response.is_valid, "{:?}", response.invalid_reason);
+
+        let settlement = facilitator
+            .settle_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert!(settlement.success);
+        assert_eq!(settlement.payer, response.payer);
+        assert_eq!(settlement.network, "base-sepolia");
+    }
+
+    #[tokio::test]
+    async fn test_settle_verifies_payment() {
+        let facilitator = LocalFacilitator::new(InMemoryStorage::new(), MockSettler);
+        let requirements = requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        // A forged payment is refused without reaching the settler
+        let mut forged = payment.clone();
+        forged.payload.authorization.from =
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string();
+        let settlement = facilitator
+            .settle_payment(&forged, &requirements)
+            .await
+            .unwrap();
+        assert!(!settlement.success);
+        assert_eq!(
+            settlement.error_reason.as_deref(),
+            Some("invalid_signature")
+        );
+        assert!(settlement.transaction.is_empty());
+
+        // Settling without verifying first works, but only once
+        let settlement = facilitator
+            .settle_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert!(settlement.success, "{:?}", settlement.error_reason);
+        let settlement = facilitator
+            .settle_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert_eq!(
+            settlement.error_reason.as_deref(),
+            Some("nonce_already_used")
+        );
+        let response = facilitator
+            .verify_payment(&payment, &requirements)
+            .await
+            .unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("nonce_already_used")
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Pluggable settlement backends
+//!
+//! [`FacilitatorSettler`] settles through a remote facilitator,
+//! [`OnChainSettler`] submits the transfer itself and [`MockSettler`] only
+//! pretends to. The payment middleware and [`LocalFacilitator`](super::LocalFacilitator)
+//! accept any of them.
+
+use super::FacilitatorClient;
+use crate::blockchain_facilitator::BlockchainFacilitatorClient;
+use crate::types::{PaymentPayload, PaymentRequirements, SettleResponse};
+use crate::Result;
+use async_trait::async_trait;
+use rand::Rng;
+use std::sync::Arc;
+
+/// Settles verified payments
+#[async_trait]
+pub trait Settler: std::fmt::Debug + Send + Sync {
+    /// Settle `payment` against `requirements`
+    async fn settle(
+        &self,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse>;
+}
+
+/// Settles through a facilitator's `/settle` endpoint
+#[derive(Debug, Clone)]
+pub struct FacilitatorSettler {
+    client: Arc<FacilitatorClient>,
+}
+
+impl FacilitatorSettler {
+    /// Settle with `client`, owned or shared
+    pub fn new(client: impl Into<Arc<FacilitatorClient>>) -> Self {
+        Self {
+            client: client.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Settler for FacilitatorSettler {
+    async fn settle(
+        &self,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        self.client.settle(payment, requirements).await
+    }
+}
+
+/// Submits `transferWithAuthorization` on-chain
+#[derive(Clone)]
+pub struct OnChainSettler {
+    client: Arc<BlockchainFacilitatorClient>,
+}
+
+impl OnChainSettler {
+    /// Settle with `client`, which verifies each payment again before submitting it
+    pub fn new(client: BlockchainFacilitatorClient) -> Self {
+        Self {
+            client: Arc::new(client),
+        }
+    }
+}
+
+// Not derived: the blockchain client holds RPC connections
+impl std::fmt::Debug for OnChainSettler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OnChainSettler").finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Settler for OnChainSettler {
+    async fn settle(
+        &self,
+        payment: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        self.client.settle(payment, requirements).await
+    }
+}
+
+/// Reports every payment as settled with a random transaction hash
+///
+/// Nothing is submitted on-chain; use it for demos and local development.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockSettler;
+
+#[async_trait]
+impl Settler for MockSettler {
+    async fn settle(
+        &self,
+        payment: &PaymentPayload,
+        _requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        Ok(SettleResponse {
+            success: true,
+            error_reason: None,
+            transaction: format!("0x{:064x}", rand::thread_rng().gen::<u128>()),
+            network: payment.network.clone(),
+            payer: Some(payment.payload.authorization.from.clone()),
+            fee_amount: None,
+            net_amount: None,
+        })
+    }
+}
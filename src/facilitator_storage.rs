@@ -2,9 +2,39 @@
 //!
 //! This module provides a trait-based storage abstraction for tracking
 //! processed nonces to prevent replay attacks.
+//!
+//! Nonces are tracked per scope (see [`nonce_scope`]), so the same nonce may be
+//! used once on each network and token contract.
+//!
+//! ## Migration
+//!
+//! Earlier versions keyed nonces on the bare nonce string. Redis keys written
+//! in that format (`{prefix}{nonce}`) are still honored by `RedisStorage`
+//! until they expire (24 hours), so upgrading does not reopen a replay window.
+//! Scoped keys have the form `{prefix}{network}:{asset}:{nonce}`.
 
 use crate::Result;
 use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Build the replay-protection scope for a network and token contract
+pub fn nonce_scope(network: &str, asset: &str) -> String {
+    format!("{}:{}", network, asset.to_lowercase())
+}
+
+/// Canonical form of a 32-byte nonce: lowercase hex with a `0x` prefix
+///
+/// Signatures are checked against the parsed nonce, which ignores case and
+/// the prefix, so nonces must be stored in one form or a payment could be
+/// replayed by respelling its nonce. `None` when the nonce is not 32 bytes
+/// of hex.
+pub fn canonical_nonce(nonce: &str) -> Option<String> {
+    nonce
+        .parse::<ethereum_types::H256>()
+        .ok()
+        .map(|nonce| format!("{:#x}", nonce))
+}
 
 /// Trait for storing and retrieving nonce information
 ///
@@ -12,32 +42,138 @@ use async_trait::async_trait;
 /// enabling flexibility in deployment scenarios.
 #[async_trait]
 pub trait NonceStorage: Send + Sync {
-    /// Check if a nonce has been processed
-    async fn has_nonce(&self, nonce: &str) -> Result<bool>;
+    /// Check if a nonce has been processed within `scope`
+    async fn has_nonce(&self, scope: &str, nonce: &str) -> Result<bool>;
+
+    /// Mark a nonce as processed within `scope`
+    async fn mark_nonce(&self, scope: &str, nonce: &str) -> Result<()>;
 
-    /// Mark a nonce as processed
-    async fn mark_nonce(&self, nonce: &str) -> Result<()>;
+    /// Remove a nonce from `scope` (optional cleanup)
+    async fn remove_nonce(&self, scope: &str, nonce: &str) -> Result<()>;
 
-    /// Remove a nonce (optional cleanup)
-    async fn remove_nonce(&self, nonce: &str) -> Result<()>;
+    /// Mark a nonce as processed within `scope` unless it already is
+    ///
+    /// Returns `true` if this call claimed the nonce. Backends should override
+    /// this with an atomic check-and-set; the default checks then marks, so
+    /// two concurrent claims can both succeed.
+    async fn claim_nonce(&self, scope: &str, nonce: &str) -> Result<bool> {
+        if self.has_nonce(scope, nonce).await? {
+            return Ok(false);
+        }
+        self.mark_nonce(scope, nonce).await?;
+        Ok(true)
+    }
+
+    /// Check that the backend is reachable
+    async fn ping(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 /// In-memory storage implementation
 ///
 /// This is the default storage implementation that uses an in-memory HashMap.
-/// Data is lost when the server restarts.
+/// Data is lost when the server restarts. Nonces are kept forever unless a
+/// TTL or capacity is set; an evicted nonce can be replayed, so the TTL should
+/// outlast the authorizations' validity windows.
 #[derive(Debug, Clone)]
 pub struct InMemoryStorage {
-    nonces: std::sync::Arc<tokio::sync::RwLock<std::collections::HashMap<String, bool>>>,
+    nonces: std::sync::Arc<tokio::sync::RwLock<Nonces>>,
+    ttl: Option<Duration>,
+    max_entries: Option<usize>,
+}
+
+/// Marked nonces with their marking time, oldest first in `order`
+#[derive(Debug, Default)]
+struct Nonces {
+    marked: HashMap<String, Instant>,
+    order: VecDeque<(Instant, String)>,
+}
+
+impl Nonces {
+    /// Drop nonces older than `ttl`, then the oldest beyond `max_entries`
+    fn evict(&mut self, ttl: Option<Duration>, max_entries: Option<usize>, now: Instant) {
+        while let Some((marked_at, key)) = self.order.front() {
+            let expired = ttl.is_some_and(|ttl| now.duration_since(*marked_at) >= ttl);
+            let over_capacity = max_entries.is_some_and(|max| self.marked.len() > max);
+            // Entries re-marked or removed since are stale and just dropped
+            let stale = self.marked.get(key) != Some(marked_at);
+            if !(expired || over_capacity || stale) {
+                break;
+            }
+            if !stale {
+                self.marked.remove(key);
+            }
+            self.order.pop_front();
+        }
+        self.compact();
+    }
+
+    /// Drop stale entries once they outnumber the marked nonces
+    ///
+    /// Eviction only drops stale entries at the front, so those behind a live
+    /// nonce would otherwise pile up as nonces are removed or re-marked.
+    fn compact(&mut self) {
+        if self.order.len() > 2 * self.marked.len() {
+            let marked = &self.marked;
+            self.order
+                .retain(|(marked_at, key)| marked.get(key) == Some(marked_at));
+        }
+    }
 }
 
 impl InMemoryStorage {
     /// Create a new in-memory storage instance
     pub fn new() -> Self {
         Self {
-            nonces: std::sync::Arc::new(tokio::sync::RwLock::new(std::collections::HashMap::new())),
+            nonces: Default::default(),
+            ttl: None,
+            max_entries: None,
+        }
+    }
+
+    /// Forget nonces `ttl` after they were marked
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Keep at most `max_entries` nonces, forgetting the oldest first
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Whether a nonce marked at `marked_at` has not yet expired
+    fn is_live(&self, marked_at: Instant) -> bool {
+        self.ttl.is_none_or(|ttl| marked_at.elapsed() < ttl)
+    }
+
+    /// Record `key` as marked now, evicting what the bounds require
+    fn insert(&self, nonces: &mut Nonces, key: String) {
+        let now = Instant::now();
+        nonces.marked.insert(key.clone(), now);
+        // Marking order is only needed to bound the storage
+        if self.ttl.is_some() || self.max_entries.is_some() {
+            nonces.order.push_back((now, key));
+            nonces.evict(self.ttl, self.max_entries, now);
         }
     }
+
+    /// Number of nonces currently remembered
+    pub async fn len(&self) -> usize {
+        self.nonces.read().await.marked.len()
+    }
+
+    /// Whether no nonces are remembered
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
+/// Storage key for a nonce within a scope
+fn scoped_key(scope: &str, nonce: &str) -> String {
+    format!("{}:{}", scope, nonce)
 }
 
 impl Default for InMemoryStorage {
@@ -48,20 +184,40 @@ impl Default for InMemoryStorage {
 
 #[async_trait]
 impl NonceStorage for InMemoryStorage {
-    async fn has_nonce(&self, nonce: &str) -> Result<bool> {
+    async fn has_nonce(&self, scope: &str, nonce: &str) -> Result<bool> {
         let nonces = self.nonces.read().await;
-        Ok(nonces.contains_key(nonce))
+        // Expired nonces may not have been evicted yet
+        Ok(nonces
+            .marked
+            .get(&scoped_key(scope, nonce))
+            .is_some_and(|marked_at| self.is_live(*marked_at)))
     }
 
-    async fn mark_nonce(&self, nonce: &str) -> Result<()> {
+    async fn mark_nonce(&self, scope: &str, nonce: &str) -> Result<()> {
         let mut nonces = self.nonces.write().await;
-        nonces.insert(nonce.to_string(), true);
+        self.insert(&mut nonces, scoped_key(scope, nonce));
         Ok(())
     }
 
-    async fn remove_nonce(&self, nonce: &str) -> Result<()> {
+    async fn claim_nonce(&self, scope: &str, nonce: &str) -> Result<bool> {
+        let mut nonces = self.nonces.write().await;
+        let key = scoped_key(scope, nonce);
+        // Checked and marked under the one write lock
+        if nonces
+            .marked
+            .get(&key)
+            .is_some_and(|marked_at| self.is_live(*marked_at))
+        {
+            return Ok(false);
+        }
+        self.insert(&mut nonces, key);
+        Ok(true)
+    }
+
+    async fn remove_nonce(&self, scope: &str, nonce: &str) -> Result<()> {
         let mut nonces = self.nonces.write().await;
-        nonces.remove(nonce);
+        nonces.marked.remove(&scoped_key(scope, nonce));
+        nonces.compact();
         Ok(())
     }
 }
@@ -70,10 +226,105 @@ impl NonceStorage for InMemoryStorage {
 mod tests {
     use super::*;
 
+    const SCOPE: &str = "base-sepolia:0x036cbd53842c5426634e7929541ec2318f3dcf7e";
+
+    #[test]
+    fn test_nonce_scope() {
+        assert_eq!(
+            nonce_scope("base-sepolia", "0x036CbD53842c5426634e7929541eC2318f3dCF7e"),
+            SCOPE
+        );
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_storage_nonce_scoped_by_network() {
+        let storage = InMemoryStorage::new();
+        let asset = "0x036CbD53842c5426634e7929541eC2318f3dCF7e";
+        let sepolia = nonce_scope("base-sepolia", asset);
+        let fuji = nonce_scope("avalanche-fuji", asset);
+        let nonce = "0xshared";
+
+        storage.mark_nonce(&sepolia, nonce).await.unwrap();
+
+        // The same nonce is still available on another network
+        assert!(!storage.has_nonce(&fuji, nonce).await.unwrap());
+        storage.mark_nonce(&fuji, nonce).await.unwrap();
+
+        // But a repeat within either scope is blocked
+        assert!(storage.has_nonce(&sepolia, nonce).await.unwrap());
+        assert!(storage.has_nonce(&fuji, nonce).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_storage_ttl() {
+        let storage = InMemoryStorage::new().with_ttl(Duration::from_millis(50));
+        storage.mark_nonce(SCOPE, "old").await.unwrap();
+        assert!(storage.has_nonce(SCOPE, "old").await.unwrap());
+
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        // Expired before eviction, and evicted by the next mark
+        assert!(!storage.has_nonce(SCOPE, "old").await.unwrap());
+        storage.mark_nonce(SCOPE, "new").await.unwrap();
+        assert_eq!(storage.len().await, 1);
+        assert!(storage.has_nonce(SCOPE, "new").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_storage_max_entries() {
+        let storage = InMemoryStorage::new().with_max_entries(2);
+        for nonce in ["a", "b", "c"] {
+            storage.mark_nonce(SCOPE, nonce).await.unwrap();
+        }
+
+        assert_eq!(storage.len().await, 2);
+        assert!(!storage.has_nonce(SCOPE, "a").await.unwrap());
+        assert!(storage.has_nonce(SCOPE, "b").await.unwrap());
+        assert!(storage.has_nonce(SCOPE, "c").await.unwrap());
+
+        // A removed nonce frees its slot without evicting another
+        storage.remove_nonce(SCOPE, "b").await.unwrap();
+        storage.mark_nonce(SCOPE, "d").await.unwrap();
+        assert!(storage.has_nonce(SCOPE, "c").await.unwrap());
+        assert!(storage.has_nonce(SCOPE, "d").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_storage_order_bounded() {
+        let storage = InMemoryStorage::new().with_ttl(Duration::from_secs(3600));
+        // A live nonce at the front keeps stale entries from reaching it
+        storage.mark_nonce(SCOPE, "live").await.unwrap();
+        for i in 0..1000 {
+            let nonce = format!("released-{}", i);
+            assert!(storage.claim_nonce(SCOPE, &nonce).await.unwrap());
+            storage.remove_nonce(SCOPE, &nonce).await.unwrap();
+        }
+
+        let nonces = storage.nonces.read().await;
+        assert_eq!(nonces.marked.len(), 1);
+        assert!(nonces.order.len() <= 2);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_storage_claim_nonce() {
+        let storage = InMemoryStorage::new().with_ttl(Duration::from_millis(50));
+        assert!(storage.claim_nonce(SCOPE, "claimed").await.unwrap());
+        assert!(!storage.claim_nonce(SCOPE, "claimed").await.unwrap());
+        assert!(storage.has_nonce(SCOPE, "claimed").await.unwrap());
+
+        // An expired nonce can be claimed again
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        assert!(storage.claim_nonce(SCOPE, "claimed").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_storage_ping() {
+        assert!(InMemoryStorage::new().ping().await.is_ok());
+    }
+
     #[tokio::test]
     async fn test_in_memory_storage_creation() {
         let storage = InMemoryStorage::new();
-        assert!(storage.has_nonce("test").await.unwrap() == false);
+        assert!(!storage.has_nonce(SCOPE, "test").await.unwrap());
     }
 
     #[tokio::test]
@@ -82,14 +333,14 @@ mod tests {
         let test_nonce = "test_nonce_123";
 
         // Initially, nonce should not exist
-        let exists = storage.has_nonce(test_nonce).await.unwrap();
+        let exists = storage.has_nonce(SCOPE, test_nonce).await.unwrap();
         assert!(!exists, "Nonce should not exist initially");
 
         // Mark nonce as processed
-        storage.mark_nonce(test_nonce).await.unwrap();
+        storage.mark_nonce(SCOPE, test_nonce).await.unwrap();
 
         // Now nonce should exist
-        let exists = storage.has_nonce(test_nonce).await.unwrap();
+        let exists = storage.has_nonce(SCOPE, test_nonce).await.unwrap();
         assert!(exists, "Nonce should exist after marking");
     }
 
@@ -99,11 +350,11 @@ mod tests {
         let test_nonce = "test_nonce_mark_456";
 
         // Mark nonce should succeed
-        let result = storage.mark_nonce(test_nonce).await;
+        let result = storage.mark_nonce(SCOPE, test_nonce).await;
         assert!(result.is_ok(), "mark_nonce should succeed");
 
         // Verify nonce was marked
-        let exists = storage.has_nonce(test_nonce).await.unwrap();
+        let exists = storage.has_nonce(SCOPE, test_nonce).await.unwrap();
         assert!(exists, "Nonce should exist after marking");
     }
 
@@ -113,15 +364,15 @@ mod tests {
         let test_nonce = "test_nonce_remove_789";
 
         // Mark nonce first
-        storage.mark_nonce(test_nonce).await.unwrap();
-        assert!(storage.has_nonce(test_nonce).await.unwrap());
+        storage.mark_nonce(SCOPE, test_nonce).await.unwrap();
+        assert!(storage.has_nonce(SCOPE, test_nonce).await.unwrap());
 
         // Remove nonce
-        let result = storage.remove_nonce(test_nonce).await;
+        let result = storage.remove_nonce(SCOPE, test_nonce).await;
         assert!(result.is_ok(), "remove_nonce should succeed");
 
         // Verify nonce was removed
-        let exists = storage.has_nonce(test_nonce).await.unwrap();
+        let exists = storage.has_nonce(SCOPE, test_nonce).await.unwrap();
         assert!(!exists, "Nonce should not exist after removal");
     }
 
@@ -131,13 +382,13 @@ mod tests {
         let test_nonce = "test_nonce_replay_abc";
 
         // First mark should succeed
-        assert!(storage.has_nonce(test_nonce).await.unwrap() == false);
-        storage.mark_nonce(test_nonce).await.unwrap();
+        assert!(!storage.has_nonce(SCOPE, test_nonce).await.unwrap());
+        storage.mark_nonce(SCOPE, test_nonce).await.unwrap();
 
         // Second mark should still work (idempotent), but has_nonce should return true
-        storage.mark_nonce(test_nonce).await.unwrap();
+        storage.mark_nonce(SCOPE, test_nonce).await.unwrap();
         assert!(
-            storage.has_nonce(test_nonce).await.unwrap(),
+            storage.has_nonce(SCOPE, test_nonce).await.unwrap(),
             "Nonce should still exist after second mark"
         );
     }
@@ -151,26 +402,26 @@ mod tests {
         let nonce3 = "nonce3";
 
         // Mark multiple nonces
-        storage.mark_nonce(nonce1).await.unwrap();
-        storage.mark_nonce(nonce2).await.unwrap();
-        storage.mark_nonce(nonce3).await.unwrap();
+        storage.mark_nonce(SCOPE, nonce1).await.unwrap();
+        storage.mark_nonce(SCOPE, nonce2).await.unwrap();
+        storage.mark_nonce(SCOPE, nonce3).await.unwrap();
 
         // Verify all exist
-        assert!(storage.has_nonce(nonce1).await.unwrap());
-        assert!(storage.has_nonce(nonce2).await.unwrap());
-        assert!(storage.has_nonce(nonce3).await.unwrap());
+        assert!(storage.has_nonce(SCOPE, nonce1).await.unwrap());
+        assert!(storage.has_nonce(SCOPE, nonce2).await.unwrap());
+        assert!(storage.has_nonce(SCOPE, nonce3).await.unwrap());
 
         // Remove one
-        storage.remove_nonce(nonce2).await.unwrap();
-        assert!(!storage.has_nonce(nonce2).await.unwrap());
-        assert!(storage.has_nonce(nonce1).await.unwrap());
-        assert!(storage.has_nonce(nonce3).await.unwrap());
+        storage.remove_nonce(SCOPE, nonce2).await.unwrap();
+        assert!(!storage.has_nonce(SCOPE, nonce2).await.unwrap());
+        assert!(storage.has_nonce(SCOPE, nonce1).await.unwrap());
+        assert!(storage.has_nonce(SCOPE, nonce3).await.unwrap());
     }
 }
 
 #[cfg(feature = "redis")]
 pub mod redis_storage {
-    use super::{NonceStorage, Result};
+    use super::{scoped_key, NonceStorage, Result};
     use redis::{AsyncCommands, Client};
 
     /// Redis-based storage implementation
@@ -201,14 +452,19 @@ pub mod redis_storage {
             Ok(Self { client, key_prefix })
         }
 
-        fn make_key(&self, nonce: &str) -> String {
+        fn make_key(&self, scope: &str, nonce: &str) -> String {
+            format!("{}{}", self.key_prefix, scoped_key(scope, nonce))
+        }
+
+        /// Key used before nonces were scoped, still checked during migration
+        fn legacy_key(&self, nonce: &str) -> String {
             format!("{}{}", self.key_prefix, nonce)
         }
     }
 
     #[async_trait::async_trait]
     impl NonceStorage for RedisStorage {
-        async fn has_nonce(&self, nonce: &str) -> Result<bool> {
+        async fn has_nonce(&self, scope: &str, nonce: &str) -> Result<bool> {
             let mut conn = self
                 .client
                 .get_multiplexed_async_connection()
@@ -217,15 +473,15 @@ pub mod redis_storage {
                     crate::X402Error::config(format!("Failed to get Redis connection: {}", e))
                 })?;
 
-            let key = self.make_key(nonce);
-            let exists: bool = conn.exists(&key).await.map_err(|e| {
+            let keys = [self.make_key(scope, nonce), self.legacy_key(nonce)];
+            let count: u32 = conn.exists(&keys).await.map_err(|e| {
                 crate::X402Error::config(format!("Redis EXISTS command failed: {}", e))
             })?;
 
-            Ok(exists)
+            Ok(count > 0)
         }
 
-        async fn mark_nonce(&self, nonce: &str) -> Result<()> {
+        async fn mark_nonce(&self, scope: &str, nonce: &str) -> Result<()> {
             let mut conn = self
                 .client
                 .get_multiplexed_async_connection()
@@ -234,7 +490,7 @@ pub mod redis_storage {
                     crate::X402Error::config(format!("Failed to get Redis connection: {}", e))
                 })?;
 
-            let key = self.make_key(nonce);
+            let key = self.make_key(scope, nonce);
             // Set with TTL of 24 hours to prevent unbounded growth
             conn.set_ex::<_, _, ()>(&key, "1", 86400)
                 .await
@@ -245,7 +501,7 @@ pub mod redis_storage {
             Ok(())
         }
 
-        async fn remove_nonce(&self, nonce: &str) -> Result<()> {
+        async fn claim_nonce(&self, scope: &str, nonce: &str) -> Result<bool> {
             let mut conn = self
                 .client
                 .get_multiplexed_async_connection()
@@ -254,13 +510,64 @@ pub mod redis_storage {
                     crate::X402Error::config(format!("Failed to get Redis connection: {}", e))
                 })?;
 
-            let key = self.make_key(nonce);
+            let legacy: bool = conn.exists(self.legacy_key(nonce)).await.map_err(|e| {
+                crate::X402Error::config(format!("Redis EXISTS command failed: {}", e))
+            })?;
+            if legacy {
+                return Ok(false);
+            }
+
+            // SET NX answers nil when the key already exists
+            let claimed: Option<String> = redis::cmd("SET")
+                .arg(self.make_key(scope, nonce))
+                .arg("1")
+                .arg("NX")
+                .arg("EX")
+                .arg(86400)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| {
+                    crate::X402Error::config(format!("Redis SET NX command failed: {}", e))
+                })?;
+
+            Ok(claimed.is_some())
+        }
+
+        async fn remove_nonce(&self, scope: &str, nonce: &str) -> Result<()> {
+            let mut conn = self
+                .client
+                .get_multiplexed_async_connection()
+                .await
+                .map_err(|e| {
+                    crate::X402Error::config(format!("Failed to get Redis connection: {}", e))
+                })?;
+
+            let key = self.make_key(scope, nonce);
             conn.del::<_, ()>(&key).await.map_err(|e| {
                 crate::X402Error::config(format!("Redis DEL command failed: {}", e))
             })?;
 
             Ok(())
         }
+
+        async fn ping(&self) -> Result<()> {
+            let mut conn = self
+                .client
+                .get_multiplexed_async_connection()
+                .await
+                .map_err(|e| {
+                    crate::X402Error::config(format!("Failed to get Redis connection: {}", e))
+                })?;
+
+            let _: String = redis::cmd("PING")
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| {
+                    crate::X402Error::config(format!("Redis PING command failed: {}", e))
+                })?;
+
+            Ok(())
+        }
     }
 
     #[cfg(test)]
@@ -268,6 +575,8 @@ pub mod redis_storage {
         use super::*;
         use std::env;
 
+        const SCOPE: &str = "base-sepolia:0x036cbd53842c5426634e7929541ec2318f3dcf7e";
+
         /// Helper function to check if Redis is available
         /// Tests will be skipped if Redis is not available
         async fn check_redis_available(redis_url: &str) -> bool {
@@ -311,6 +620,15 @@ pub mod redis_storage {
             assert_eq!(storage.key_prefix, "x402:nonce:");
         }
 
+        #[tokio::test]
+        async fn test_redis_storage_ping_unreachable() {
+            // Nothing listens on port 1, so the ping must fail rather than hang
+            let storage = RedisStorage::new("redis://127.0.0.1:1", None)
+                .await
+                .unwrap();
+            assert!(storage.ping().await.is_err());
+        }
+
         #[tokio::test]
         async fn test_redis_storage_custom_prefix() {
             let redis_url =
@@ -347,18 +665,18 @@ pub mod redis_storage {
             let test_nonce = "test_nonce_123";
 
             // Initially, nonce should not exist
-            let exists = storage.has_nonce(test_nonce).await.unwrap();
+            let exists = storage.has_nonce(SCOPE, test_nonce).await.unwrap();
             assert!(!exists, "Nonce should not exist initially");
 
             // Mark nonce as processed
-            storage.mark_nonce(test_nonce).await.unwrap();
+            storage.mark_nonce(SCOPE, test_nonce).await.unwrap();
 
             // Now nonce should exist
-            let exists = storage.has_nonce(test_nonce).await.unwrap();
+            let exists = storage.has_nonce(SCOPE, test_nonce).await.unwrap();
             assert!(exists, "Nonce should exist after marking");
 
             // Clean up
-            storage.remove_nonce(test_nonce).await.unwrap();
+            storage.remove_nonce(SCOPE, test_nonce).await.unwrap();
         }
 
         #[tokio::test]
@@ -379,15 +697,15 @@ pub mod redis_storage {
             let test_nonce = "test_nonce_mark_456";
 
             // Mark nonce should succeed
-            let result = storage.mark_nonce(test_nonce).await;
+            let result = storage.mark_nonce(SCOPE, test_nonce).await;
             assert!(result.is_ok(), "mark_nonce should succeed");
 
             // Verify nonce was marked
-            let exists = storage.has_nonce(test_nonce).await.unwrap();
+            let exists = storage.has_nonce(SCOPE, test_nonce).await.unwrap();
             assert!(exists, "Nonce should exist after marking");
 
             // Clean up
-            storage.remove_nonce(test_nonce).await.unwrap();
+            storage.remove_nonce(SCOPE, test_nonce).await.unwrap();
         }
 
         #[tokio::test]
@@ -408,15 +726,15 @@ pub mod redis_storage {
             let test_nonce = "test_nonce_remove_789";
 
             // Mark nonce first
-            storage.mark_nonce(test_nonce).await.unwrap();
-            assert!(storage.has_nonce(test_nonce).await.unwrap());
+            storage.mark_nonce(SCOPE, test_nonce).await.unwrap();
+            assert!(storage.has_nonce(SCOPE, test_nonce).await.unwrap());
 
             // Remove nonce
-            let result = storage.remove_nonce(test_nonce).await;
+            let result = storage.remove_nonce(SCOPE, test_nonce).await;
             assert!(result.is_ok(), "remove_nonce should succeed");
 
             // Verify nonce was removed
-            let exists = storage.has_nonce(test_nonce).await.unwrap();
+            let exists = storage.has_nonce(SCOPE, test_nonce).await.unwrap();
             assert!(!exists, "Nonce should not exist after removal");
         }
 
@@ -438,18 +756,52 @@ pub mod redis_storage {
             let test_nonce = "test_nonce_replay_abc";
 
             // First mark should succeed
-            assert!(storage.has_nonce(test_nonce).await.unwrap() == false);
-            storage.mark_nonce(test_nonce).await.unwrap();
+            assert!(!storage.has_nonce(SCOPE, test_nonce).await.unwrap());
+            storage.mark_nonce(SCOPE, test_nonce).await.unwrap();
 
             // Second mark should still work (idempotent), but has_nonce should return true
-            storage.mark_nonce(test_nonce).await.unwrap();
+            storage.mark_nonce(SCOPE, test_nonce).await.unwrap();
             assert!(
-                storage.has_nonce(test_nonce).await.unwrap(),
+                storage.has_nonce(SCOPE, test_nonce).await.unwrap(),
                 "Nonce should still exist after second mark"
             );
 
             // Clean up
-            storage.remove_nonce(test_nonce).await.unwrap();
+            storage.remove_nonce(SCOPE, test_nonce).await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn test_redis_storage_scoped_and_legacy_keys() {
+            let redis_url =
+                env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+
+            if !check_redis_available(&redis_url).await {
+                println!("Skipping Redis test: Redis not available at {}", redis_url);
+                return;
+            }
+
+            let test_prefix = format!("test:{}:", uuid::Uuid::new_v4());
+            let storage = RedisStorage::new(&redis_url, Some(&test_prefix))
+                .await
+                .unwrap();
+            let other_scope = "avalanche-fuji:0x5425890298aed601595a70ab815c96711a31bc65";
+
+            storage.mark_nonce(SCOPE, "scoped").await.unwrap();
+            assert!(storage.has_nonce(SCOPE, "scoped").await.unwrap());
+            assert!(!storage.has_nonce(other_scope, "scoped").await.unwrap());
+
+            // Nonces written before scoping still block replays in every scope
+            let mut conn = storage
+                .client
+                .get_multiplexed_async_connection()
+                .await
+                .unwrap();
+            let legacy = storage.legacy_key("legacy");
+            conn.set_ex::<_, _, ()>(&legacy, "1", 60).await.unwrap();
+            assert!(storage.has_nonce(other_scope, "legacy").await.unwrap());
+
+            storage.remove_nonce(SCOPE, "scoped").await.unwrap();
+            conn.del::<_, ()>(&legacy).await.unwrap();
         }
 
         #[tokio::test]
@@ -470,7 +822,7 @@ pub mod redis_storage {
             let test_nonce = "test_nonce_ttl_xyz";
 
             // Mark nonce (should have TTL of 24 hours)
-            storage.mark_nonce(test_nonce).await.unwrap();
+            storage.mark_nonce(SCOPE, test_nonce).await.unwrap();
 
             // Verify key exists and has TTL
             let mut conn = storage
@@ -478,7 +830,7 @@ pub mod redis_storage {
                 .get_multiplexed_async_connection()
                 .await
                 .unwrap();
-            let key = storage.make_key(test_nonce);
+            let key = storage.make_key(SCOPE, test_nonce);
             let ttl: i64 = conn.ttl(&key).await.unwrap();
 
             // TTL should be positive (less than 86400 seconds = 24 hours)
@@ -489,7 +841,7 @@ pub mod redis_storage {
             );
 
             // Clean up
-            storage.remove_nonce(test_nonce).await.unwrap();
+            storage.remove_nonce(SCOPE, test_nonce).await.unwrap();
         }
 
         #[tokio::test]
@@ -512,24 +864,24 @@ pub mod redis_storage {
             let nonce3 = "nonce3";
 
             // Mark multiple nonces
-            storage.mark_nonce(nonce1).await.unwrap();
-            storage.mark_nonce(nonce2).await.unwrap();
-            storage.mark_nonce(nonce3).await.unwrap();
+            storage.mark_nonce(SCOPE, nonce1).await.unwrap();
+            storage.mark_nonce(SCOPE, nonce2).await.unwrap();
+            storage.mark_nonce(SCOPE, nonce3).await.unwrap();
 
             // Verify all exist
-            assert!(storage.has_nonce(nonce1).await.unwrap());
-            assert!(storage.has_nonce(nonce2).await.unwrap());
-            assert!(storage.has_nonce(nonce3).await.unwrap());
+            assert!(storage.has_nonce(SCOPE, nonce1).await.unwrap());
+            assert!(storage.has_nonce(SCOPE, nonce2).await.unwrap());
+            assert!(storage.has_nonce(SCOPE, nonce3).await.unwrap());
 
             // Remove one
-            storage.remove_nonce(nonce2).await.unwrap();
-            assert!(!storage.has_nonce(nonce2).await.unwrap());
-            assert!(storage.has_nonce(nonce1).await.unwrap());
-            assert!(storage.has_nonce(nonce3).await.unwrap());
+            storage.remove_nonce(SCOPE, nonce2).await.unwrap();
+            assert!(!storage.has_nonce(SCOPE, nonce2).await.unwrap());
+            assert!(storage.has_nonce(SCOPE, nonce1).await.unwrap());
+            assert!(storage.has_nonce(SCOPE, nonce3).await.unwrap());
 
             // Clean up
-            storage.remove_nonce(nonce1).await.unwrap();
-            storage.remove_nonce(nonce3).await.unwrap();
+            storage.remove_nonce(SCOPE, nonce1).await.unwrap();
+            storage.remove_nonce(SCOPE, nonce3).await.unwrap();
         }
     }
 }
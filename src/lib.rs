@@ -7,9 +7,17 @@ pub mod crypto;
 pub mod error;
 pub mod facilitator;
 pub mod facilitator_storage;
+pub mod native;
+pub mod settlement_store;
+pub mod signer;
 pub mod template;
 pub mod types;
 pub mod wallet;
+pub mod webhook;
+
+// Solana payment scheme (feature-gated)
+#[cfg(feature = "solana")]
+pub mod solana;
 
 // HTTP/3 support (feature-gated)
 #[cfg(feature = "http3")]
@@ -27,13 +35,30 @@ pub mod middleware;
 #[cfg(feature = "axum")]
 pub mod proxy;
 
+// Browser-side payment signing (feature-gated)
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+// Mock facilitator for tests (feature-gated; always built for the crate's own tests)
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_support;
+
+// OpenTelemetry span export (feature-gated)
+#[cfg(feature = "otel")]
+pub mod otel;
+
+// Per-event metering for streamed responses (feature-gated, requires axum)
+#[cfg(feature = "axum")]
+pub mod metering;
+
 // Re-exports for convenience
 pub use blockchain::{BlockchainClient, BlockchainClientFactory};
 pub use blockchain_facilitator::{
     BlockchainFacilitatorClient, BlockchainFacilitatorConfig, BlockchainFacilitatorFactory,
 };
-pub use client::X402Client;
+pub use client::{PaidResponse, PaymentReceipt, PaymentSelector, X402Client};
 pub use error::{Result, X402Error};
+pub use signer::{LocalSigner, Signer};
 pub use types::*;
 pub use wallet::{Wallet, WalletFactory};
 
@@ -62,6 +87,12 @@ mod tests {
         assert_eq!(X402_VERSION, 1);
         // VERSION is a const string, so it's never empty
         assert!(!VERSION.is_empty());
+
+        assert!(supported_versions().contains(&X402_VERSION));
+        assert!(is_supported_version(X402_MIN_VERSION));
+        assert!(is_supported_version(X402_MAX_VERSION));
+        assert!(!is_supported_version(X402_MIN_VERSION - 1));
+        assert!(!is_supported_version(X402_MAX_VERSION + 1));
     }
 
     #[test]
@@ -161,6 +192,103 @@ mod tests {
         assert_eq!(payment_payload.network, decoded.network);
     }
 
+    #[test]
+    fn test_payment_payload_header_value_round_trip() {
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+
+        let payload = ExactEvmPayload {
+            signature: "0x2d6a7588d6acca505cbf0d9a4a227e0c52c6c34008c8e8986a1283259764173608a2ce6496642e377d6da8dbbf5836e9bd15092f9ecab05ded3d6293af148b571c".to_string(),
+            authorization,
+        };
+
+        let payment_payload = PaymentPayload::new("exact", "base-sepolia", payload);
+        let header_value = payment_payload.to_header_value().unwrap();
+        assert_eq!(
+            header_value.to_str().unwrap(),
+            payment_payload.to_base64().unwrap()
+        );
+
+        let decoded = PaymentPayload::from_header_value(&header_value).unwrap();
+        assert_eq!(payment_payload.scheme, decoded.scheme);
+        assert_eq!(payment_payload.network, decoded.network);
+        assert_eq!(
+            payment_payload.payload.authorization.nonce,
+            decoded.payload.authorization.nonce
+        );
+    }
+
+    #[test]
+    fn test_payment_payload_strict_rejects_unknown_fields() {
+        use base64::{engine::general_purpose, Engine as _};
+
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1745323800",
+            "1745323985",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let payload = ExactEvmPayload {
+            signature: "0x2d6a7588d6acca505cbf0d9a4a227e0c52c6c34008c8e8986a1283259764173608a2ce6496642e377d6da8dbbf5836e9bd15092f9ecab05ded3d6293af148b571c".to_string(),
+            authorization,
+        };
+        let payment_payload = PaymentPayload::new("exact", "base-sepolia", payload);
+
+        // Well-formed payloads parse in both modes
+        let encoded = payment_payload.to_base64().unwrap();
+        assert!(PaymentPayload::from_base64_strict(&encoded).is_ok());
+
+        let mut json = serde_json::to_value(&payment_payload).unwrap();
+        json["foo"] = serde_json::json!("bar");
+        let encoded = general_purpose::STANDARD.encode(json.to_string());
+        assert!(PaymentPayload::from_base64(&encoded).is_ok());
+        assert!(PaymentPayload::from_base64_strict(&encoded).is_err());
+
+        // Unknown fields are rejected in nested objects too
+        let mut json = serde_json::to_value(&payment_payload).unwrap();
+        json["payload"]["authorization"]["foo"] = serde_json::json!("bar");
+        let encoded = general_purpose::STANDARD.encode(json.to_string());
+        assert!(PaymentPayload::from_base64(&encoded).is_ok());
+        assert!(PaymentPayload::from_base64_strict(&encoded).is_err());
+    }
+
+    #[test]
+    fn test_payment_requirements_strict_rejects_unknown_fields() {
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+
+        let json = serde_json::to_vec(&requirements).unwrap();
+        assert!(PaymentRequirements::from_json_strict(&json).is_ok());
+
+        let mut value = serde_json::to_value(&requirements).unwrap();
+        value["foo"] = serde_json::json!(1);
+        let json = serde_json::to_vec(&value).unwrap();
+        assert!(serde_json::from_slice::<PaymentRequirements>(&json).is_ok());
+        assert!(PaymentRequirements::from_json_strict(&json).is_err());
+    }
+
+    #[test]
+    fn test_payment_payload_header_value_invalid_base64() {
+        let header_value = http::HeaderValue::from_static("not-valid-base64!!");
+        let result = PaymentPayload::from_header_value(&header_value);
+        assert!(matches!(result, Err(X402Error::Base64(_))));
+    }
+
     #[test]
     fn test_authorization_validity() {
         let now = chrono::Utc::now().timestamp();
@@ -197,27 +325,419 @@ mod tests {
         assert!(!authorization.is_valid_now().unwrap());
     }
 
+    #[test]
+    fn test_payment_requirements_matches() {
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+        let payload = |network: &str, to: &str, value: &str| {
+            PaymentPayload::new(
+                "exact",
+                network,
+                ExactEvmPayload {
+                    signature: "0x00".to_string(),
+                    authorization: ExactEvmPayloadAuthorization::new(
+                        "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+                        to,
+                        value,
+                        "1745323800",
+                        "1745323985",
+                        "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+                    ),
+                },
+            )
+        };
+        let pay_to = "0x209693bc6afc0c5328ba36faf03c514ef312287c";
+
+        // Recipient comparison ignores checksum casing; overpaying is fine
+        assert!(requirements.matches(&payload("base-sepolia", pay_to, "1000000")));
+        assert!(requirements.matches(&payload("base-sepolia", pay_to, "2000000")));
+
+        assert!(!requirements.matches(&payload("base", pay_to, "1000000")));
+        assert!(!requirements.matches(&payload(
+            "base-sepolia",
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "1000000"
+        )));
+        assert!(!requirements.matches(&payload("base-sepolia", pay_to, "999999")));
+        assert!(!requirements.matches(&payload("base-sepolia", pay_to, "lots")));
+
+        let mut other_scheme = payload("base-sepolia", pay_to, "1000000");
+        other_scheme.scheme = schemes::UPTO.to_string();
+        assert!(!requirements.matches(&other_scheme));
+    }
+
+    #[test]
+    fn test_payment_requirements_display_amount() {
+        let display = |atomic: &str, decimals: u8, symbol: &str| {
+            PaymentRequirements::new(
+                "exact",
+                "base-sepolia",
+                atomic,
+                "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+                "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+                "https://example.com/test",
+                "Test payment",
+            )
+            .display_amount(decimals, symbol)
+        };
+
+        // Sub-cent amounts stay in plain notation
+        assert_eq!(display("100", 6, "USDC"), "0.0001 USDC");
+        assert_eq!(display("1", 6, "USDC"), "0.000001 USDC");
+        assert_eq!(display("1", 18, "ETH"), "0.000000000000000001 ETH");
+
+        // Whole amounts drop the fraction
+        assert_eq!(display("1000000", 6, "USDC"), "1 USDC");
+        assert_eq!(display("0", 6, "USDC"), "0 USDC");
+        assert_eq!(display("1500000", 6, "USDC"), "1.5 USDC");
+
+        // Large amounts are grouped, beyond what fits in a u128
+        assert_eq!(display("1234567890000", 6, "USDC"), "1,234,567.89 USDC");
+        assert_eq!(display("1000", 0, ""), "1,000");
+        assert_eq!(
+            display("123456789012345678901234567890123456789012", 18, "ETH"),
+            "123,456,789,012,345,678,901,234.567890123456789012 ETH"
+        );
+
+        assert_eq!(display("not-a-number", 6, "USDC"), "not-a-number USDC");
+    }
+
+    #[test]
+    fn test_payment_requirements_decimal_amount_round_trip() {
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "0",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+        let amount = rust_decimal::Decimal::new(1, 4);
+
+        let requirements = requirements
+            .with_decimal_amount(amount, USDC_DECIMALS)
+            .unwrap();
+        assert_eq!(requirements.max_amount_required, "100");
+        assert_eq!(requirements.decimal_amount(USDC_DECIMALS).unwrap(), amount);
+
+        // Sub-atomic precision and negative amounts are rejected
+        let too_precise = rust_decimal::Decimal::new(1, 7);
+        assert!(requirements
+            .clone()
+            .with_decimal_amount(too_precise, USDC_DECIMALS)
+            .is_err());
+        assert!(requirements
+            .with_decimal_amount(-amount, USDC_DECIMALS)
+            .is_err());
+    }
+
+    #[test]
+    fn test_amount_conversions() {
+        let amount = Amount::from_decimal(rust_decimal::Decimal::new(1, 4), USDC_DECIMALS).unwrap();
+        assert_eq!(amount.atomic(), 100);
+        assert_eq!(amount.as_atomic_string(), "100");
+        assert_eq!(amount.to_decimal().unwrap().to_string(), "0.0001");
+        assert_eq!(
+            Amount::new(1_234_500_000, 6).unwrap().to_string(),
+            "1,234.5"
+        );
+
+        let price = Amount::from_atomic_str("250", USDC_DECIMALS).unwrap();
+        assert_eq!(price.checked_add(amount).unwrap().atomic(), 350);
+        assert_eq!(price.checked_sub(amount).unwrap().atomic(), 150);
+        assert_eq!(price.checked_mul(4).unwrap().atomic(), 1000);
+        assert!(price > amount);
+
+        // Amounts of differently scaled tokens do not mix
+        let wei = Amount::new(100, NATIVE_DECIMALS).unwrap();
+        assert!(amount.checked_add(wei).is_err());
+        assert_eq!(amount.partial_cmp(&wei), None);
+
+        assert!(Amount::from_atomic_str("1.5", USDC_DECIMALS).is_err());
+        assert!(Amount::from_atomic_str("-1", USDC_DECIMALS).is_err());
+    }
+
+    #[test]
+    fn test_amount_overflow() {
+        let overflow = |result: Result<Amount>| {
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "Invalid payment requirements: amount overflow"
+            );
+        };
+
+        // u128::MAX parses as an integer but exceeds the cap
+        overflow(Amount::new(u128::MAX, 18));
+        overflow(Amount::from_atomic_str(&u128::MAX.to_string(), 18));
+        overflow(Amount::from_atomic_str(
+            "340282366920938463463374607431768211456",
+            18,
+        ));
+
+        let max = Amount::new(MAX_ATOMIC_AMOUNT, 18).unwrap();
+        assert_eq!(
+            max.to_decimal().unwrap() * rust_decimal::Decimal::from(10u64.pow(18)),
+            rust_decimal::Decimal::MAX
+        );
+        overflow(max.checked_add(Amount::new(1, 18).unwrap()));
+        overflow(max.checked_mul(2));
+        overflow(
+            Amount::new(0, 18)
+                .unwrap()
+                .checked_sub(Amount::new(1, 18).unwrap()),
+        );
+
+        // Decimal conversions that cannot fit in atomic units
+        overflow(Amount::from_decimal(rust_decimal::Decimal::MAX, 18));
+        overflow(Amount::from_decimal(rust_decimal::Decimal::ONE, 40));
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "0",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/resource",
+            "Test resource",
+        );
+        assert!(requirements
+            .clone()
+            .with_decimal_amount(rust_decimal::Decimal::MAX, USDC_DECIMALS)
+            .is_err());
+
+        // Negative amounts are rejected; Decimal has no NaN to convert
+        assert!(Amount::from_decimal(rust_decimal::Decimal::NEGATIVE_ONE, 6).is_err());
+        assert!(Amount::from_decimal(rust_decimal::Decimal::ZERO, 6).is_ok());
+        assert!(
+            <rust_decimal::Decimal as rust_decimal::prelude::FromPrimitive>::from_f64(f64::NAN)
+                .is_none()
+        );
+
+        // An authorization for u128::MAX never matches the requirements
+        let mut payload = PaymentPayload::new(
+            "exact",
+            "base-sepolia",
+            ExactEvmPayload {
+                signature: "0x00".to_string(),
+                authorization: ExactEvmPayloadAuthorization::new(
+                    "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+                    "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+                    u128::MAX.to_string(),
+                    "1745323800",
+                    "1745323985",
+                    "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+                ),
+            },
+        );
+        assert!(!requirements.matches(&payload));
+        payload.payload.authorization.value = "1".to_string();
+        assert!(requirements.matches(&payload));
+    }
+
+    #[test]
+    fn test_payment_requirements_builder() {
+        let requirements = PaymentRequirements::builder()
+            .scheme("exact")
+            .network(networks::BASE_SEPOLIA)
+            .amount_atomic("1000000")
+            .asset("0x036CbD53842c5426634e7929541eC2318f3dCF7e")
+            .pay_to("0x209693Bc6afc0C5328bA36FaF03C514EF312287C")
+            .resource("https://example.com/resource")
+            .description("Test resource")
+            .max_timeout_seconds(120)
+            .build()
+            .unwrap();
+
+        assert_eq!(requirements.network, "base-sepolia");
+        assert_eq!(requirements.max_amount_required, "1000000");
+        assert_eq!(
+            requirements.pay_to,
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C"
+        );
+        assert_eq!(requirements.max_timeout_seconds, 120);
+    }
+
+    #[test]
+    fn test_payment_requirements_builder_validation() {
+        let builder = PaymentRequirements::builder()
+            .scheme("exact")
+            .network(networks::BASE_SEPOLIA)
+            .amount_atomic("1000000")
+            .asset("0x036CbD53842c5426634e7929541eC2318f3dCF7e")
+            .resource("https://example.com/resource");
+
+        let missing = builder.clone().build().unwrap_err();
+        assert!(missing.to_string().contains("pay_to"), "{}", missing);
+
+        let bad_address = builder
+            .clone()
+            .pay_to("https://example.com/resource")
+            .build()
+            .unwrap_err();
+        assert!(matches!(
+            bad_address,
+            X402Error::InvalidPaymentRequirements { .. }
+        ));
+
+        let bad_amount = builder
+            .pay_to("0x209693Bc6afc0C5328bA36FaF03C514EF312287C")
+            .amount_atomic("0.5")
+            .build();
+        assert!(bad_amount.is_err());
+    }
+
+    #[test]
+    fn test_authorization_clock_skew() {
+        let now = chrono::Utc::now().timestamp();
+        let authorization = |valid_after: i64, valid_before: i64| {
+            ExactEvmPayloadAuthorization::new(
+                "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+                "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+                "1000000",
+                valid_after.to_string(),
+                valid_before.to_string(),
+                "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+            )
+        };
+
+        // validAfter slightly in the future (client clock ahead)
+        let early = authorization(now + 3, now + 100);
+        assert!(!early.is_valid_now().unwrap());
+        assert!(early.is_valid_now_with_skew(DEFAULT_CLOCK_SKEW).unwrap());
+
+        // validBefore slightly in the past
+        let late = authorization(now - 100, now - 3);
+        assert!(!late.is_valid_now().unwrap());
+        assert!(late.is_valid_now_with_skew(DEFAULT_CLOCK_SKEW).unwrap());
+
+        // Beyond the tolerance at either end
+        let skew = std::time::Duration::from_secs(5);
+        assert!(!authorization(now + 30, now + 100)
+            .is_valid_now_with_skew(skew)
+            .unwrap());
+        assert!(!authorization(now - 100, now - 30)
+            .is_valid_now_with_skew(skew)
+            .unwrap());
+    }
+
+    #[test]
+    fn test_authorization_validity_with_clock() {
+        let authorization = ExactEvmPayloadAuthorization::new(
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "1000000",
+            "1000",
+            "2000",
+            "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        );
+        let no_skew = std::time::Duration::ZERO;
+
+        // A host-supplied clock, as a browser would pass from `Date.now()`
+        let clock = || 1500;
+        assert!(authorization.is_valid_with_clock(&clock, no_skew).unwrap());
+        assert!(!authorization
+            .is_valid_with_clock(&|| 2001, no_skew)
+            .unwrap());
+
+        assert!(authorization.is_valid_at(1000, no_skew).unwrap());
+        assert!(authorization.is_valid_at(2000, no_skew).unwrap());
+        assert!(!authorization.is_valid_at(999, no_skew).unwrap());
+        assert!(authorization
+            .is_valid_at(995, std::time::Duration::from_secs(5))
+            .unwrap());
+
+        // The system clock agrees with chrono
+        let now = chrono::Utc::now().timestamp();
+        assert!((SystemClock.unix_timestamp() - now).abs() <= 1);
+    }
+
     #[test]
     fn test_facilitator_config() {
         let config = FacilitatorConfig {
             url: "https://example.com/facilitator".to_string(),
             timeout: Some(std::time::Duration::from_secs(30)),
+            verify_timeout: None,
+            settle_timeout: None,
             create_auth_headers: None,
+            endpoints: FacilitatorEndpoints::default(),
+            http_client: None,
+            supported_cache_ttl: None,
         };
 
         assert_eq!(config.url, "https://example.com/facilitator".to_string());
         assert_eq!(config.timeout, Some(std::time::Duration::from_secs(30)));
     }
 
+    #[test]
+    fn test_fee_policy_basis_points() {
+        let price = 1_000_000u128;
+        for (bps, fee) in [
+            (0, 0),
+            (1, 100),
+            (30, 3_000),
+            (100, 10_000),
+            (250, 25_000),
+            (10_000, 1_000_000),
+        ] {
+            let policy = FeePolicy::BasisPoints(bps);
+            assert_eq!(policy.fee_for(price).unwrap(), fee, "{} bps", bps);
+            assert_eq!(policy.total_for(price).unwrap(), price + fee);
+        }
+
+        // Fractional fees round up in the facilitator's favour
+        assert_eq!(FeePolicy::BasisPoints(30).fee_for(1).unwrap(), 1);
+        assert_eq!(FeePolicy::BasisPoints(30).fee_for(333).unwrap(), 1);
+        assert_eq!(FeePolicy::BasisPoints(30).fee_for(334).unwrap(), 2);
+
+        assert_eq!(FeePolicy::Flat(5_000).fee_for(price).unwrap(), 5_000);
+        assert!(FeePolicy::BasisPoints(100).fee_for(u128::MAX).is_err());
+        assert!(FeePolicy::Flat(1).total_for(u128::MAX).is_err());
+    }
+
+    #[test]
+    fn test_settle_response_fee_breakdown() {
+        let response = SettleResponse {
+            success: true,
+            error_reason: None,
+            transaction: "0xabc".to_string(),
+            network: "base-sepolia".to_string(),
+            payer: None,
+            fee_amount: None,
+            net_amount: None,
+        };
+
+        // Fee fields are omitted unless a fee was taken
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json.get("feeAmount").is_none());
+        let decoded: SettleResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.fee_amount, None);
+
+        let response = response.with_fee_breakdown(1_010_000, 10_000);
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["feeAmount"], "10000");
+        assert_eq!(json["netAmount"], "1000000");
+    }
+
     #[test]
     fn test_blockchain_facilitator_config() {
         let config = BlockchainFacilitatorConfig {
             rpc_url: Some("https://example.com/facilitator".to_string()),
+            rpc_registry: Default::default(),
             network: "base-sepolia".to_string(),
             verification_timeout: std::time::Duration::from_secs(30),
             confirmation_blocks: 1,
             max_retries: 3,
             retry_delay: std::time::Duration::from_secs(1),
+            clock_skew_tolerance: DEFAULT_CLOCK_SKEW,
+            fee: None,
+            simulate_before_settle: false,
         };
 
         assert_eq!(
@@ -230,6 +750,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_supported_kind_metadata() {
+        let kind: SupportedKind = serde_json::from_value(serde_json::json!({
+            "x402Version": 1,
+            "scheme": "exact",
+            "network": "base-sepolia",
+            "metadata": {
+                "feeBps": 25,
+                "minAmount": "1000",
+                "maxAmount": 50000000,
+                "assets": ["0x036CbD53842c5426634e7929541eC2318f3dCF7e"],
+                "operator": "example"
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(kind.fee_bps(), Some(25));
+        assert_eq!(kind.min_amount(), Some(1000));
+        assert_eq!(kind.max_amount(), Some(50_000_000));
+        assert_eq!(
+            kind.assets(),
+            vec!["0x036CbD53842c5426634e7929541eC2318f3dCF7e".to_string()]
+        );
+
+        #[derive(serde::Deserialize)]
+        struct Operator {
+            operator: String,
+        }
+        let operator: Operator = kind.metadata_as().unwrap().unwrap();
+        assert_eq!(operator.operator, "example");
+        assert!(kind.metadata_as::<Vec<String>>().is_err());
+
+        // Without metadata every accessor is empty
+        let bare = SupportedKind {
+            metadata: None,
+            ..kind
+        };
+        assert_eq!(bare.fee_bps(), None);
+        assert_eq!(bare.min_amount(), None);
+        assert!(bare.assets().is_empty());
+        assert!(bare.metadata_as::<Operator>().unwrap().is_none());
+    }
+
     #[test]
     fn test_networks() {
         assert_eq!(networks::BASE_MAINNET, "base");
@@ -251,6 +814,78 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_requirements_signature() {
+        let signer =
+            LocalSigner::new("0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")
+                .unwrap();
+        let address = format!("{:?}", Signer::address(&signer));
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+        let signed =
+            PaymentRequirementsResponse::new("X-PAYMENT header is required", vec![requirements])
+                .with_expires(1_900_000_000)
+                .signed(&signer)
+                .unwrap();
+
+        // The signature survives the trip through JSON
+        let received: PaymentRequirementsResponse =
+            serde_json::from_str(&serde_json::to_string(&signed).unwrap()).unwrap();
+        received.verify_signature(&address).unwrap();
+
+        let mut tampered = received.clone();
+        tampered.accepts[0].max_amount_required = "100000000".to_string();
+        assert!(matches!(
+            tampered.verify_signature(&address),
+            Err(X402Error::InvalidSignature { .. })
+        ));
+        let mut tampered = received.clone();
+        tampered.accepts[0].pay_to = "0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string();
+        assert!(tampered.verify_signature(&address).is_err());
+
+        assert!(received
+            .verify_signature("0x857b06519E91e3A54538791bDbb0E22373e36b66")
+            .is_err());
+        let unsigned = PaymentRequirementsResponse {
+            signature: None,
+            ..received
+        };
+        assert!(unsigned.verify_signature(&address).is_err());
+    }
+
+    #[test]
+    fn test_network_chain_ids() {
+        let expected = [
+            (Network::Mainnet, "base", 8453, false),
+            (Network::Testnet, "base-sepolia", 84532, true),
+            (Network::Avalanche, "avalanche", 43114, false),
+            (Network::AvalancheFuji, "avalanche-fuji", 43113, true),
+        ];
+        for (network, name, chain_id, is_testnet) in expected {
+            assert_eq!(network.as_str(), name);
+            assert_eq!(Network::from_name(name), Some(network));
+            assert_eq!(network.chain_id(), chain_id);
+            assert_eq!(network.is_testnet(), is_testnet);
+
+            // The enum agrees with the name-keyed configuration
+            let config = NetworkConfig::from_name(name).unwrap();
+            assert_eq!(config.chain_id, chain_id);
+            assert_eq!(config.is_testnet, is_testnet);
+            assert_eq!(
+                Some(network.usdc_address().as_str()),
+                networks::get_usdc_address(name)
+            );
+        }
+        assert_eq!(Network::from_name("unsupported-network"), None);
+    }
+
     #[test]
     fn test_schemes() {
         assert_eq!(schemes::EXACT, "exact");
@@ -0,0 +1,463 @@
+//! Per-event metering for streamed responses
+//!
+//! For pay-per-token APIs the client authorizes a ceiling under the
+//! [`upto`](crate::types::schemes::UPTO) scheme and the server settles only
+//! what was consumed. [`MeteredStream`] wraps a stream of events, counts them,
+//! stops before the ceiling would be exceeded and, when the stream ends or is
+//! dropped, settles `events × unit price` with the facilitator.
+//!
+//! Only facilitators that advertise `upto` for the payment's network can
+//! settle less than the signed value, so [`Meter::new`] refuses any other.
+//!
+//! ```rust,no_run
+//! use rust_x402::metering::{metered_sse, Meter};
+//! # async fn handler(
+//! #     meter: Meter,
+//! #     tokens: impl futures_util::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>> + Send + 'static,
+//! # ) -> impl axum::response::IntoResponse {
+//! let (sse, report) = metered_sse(tokens, meter);
+//! tokio::spawn(async move {
+//!     if let Ok(Ok(report)) = report.await {
+//!         tracing::info!("Streamed {} events for {}", report.events, report.amount);
+//!     }
+//! });
+//! sse
+//! # }
+//! ```
+
+use crate::facilitator::FacilitatorClient;
+use crate::types::{schemes, PaymentPayload, PaymentRequirements, SettleResponse};
+use crate::{Result, X402Error};
+use futures_util::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::oneshot;
+
+/// Pricing and settlement details for a metered stream
+#[derive(Debug, Clone)]
+pub struct Meter {
+    facilitator: FacilitatorClient,
+    payment: PaymentPayload,
+    requirements: PaymentRequirements,
+    unit_price: u128,
+}
+
+impl Meter {
+    /// Meter a verified payment at `unit_price` atomic units per event
+    ///
+    /// The authorized ceiling is the payment's authorization value. The
+    /// payment must use the `upto` scheme and `facilitator` must support
+    /// `upto` on its network; otherwise settling the consumed amount would
+    /// charge the whole ceiling, and [`X402Error::SchemeNotSupported`] is
+    /// returned instead.
+    pub async fn new(
+        facilitator: FacilitatorClient,
+        payment: PaymentPayload,
+        requirements: PaymentRequirements,
+        unit_price: u128,
+    ) -> Result<Self> {
+        let upto = payment.scheme == schemes::UPTO && requirements.scheme == schemes::UPTO;
+        if !upto
+            || !facilitator
+                .supported_for(schemes::UPTO, &payment.network)
+                .await?
+        {
+            return Err(X402Error::SchemeNotSupported {
+                scheme: format!("{} on {}", schemes::UPTO, payment.network),
+            });
+        }
+
+        Ok(Self {
+            facilitator,
+            payment,
+            requirements,
+            unit_price,
+        })
+    }
+
+    /// Maximum amount the payer authorized, in atomic units
+    pub fn ceiling(&self) -> Result<u128> {
+        self.payment
+            .payload
+            .authorization
+            .value
+            .parse()
+            .map_err(|_| X402Error::invalid_payment_payload("Invalid payment amount"))
+    }
+
+    /// Amount owed for `events` events, in atomic units
+    pub fn amount_for(&self, events: u64) -> Result<u128> {
+        self.unit_price
+            .checked_mul(u128::from(events))
+            .ok_or_else(|| X402Error::config("Metered amount overflow"))
+    }
+
+    /// Settle the amount owed for `events` events
+    ///
+    /// Nothing is settled when no events were sent. A settlement the
+    /// facilitator refuses is reported with a zero `amount`.
+    pub async fn settle(&self, events: u64) -> Result<MeterReport> {
+        let owed = self.amount_for(events)?;
+        if owed > self.ceiling()? {
+            return Err(X402Error::invalid_payment_payload(
+                "Metered amount exceeds the authorized ceiling",
+            ));
+        }
+        if events == 0 {
+            return Ok(MeterReport {
+                events,
+                amount: 0,
+                settlement: None,
+            });
+        }
+
+        let mut requirements = self.requirements.clone();
+        requirements.max_amount_required = owed.to_string();
+        let settlement = self
+            .facilitator
+            .settle(&self.payment, &requirements)
+            .await?;
+
+        Ok(MeterReport {
+            events,
+            amount: if settlement.success { owed } else { 0 },
+            settlement: Some(settlement),
+        })
+    }
+}
+
+/// Receiver for the report of a metered stream's settlement
+pub type MeterReceiver = oneshot::Receiver<Result<MeterReport>>;
+
+/// Outcome of settling a metered stream
+#[derive(Debug, Clone)]
+pub struct MeterReport {
+    /// Number of events sent to the client
+    pub events: u64,
+    /// Amount actually settled in atomic units, zero if settlement failed
+    pub amount: u128,
+    /// Facilitator settlement, if anything was owed
+    pub settlement: Option<SettleResponse>,
+}
+
+/// Stream wrapper that counts events and settles on completion
+///
+/// Only `Ok` items are charged. The stream ends early once the next event
+/// would exceed the authorized ceiling.
+pub struct MeteredStream<S> {
+    inner: S,
+    events: u64,
+    max_events: u64,
+    meter: Option<Meter>,
+    report: Option<oneshot::Sender<Result<MeterReport>>>,
+}
+
+impl<S> MeteredStream<S> {
+    /// Wrap `inner`, returning the stream and a receiver for the settlement report
+    pub fn new(inner: S, meter: Meter) -> (Self, MeterReceiver) {
+        let max_events = match meter.ceiling() {
+            Ok(ceiling) if meter.unit_price > 0 => {
+                u64::try_from(ceiling / meter.unit_price).unwrap_or(u64::MAX)
+            }
+            Ok(_) => u64::MAX,
+            Err(_) => 0,
+        };
+        let (sender, receiver) = oneshot::channel();
+
+        (
+            Self {
+                inner,
+                events: 0,
+                max_events,
+                meter: Some(meter),
+                report: Some(sender),
+            },
+            receiver,
+        )
+    }
+
+    /// Events sent so far
+    pub fn events(&self) -> u64 {
+        self.events
+    }
+
+    /// Settle the consumed amount once, in the background
+    fn finish(&mut self) {
+        let (Some(meter), Some(report)) = (self.meter.take(), self.report.take()) else {
+            return;
+        };
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!("Metered stream dropped outside a runtime; settlement skipped");
+            return;
+        };
+        let events = self.events;
+        runtime.spawn(async move {
+            let result = meter.settle(events).await;
+            if let Err(e) = &result {
+                tracing::warn!("Metered settlement failed: {}", e);
+            }
+            let _ = report.send(result);
+        });
+    }
+}
+
+impl<S, T, E> Stream for MeteredStream<S>
+where
+    S: Stream<Item = std::result::Result<T, E>> + Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.meter.is_none() {
+            return Poll::Ready(None);
+        }
+        if self.events >= self.max_events {
+            self.finish();
+            return Poll::Ready(None);
+        }
+
+        match Pin::new(&mut self.inner).poll_next(cx) {
+            Poll::Ready(Some(Ok(item))) => {
+                self.events += 1;
+                Poll::Ready(Some(Ok(item)))
+            }
+            Poll::Ready(None) => {
+                self.finish();
+                Poll::Ready(None)
+            }
+            other => other,
+        }
+    }
+}
+
+impl<S> Drop for MeteredStream<S> {
+    fn drop(&mut self) {
+        // A client disconnecting mid-stream still pays for what it received
+        self.finish();
+    }
+}
+
+/// Wrap a stream of SSE events in a metered [`Sse`](axum::response::Sse) response
+pub fn metered_sse<S, E>(
+    events: S,
+    meter: Meter,
+) -> (
+    axum::response::Sse<MeteredStream<Pin<Box<S>>>>,
+    MeterReceiver,
+)
+where
+    S: Stream<Item = std::result::Result<axum::response::sse::Event, E>> + Send + 'static,
+    E: Into<axum::BoxError>,
+{
+    let (stream, report) = MeteredStream::new(Box::pin(events), meter);
+    (axum::response::Sse::new(stream), report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::*;
+    use axum::response::sse::Event;
+    use axum::response::IntoResponse;
+    use std::convert::Infallible;
+
+    const PAYER: &str = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+    const PAY_TO: &str = "0x209693bc6afc0c5328ba36faf03c514ef312287c";
+
+    /// Advertise `scheme` on base-sepolia
+    async fn mock_supported(facilitator: &mut mockito::ServerGuard, scheme: &str) {
+        facilitator
+            .mock("GET", "/supported")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({"kinds": [
+                    {"x402Version": 1, "scheme": scheme, "network": "base-sepolia"}
+                ]})
+                .to_string(),
+            )
+            .create_async()
+            .await;
+    }
+
+    fn upto_payment(ceiling: &str) -> (PaymentPayload, PaymentRequirements) {
+        let requirements = PaymentRequirements::new(
+            schemes::UPTO,
+            "base-sepolia",
+            ceiling,
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            PAY_TO,
+            "https://example.com/completions",
+            "Per-token completion",
+        );
+        let payment = PaymentPayload::new(
+            schemes::UPTO,
+            "base-sepolia",
+            ExactEvmPayload {
+                signature: "0x00".to_string(),
+                authorization: ExactEvmPayloadAuthorization::new(
+                    PAYER,
+                    PAY_TO,
+                    ceiling,
+                    "1745323800",
+                    "1745323985",
+                    "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+                ),
+            },
+        );
+        (payment, requirements)
+    }
+
+    async fn meter(facilitator: &mockito::ServerGuard, ceiling: &str, unit_price: u128) -> Meter {
+        let (payment, requirements) = upto_payment(ceiling);
+        Meter::new(
+            FacilitatorClient::new(FacilitatorConfig::new(facilitator.url())).unwrap(),
+            payment,
+            requirements,
+            unit_price,
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn mock_settle(facilitator: &mut mockito::ServerGuard, amount: &str) -> mockito::Mock {
+        mock_settle_with(
+            facilitator,
+            amount,
+            r#"{"success":true,"transaction":"0xabc","network":"base-sepolia"}"#,
+        )
+        .await
+    }
+
+    async fn mock_settle_with(
+        facilitator: &mut mockito::ServerGuard,
+        amount: &str,
+        response: &str,
+    ) -> mockito::Mock {
+        mock_supported(facilitator, schemes::UPTO).await;
+        facilitator
+            .mock("POST", "/settle")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "paymentRequirements": {"maxAmountRequired": amount}
+            })))
+            .with_status(200)
+            .with_body(response)
+            .expect(1)
+            .create_async()
+            .await
+    }
+
+    fn tokens(count: usize) -> impl Stream<Item = std::result::Result<Event, Infallible>> {
+        futures_util::stream::iter(
+            (0..count).map(|i| Ok(Event::default().data(format!("token {}", i)))),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_settles_per_event() {
+        let mut facilitator = mockito::Server::new_async().await;
+        let settle = mock_settle(&mut facilitator, "35").await;
+
+        let (sse, report) = metered_sse(tokens(7), meter(&facilitator, "1000", 5).await);
+        let body = axum::body::to_bytes(sse.into_response().into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&body)
+                .matches("data: token")
+                .count(),
+            7
+        );
+
+        let report = report.await.unwrap().unwrap();
+        assert_eq!(report.events, 7);
+        assert_eq!(report.amount, 7 * 5);
+        assert_eq!(report.settlement.unwrap().transaction, "0xabc");
+        settle.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_stops_at_authorized_ceiling() {
+        let mut facilitator = mockito::Server::new_async().await;
+        let settle = mock_settle(&mut facilitator, "30").await;
+
+        // 32 units authorized at 10 per event covers three events
+        let (stream, report) =
+            MeteredStream::new(Box::pin(tokens(10)), meter(&facilitator, "32", 10).await);
+        let sent: Vec<_> = futures_util::StreamExt::collect(stream).await;
+        assert_eq!(sent.len(), 3);
+
+        let report = report.await.unwrap().unwrap();
+        assert_eq!(report.events, 3);
+        assert_eq!(report.amount, 30);
+        settle.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_dropped_stream_settles_consumed_events() {
+        let mut facilitator = mockito::Server::new_async().await;
+        let settle = mock_settle(&mut facilitator, "2").await;
+
+        let (mut stream, report) =
+            MeteredStream::new(Box::pin(tokens(10)), meter(&facilitator, "1000", 1).await);
+        for _ in 0..2 {
+            assert!(futures_util::StreamExt::next(&mut stream)
+                .await
+                .unwrap()
+                .is_ok());
+        }
+        drop(stream);
+
+        assert_eq!(report.await.unwrap().unwrap().amount, 2);
+        settle.assert_async().await;
+
+        // Nothing is settled for an empty stream
+        let (stream, report) =
+            MeteredStream::new(Box::pin(tokens(0)), meter(&facilitator, "1000", 1).await);
+        drop(stream);
+        let report = report.await.unwrap().unwrap();
+        assert_eq!(report.events, 0);
+        assert!(report.settlement.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_refused_settlement_charges_nothing() {
+        let mut facilitator = mockito::Server::new_async().await;
+        let settle = mock_settle_with(
+            &mut facilitator,
+            "4",
+            r#"{"success":false,"errorReason":"insufficient_funds","transaction":"","network":"base-sepolia"}"#,
+        )
+        .await;
+
+        let report = meter(&facilitator, "1000", 2)
+            .await
+            .settle(2)
+            .await
+            .unwrap();
+        assert_eq!(report.events, 2);
+        assert_eq!(report.amount, 0);
+        assert!(!report.settlement.unwrap().success);
+        settle.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_requires_upto_support() {
+        let mut facilitator = mockito::Server::new_async().await;
+        mock_supported(&mut facilitator, schemes::EXACT).await;
+        let client = FacilitatorClient::new(FacilitatorConfig::new(facilitator.url())).unwrap();
+
+        // A facilitator without upto would charge the whole ceiling
+        let (payment, requirements) = upto_payment("1000");
+        let result = Meter::new(client.clone(), payment, requirements, 1).await;
+        assert!(matches!(result, Err(X402Error::SchemeNotSupported { .. })));
+
+        // An exact payment can only be settled in full
+        let mut facilitator = mockito::Server::new_async().await;
+        mock_supported(&mut facilitator, schemes::UPTO).await;
+        let client = FacilitatorClient::new(FacilitatorConfig::new(facilitator.url())).unwrap();
+        let (mut payment, requirements) = upto_payment("1000");
+        payment.scheme = schemes::EXACT.to_string();
+        let result = Meter::new(client, payment, requirements, 1).await;
+        assert!(matches!(result, Err(X402Error::SchemeNotSupported { .. })));
+    }
+}
@@ -4,7 +4,7 @@ use crate::types::{Network, *};
 use crate::{Result, X402Error};
 use axum::{
     extract::{Request, State},
-    http::{HeaderValue, StatusCode},
+    http::StatusCode,
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
@@ -14,11 +14,16 @@ use std::sync::Arc;
 use tower::ServiceBuilder;
 use tower_http::trace::TraceLayer;
 
+/// Default upper bound on the advertised `maxTimeoutSeconds`
+pub const DEFAULT_MAX_TIMEOUT_CLAMP_SECONDS: u32 = 300;
+
 /// Configuration for payment middleware
 #[derive(Debug, Clone)]
 pub struct PaymentMiddlewareConfig {
     /// Payment amount in decimal units (e.g., 0.0001 for 1/10th of a cent)
     pub amount: Decimal,
+    /// Smallest amount requirements may be built for, in decimal units
+    pub min_amount: Option<Decimal>,
     /// Recipient wallet address
     pub pay_to: String,
     /// Payment description
@@ -27,18 +32,45 @@ pub struct PaymentMiddlewareConfig {
     pub mime_type: Option<String>,
     /// Maximum timeout in seconds
     pub max_timeout_seconds: u32,
+    /// Upper bound applied to `max_timeout_seconds` in payment requirements
+    pub max_timeout_clamp_seconds: u32,
     /// JSON schema for response format
     pub output_schema: Option<serde_json::Value>,
     /// Facilitator configuration
     pub facilitator_config: FacilitatorConfig,
     /// Whether this is a testnet
     pub testnet: bool,
+    /// Networks also accepted, after the primary one, at the same price and recipient
+    pub additional_networks: Vec<Network>,
     /// Custom paywall HTML for web browsers
     pub custom_paywall_html: Option<String>,
     /// Resource URL (if different from request URL)
     pub resource: Option<String>,
     /// Resource root URL for constructing full resource URLs
     pub resource_root_url: Option<String>,
+    /// Maximum request body size in bytes
+    pub max_body_bytes: Option<u64>,
+    /// Paths served without payment (exact, or globs using `*`)
+    pub bypass_paths: Vec<String>,
+    /// HTTP methods that require payment (`None` means all)
+    pub paid_methods: Option<Vec<http::Method>>,
+    /// Origins allowed to call the resource cross-origin (`None` disables CORS)
+    pub cors_origins: Option<Vec<String>>,
+    /// Check JSON responses against `output_schema` before settling
+    #[cfg(feature = "json-schema")]
+    pub validate_responses: bool,
+    /// Handler statuses the payment is settled for (`None` means any 2xx)
+    pub settle_statuses: Option<Vec<http::StatusCode>>,
+    /// How long a quoted price in a 402 response stays valid
+    pub quote_ttl: Option<std::time::Duration>,
+    /// Key signing JSON 402 bodies, so clients can detect tampered quotes
+    pub quote_signer: Option<crate::signer::LocalSigner>,
+    /// Publish the accepted payments at [`WELL_KNOWN_X402_PATH`]
+    pub serve_well_known: bool,
+    /// Decides whether a 402 is the HTML paywall or JSON
+    pub client_type_resolver: fn(&http::HeaderMap) -> ClientType,
+    /// Longest a paid handler may take to respond
+    pub handler_timeout: Option<std::time::Duration>,
 }
 
 impl PaymentMiddlewareConfig {
@@ -48,16 +80,31 @@ impl PaymentMiddlewareConfig {
         let pay_to_normalized = pay_to.into().to_lowercase();
         Self {
             amount,
+            min_amount: None,
             pay_to: pay_to_normalized,
             description: None,
             mime_type: None,
             max_timeout_seconds: 60,
+            max_timeout_clamp_seconds: DEFAULT_MAX_TIMEOUT_CLAMP_SECONDS,
             output_schema: None,
             facilitator_config: FacilitatorConfig::default(),
             testnet: true,
+            additional_networks: Vec::new(),
             custom_paywall_html: None,
             resource: None,
             resource_root_url: None,
+            max_body_bytes: None,
+            bypass_paths: Vec::new(),
+            paid_methods: None,
+            cors_origins: None,
+            #[cfg(feature = "json-schema")]
+            validate_responses: false,
+            settle_statuses: None,
+            quote_ttl: None,
+            quote_signer: None,
+            serve_well_known: false,
+            client_type_resolver: ClientType::from_headers,
+            handler_timeout: None,
         }
     }
 
@@ -79,12 +126,45 @@ impl PaymentMiddlewareConfig {
         self
     }
 
+    /// Cap the advertised timeout at `clamp_seconds`
+    ///
+    /// The timeout bounds how long a signed authorization stays valid, so a
+    /// misconfigured multi-day window is clamped down (with a warning) rather
+    /// than quoted. Defaults to [`DEFAULT_MAX_TIMEOUT_CLAMP_SECONDS`].
+    pub fn with_max_timeout_clamp_seconds(mut self, clamp_seconds: u32) -> Self {
+        self.max_timeout_clamp_seconds = clamp_seconds;
+        self
+    }
+
     /// Set the output schema
     pub fn with_output_schema(mut self, output_schema: serde_json::Value) -> Self {
         self.output_schema = Some(output_schema);
         self
     }
 
+    /// Also accept USDC on `network`
+    ///
+    /// Each additional network is advertised in the 402 `accepts` after the
+    /// primary one, at the same price and recipient, and payments on it are
+    /// verified and settled against its own requirements.
+    pub fn with_additional_network(mut self, network: Network) -> Self {
+        if !self.additional_networks.contains(&network) {
+            self.additional_networks.push(network);
+        }
+        self
+    }
+
+    /// Refuse to build requirements for less than `min_amount`
+    ///
+    /// Guards against dust prices that cost more in gas to settle than they
+    /// earn. The floor is converted with the asset's decimals, rounding up, and
+    /// requirements below it fail with
+    /// [`X402Error::InvalidPaymentRequirements`].
+    pub fn with_min_amount(mut self, min_amount: Decimal) -> Self {
+        self.min_amount = Some(min_amount);
+        self
+    }
+
     /// Set the facilitator configuration
     pub fn with_facilitator_config(mut self, facilitator_config: FacilitatorConfig) -> Self {
         self.facilitator_config = facilitator_config;
@@ -115,65 +195,970 @@ impl PaymentMiddlewareConfig {
         self
     }
 
-    /// Create payment requirements from this config
-    pub fn create_payment_requirements(&self, request_uri: &str) -> Result<PaymentRequirements> {
-        let network = if self.testnet {
+    /// Cap the request body size, tying the price to a size bound
+    ///
+    /// Requests declaring a larger `Content-Length` get `413 Payload Too Large`
+    /// before any payment is verified; bodies without a length are cut off
+    /// once the limit is reached.
+    pub fn with_max_body_bytes(mut self, max_body_bytes: u64) -> Self {
+        self.max_body_bytes = Some(max_body_bytes);
+        self
+    }
+
+    /// Serve these paths without payment, e.g. `/health` or `/metrics/*`
+    ///
+    /// Patterns match the whole path; `*` matches any run of characters.
+    pub fn with_bypass_paths(mut self, paths: Vec<String>) -> Self {
+        self.bypass_paths = paths;
+        self
+    }
+
+    /// Whether `path` skips the payment flow
+    pub fn is_bypassed(&self, path: &str) -> bool {
+        self.bypass_paths
+            .iter()
+            .any(|pattern| glob_matches(pattern, path))
+    }
+
+    /// Only require payment for these HTTP methods; others pass through free
+    pub fn with_paid_methods(mut self, methods: Vec<http::Method>) -> Self {
+        self.paid_methods = Some(methods);
+        self
+    }
+
+    /// Whether a request with `method` to `path` goes through the payment flow
+    ///
+    /// `OPTIONS` is always free so CORS preflight requests succeed.
+    pub fn requires_payment(&self, method: &http::Method, path: &str) -> bool {
+        if method == http::Method::OPTIONS || self.is_bypassed(path) {
+            return false;
+        }
+        self.paid_methods
+            .as_ref()
+            .is_none_or(|methods| methods.contains(method))
+    }
+
+    /// Add CORS headers to payment responses for these origins
+    ///
+    /// An empty list or `"*"` echoes any `Origin`. Browsers can then send
+    /// `X-PAYMENT` and read `X-PAYMENT-RESPONSE` and the 402 body, and
+    /// preflight requests are answered by the middleware.
+    pub fn with_cors_headers(mut self, origins: Vec<String>) -> Self {
+        self.cors_origins = Some(origins);
+        self
+    }
+
+    /// Validate successful JSON responses against the output schema
+    ///
+    /// A response that does not match is replaced by a 502 and the payment is
+    /// not settled, so clients never pay for a malformed response.
+    #[cfg(feature = "json-schema")]
+    pub fn with_response_validation(mut self, enabled: bool) -> Self {
+        self.validate_responses = enabled;
+        self
+    }
+
+    /// Only settle when the handler responds with one of these statuses
+    ///
+    /// By default any 2xx settles. Other responses are returned as they are,
+    /// without settling the payment or adding `X-PAYMENT-RESPONSE`.
+    pub fn with_settle_on_statuses(mut self, statuses: Vec<http::StatusCode>) -> Self {
+        self.settle_statuses = Some(statuses);
+        self
+    }
+
+    /// Whether a handler response with `status` is paid for
+    pub(crate) fn settles_status(&self, status: http::StatusCode) -> bool {
+        match &self.settle_statuses {
+            Some(statuses) => statuses.contains(&status),
+            None => status.is_success(),
+        }
+    }
+
+    /// Advertise how long the price quoted in 402 responses stays valid
+    ///
+    /// JSON 402 bodies gain an `expires` Unix timestamp, and every 402 carries
+    /// matching `Cache-Control: max-age` and `Expires` headers.
+    pub fn with_quote_ttl(mut self, ttl: std::time::Duration) -> Self {
+        self.quote_ttl = Some(ttl);
+        self
+    }
+
+    /// Sign JSON 402 bodies with `signer`
+    ///
+    /// Clients configured with the signer's address (see
+    /// [`X402Client::with_quote_signer`](crate::client::X402Client::with_quote_signer))
+    /// refuse to pay a 402 whose requirements were altered in transit. The
+    /// signature covers the expiry set by [`with_quote_ttl`](Self::with_quote_ttl).
+    pub fn with_quote_signer(mut self, signer: crate::signer::LocalSigner) -> Self {
+        self.quote_signer = Some(signer);
+        self
+    }
+
+    /// Build a 402 body, stamped with the quote's expiry if a TTL is set
+    /// and signed if a quote signer is set
+    pub(crate) fn payment_required_body(
+        &self,
+        error: impl Into<String>,
+        accepts: Vec<PaymentRequirements>,
+    ) -> PaymentRequirementsResponse {
+        let mut body = PaymentRequirementsResponse::new(error, accepts);
+        if let Some(expires) = self.quote_expiry() {
+            body = body.with_expires(expires.timestamp());
+        }
+        match &self.quote_signer {
+            Some(signer) => match body.clone().signed(signer) {
+                Ok(signed) => signed,
+                Err(e) => {
+                    tracing::warn!("Failed to sign payment requirements: {}", e);
+                    body
+                }
+            },
+            None => body,
+        }
+    }
+
+    /// Add the quote's freshness headers to a 402 response
+    pub(crate) fn apply_quote_headers(&self, headers: &mut http::HeaderMap) {
+        let (Some(ttl), Some(expires)) = (self.quote_ttl, self.quote_expiry()) else {
+            return;
+        };
+        let cache_control = format!("max-age={}", ttl.as_secs());
+        let expires = expires.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        for (name, value) in [
+            (http::header::CACHE_CONTROL, cache_control),
+            (http::header::EXPIRES, expires),
+        ] {
+            if let Ok(value) = http::HeaderValue::from_str(&value) {
+                headers.insert(name, value);
+            }
+        }
+    }
+
+    /// When a quote made now expires, truncated to whole seconds
+    fn quote_expiry(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let ttl = chrono::Duration::seconds(i64::try_from(self.quote_ttl?.as_secs()).ok()?);
+        let now = chrono::Utc::now().timestamp();
+        chrono::DateTime::from_timestamp(now, 0)?.checked_add_signed(ttl)
+    }
+
+    /// Serve the accepted payments at [`WELL_KNOWN_X402_PATH`] without payment
+    ///
+    /// `GET /.well-known/x402` then returns [`payment_options`](Self::payment_options)
+    /// so agents and crawlers can read the price without triggering a 402.
+    pub fn with_well_known(mut self, enabled: bool) -> Self {
+        self.serve_well_known = enabled;
+        self
+    }
+
+    /// Decide with `resolver` whether a 402 is the HTML paywall or JSON
+    ///
+    /// Defaults to [`ClientType::from_headers`].
+    pub fn with_client_type_resolver(
+        mut self,
+        resolver: fn(&http::HeaderMap) -> ClientType,
+    ) -> Self {
+        self.client_type_resolver = resolver;
+        self
+    }
+
+    /// Give up on paid handlers that take longer than `timeout` to respond
+    ///
+    /// The client gets a 504 and the payment is not settled. Only the time to
+    /// the response head counts; a streaming body is not limited.
+    pub fn with_handler_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.handler_timeout = Some(timeout);
+        self
+    }
+
+    /// Run the paid handler, or return a 504 if it exceeds the handler timeout
+    pub(crate) async fn run_handler(
+        &self,
+        request: Request,
+        next: Next,
+    ) -> std::result::Result<Response, Response> {
+        let Some(timeout) = self.handler_timeout else {
+            return Ok(next.run(request).await);
+        };
+        tokio::time::timeout(timeout, next.run(request))
+            .await
+            .map_err(|_| {
+                tracing::warn!("Handler timed out after {:?}; not settling", timeout);
+                (
+                    StatusCode::GATEWAY_TIMEOUT,
+                    Json(serde_json::json!({
+                        "x402Version": X402_VERSION,
+                        "error": "Handler timed out",
+                    })),
+                )
+                    .into_response()
+            })
+    }
+
+    /// Kind of client a request comes from
+    pub(crate) fn client_type(&self, headers: &http::HeaderMap) -> ClientType {
+        (self.client_type_resolver)(headers)
+    }
+
+    /// Discovery document listing the payments this config accepts
+    ///
+    /// The requirements are those of a 402 for the root resource.
+    pub fn payment_options(&self) -> Result<PaymentOptions> {
+        Ok(PaymentOptions::new(self.create_accepts("/")?))
+    }
+
+    /// Answer a request for the discovery document, if enabled
+    pub(crate) fn well_known_response(&self, request: &Request) -> Option<Response> {
+        if !self.serve_well_known
+            || request.uri().path() != WELL_KNOWN_X402_PATH
+            || !matches!(*request.method(), http::Method::GET | http::Method::HEAD)
+        {
+            return None;
+        }
+        Some(self.well_known_document())
+    }
+
+    /// JSON response carrying the discovery document
+    pub(crate) fn well_known_document(&self) -> Response {
+        match self.payment_options() {
+            Ok(options) => Json(options).into_response(),
+            Err(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": e.to_string(), "x402Version": 1 })),
+            )
+                .into_response(),
+        }
+    }
+
+    /// Whether `origin` may call the resource cross-origin
+    fn allows_origin(&self, origin: &str) -> bool {
+        self.cors_origins.as_ref().is_some_and(|origins| {
+            origins.is_empty() || origins.iter().any(|o| o == "*" || o == origin)
+        })
+    }
+
+    /// Add CORS headers for the request's `origin` to a response
+    pub(crate) fn apply_cors_headers(
+        &self,
+        origin: Option<&http::HeaderValue>,
+        headers: &mut http::HeaderMap,
+    ) {
+        let Some(origin) = origin.filter(|o| o.to_str().is_ok_and(|o| self.allows_origin(o)))
+        else {
+            return;
+        };
+        headers.insert(http::header::ACCESS_CONTROL_ALLOW_ORIGIN, origin.clone());
+        headers.insert(
+            http::header::ACCESS_CONTROL_EXPOSE_HEADERS,
+            http::HeaderValue::from_static("X-PAYMENT-RESPONSE"),
+        );
+        headers.append(http::header::VARY, http::HeaderValue::from_static("Origin"));
+    }
+
+    /// Response to a CORS preflight request, when CORS is enabled
+    pub(crate) fn preflight_response(&self, request: &Request) -> Option<Response> {
+        let headers = request.headers();
+        if self.cors_origins.is_none()
+            || request.method() != http::Method::OPTIONS
+            || !headers.contains_key(http::header::ACCESS_CONTROL_REQUEST_METHOD)
+        {
+            return None;
+        }
+
+        let mut response = StatusCode::NO_CONTENT.into_response();
+        let response_headers = response.headers_mut();
+        self.apply_cors_headers(headers.get(http::header::ORIGIN), response_headers);
+        response_headers.insert(
+            http::header::ACCESS_CONTROL_ALLOW_METHODS,
+            http::HeaderValue::from_static("GET, POST, PUT, PATCH, DELETE, OPTIONS"),
+        );
+        response_headers.insert(
+            http::header::ACCESS_CONTROL_ALLOW_HEADERS,
+            http::HeaderValue::from_static("Content-Type, Authorization, X-PAYMENT"),
+        );
+        Some(response)
+    }
+
+    /// Whether the request declares a body larger than the limit
+    pub(crate) fn exceeds_body_limit(&self, headers: &http::HeaderMap) -> bool {
+        let Some(limit) = self.max_body_bytes else {
+            return false;
+        };
+        headers
+            .get(http::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+            .is_some_and(|length| length > limit)
+    }
+
+    /// Response returned when the request body exceeds the limit
+    pub(crate) fn payload_too_large_response(&self) -> Response {
+        (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            Json(serde_json::json!({
+                "x402Version": X402_VERSION,
+                "error": format!(
+                    "Request body exceeds {} bytes",
+                    self.max_body_bytes.unwrap_or_default()
+                ),
+            })),
+        )
+            .into_response()
+    }
+
+    /// Network payments are requested on
+    pub(crate) fn network(&self) -> &'static str {
+        if self.testnet {
             networks::BASE_SEPOLIA
         } else {
             networks::BASE_MAINNET
+        }
+    }
+
+    /// Create payment requirements from this config, on the primary network
+    pub fn create_payment_requirements(&self, request_uri: &str) -> Result<PaymentRequirements> {
+        let network = if self.testnet {
+            Network::Testnet
+        } else {
+            Network::Mainnet
         };
+        self.create_payment_requirements_on(network, request_uri)
+    }
 
-        let usdc_address =
-            networks::get_usdc_address(network).ok_or_else(|| X402Error::NetworkNotSupported {
-                network: network.to_string(),
-            })?;
+    /// Every payment option for a request: the primary network, then the additional ones
+    pub fn create_accepts(&self, request_uri: &str) -> Result<Vec<PaymentRequirements>> {
+        let mut accepts = vec![self.create_payment_requirements(request_uri)?];
+        for &network in &self.additional_networks {
+            if network.as_str() != self.network() {
+                accepts.push(self.create_payment_requirements_on(network, request_uri)?);
+            }
+        }
+        Ok(accepts)
+    }
+
+    /// Create payment requirements for USDC on `network`
+    pub fn create_payment_requirements_on(
+        &self,
+        network: Network,
+        request_uri: &str,
+    ) -> Result<PaymentRequirements> {
+        let usdc_address = network.usdc_address();
 
         let resource = if let Some(ref resource_url) = self.resource {
             resource_url.clone()
         } else if let Some(ref root_url) = self.resource_root_url {
-            format!("{}{}", root_url, request_uri)
+            resource_url(root_url, request_uri)
         } else {
             request_uri.to_string()
         };
 
-        let max_amount_required = (self.amount * Decimal::from(1_000_000u64))
-            .normalize()
-            .to_string();
-
         // Normalize pay_to to lowercase to avoid EIP-55 checksum mismatches
         let pay_to_normalized = self.pay_to.to_lowercase();
 
         let mut requirements = PaymentRequirements::new(
             schemes::EXACT,
-            network,
-            max_amount_required,
+            network.as_str(),
+            "0",
             usdc_address,
             &pay_to_normalized,
             resource,
             self.description.as_deref().unwrap_or("Payment required"),
-        );
+        )
+        .with_decimal_amount(self.amount, USDC_DECIMALS)?;
+
+        if let Some(min_amount) = self.min_amount {
+            let decimals = requirements.asset_decimals();
+            if requirements.required_amount(decimals)?
+                < Amount::from_decimal_ceil(min_amount, decimals)?
+            {
+                return Err(X402Error::invalid_payment_requirements(format!(
+                    "Amount {} is below the minimum of {}",
+                    self.amount, min_amount
+                )));
+            }
+        }
 
         requirements.mime_type = self.mime_type.clone();
         requirements.output_schema = self.output_schema.clone();
         requirements.max_timeout_seconds = self.max_timeout_seconds;
+        if self.max_timeout_seconds > self.max_timeout_clamp_seconds {
+            tracing::warn!(
+                "max_timeout_seconds {} exceeds the clamp; advertising {}",
+                self.max_timeout_seconds,
+                self.max_timeout_clamp_seconds
+            );
+            requirements.max_timeout_seconds = self.max_timeout_clamp_seconds;
+        }
 
-        let network = if self.testnet {
-            Network::Testnet
-        } else {
-            Network::Mainnet
-        };
         requirements.set_usdc_info(network)?;
 
         Ok(requirements)
     }
 }
 
+/// The option among `accepts` that `payment` was made against
+///
+/// Matched on scheme and network. A payment matching none is rejected here,
+/// before any facilitator call, rather than trusting the facilitator to
+/// notice a payload for another network.
+pub(crate) fn requirements_for_payment<'a>(
+    accepts: &'a [PaymentRequirements],
+    payment: &PaymentPayload,
+) -> Result<&'a PaymentRequirements> {
+    accepts
+        .iter()
+        .find(|r| r.scheme == payment.scheme && r.network == payment.network)
+        .ok_or_else(|| {
+            X402Error::payment_verification_failed(format!(
+                "Payment scheme '{}' on network '{}' does not match any accepted payment requirements",
+                payment.scheme, payment.network
+            ))
+        })
+}
+
+/// Kind of client a 402 response is rendered for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClientType {
+    /// A browser, shown the HTML paywall
+    Browser,
+    /// A program, sent the JSON payment requirements
+    Api,
+}
+
+impl ClientType {
+    /// Resolve the client type from request headers
+    ///
+    /// An explicit `X-Payment-Client: api|browser` wins. Otherwise a client
+    /// whose `Accept` ranks `text/html` above `application/json` is a browser,
+    /// and one ranking JSON at least as high is an API client. When `Accept`
+    /// names neither, only a top-level navigation (`Sec-Fetch-Mode: navigate`)
+    /// is a browser.
+    pub fn from_headers(headers: &http::HeaderMap) -> Self {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+        };
+
+        match header(X_PAYMENT_CLIENT_HEADER) {
+            Some(value) if value.eq_ignore_ascii_case("browser") => return Self::Browser,
+            Some(value) if value.eq_ignore_ascii_case("api") => return Self::Api,
+            _ => {}
+        }
+
+        let accept = header("Accept").unwrap_or("");
+        let html = accept_quality(accept, &["text/html", "application/xhtml+xml"]);
+        let json = accept_quality(accept, &["application/json"]);
+        if html > 0.0 && html > json {
+            return Self::Browser;
+        }
+        if json > 0.0 {
+            return Self::Api;
+        }
+
+        match header("Sec-Fetch-Mode") {
+            Some(mode) if mode.eq_ignore_ascii_case("navigate") => Self::Browser,
+            _ => Self::Api,
+        }
+    }
+}
+
+/// Highest quality `accept` gives any of `media_types` (0 when none is listed)
+fn accept_quality(accept: &str, media_types: &[&str]) -> f32 {
+    accept
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let media_type = parts.next()?.trim();
+            if !media_types
+                .iter()
+                .any(|wanted| media_type.eq_ignore_ascii_case(wanted))
+            {
+                return None;
+            }
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            Some(quality)
+        })
+        .fold(0.0, f32::max)
+}
+
 /// Axum middleware for x402 payments
 #[derive(Debug, Clone)]
 pub struct PaymentMiddleware {
     pub config: Arc<PaymentMiddlewareConfig>,
-    pub facilitator: Option<crate::facilitator::FacilitatorClient>,
+    /// Facilitator shared by every clone of the middleware
+    pub facilitator: Option<Arc<crate::facilitator::FacilitatorClient>>,
     pub template_config: Option<crate::template::PaywallConfig>,
+    pub rate_limiter: Option<RateLimiter>,
+    pub free_tier: Option<FreeTier>,
+    /// Facilitator built from `config` on first use, shared across clones
+    pub(crate) lazy_facilitator:
+        Arc<std::sync::OnceLock<Arc<crate::facilitator::FacilitatorClient>>>,
+    /// Check that the facilitator supports the configured scheme and network
+    pub(crate) supported_check: Option<SupportedCheck>,
+    /// Verify payments in-process instead of calling the facilitator
+    pub(crate) local_verifier: Option<crate::facilitator::LocalVerifier>,
+    /// Settle payments with this instead of the facilitator
+    pub(crate) settler: Option<Arc<dyn crate::facilitator::Settler>>,
+    /// Verifications in flight, shared by concurrent identical payments
+    pub(crate) in_flight: InFlightVerifications,
+}
+
+/// Shared result of one in-flight verification
+type SharedVerify = futures_util::future::Shared<
+    futures_util::future::BoxFuture<'static, std::result::Result<VerifyResponse, Arc<X402Error>>>,
+>;
+
+/// Single-flight table of verifications, keyed by authorization nonce
+///
+/// Each entry keeps a fingerprint of the payload and requirements, so only a
+/// byte-identical payment joins an existing call; a different payment reusing
+/// the nonce is verified on its own.
+#[derive(Clone, Default)]
+pub(crate) struct InFlightVerifications {
+    calls: Arc<std::sync::Mutex<std::collections::HashMap<String, (String, SharedVerify)>>>,
+}
+
+impl std::fmt::Debug for InFlightVerifications {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let in_flight = self.calls.lock().map(|calls| calls.len()).unwrap_or(0);
+        f.debug_struct("InFlightVerifications")
+            .field("in_flight", &in_flight)
+            .finish()
+    }
+}
+
+/// Cached check of the middleware's `(scheme, network)` against `/supported`
+#[derive(Debug, Clone)]
+pub(crate) struct SupportedCheck {
+    /// Reject requests instead of only warning when unsupported
+    strict: bool,
+    /// Whether the kind is supported, once the facilitator has answered
+    supported: Arc<tokio::sync::OnceCell<bool>>,
+}
+
+impl SupportedCheck {
+    fn new(strict: bool) -> Self {
+        Self {
+            strict,
+            supported: Default::default(),
+        }
+    }
+}
+
+/// In-memory token-bucket rate limiter keyed by payer address
+///
+/// Each payer gets a bucket of `max_requests` tokens that refills evenly over
+/// `per`. Keys are compared case-insensitively so checksummed and lowercase
+/// addresses share a bucket.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    max_requests: u32,
+    per: std::time::Duration,
+    buckets: Arc<std::sync::Mutex<std::collections::HashMap<String, TokenBucket>>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: std::time::Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing `max_requests` per `per` for each key
+    pub fn new(max_requests: u32, per: std::time::Duration) -> Self {
+        Self {
+            max_requests,
+            per,
+            buckets: Arc::new(std::sync::Mutex::new(std::collections::HashMap::new())),
+        }
+    }
+
+    /// Take a token for `key`, returning `false` if the limit is exceeded
+    pub fn check(&self, key: &str) -> bool {
+        let now = std::time::Instant::now();
+        let capacity = f64::from(self.max_requests);
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let bucket = buckets.entry(key.to_lowercase()).or_insert(TokenBucket {
+            tokens: capacity,
+            last_refill: now,
+        });
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        let refill = if self.per.is_zero() {
+            capacity
+        } else {
+            elapsed * capacity / self.per.as_secs_f64()
+        };
+        bucket.tokens = (bucket.tokens + refill).min(capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Response returned when a payer exceeds the limit
+    pub(crate) fn too_many_requests_response() -> Response {
+        (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(serde_json::json!({
+                "x402Version": X402_VERSION,
+                "error": "Rate limit exceeded for payer",
+            })),
+        )
+            .into_response()
+    }
+}
+
+/// Extracts the identity a free-tier quota is keyed on, such as an API key or source IP
+///
+/// Returning `None` means the request has no identity and must pay. The
+/// identity must be one the client cannot forge, such as a key validated
+/// against your records or the peer address: a client that can make up a
+/// new identity per request gets a fresh quota each time.
+pub type FreeTierKeyExtractor = Arc<dyn Fn(&Request) -> Option<String> + Send + Sync>;
+
+/// Counter store backing free-tier quotas
+#[async_trait::async_trait]
+pub trait FreeTierStore: Send + Sync {
+    /// Atomically increment the usage count for `key` and return the new count
+    ///
+    /// If `window` is set, a count older than the window is reset before
+    /// incrementing.
+    async fn increment(&self, key: &str, window: Option<std::time::Duration>) -> Result<u64>;
+}
+
+/// In-memory free-tier counter store
+///
+/// Keeps at most [`DEFAULT_MAX_ENTRIES`](Self::DEFAULT_MAX_ENTRIES)
+/// identities unless configured otherwise, forgetting the oldest windows
+/// first. Windows past the reset window are dropped as new requests arrive.
+/// A forgotten identity starts a fresh quota.
+#[derive(Debug, Clone)]
+pub struct InMemoryFreeTierStore {
+    counts: Arc<std::sync::Mutex<FreeTierCounts>>,
+    max_entries: usize,
+}
+
+/// Usage counts with their window start, oldest window first in `order`
+#[derive(Debug, Default)]
+struct FreeTierCounts {
+    windows: std::collections::HashMap<String, (u64, std::time::Instant)>,
+    order: std::collections::VecDeque<(std::time::Instant, String)>,
+}
+
+impl FreeTierCounts {
+    /// Drop windows older than `window`, then the oldest beyond `max_entries`
+    fn evict(
+        &mut self,
+        window: Option<std::time::Duration>,
+        max_entries: usize,
+        now: std::time::Instant,
+    ) {
+        // Windows are only ever removed here, so `order` matches `windows`
+        while let Some((started, key)) = self.order.front() {
+            let expired = window.is_some_and(|window| now.duration_since(*started) >= window);
+            if !(expired || self.windows.len() > max_entries) {
+                break;
+            }
+            self.windows.remove(key);
+            self.order.pop_front();
+        }
+    }
+}
+
+impl InMemoryFreeTierStore {
+    /// Identities remembered by default
+    pub const DEFAULT_MAX_ENTRIES: usize = 100_000;
+
+    /// Create a new in-memory store
+    pub fn new() -> Self {
+        Self {
+            counts: Default::default(),
+            max_entries: Self::DEFAULT_MAX_ENTRIES,
+        }
+    }
+
+    /// Remember at most `max_entries` identities, forgetting the oldest first
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries.max(1);
+        self
+    }
+
+    /// Number of identities currently remembered
+    pub fn len(&self) -> usize {
+        self.lock().windows.len()
+    }
+
+    /// Whether no identities are remembered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, FreeTierCounts> {
+        self.counts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for InMemoryFreeTierStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl FreeTierStore for InMemoryFreeTierStore {
+    async fn increment(&self, key: &str, window: Option<std::time::Duration>) -> Result<u64> {
+        let now = std::time::Instant::now();
+        let mut counts = self.lock();
+        counts.evict(window, self.max_entries, now);
+
+        let count = match counts.windows.get_mut(key) {
+            Some((count, _)) => {
+                *count += 1;
+                *count
+            }
+            // New or expired: start a window
+            None => {
+                counts.windows.insert(key.to_string(), (1, now));
+                counts.order.push_back((now, key.to_string()));
+                counts.evict(window, self.max_entries, now);
+                1
+            }
+        };
+        Ok(count)
+    }
+}
+
+/// Policy letting the first `count` requests per identity through without payment
+#[derive(Clone)]
+pub struct FreeTier {
+    count: u64,
+    key_extractor: FreeTierKeyExtractor,
+    store: Arc<dyn FreeTierStore>,
+    reset_window: Option<std::time::Duration>,
+}
+
+impl FreeTier {
+    /// Create a free tier of `count` requests backed by an in-memory store
+    pub fn new(
+        count: u64,
+        key_extractor: impl Fn(&Request) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            count,
+            key_extractor: Arc::new(key_extractor),
+            store: Arc::new(InMemoryFreeTierStore::new()),
+            reset_window: None,
+        }
+    }
+
+    /// Use a custom counter store
+    pub fn with_store(mut self, store: impl FreeTierStore + 'static) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Reset each identity's quota after `window`
+    pub fn with_reset_window(mut self, window: std::time::Duration) -> Self {
+        self.reset_window = Some(window);
+        self
+    }
+
+    /// Check whether the request is covered by the free quota, consuming one use if so
+    ///
+    /// The counter is incremented atomically, so concurrent requests never let
+    /// more than `count` through per window.
+    ///
+    /// The identity is extracted up front so the returned future does not
+    /// borrow the request.
+    pub fn allows(
+        &self,
+        request: &Request,
+    ) -> impl std::future::Future<Output = Result<bool>> + Send {
+        let key = (self.key_extractor)(request);
+        let store = self.store.clone();
+        let count = self.count;
+        let reset_window = self.reset_window;
+
+        async move {
+            let Some(key) = key else {
+                return Ok(false);
+            };
+            let used = store.increment(&key, reset_window).await?;
+            Ok(used <= count)
+        }
+    }
+}
+
+impl std::fmt::Debug for FreeTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FreeTier")
+            .field("count", &self.count)
+            .field("key_extractor", &"<function>")
+            .field("store", &"<store>")
+            .field("reset_window", &self.reset_window)
+            .finish()
+    }
+}
+
+/// Verified payment, inserted into request extensions before the handler runs
+///
+/// Handlers can extract it with `Extension<VerifiedPayment>`. Settlement only
+/// happens after the handler returns, so the [`SettleResponse`] is not part of
+/// this value; it is inserted into the response extensions instead, where
+/// outer layers can read it.
+#[derive(Debug, Clone)]
+pub struct VerifiedPayment {
+    /// Payment payload decoded from the `X-PAYMENT` header
+    pub payload: PaymentPayload,
+    /// Requirements the payment was verified against
+    pub requirements: PaymentRequirements,
+    /// Payer address
+    pub payer: String,
+}
+
+impl VerifiedPayment {
+    /// Create a verified payment, taking the payer from the verify response if present
+    ///
+    /// Falls back to the payload's `authorization.from` otherwise.
+    pub fn new(
+        payload: PaymentPayload,
+        requirements: PaymentRequirements,
+        verify_response: &VerifyResponse,
+    ) -> Self {
+        let payer = verify_response
+            .payer
+            .clone()
+            .unwrap_or_else(|| payload.payload.authorization.from.clone());
+        Self {
+            payload,
+            requirements,
+            payer,
+        }
+    }
+}
+
+/// Join a resource root URL and a request URI into one normalized URL
+///
+/// Exactly one slash separates the root's path from the request path, the
+/// query string is kept and the host is lowercased. `request_uri` may be a
+/// path with an optional query or an absolute URL, whose path is used.
+fn resource_url(root_url: &str, request_uri: &str) -> String {
+    let path_and_query = match url::Url::parse(request_uri) {
+        Ok(absolute) => absolute[url::Position::BeforePath..].to_string(),
+        Err(_) => request_uri.to_string(),
+    };
+    let (path, query) = match path_and_query.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (path_and_query.as_str(), None),
+    };
+
+    let Ok(mut url) = url::Url::parse(root_url) else {
+        return format!(
+            "{}/{}",
+            root_url.trim_end_matches('/'),
+            path_and_query.trim_start_matches('/')
+        );
+    };
+    let joined = format!(
+        "{}/{}",
+        url.path().trim_end_matches('/'),
+        path.trim_start_matches('/')
+    );
+    url.set_path(&joined);
+    url.set_query(query);
+    // `Url` lowercases hosts of special schemes only
+    if let Some(host) = url.host_str().map(str::to_lowercase) {
+        let _ = url.set_host(Some(&host));
+    }
+    url.into()
+}
+
+/// Buffer a successful JSON response and validate it against `schema`
+#[cfg(feature = "json-schema")]
+async fn validate_json_response(
+    response: Response,
+    schema: &serde_json::Value,
+) -> std::result::Result<Response, Response> {
+    let is_json = response
+        .headers()
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(str::trim)
+        .is_some_and(|mime| mime == "application/json" || mime.ends_with("+json"));
+    if !response.status().is_success() || !is_json {
+        return Ok(response);
+    }
+
+    let error = |status: StatusCode, message: &str| {
+        (
+            status,
+            Json(serde_json::json!({ "error": message, "x402Version": 1 })),
+        )
+            .into_response()
+    };
+    let validator = jsonschema::validator_for(schema).map_err(|e| {
+        tracing::error!("Invalid output schema: {}", e);
+        error(StatusCode::INTERNAL_SERVER_ERROR, "Invalid output schema")
+    })?;
+
+    let (parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|_| error(StatusCode::BAD_GATEWAY, "Failed to read response body"))?;
+    let conforms = match serde_json::from_slice::<serde_json::Value>(&bytes) {
+        Ok(value) => match validator.validate(&value) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("Response does not match output schema: {}", e);
+                false
+            }
+        },
+        Err(e) => {
+            tracing::warn!("Response is not valid JSON: {}", e);
+            false
+        }
+    };
+    if !conforms {
+        return Err(error(
+            StatusCode::BAD_GATEWAY,
+            "Response does not match the declared output schema",
+        ));
+    }
+
+    Ok(Response::from_parts(parts, axum::body::Body::from(bytes)))
+}
+
+/// Match `text` against a pattern where `*` matches any run of characters
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let Some((prefix, rest)) = pattern.split_once('*') else {
+        return pattern == text;
+    };
+    let Some(mut remaining) = text.strip_prefix(prefix) else {
+        return false;
+    };
+
+    let mut parts = rest.split('*').peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            return remaining.ends_with(part);
+        }
+        match remaining.find(part) {
+            Some(index) => remaining = &remaining[index + part.len()..],
+            None => return false,
+        }
+    }
+    true
 }
 
 /// Payment processing result
@@ -190,6 +1175,42 @@ pub enum PaymentResult {
     VerificationFailed { response: axum::response::Response },
     /// Payment settlement failed
     SettlementFailed { response: axum::response::Response },
+    /// Payer exceeded the rate limit (429 response)
+    RateLimited { response: axum::response::Response },
+    /// Request was served under the free tier without payment
+    Free { response: axum::response::Response },
+    /// Request body exceeded the configured limit (413 response)
+    PayloadTooLarge { response: axum::response::Response },
+    /// Path or method is exempt from payment and was served directly
+    Bypassed { response: axum::response::Response },
+    /// Handler response did not match the output schema; nothing was settled (502 response)
+    InvalidResponse { response: axum::response::Response },
+    /// Handler responded with a status that is not paid for; nothing was settled
+    Unsettled { response: axum::response::Response },
+    /// Discovery document served by the middleware at `/.well-known/x402`
+    Discovery { response: axum::response::Response },
+    /// Handler exceeded the handler timeout; nothing was settled (504 response)
+    TimedOut { response: axum::response::Response },
+}
+
+impl PaymentResult {
+    /// The response to send, whatever the outcome
+    pub fn response_mut(&mut self) -> &mut axum::response::Response {
+        match self {
+            PaymentResult::Success { response, .. }
+            | PaymentResult::PaymentRequired { response }
+            | PaymentResult::VerificationFailed { response }
+            | PaymentResult::SettlementFailed { response }
+            | PaymentResult::RateLimited { response }
+            | PaymentResult::Free { response }
+            | PaymentResult::PayloadTooLarge { response }
+            | PaymentResult::Bypassed { response }
+            | PaymentResult::InvalidResponse { response }
+            | PaymentResult::Unsettled { response }
+            | PaymentResult::Discovery { response }
+            | PaymentResult::TimedOut { response } => response,
+        }
+    }
 }
 
 impl PaymentMiddleware {
@@ -199,6 +1220,13 @@ impl PaymentMiddleware {
             config: Arc::new(PaymentMiddlewareConfig::new(amount, pay_to)),
             facilitator: None,
             template_config: None,
+            rate_limiter: None,
+            free_tier: None,
+            lazy_facilitator: Default::default(),
+            supported_check: None,
+            local_verifier: None,
+            settler: None,
+            in_flight: Default::default(),
         }
     }
 
@@ -220,21 +1248,45 @@ impl PaymentMiddleware {
         self
     }
 
+    /// Cap the advertised timeout at `clamp_seconds`
+    pub fn with_max_timeout_clamp_seconds(mut self, clamp_seconds: u32) -> Self {
+        Arc::make_mut(&mut self.config).max_timeout_clamp_seconds = clamp_seconds;
+        self
+    }
+
     /// Set the output schema
     pub fn with_output_schema(mut self, output_schema: serde_json::Value) -> Self {
         Arc::make_mut(&mut self.config).output_schema = Some(output_schema);
         self
     }
 
+    /// Also accept USDC on `network`
+    pub fn with_additional_network(mut self, network: Network) -> Self {
+        let config = Arc::make_mut(&mut self.config);
+        if !config.additional_networks.contains(&network) {
+            config.additional_networks.push(network);
+        }
+        self
+    }
+
+    /// Refuse to build requirements for less than `min_amount`
+    pub fn with_min_amount(mut self, min_amount: Decimal) -> Self {
+        Arc::make_mut(&mut self.config).min_amount = Some(min_amount);
+        self
+    }
+
     /// Set the facilitator configuration
     pub fn with_facilitator_config(mut self, facilitator_config: FacilitatorConfig) -> Self {
         Arc::make_mut(&mut self.config).facilitator_config = facilitator_config;
+        self.lazy_facilitator = Default::default();
+        self.reset_supported_check();
         self
     }
 
     /// Set whether this is a testnet
     pub fn with_testnet(mut self, testnet: bool) -> Self {
         Arc::make_mut(&mut self.config).testnet = testnet;
+        self.reset_supported_check();
         self
     }
 
@@ -256,55 +1308,300 @@ impl PaymentMiddleware {
         self
     }
 
+    /// Cap the request body size
+    pub fn with_max_body_bytes(mut self, max_body_bytes: u64) -> Self {
+        Arc::make_mut(&mut self.config).max_body_bytes = Some(max_body_bytes);
+        self
+    }
+
+    /// Serve these paths without payment
+    pub fn with_bypass_paths(mut self, paths: Vec<String>) -> Self {
+        Arc::make_mut(&mut self.config).bypass_paths = paths;
+        self
+    }
+
+    /// Only require payment for these HTTP methods
+    pub fn with_paid_methods(mut self, methods: Vec<http::Method>) -> Self {
+        Arc::make_mut(&mut self.config).paid_methods = Some(methods);
+        self
+    }
+
+    /// Add CORS headers to payment responses for these origins
+    pub fn with_cors_headers(mut self, origins: Vec<String>) -> Self {
+        Arc::make_mut(&mut self.config).cors_origins = Some(origins);
+        self
+    }
+
+    /// Only settle when the handler responds with one of these statuses
+    pub fn with_settle_on_statuses(mut self, statuses: Vec<http::StatusCode>) -> Self {
+        Arc::make_mut(&mut self.config).settle_statuses = Some(statuses);
+        self
+    }
+
+    /// Advertise how long the price quoted in 402 responses stays valid
+    pub fn with_quote_ttl(mut self, ttl: std::time::Duration) -> Self {
+        Arc::make_mut(&mut self.config).quote_ttl = Some(ttl);
+        self
+    }
+
+    /// Sign JSON 402 bodies with `signer`
+    pub fn with_quote_signer(mut self, signer: crate::signer::LocalSigner) -> Self {
+        Arc::make_mut(&mut self.config).quote_signer = Some(signer);
+        self
+    }
+
+    /// Give up on paid handlers that take longer than `timeout` to respond
+    pub fn with_handler_timeout(mut self, timeout: std::time::Duration) -> Self {
+        Arc::make_mut(&mut self.config).handler_timeout = Some(timeout);
+        self
+    }
+
+    /// Decide with `resolver` whether a 402 is the HTML paywall or JSON
+    pub fn with_client_type_resolver(
+        mut self,
+        resolver: fn(&http::HeaderMap) -> ClientType,
+    ) -> Self {
+        Arc::make_mut(&mut self.config).client_type_resolver = resolver;
+        self
+    }
+
+    /// Serve the accepted payments at `/.well-known/x402` without payment
+    pub fn with_well_known(mut self, enabled: bool) -> Self {
+        Arc::make_mut(&mut self.config).serve_well_known = enabled;
+        self
+    }
+
+    /// Validate successful JSON responses against the output schema before settling
+    #[cfg(feature = "json-schema")]
+    pub fn with_response_validation(mut self, enabled: bool) -> Self {
+        Arc::make_mut(&mut self.config).validate_responses = enabled;
+        self
+    }
+
     /// Get the middleware configuration
     pub fn config(&self) -> &PaymentMiddlewareConfig {
         &self.config
     }
 
-    /// Set the facilitator client
-    pub fn with_facilitator(mut self, facilitator: crate::facilitator::FacilitatorClient) -> Self {
-        self.facilitator = Some(facilitator);
-        self
-    }
+    /// Verify payments in-process, recording nonces in `storage`
+    ///
+    /// Signature, validity window, recipient, amount and nonce replay are
+    /// checked locally; the facilitator is only called to settle.
+    pub fn with_local_verification(
+        mut self,
+        storage: impl crate::facilitator_storage::NonceStorage + 'static,
+    ) -> Self {
+        self.local_verifier = Some(crate::facilitator::LocalVerifier::new(storage));
+        self
+    }
+
+    /// Settle payments with `settler` instead of the facilitator
+    pub fn with_settler(self, settler: impl crate::facilitator::Settler + 'static) -> Self {
+        self.with_shared_settler(Arc::new(settler))
+    }
+
+    /// Settle payments with a settler shared with other users
+    pub fn with_shared_settler(mut self, settler: Arc<dyn crate::facilitator::Settler>) -> Self {
+        self.settler = Some(settler);
+        self
+    }
+
+    /// Set the facilitator client
+    pub fn with_facilitator(self, facilitator: crate::facilitator::FacilitatorClient) -> Self {
+        self.with_shared_facilitator(Arc::new(facilitator))
+    }
+
+    /// Set a facilitator client shared with other users
+    pub fn with_shared_facilitator(
+        mut self,
+        facilitator: Arc<crate::facilitator::FacilitatorClient>,
+    ) -> Self {
+        self.facilitator = Some(facilitator);
+        self.reset_supported_check();
+        self
+    }
+
+    /// Check on the first request that the facilitator supports this middleware
+    ///
+    /// The facilitator's `/supported` kinds are fetched once and must include
+    /// the configured scheme and network. A mismatch is logged as a warning, or
+    /// with `strict` every request fails with `NetworkNotSupported`.
+    pub fn with_supported_check(mut self, strict: bool) -> Self {
+        self.supported_check = Some(SupportedCheck::new(strict));
+        self
+    }
+
+    /// Forget a cached supported check after the facilitator or network changes
+    fn reset_supported_check(&mut self) {
+        if let Some(check) = &mut self.supported_check {
+            *check = SupportedCheck::new(check.strict);
+        }
+    }
+
+    /// Ask the facilitator whether it supports the configured scheme and network
+    pub async fn check_supported(&self) -> crate::Result<bool> {
+        let network = self.config.network();
+        let supported = self.facilitator_client()?.supported().await?;
+        Ok(supported
+            .kinds
+            .iter()
+            .any(|kind| kind.scheme == schemes::EXACT && kind.network == network))
+    }
+
+    /// Run the configured supported check, caching the facilitator's answer
+    ///
+    /// Failing to reach the facilitator is logged and retried on a later request.
+    pub(crate) async fn ensure_supported(&self) -> crate::Result<()> {
+        let Some(check) = &self.supported_check else {
+            return Ok(());
+        };
+
+        let supported = match check
+            .supported
+            .get_or_try_init(|| self.check_supported())
+            .await
+        {
+            Ok(supported) => *supported,
+            Err(e) => {
+                tracing::warn!("Failed to fetch facilitator supported kinds: {}", e);
+                return Ok(());
+            }
+        };
+
+        if supported {
+            return Ok(());
+        }
+
+        let network = self.config.network();
+        tracing::warn!(
+            "Facilitator does not support scheme '{}' on network '{}'",
+            schemes::EXACT,
+            network
+        );
+        if check.strict {
+            return Err(X402Error::NetworkNotSupported {
+                network: network.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Set the template configuration
+    pub fn with_template_config(mut self, template_config: crate::template::PaywallConfig) -> Self {
+        self.template_config = Some(template_config);
+        self
+    }
+
+    /// Limit each verified payer to `max_requests` per `per`
+    ///
+    /// The limit is checked after verification, keyed on the payer address.
+    /// Payers over the limit receive `429 Too Many Requests`.
+    pub fn with_rate_limit(mut self, max_requests: u32, per: std::time::Duration) -> Self {
+        self.rate_limiter = Some(RateLimiter::new(max_requests, per));
+        self
+    }
+
+    /// Let the first `count` requests per identity through without payment
+    ///
+    /// `key_extractor` pulls the identity (e.g. an API key header or source IP)
+    /// from the request. Use [`with_free_tier_policy`](Self::with_free_tier_policy)
+    /// for a custom counter store or reset window.
+    pub fn with_free_tier(
+        self,
+        count: u64,
+        key_extractor: impl Fn(&Request) -> Option<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.with_free_tier_policy(FreeTier::new(count, key_extractor))
+    }
+
+    /// Set the free-tier policy
+    pub fn with_free_tier_policy(mut self, free_tier: FreeTier) -> Self {
+        self.free_tier = Some(free_tier);
+        self
+    }
+
+    /// Apply the configured body size limit to a request
+    ///
+    /// Returns a `413` response for uploads declaring an oversized body, so they
+    /// are rejected before anyone pays for them. Bodies without a declared
+    /// length are cut off once they pass the limit.
+    pub(crate) fn limit_body(&self, request: &mut Request) -> Option<Response> {
+        let limit = self.config.max_body_bytes?;
+        if self.config.exceeds_body_limit(request.headers()) {
+            return Some(self.config.payload_too_large_response());
+        }
+
+        let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+        let body = std::mem::take(request.body_mut());
+        *request.body_mut() = axum::body::Body::new(http_body_util::Limited::new(body, limit));
+        None
+    }
+
+    /// Check whether the request is covered by the free tier
+    ///
+    /// Counter store errors are logged and treated as "not free".
+    pub(crate) fn is_free(
+        &self,
+        request: &Request,
+    ) -> impl std::future::Future<Output = bool> + Send {
+        let check = self
+            .free_tier
+            .as_ref()
+            .map(|free_tier| free_tier.allows(request));
+
+        async move {
+            match check {
+                Some(check) => check.await.unwrap_or_else(|e| {
+                    tracing::warn!("Free tier check failed: {}", e);
+                    false
+                }),
+                None => false,
+            }
+        }
+    }
+
+    /// Get the configured facilitator client, or build one from the config
+    ///
+    /// The built client is created once and reused for every later request.
+    pub(crate) fn facilitator_client(
+        &self,
+    ) -> crate::Result<&Arc<crate::facilitator::FacilitatorClient>> {
+        if let Some(facilitator) = &self.facilitator {
+            return Ok(facilitator);
+        }
+        if let Some(facilitator) = self.lazy_facilitator.get() {
+            return Ok(facilitator);
+        }
 
-    /// Set the template configuration
-    pub fn with_template_config(mut self, template_config: crate::template::PaywallConfig) -> Self {
-        self.template_config = Some(template_config);
-        self
+        let facilitator = Arc::new(crate::facilitator::FacilitatorClient::new(
+            self.config.facilitator_config.clone(),
+        )?);
+        // A concurrent request may have won the race; either client is equivalent
+        Ok(self.lazy_facilitator.get_or_init(|| facilitator))
     }
 
     /// Verify a payment payload
     pub async fn verify(&self, payment_payload: &PaymentPayload) -> bool {
-        // Create facilitator if not already configured
-        let facilitator = if let Some(facilitator) = &self.facilitator {
-            facilitator.clone()
-        } else {
-            match crate::facilitator::FacilitatorClient::new(self.config.facilitator_config.clone())
-            {
-                Ok(facilitator) => facilitator,
-                Err(_) => return false,
-            }
+        let Ok(accepts) = self.config.create_accepts("/") else {
+            return false;
         };
-
-        if let Ok(requirements) = self.config.create_payment_requirements("/") {
-            if let Ok(response) = facilitator.verify(payment_payload, &requirements).await {
-                return response.is_valid;
-            }
+        let Ok(requirements) = requirements_for_payment(&accepts, payment_payload) else {
+            return false;
+        };
+        match self.verify_payment(payment_payload, requirements).await {
+            Ok(response) => response.is_valid,
+            Err(_) => false,
         }
-        false
     }
 
     /// Settle a payment
     pub async fn settle(&self, payment_payload: &PaymentPayload) -> crate::Result<SettleResponse> {
-        // Create facilitator if not already configured
-        let facilitator = if let Some(facilitator) = &self.facilitator {
-            facilitator.clone()
-        } else {
-            crate::facilitator::FacilitatorClient::new(self.config.facilitator_config.clone())?
-        };
-
-        let requirements = self.config.create_payment_requirements("/")?;
-        facilitator.settle(payment_payload, &requirements).await
+        let accepts = self.config.create_accepts("/")?;
+        self.settle_with_requirements(
+            payment_payload,
+            requirements_for_payment(&accepts, payment_payload)?,
+        )
+        .await
     }
 
     /// Verify payment with specific requirements
@@ -313,14 +1610,108 @@ impl PaymentMiddleware {
         payment_payload: &PaymentPayload,
         requirements: &PaymentRequirements,
     ) -> crate::Result<bool> {
-        let facilitator = if let Some(facilitator) = &self.facilitator {
-            facilitator.clone()
-        } else {
-            crate::facilitator::FacilitatorClient::new(self.config.facilitator_config.clone())?
+        let response = self.verify_payment(payment_payload, requirements).await?;
+        Ok(response.is_valid)
+    }
+
+    /// Verify locally when configured, otherwise with the facilitator
+    ///
+    /// Concurrent calls for the same payment share one verification, so a
+    /// client retrying aggressively costs a single facilitator call. Only the
+    /// call that started it may spend the payment: the others are answered
+    /// `nonce_already_used`, so one payment runs the handler once.
+    pub async fn verify_payment(
+        &self,
+        payment_payload: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> crate::Result<VerifyResponse> {
+        use futures_util::FutureExt;
+
+        let nonce = payment_payload.payload.authorization.nonce.clone();
+        let fingerprint = serde_json::to_string(&(payment_payload, requirements))?;
+        let (shared, leader) = {
+            let mut calls = self
+                .in_flight
+                .calls
+                .lock()
+                .map_err(|_| X402Error::config("in-flight verification table poisoned"))?;
+            match calls.get(&nonce) {
+                Some((existing, shared)) if *existing == fingerprint => {
+                    (Some(shared.clone()), false)
+                }
+                // Same nonce, different payment: never share its result
+                Some(_) => (None, false),
+                None => {
+                    let middleware = self.clone();
+                    let (payload, requirements) = (payment_payload.clone(), requirements.clone());
+                    let key = nonce.clone();
+                    let shared = async move {
+                        let result = middleware
+                            .verify_payment_uncoalesced(&payload, &requirements)
+                            .await
+                            .map_err(Arc::new);
+                        if let Ok(mut calls) = middleware.in_flight.calls.lock() {
+                            calls.remove(&key);
+                        }
+                        result
+                    }
+                    .boxed()
+                    .shared();
+                    calls.insert(nonce, (fingerprint, shared.clone()));
+                    (Some(shared), true)
+                }
+            }
+        };
+        let Some(shared) = shared else {
+            return self
+                .verify_payment_uncoalesced(payment_payload, requirements)
+                .await;
         };
 
-        let response = facilitator.verify(payment_payload, requirements).await?;
-        Ok(response.is_valid)
+        let response = shared.await.map_err(|error| {
+            Arc::try_unwrap(error)
+                .unwrap_or_else(|error| X402Error::facilitator_error(error.to_string()))
+        })?;
+        if leader || !response.is_valid {
+            return Ok(response);
+        }
+        // A duplicate of a payment another request is spending
+        Ok(VerifyResponse {
+            is_valid: false,
+            invalid_reason: Some("nonce_already_used".to_string()),
+            payer: response.payer,
+        })
+    }
+
+    /// Verify without joining an in-flight verification of the same payment
+    async fn verify_payment_uncoalesced(
+        &self,
+        payment_payload: &PaymentPayload,
+        requirements: &PaymentRequirements,
+    ) -> crate::Result<VerifyResponse> {
+        let response = match &self.local_verifier {
+            Some(verifier) => verifier.verify(payment_payload, requirements).await?,
+            None => {
+                self.facilitator_client()?
+                    .verify(payment_payload, requirements)
+                    .await?
+            }
+        };
+        Ok(response.fill_payer(payment_payload))
+    }
+
+    /// Settler for verified payments
+    ///
+    /// The one set with [`with_settler`](Self::with_settler), otherwise a
+    /// [`FacilitatorSettler`](crate::facilitator::FacilitatorSettler) for the
+    /// configured facilitator.
+    pub fn settler(&self) -> crate::Result<Arc<dyn crate::facilitator::Settler>> {
+        match &self.settler {
+            Some(settler) => Ok(Arc::clone(settler)),
+            None => Ok(Arc::new(crate::facilitator::FacilitatorSettler::new(
+                Arc::clone(self.facilitator_client()?),
+            ))),
+        }
     }
 
     /// Settle payment with specific requirements
@@ -329,61 +1720,110 @@ impl PaymentMiddleware {
         payment_payload: &PaymentPayload,
         requirements: &PaymentRequirements,
     ) -> crate::Result<SettleResponse> {
-        let facilitator = if let Some(facilitator) = &self.facilitator {
-            facilitator.clone()
-        } else {
-            crate::facilitator::FacilitatorClient::new(self.config.facilitator_config.clone())?
-        };
-
-        facilitator.settle(payment_payload, requirements).await
+        self.settler()?.settle(payment_payload, requirements).await
     }
 
     /// Process payment with unified flow
+    ///
+    /// On successful verification a [`VerifiedPayment`] is inserted into the
+    /// request extensions for the handler. The payment is settled after the
+    /// handler returns and the [`SettleResponse`] is inserted into the response
+    /// extensions alongside the `X-PAYMENT-RESPONSE` header.
+    ///
+    /// Settlement waits only for the response head, never the body, so
+    /// streaming responses (SSE, large downloads) are passed through unbuffered
+    /// with the header already attached.
+    ///
+    /// With [`with_cors_headers`](Self::with_cors_headers), preflight requests
+    /// are answered here and every outcome carries the CORS headers.
     pub async fn process_payment(
         &self,
         request: Request,
         next: Next,
     ) -> crate::Result<PaymentResult> {
+        if let Some(response) = self.config.preflight_response(&request) {
+            return Ok(PaymentResult::Bypassed { response });
+        }
+
+        let origin = request.headers().get(http::header::ORIGIN).cloned();
+        let mut result = self.run_payment_flow(request, next).await?;
+        self.config
+            .apply_cors_headers(origin.as_ref(), result.response_mut().headers_mut());
+        Ok(result)
+    }
+
+    async fn run_payment_flow(
+        &self,
+        mut request: Request,
+        next: Next,
+    ) -> crate::Result<PaymentResult> {
+        if let Some(response) = self.config.well_known_response(&request) {
+            return Ok(PaymentResult::Discovery { response });
+        }
+
+        // Bypassed paths, unpaid methods and CORS preflight never see the payment flow
+        if !self
+            .config
+            .requires_payment(request.method(), request.uri().path())
+        {
+            return Ok(PaymentResult::Bypassed {
+                response: next.run(request).await,
+            });
+        }
+
+        if let Some(response) = self.limit_body(&mut request) {
+            return Ok(PaymentResult::PayloadTooLarge { response });
+        }
+
+        // Requests under the free quota skip the payment flow entirely
+        if self.is_free(&request).await {
+            return Ok(PaymentResult::Free {
+                response: next.run(request).await,
+            });
+        }
+
+        self.ensure_supported().await?;
+
         let headers = request.headers();
         let uri = request.uri().to_string();
 
         // Check if this is a web browser request
-        let user_agent = headers
-            .get("User-Agent")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
-        let accept = headers
-            .get("Accept")
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("");
-
-        let is_web_browser = accept.contains("text/html") && user_agent.contains("Mozilla");
+        let is_web_browser = self.config.client_type(headers) == ClientType::Browser;
 
-        // Create payment requirements
-        let payment_requirements = self.config.create_payment_requirements(&uri)?;
+        // Create the payment options
+        let accepts = self.config.create_accepts(&uri)?;
 
         // Check for payment header
-        let payment_header = headers.get("X-PAYMENT").and_then(|v| v.to_str().ok());
+        let payment_header = headers.get(X_PAYMENT_HEADER);
 
         match payment_header {
-            Some(payment_b64) => {
+            Some(header_value) => {
                 // Decode payment payload
-                let payment_payload = PaymentPayload::from_base64(payment_b64).map_err(|e| {
-                    X402Error::invalid_payment_payload(format!("Failed to decode payment: {}", e))
-                })?;
-
-                // Get facilitator client
-                let facilitator = if let Some(facilitator) = &self.facilitator {
-                    facilitator.clone()
-                } else {
-                    crate::facilitator::FacilitatorClient::new(
-                        self.config.facilitator_config.clone(),
-                    )?
-                };
+                let payment_payload =
+                    PaymentPayload::from_header_value(header_value).map_err(|e| {
+                        X402Error::invalid_payment_payload(format!(
+                            "Failed to decode payment: {}",
+                            e
+                        ))
+                    })?;
+                // Reject a payload for another scheme or network up front
+                let payment_requirements =
+                    match requirements_for_payment(&accepts, &payment_payload) {
+                        Ok(requirements) => requirements.clone(),
+                        Err(e) => {
+                            return Ok(PaymentResult::VerificationFailed {
+                                response: self.create_payment_required_response(
+                                    &e.to_string(),
+                                    &accepts,
+                                    is_web_browser,
+                                )?,
+                            });
+                        }
+                    };
 
                 // Verify payment
-                let verify_response = facilitator
-                    .verify(&payment_payload, &payment_requirements)
+                let verify_response = self
+                    .verify_payment(&payment_payload, &payment_requirements)
                     .await
                     .map_err(|e| {
                         X402Error::facilitator_error(format!("Payment verification failed: {}", e))
@@ -392,7 +1832,7 @@ impl PaymentMiddleware {
                 if !verify_response.is_valid {
                     let error_response = self.create_payment_required_response(
                         "Payment verification failed",
-                        &payment_requirements,
+                        &accepts,
                         is_web_browser,
                     )?;
                     return Ok(PaymentResult::VerificationFailed {
@@ -400,27 +1840,69 @@ impl PaymentMiddleware {
                     });
                 }
 
-                // Execute the handler
-                let mut response = next.run(request).await;
+                let verified_payment = VerifiedPayment::new(
+                    payment_payload.clone(),
+                    payment_requirements.clone(),
+                    &verify_response,
+                );
+
+                // Enforce the per-payer rate limit
+                if let Some(rate_limiter) = &self.rate_limiter {
+                    if !rate_limiter.check(&verified_payment.payer) {
+                        return Ok(PaymentResult::RateLimited {
+                            response: RateLimiter::too_many_requests_response(),
+                        });
+                    }
+                }
+
+                // Expose the verified payment to the handler
+                request.extensions_mut().insert(verified_payment);
+
+                // Execute the handler; a streaming body is left unpolled
+                let response = match self.config.run_handler(request, next).await {
+                    Ok(response) => response,
+                    Err(response) => return Ok(PaymentResult::TimedOut { response }),
+                };
+                let mut response = match self.check_response(response, &payment_requirements).await
+                {
+                    Ok(response) => response,
+                    Err(response) => return Ok(PaymentResult::InvalidResponse { response }),
+                };
+
+                // The client only pays for responses it was promised
+                if !self.config.settles_status(response.status()) {
+                    return Ok(PaymentResult::Unsettled { response });
+                }
 
                 // Settle the payment
-                let settle_response = facilitator
-                    .settle(&payment_payload, &payment_requirements)
+                let settle_response = match self
+                    .settle_with_requirements(&payment_payload, &payment_requirements)
                     .await
-                    .map_err(|e| {
-                        X402Error::facilitator_error(format!("Payment settlement failed: {}", e))
-                    })?;
+                {
+                    Ok(settlement) if settlement.success => settlement,
+                    outcome => {
+                        let reason = match outcome {
+                            Ok(settlement) => settlement.error_reason.unwrap_or_default(),
+                            Err(e) => e.to_string(),
+                        };
+                        tracing::warn!("Payment settlement failed: {}", reason);
+                        let response = self.create_payment_required_response(
+                            "Payment settlement failed",
+                            &accepts,
+                            is_web_browser,
+                        )?;
+                        return Ok(PaymentResult::SettlementFailed { response });
+                    }
+                };
 
                 // Add settlement header
-                let settlement_header = settle_response.to_base64().map_err(|e| {
+                let header_value = settle_response.to_header_value().map_err(|e| {
                     X402Error::config(format!("Failed to encode settlement response: {}", e))
                 })?;
-
-                if let Ok(header_value) = HeaderValue::from_str(&settlement_header) {
-                    response
-                        .headers_mut()
-                        .insert("X-PAYMENT-RESPONSE", header_value);
-                }
+                response
+                    .headers_mut()
+                    .insert(X_PAYMENT_RESPONSE_HEADER, header_value);
+                response.extensions_mut().insert(settle_response.clone());
 
                 Ok(PaymentResult::Success {
                     response,
@@ -431,7 +1913,7 @@ impl PaymentMiddleware {
                 // No payment provided, return 402 with requirements
                 let response = self.create_payment_required_response(
                     "X-PAYMENT header is required",
-                    &payment_requirements,
+                    &accepts,
                     is_web_browser,
                 )?;
                 Ok(PaymentResult::PaymentRequired { response })
@@ -439,14 +1921,34 @@ impl PaymentMiddleware {
         }
     }
 
+    /// Check a handler response against the requirements' output schema
+    ///
+    /// Only applies with response validation enabled, to successful JSON
+    /// responses; the body is buffered for those. Returns the response to
+    /// send instead of settling when it does not conform.
+    pub(crate) async fn check_response(
+        &self,
+        response: Response,
+        #[cfg_attr(not(feature = "json-schema"), allow(unused_variables))]
+        requirements: &PaymentRequirements,
+    ) -> std::result::Result<Response, Response> {
+        #[cfg(feature = "json-schema")]
+        if self.config.validate_responses {
+            if let Some(schema) = &requirements.output_schema {
+                return validate_json_response(response, schema).await;
+            }
+        }
+        Ok(response)
+    }
+
     /// Create payment required response
     fn create_payment_required_response(
         &self,
         error: &str,
-        payment_requirements: &PaymentRequirements,
+        accepts: &[PaymentRequirements],
         is_web_browser: bool,
     ) -> crate::Result<axum::response::Response> {
-        if is_web_browser {
+        let mut response = if is_web_browser {
             let html = if let Some(custom_html) = &self.config.custom_paywall_html {
                 custom_html.clone()
             } else {
@@ -457,26 +1959,22 @@ impl PaymentMiddleware {
                         .with_app_logo("💰")
                 });
 
-                crate::template::generate_paywall_html(
-                    error,
-                    std::slice::from_ref(payment_requirements),
-                    Some(&paywall_config),
-                )
+                crate::template::generate_paywall_html(error, accepts, Some(&paywall_config))
             };
 
-            let response = Response::builder()
+            Response::builder()
                 .status(StatusCode::PAYMENT_REQUIRED)
-                .header("Content-Type", "text/html")
+                .header(http::header::CONTENT_TYPE, "text/html; charset=utf-8")
                 .body(html.into())
-                .map_err(|e| X402Error::config(format!("Failed to create HTML response: {}", e)))?;
-
-            Ok(response)
+                .map_err(|e| X402Error::config(format!("Failed to create HTML response: {}", e)))?
         } else {
-            let payment_response =
-                PaymentRequirementsResponse::new(error, vec![payment_requirements.clone()]);
+            let payment_response = self.config.payment_required_body(error, accepts.to_vec());
 
-            Ok(Json(payment_response).into_response())
-        }
+            (StatusCode::PAYMENT_REQUIRED, Json(payment_response)).into_response()
+        };
+
+        self.config.apply_quote_headers(response.headers_mut());
+        Ok(response)
     }
 }
 
@@ -491,6 +1989,14 @@ pub async fn payment_middleware(
         PaymentResult::PaymentRequired { response } => Ok(response),
         PaymentResult::VerificationFailed { response } => Ok(response),
         PaymentResult::SettlementFailed { response } => Ok(response),
+        PaymentResult::RateLimited { response } => Ok(response),
+        PaymentResult::Free { response } => Ok(response),
+        PaymentResult::PayloadTooLarge { response } => Ok(response),
+        PaymentResult::Bypassed { response } => Ok(response),
+        PaymentResult::InvalidResponse { response } => Ok(response),
+        PaymentResult::Unsettled { response } => Ok(response),
+        PaymentResult::Discovery { response } => Ok(response),
+        PaymentResult::TimedOut { response } => Ok(response),
     }
 }
 
@@ -568,24 +2074,20 @@ where
         let middleware = self.middleware.clone();
 
         // Extract payment header before moving the request
-        let payment_header = req
-            .headers()
-            .get("X-PAYMENT")
-            .and_then(|h| h.to_str().ok())
-            .map(|s| s.to_string());
-        let uri_path = req.uri().path().to_string();
+        let payment_header = req.headers().get(X_PAYMENT_HEADER).cloned();
+        let uri = req.uri().to_string();
 
         let future = self.inner.call(req);
 
         Box::pin(async move {
             match payment_header {
-                Some(payment_b64) => {
+                Some(header_value) => {
                     // Parse payment payload
-                    match crate::types::PaymentPayload::from_base64(&payment_b64) {
+                    match crate::types::PaymentPayload::from_header_value(&header_value) {
                         Ok(payment_payload) => {
                             // Create payment requirements
                             let requirements =
-                                match middleware.config.create_payment_requirements(&uri_path) {
+                                match middleware.config.create_payment_requirements(&uri) {
                                     Ok(req) => req,
                                     Err(e) => {
                                         // Return 500 error if we can't create requirements
@@ -705,6 +2207,145 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_quote_signed() {
+        let signer = crate::signer::LocalSigner::new(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let address = format!("{:?}", crate::signer::Signer::address(&signer));
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_quote_ttl(std::time::Duration::from_secs(60))
+        .with_quote_signer(signer);
+
+        let accepts = config.create_accepts("/test").unwrap();
+        let body = config.payment_required_body("X-PAYMENT header is required", accepts);
+        assert!(body.expires.is_some());
+        body.verify_signature(&address).unwrap();
+    }
+
+    #[test]
+    fn test_min_amount() {
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        );
+
+        let at_floor = config
+            .clone()
+            .with_min_amount(Decimal::from_str("0.001").unwrap());
+        assert_eq!(
+            at_floor
+                .create_payment_requirements("/test")
+                .unwrap()
+                .max_amount_required,
+            "1000"
+        );
+
+        let below_floor = config.with_min_amount(Decimal::from_str("0.01").unwrap());
+        assert!(matches!(
+            below_floor.create_payment_requirements("/test"),
+            Err(X402Error::InvalidPaymentRequirements { .. })
+        ));
+    }
+
+    #[test]
+    fn test_additional_network_accepts() {
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_additional_network(Network::AvalancheFuji)
+        .with_additional_network(Network::AvalancheFuji)
+        .with_additional_network(Network::Testnet);
+
+        let accepts = config.create_accepts("/test").unwrap();
+        let networks: Vec<_> = accepts.iter().map(|r| r.network.as_str()).collect();
+        assert_eq!(networks, ["base-sepolia", "avalanche-fuji"]);
+        assert_eq!(accepts[1].asset, Network::AvalancheFuji.usdc_address());
+        assert_eq!(
+            accepts[1].max_amount_required,
+            accepts[0].max_amount_required
+        );
+    }
+
+    #[test]
+    fn test_max_timeout_clamped() {
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_max_timeout_seconds(3 * 24 * 60 * 60);
+        let requirements = config.create_payment_requirements("/test").unwrap();
+        assert_eq!(
+            requirements.max_timeout_seconds,
+            DEFAULT_MAX_TIMEOUT_CLAMP_SECONDS
+        );
+
+        // Values under the clamp are kept, and the clamp is configurable
+        let config = config.with_max_timeout_seconds(120);
+        assert_eq!(
+            config
+                .create_payment_requirements("/test")
+                .unwrap()
+                .max_timeout_seconds,
+            120
+        );
+        let config = config.with_max_timeout_clamp_seconds(90);
+        assert_eq!(
+            config
+                .create_payment_requirements("/test")
+                .unwrap()
+                .max_timeout_seconds,
+            90
+        );
+    }
+
+    #[test]
+    fn test_resource_url_normalization() {
+        let resource = |root: &str, uri: &str| {
+            PaymentMiddlewareConfig::new(
+                Decimal::from_str("0.0001").unwrap(),
+                "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            )
+            .with_resource_root_url(root)
+            .create_payment_requirements(uri)
+            .unwrap()
+            .resource
+        };
+
+        assert_eq!(
+            resource("https://API.example.com/", "/weather"),
+            "https://api.example.com/weather"
+        );
+        assert_eq!(
+            resource("https://api.example.com/v1/", "//weather"),
+            "https://api.example.com/v1/weather"
+        );
+        assert_eq!(
+            resource("https://api.example.com", "weather"),
+            "https://api.example.com/weather"
+        );
+        assert_eq!(
+            resource(
+                "https://api.example.com/",
+                "/weather?city=Paris&units=metric"
+            ),
+            "https://api.example.com/weather?city=Paris&units=metric"
+        );
+        // An absolute request URI contributes only its path and query
+        assert_eq!(
+            resource(
+                "https://api.example.com",
+                "http://10.0.0.1:8080/weather?city=Paris"
+            ),
+            "https://api.example.com/weather?city=Paris"
+        );
+    }
+
     #[test]
     fn test_payment_middleware_config_builder() {
         let config = PaymentMiddlewareConfig::new(
@@ -729,6 +2370,238 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bypass_paths() {
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("0.01").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        );
+        assert!(config.bypass_paths.is_empty());
+        assert!(!config.is_bypassed("/health"));
+
+        let config = config.with_bypass_paths(vec![
+            "/health".to_string(),
+            "/metrics/*".to_string(),
+            "/static/*.css".to_string(),
+        ]);
+        assert!(config.is_bypassed("/health"));
+        assert!(!config.is_bypassed("/health/deep"));
+        assert!(!config.is_bypassed("/healthz"));
+        assert!(config.is_bypassed("/metrics/"));
+        assert!(config.is_bypassed("/metrics/prometheus"));
+        assert!(!config.is_bypassed("/metrics"));
+        assert!(config.is_bypassed("/static/app/site.css"));
+        assert!(!config.is_bypassed("/static/site.js"));
+        assert!(!config.is_bypassed("/api"));
+        assert!(!config.requires_payment(&http::Method::GET, "/health"));
+        assert!(config.requires_payment(&http::Method::GET, "/api"));
+
+        // Preflight is always free, even when every method is paid
+        assert!(!config.requires_payment(&http::Method::OPTIONS, "/api"));
+        let config = config.with_paid_methods(vec![http::Method::POST]);
+        assert!(!config.requires_payment(&http::Method::GET, "/api"));
+        assert!(config.requires_payment(&http::Method::POST, "/api"));
+    }
+
+    #[test]
+    fn test_client_type_resolution() {
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            let mut headers = http::HeaderMap::new();
+            for (name, value) in pairs {
+                headers.insert(*name, http::HeaderValue::from_static(value));
+            }
+            headers
+        };
+        let curl = headers(&[("User-Agent", "curl/8.5.0"), ("Accept", "*/*")]);
+        let browser = headers(&[
+            (
+                "User-Agent",
+                "Opera/9.80 (X11; Linux x86_64) Presto/2.12.388",
+            ),
+            (
+                "Accept",
+                "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+            ),
+            ("Sec-Fetch-Mode", "navigate"),
+        ]);
+
+        assert_eq!(ClientType::from_headers(&curl), ClientType::Api);
+        assert_eq!(ClientType::from_headers(&browser), ClientType::Browser);
+
+        // Quality values rank the types; a refused type does not count
+        for (accept, expected) in [
+            ("application/json, text/html;q=0.5", ClientType::Api),
+            (
+                "text/html;q=0.9, application/json;q=0.8",
+                ClientType::Browser,
+            ),
+            ("text/html;q=0", ClientType::Api),
+        ] {
+            let mut headers = http::HeaderMap::new();
+            headers.insert("Accept", http::HeaderValue::from_static(accept));
+            assert_eq!(ClientType::from_headers(&headers), expected, "{}", accept);
+        }
+
+        // A navigation without a telling Accept is a browser
+        let navigation = headers(&[("Accept", "*/*"), ("Sec-Fetch-Mode", "navigate")]);
+        assert_eq!(ClientType::from_headers(&navigation), ClientType::Browser);
+
+        // The explicit header overrides everything else
+        let mut overridden = browser.clone();
+        overridden.insert(
+            X_PAYMENT_CLIENT_HEADER,
+            http::HeaderValue::from_static("api"),
+        );
+        assert_eq!(ClientType::from_headers(&overridden), ClientType::Api);
+        let mut overridden = curl.clone();
+        overridden.insert(
+            X_PAYMENT_CLIENT_HEADER,
+            http::HeaderValue::from_static("Browser"),
+        );
+        assert_eq!(ClientType::from_headers(&overridden), ClientType::Browser);
+
+        // The resolver is configurable
+        let config = PaymentMiddlewareConfig::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_client_type_resolver(|_| ClientType::Browser);
+        assert_eq!(config.client_type(&curl), ClientType::Browser);
+    }
+
+    #[test]
+    fn test_paywall_content_type() {
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.0001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        );
+        let requirements = middleware.config.create_payment_requirements("/").unwrap();
+
+        let response = middleware
+            .create_payment_required_response(
+                "Payment required",
+                std::slice::from_ref(&requirements),
+                true,
+            )
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+
+        let response = middleware
+            .create_payment_required_response(
+                "Payment required",
+                std::slice::from_ref(&requirements),
+                false,
+            )
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+        assert_eq!(
+            response.headers()[http::header::CONTENT_TYPE],
+            "application/json"
+        );
+    }
+
+    #[test]
+    fn test_rate_limiter_per_key() {
+        let limiter = RateLimiter::new(2, std::time::Duration::from_secs(60));
+
+        assert!(limiter.check("0xAbC"));
+        assert!(limiter.check("0xabc"));
+        assert!(!limiter.check("0xABC"));
+        assert!(limiter.check("0xdef"));
+    }
+
+    #[test]
+    fn test_rate_limiter_refills() {
+        let limiter = RateLimiter::new(1, std::time::Duration::from_millis(20));
+
+        assert!(limiter.check("0xabc"));
+        assert!(!limiter.check("0xabc"));
+        std::thread::sleep(std::time::Duration::from_millis(30));
+        assert!(limiter.check("0xabc"));
+    }
+
+    fn keyed_request(key: &str) -> Request {
+        Request::builder()
+            .header("x-api-key", key)
+            .body(axum::body::Body::empty())
+            .unwrap()
+    }
+
+    fn api_key(request: &Request) -> Option<String> {
+        request
+            .headers()
+            .get("x-api-key")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    }
+
+    #[tokio::test]
+    async fn test_free_tier_concurrent_requests_respect_quota() {
+        let free_tier = FreeTier::new(5, api_key);
+
+        let handles: Vec<_> = (0..20)
+            .map(|_| {
+                let free_tier = free_tier.clone();
+                tokio::spawn(async move { free_tier.allows(&keyed_request("alice")).await })
+            })
+            .collect();
+
+        let mut allowed = 0;
+        for handle in handles {
+            if handle.await.unwrap().unwrap() {
+                allowed += 1;
+            }
+        }
+        assert_eq!(allowed, 5);
+
+        // Requests without an identity are never free
+        let anonymous = Request::builder().body(axum::body::Body::empty()).unwrap();
+        assert!(!free_tier.allows(&anonymous).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_free_tier_reset_window() {
+        let free_tier =
+            FreeTier::new(1, api_key).with_reset_window(std::time::Duration::from_millis(20));
+
+        assert!(free_tier.allows(&keyed_request("alice")).await.unwrap());
+        assert!(!free_tier.allows(&keyed_request("alice")).await.unwrap());
+        tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+        assert!(free_tier.allows(&keyed_request("alice")).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_free_tier_store_bounded() {
+        let store = InMemoryFreeTierStore::new().with_max_entries(3);
+
+        // Rotating identities cannot grow the store past its capacity
+        for i in 0..100 {
+            store.increment(&format!("key-{}", i), None).await.unwrap();
+        }
+        assert_eq!(store.len(), 3);
+        assert_eq!(store.lock().order.len(), 3);
+        assert_eq!(store.increment("key-99", None).await.unwrap(), 2);
+        assert_eq!(store.increment("key-0", None).await.unwrap(), 1);
+
+        // Windows past the reset window are dropped on the next request
+        let window = Some(std::time::Duration::from_millis(20));
+        let store = InMemoryFreeTierStore::new();
+        for i in 0..10 {
+            store
+                .increment(&format!("key-{}", i), window)
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(30)).await;
+        assert_eq!(store.increment("key-0", window).await.unwrap(), 1);
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.lock().order.len(), 1);
+    }
+
     #[test]
     fn test_payment_middleware_creation_with_description() {
         let middleware = PaymentMiddleware::new(
@@ -746,4 +2619,110 @@ mod tests {
             Some("Test middleware".to_string())
         );
     }
+
+    #[test]
+    fn test_facilitator_built_once() {
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator_config(FacilitatorConfig::new("https://example.com/a"));
+
+        let first = middleware.facilitator_client().unwrap();
+        assert!(std::ptr::eq(
+            first,
+            middleware.facilitator_client().unwrap()
+        ));
+
+        // Clones handed to each request share the same facilitator
+        let cloned = middleware.clone();
+        assert!(std::ptr::eq(first, cloned.facilitator_client().unwrap()));
+
+        // Replacing the config rebuilds the facilitator from the new config
+        let reconfigured =
+            cloned.with_facilitator_config(FacilitatorConfig::new("https://example.com/b"));
+        assert_eq!(
+            reconfigured.facilitator_client().unwrap().url(),
+            "https://example.com/b"
+        );
+        assert_eq!(first.url(), "https://example.com/a");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_identical_verifications_coalesced() {
+        let mut facilitator = mockito::Server::new_async().await;
+        let verify = facilitator
+            .mock("POST", "/verify")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"isValid":true}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_facilitator_config(FacilitatorConfig::new(facilitator.url()));
+
+        let requirements = middleware
+            .config()
+            .create_payment_requirements("/")
+            .unwrap();
+        let signer = crate::signer::LocalSigner::new(
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+        )
+        .unwrap();
+        let payment = crate::wallet::Wallet::new(signer, "base-sepolia")
+            .authorize_payment(&requirements)
+            .await
+            .unwrap();
+
+        let responses = futures_util::future::join_all(
+            (0..8).map(|_| middleware.verify_payment(&payment, &requirements)),
+        )
+        .await;
+        let responses: Vec<_> = responses.into_iter().map(Result::unwrap).collect();
+        assert!(responses[0].is_valid);
+        for duplicate in &responses[1..] {
+            assert_eq!(
+                duplicate.invalid_reason.as_deref(),
+                Some("nonce_already_used")
+            );
+        }
+        verify.assert_async().await;
+
+        // The entry is gone once the call completes
+        assert!(middleware.in_flight.calls.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_facilitator_shared_not_cloned() {
+        let facilitator = Arc::new(
+            crate::facilitator::FacilitatorClient::new(FacilitatorConfig::new(
+                "https://example.com",
+            ))
+            .unwrap(),
+        );
+        let middleware = PaymentMiddleware::new(
+            Decimal::from_str("0.001").unwrap(),
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        )
+        .with_shared_facilitator(Arc::clone(&facilitator));
+
+        // Every clone, and the default settler, uses the one client
+        let clones: Vec<_> = (0..4).map(|_| middleware.clone()).collect();
+        for clone in &clones {
+            assert!(Arc::ptr_eq(
+                &facilitator,
+                clone.facilitator_client().unwrap()
+            ));
+        }
+        let settler = middleware.settler().unwrap();
+        assert_eq!(Arc::strong_count(&facilitator), 7);
+
+        drop(settler);
+        drop(clones);
+        assert_eq!(Arc::strong_count(&facilitator), 2);
+    }
 }
@@ -0,0 +1,208 @@
+//! Native gas-token payments for x402
+//!
+//! Requirements whose `asset` is [`NATIVE_ASSET`] are paid in the network's
+//! gas token (ETH on Base, AVAX on Avalanche) instead of an EIP-3009 token.
+//! The payer signs a `NativeTransferAuthorization` over the same fields as
+//! `TransferWithAuthorization`; settlement submits a plain value transfer.
+//!
+//! Settlement is currently mocked; verification checks the signature, timing,
+//! recipient and amount against the payment requirements.
+
+use crate::crypto::signature::verify_native_payment_payload;
+use crate::types::{
+    NetworkConfig, PaymentPayload, PaymentRequirements, SettleResponse, VerifyResponse,
+    NATIVE_ASSET, NATIVE_DECIMALS,
+};
+use crate::Result;
+
+/// Verify a native gas-token payment against the requirements
+///
+/// Returns an invalid response with a reason rather than an error when the
+/// payment does not satisfy the requirements.
+pub fn verify_native_payment(
+    payment: &PaymentPayload,
+    requirements: &PaymentRequirements,
+) -> Result<VerifyResponse> {
+    let authorization = &payment.payload.authorization;
+    let invalid = |reason: &str| VerifyResponse {
+        is_valid: false,
+        invalid_reason: Some(reason.to_string()),
+        payer: Some(authorization.from.clone()),
+    };
+
+    if requirements.asset != NATIVE_ASSET {
+        return Ok(invalid("wrong_asset"));
+    }
+
+    if NetworkConfig::from_name(&payment.network).is_none()
+        || payment.network != requirements.network
+    {
+        return Ok(invalid("network_mismatch"));
+    }
+
+    if payment.scheme != requirements.scheme {
+        return Ok(invalid("scheme_mismatch"));
+    }
+
+    if !verify_native_payment_payload(&payment.payload, &authorization.from, &payment.network)? {
+        return Ok(invalid("invalid_signature"));
+    }
+
+    if !authorization.is_valid_at(chrono::Utc::now().timestamp(), std::time::Duration::ZERO)? {
+        return Ok(invalid("authorization_expired"));
+    }
+
+    if !authorization.to.eq_ignore_ascii_case(&requirements.pay_to) {
+        return Ok(invalid("recipient_mismatch"));
+    }
+
+    let payment_amount = authorization.amount(NATIVE_DECIMALS)?;
+    let required_amount = requirements.required_amount(NATIVE_DECIMALS)?;
+
+    if payment_amount < required_amount {
+        return Ok(invalid("insufficient_amount"));
+    }
+
+    Ok(VerifyResponse {
+        is_valid: true,
+        invalid_reason: None,
+        payer: Some(authorization.from.clone()),
+    })
+}
+
+/// Settle a verified native gas-token payment
+///
+/// Note: This is a mock implementation that returns a simulated transaction
+/// hash; no value transfer is submitted.
+pub fn settle_native_payment(payment: &PaymentPayload) -> Result<SettleResponse> {
+    use rand::Rng;
+
+    Ok(SettleResponse {
+        success: true,
+        error_reason: None,
+        transaction: format!("0x{}", hex::encode(rand::thread_rng().gen::<[u8; 32]>())),
+        network: payment.network.clone(),
+        payer: Some(payment.payload.authorization.from.clone()),
+        fee_amount: None,
+        net_amount: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{signer::LocalSigner, wallet::Wallet};
+
+    const PAY_TO: &str = "0x209693bc6afc0c5328ba36faf03c514ef312287c";
+
+    fn wallet() -> Wallet {
+        Wallet::new(
+            LocalSigner::new("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
+                .unwrap(),
+            "base-sepolia",
+        )
+    }
+
+    fn native_requirements() -> PaymentRequirements {
+        PaymentRequirements::native(
+            "base-sepolia",
+            "1000000000000000",
+            PAY_TO,
+            "https://example.com/resource",
+            "Pay in ETH",
+        )
+    }
+
+    #[test]
+    fn test_native_requirements() {
+        let requirements = native_requirements();
+        assert!(requirements.is_native());
+        assert_eq!(requirements.asset, NATIVE_ASSET);
+
+        let native_token = NetworkConfig::from_name(&requirements.network)
+            .unwrap()
+            .native_token;
+        assert_eq!(native_token.symbol, "ETH");
+        assert_eq!(
+            requirements
+                .decimal_amount(native_token.decimals)
+                .unwrap()
+                .to_string(),
+            "0.001"
+        );
+
+        // The builder accepts the native sentinel in place of a token address
+        let built = PaymentRequirements::builder()
+            .scheme("exact")
+            .network("avalanche-fuji")
+            .amount_atomic("1")
+            .asset(NATIVE_ASSET)
+            .pay_to(PAY_TO)
+            .resource("https://example.com/resource")
+            .build()
+            .unwrap();
+        assert!(built.is_native());
+    }
+
+    #[tokio::test]
+    async fn test_verify_and_settle_native_payment() {
+        let requirements = native_requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        let response = verify_native_payment(&payment, &requirements).unwrap();
+        assert!(response.is_valid, "{:?}", response.invalid_reason);
+
+        let settlement = settle_native_payment(&payment).unwrap();
+        assert!(settlement.success);
+        assert_eq!(settlement.network, "base-sepolia");
+        assert_eq!(settlement.payer, response.payer);
+    }
+
+    #[tokio::test]
+    async fn test_native_signature_is_not_a_token_signature() {
+        let requirements = native_requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        // A native authorization cannot be replayed as a USDC transfer, or vice versa
+        assert!(!payment.verify_signature().unwrap());
+
+        let mut token_requirements = requirements.clone();
+        token_requirements.asset = "0x036CbD53842c5426634e7929541eC2318f3dCF7e".to_string();
+        let token_payment = wallet()
+            .authorize_payment(&token_requirements)
+            .await
+            .unwrap();
+        let response = verify_native_payment(&token_payment, &requirements).unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("invalid_signature")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verify_native_payment_rejects_mismatches() {
+        let requirements = native_requirements();
+        let payment = wallet().authorize_payment(&requirements).await.unwrap();
+
+        let mut token = requirements.clone();
+        token.asset = "0x036CbD53842c5426634e7929541eC2318f3dCF7e".to_string();
+        let response = verify_native_payment(&payment, &token).unwrap();
+        assert_eq!(response.invalid_reason.as_deref(), Some("wrong_asset"));
+
+        let mut pricier = requirements.clone();
+        pricier.max_amount_required = "2000000000000000".to_string();
+        let response = verify_native_payment(&payment, &pricier).unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("insufficient_amount")
+        );
+
+        let mut elsewhere = requirements;
+        elsewhere.pay_to = "0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string();
+        let response = verify_native_payment(&payment, &elsewhere).unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("recipient_mismatch")
+        );
+    }
+}
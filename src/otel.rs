@@ -0,0 +1,152 @@
+//! OpenTelemetry export (the `otel` feature)
+//!
+//! Verify and settle calls, on [`FacilitatorClient`](crate::facilitator::FacilitatorClient)
+//! and the in-process [`LocalFacilitator`](crate::facilitator::LocalFacilitator),
+//! run in `x402.verify` / `x402.settle` spans carrying `x402.network`,
+//! `x402.scheme`, `x402.payer`, `x402.amount` and, once settled, `x402.tx_hash`.
+//! [`init_otel`] ships those spans to an OTLP collector, so a payment can be
+//! traced from the resource server through the facilitator.
+//!
+//! ```rust,no_run
+//! # fn main() -> rust_x402::Result<()> {
+//! let provider = rust_x402::otel::init_otel("http://localhost:4318/v1/traces")?;
+//! // ... serve requests ...
+//! let _ = provider.shutdown();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Result, X402Error};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Instrumentation scope name of the exported spans
+pub const TRACER_NAME: &str = "rust-x402";
+
+/// Layer exporting `tracing` spans through `provider`
+pub fn layer<S>(provider: &SdkTracerProvider) -> OpenTelemetryLayer<S, SdkTracer>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(TRACER_NAME))
+}
+
+/// Install a global subscriber that logs to stdout and exports spans over OTLP
+///
+/// `endpoint` is the collector's OTLP/HTTP traces URL. Spans are exported in
+/// batches; call `shutdown` on the returned provider before exiting to flush
+/// the last of them.
+pub fn init_otel(endpoint: &str) -> Result<SdkTracerProvider> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| X402Error::config(format!("Failed to build OTLP exporter: {}", e)))?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .build();
+    opentelemetry::global::set_tracer_provider(provider.clone());
+
+    tracing_subscriber::registry()
+        .with(LevelFilter::INFO)
+        .with(tracing_subscriber::fmt::layer())
+        .with(layer(&provider))
+        .try_init()
+        .map_err(|e| X402Error::config(format!("Failed to install subscriber: {}", e)))?;
+
+    Ok(provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::facilitator::{LocalFacilitator, MockSettler};
+    use crate::facilitator_storage::InMemoryStorage;
+    use crate::types::PaymentRequirements;
+    use crate::{signer::LocalSigner, wallet::Wallet};
+    use opentelemetry_sdk::trace::{InMemorySpanExporter, SpanData};
+
+    fn attribute(span: &SpanData, key: &str) -> Option<String> {
+        span.attributes
+            .iter()
+            .find(|attribute| attribute.key.as_str() == key)
+            .map(|attribute| attribute.value.as_str().into_owned())
+    }
+
+    #[tokio::test]
+    async fn test_settlement_spans_exported() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let _guard =
+            tracing::subscriber::set_default(tracing_subscriber::registry().with(layer(&provider)));
+
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693bc6afc0c5328ba36faf03c514ef312287c",
+            "https://example.com/resource",
+            "Test resource",
+        );
+        let payment = Wallet::new(
+            LocalSigner::new("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
+                .unwrap(),
+            "base-sepolia",
+        )
+        .authorize_payment(&requirements)
+        .await
+        .unwrap();
+
+        let facilitator = LocalFacilitator::new(InMemoryStorage::new(), MockSettler);
+        assert!(
+            facilitator
+                .verify_payment(&payment, &requirements)
+                .await
+                .unwrap()
+                .is_valid
+        );
+        let settlement = facilitator
+            .settle_payment(&payment, &requirements)
+            .await
+            .unwrap();
+
+        provider.force_flush().unwrap();
+        let spans = exporter.get_finished_spans().unwrap();
+        let verify = spans
+            .iter()
+            .find(|span| span.name == "x402.verify")
+            .unwrap();
+        let settle = spans
+            .iter()
+            .find(|span| span.name == "x402.settle")
+            .unwrap();
+
+        for span in [verify, settle] {
+            assert_eq!(
+                attribute(span, "x402.network").as_deref(),
+                Some("base-sepolia")
+            );
+            assert_eq!(attribute(span, "x402.scheme").as_deref(), Some("exact"));
+            assert_eq!(
+                attribute(span, "x402.payer"),
+                Some(payment.payload.authorization.from.clone())
+            );
+            assert_eq!(attribute(span, "x402.amount").as_deref(), Some("1000"));
+        }
+        assert_eq!(attribute(verify, "x402.tx_hash"), None);
+        assert_eq!(
+            attribute(settle, "x402.tx_hash"),
+            Some(settlement.transaction)
+        );
+    }
+}
@@ -273,6 +273,14 @@ async fn payment_middleware_handler(
             crate::middleware::PaymentResult::PaymentRequired { response } => response,
             crate::middleware::PaymentResult::VerificationFailed { response } => response,
             crate::middleware::PaymentResult::SettlementFailed { response } => response,
+            crate::middleware::PaymentResult::RateLimited { response } => response,
+            crate::middleware::PaymentResult::Free { response } => response,
+            crate::middleware::PaymentResult::PayloadTooLarge { response } => response,
+            crate::middleware::PaymentResult::Bypassed { response } => response,
+            crate::middleware::PaymentResult::InvalidResponse { response } => response,
+            crate::middleware::PaymentResult::Unsettled { response } => response,
+            crate::middleware::PaymentResult::Discovery { response } => response,
+            crate::middleware::PaymentResult::TimedOut { response } => response,
         },
         Err(e) => (
             axum::http::StatusCode::INTERNAL_SERVER_ERROR,
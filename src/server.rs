@@ -5,6 +5,10 @@
 
 use crate::Result;
 use axum::Router;
+use hyper_util::rt::{TokioExecutor, TokioIo, TokioTimer};
+use hyper_util::server::conn::auto;
+use hyper_util::service::TowerToHyperService;
+use std::time::Duration;
 
 /// Configuration for HTTP server binding
 #[derive(Debug, Clone)]
@@ -13,6 +17,14 @@ pub struct ServerConfig {
     pub bind_addr: String,
     /// Protocol version to use
     pub protocol: HttpProtocol,
+    /// Time allowed to receive a request's headers (`None` disables)
+    ///
+    /// Also bounds how long an idle HTTP/1.1 keep-alive connection waits for
+    /// its next request, since the timer starts as soon as it is idle.
+    pub header_read_timeout: Option<Duration>,
+    /// HTTP/2 keep-alive: ping idle connections this often and close them when
+    /// a ping is not acknowledged within the same time (`None` disables)
+    pub keep_alive_timeout: Option<Duration>,
 }
 
 /// HTTP protocol versions
@@ -28,10 +40,7 @@ pub enum HttpProtocol {
 
 impl Default for ServerConfig {
     fn default() -> Self {
-        Self {
-            bind_addr: "0.0.0.0:8080".to_string(),
-            protocol: HttpProtocol::Http1,
-        }
+        Self::new("0.0.0.0:8080", HttpProtocol::Http1)
     }
 }
 
@@ -41,8 +50,22 @@ impl ServerConfig {
         Self {
             bind_addr: bind_addr.into(),
             protocol,
+            header_read_timeout: Some(Duration::from_secs(30)),
+            keep_alive_timeout: Some(Duration::from_secs(60)),
         }
     }
+
+    /// Set the time allowed to receive a request's headers
+    pub fn with_header_read_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.header_read_timeout = timeout;
+        self
+    }
+
+    /// Set the HTTP/2 keep-alive ping timeout
+    pub fn with_keep_alive_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.keep_alive_timeout = timeout;
+        self
+    }
 }
 
 /// Trait for creating and starting HTTP servers
@@ -88,6 +111,18 @@ impl ServerBuilder {
         self
     }
 
+    /// Set the time allowed to receive a request's headers
+    pub fn header_read_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.header_read_timeout = timeout;
+        self
+    }
+
+    /// Set the HTTP/2 keep-alive ping timeout
+    pub fn keep_alive_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.config.keep_alive_timeout = timeout;
+        self
+    }
+
     /// Start the server
     pub async fn serve(self) -> Result<()> {
         match self.config.protocol {
@@ -115,11 +150,7 @@ impl HttpServer for Http1Server {
             config.bind_addr
         );
 
-        axum::serve(listener, router)
-            .await
-            .map_err(|e| crate::X402Error::config(format!("Server error: {}", e)))?;
-
-        Ok(())
+        serve_tcp(listener, router, &config).await
     }
 }
 
@@ -143,11 +174,52 @@ impl HttpServer for Http2Server {
         );
         tracing::warn!("HTTP/2 requires TLS configuration. Consider using axum with TLS support.");
 
-        axum::serve(listener, router)
-            .await
-            .map_err(|e| crate::X402Error::config(format!("Server error: {}", e)))?;
+        serve_tcp(listener, router, &config).await
+    }
+}
 
-        Ok(())
+/// Serve HTTP/1.1 and cleartext HTTP/2 connections with the configured timeouts
+///
+/// Stands in for `axum::serve`, which does not expose hyper's connection
+/// settings.
+async fn serve_tcp(
+    listener: tokio::net::TcpListener,
+    router: Router,
+    config: &ServerConfig,
+) -> Result<()> {
+    let mut builder = auto::Builder::new(TokioExecutor::new());
+    builder
+        .http1()
+        .timer(TokioTimer::new())
+        .header_read_timeout(config.header_read_timeout);
+    builder
+        .http2()
+        .timer(TokioTimer::new())
+        .keep_alive_interval(config.keep_alive_timeout);
+    if let Some(timeout) = config.keep_alive_timeout {
+        builder.http2().keep_alive_timeout(timeout);
+    }
+
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                // Per-connection failures (e.g. too many open files) are transient
+                tracing::warn!("Failed to accept connection: {}", e);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let builder = builder.clone();
+        let service = TowerToHyperService::new(router.clone());
+        tokio::spawn(async move {
+            if let Err(e) = builder
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                tracing::debug!("Connection closed: {}", e);
+            }
+        });
     }
 }
 
@@ -167,6 +239,7 @@ impl HttpServer for Http3Server {
 
         #[cfg(not(feature = "http3"))]
         {
+            let _ = (router, config);
             Err(crate::X402Error::config(
                 "HTTP/3 support is not enabled. Compile with 'http3' feature flag.".to_string(),
             ))
@@ -188,6 +261,8 @@ mod tests {
         let config = ServerConfig::default();
         assert_eq!(config.bind_addr, "0.0.0.0:8080");
         assert_eq!(config.protocol, HttpProtocol::Http1);
+        assert_eq!(config.header_read_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(config.keep_alive_timeout, Some(Duration::from_secs(60)));
     }
 
     #[test]
@@ -197,6 +272,42 @@ mod tests {
         assert_eq!(config.protocol, HttpProtocol::Http3);
     }
 
+    #[tokio::test]
+    async fn test_slow_headers_dropped() {
+        use axum::routing::get;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let config = ServerConfig::new(addr.to_string(), HttpProtocol::Http1)
+            .with_header_read_timeout(Some(Duration::from_millis(200)));
+        let router = Router::new().route("/", get(|| async { "ok" }));
+        tokio::spawn(async move { serve_tcp(listener, router, &config).await });
+
+        // A complete request is served
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+
+        // A client that never finishes its headers is disconnected
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n")
+            .await
+            .unwrap();
+        let mut response = Vec::new();
+        tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut response))
+            .await
+            .expect("slow connection was not dropped")
+            .unwrap();
+        assert!(!String::from_utf8_lossy(&response).contains("200 OK"));
+    }
+
     #[tokio::test]
     async fn test_server_builder() {
         let router = Router::new();
@@ -0,0 +1,462 @@
+//! Settlement history for facilitators
+//!
+//! Settlements are recorded keyed by authorization nonce and can be queried
+//! by payer and network, newest first, for reconciliation and support.
+
+use crate::types::SettleResponse;
+use crate::Result;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+
+/// Default number of settlements returned by a query
+pub const DEFAULT_QUERY_LIMIT: usize = 50;
+
+/// Maximum number of settlements returned by a query
+pub const MAX_QUERY_LIMIT: usize = 500;
+
+/// Filter for settlement history queries
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SettlementQuery {
+    /// Only settlements paid by this address (case-insensitive)
+    pub payer: Option<String>,
+    /// Only settlements on this network
+    pub network: Option<String>,
+    /// Maximum number of results (default 50, capped at 500)
+    pub limit: Option<usize>,
+}
+
+impl SettlementQuery {
+    /// Query settlements paid by `payer`
+    pub fn payer(payer: impl Into<String>) -> Self {
+        Self {
+            payer: Some(payer.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Restrict the query to `network`
+    pub fn with_network(mut self, network: impl Into<String>) -> Self {
+        self.network = Some(network.into());
+        self
+    }
+
+    /// Set the maximum number of results
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Effective result limit
+    pub fn effective_limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_QUERY_LIMIT)
+            .min(MAX_QUERY_LIMIT)
+    }
+
+    /// Check whether a settlement passes the filter
+    pub fn matches(&self, settlement: &SettleResponse) -> bool {
+        let payer_matches = self.payer.as_deref().is_none_or(|payer| {
+            settlement
+                .payer
+                .as_deref()
+                .is_some_and(|p| p.eq_ignore_ascii_case(payer))
+        });
+        let network_matches = self
+            .network
+            .as_deref()
+            .is_none_or(|network| settlement.network == network);
+        payer_matches && network_matches
+    }
+}
+
+/// Trait for recording and querying settlements
+#[async_trait]
+pub trait SettlementStore: Send + Sync {
+    /// Record a settlement under its authorization nonce
+    async fn record(&self, nonce: &str, settlement: &SettleResponse) -> Result<()>;
+
+    /// Look up the settlement for a nonce
+    async fn get(&self, nonce: &str) -> Result<Option<SettleResponse>>;
+
+    /// Find settlements matching `query`, newest first
+    async fn query(&self, query: &SettlementQuery) -> Result<Vec<SettleResponse>>;
+}
+
+/// In-memory settlement store with LRU eviction
+///
+/// Holds at most `capacity` settlements; recording or looking up a settlement
+/// marks it as recently used, and the least recently used one is evicted
+/// when the store is full. Data is lost when the server restarts.
+#[derive(Debug, Clone)]
+pub struct InMemorySettlementStore {
+    capacity: usize,
+    state: std::sync::Arc<tokio::sync::Mutex<LruState>>,
+}
+
+/// Settlements plus their recency order
+#[derive(Debug, Default)]
+struct LruState {
+    /// Nonce to (recency stamp, settlement)
+    entries: HashMap<String, (u64, SettleResponse)>,
+    /// Recency stamp to nonce, oldest first
+    order: BTreeMap<u64, String>,
+    next_stamp: u64,
+}
+
+impl LruState {
+    /// Mark `nonce` as most recently used
+    fn touch(&mut self, nonce: &str) {
+        if let Some((stamp, _)) = self.entries.get_mut(nonce) {
+            self.order.remove(stamp);
+            *stamp = self.next_stamp;
+            self.order.insert(self.next_stamp, nonce.to_string());
+            self.next_stamp += 1;
+        }
+    }
+}
+
+impl InMemorySettlementStore {
+    /// Default number of settlements retained
+    pub const DEFAULT_CAPACITY: usize = 10_000;
+
+    /// Create a store retaining at most `capacity` settlements
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Default::default(),
+        }
+    }
+
+    /// Number of settlements currently retained
+    pub async fn len(&self) -> usize {
+        self.state.lock().await.entries.len()
+    }
+
+    /// Check whether the store is empty
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+}
+
+impl Default for InMemorySettlementStore {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[async_trait]
+impl SettlementStore for InMemorySettlementStore {
+    async fn record(&self, nonce: &str, settlement: &SettleResponse) -> Result<()> {
+        let mut state = self.state.lock().await;
+        if state.entries.contains_key(nonce) {
+            state.touch(nonce);
+            if let Some((_, existing)) = state.entries.get_mut(nonce) {
+                *existing = settlement.clone();
+            }
+            return Ok(());
+        }
+
+        while state.entries.len() >= self.capacity {
+            let Some((_, evicted)) = state.order.pop_first() else {
+                break;
+            };
+            state.entries.remove(&evicted);
+        }
+
+        let stamp = state.next_stamp;
+        state.next_stamp += 1;
+        state.order.insert(stamp, nonce.to_string());
+        state
+            .entries
+            .insert(nonce.to_string(), (stamp, settlement.clone()));
+        Ok(())
+    }
+
+    async fn get(&self, nonce: &str) -> Result<Option<SettleResponse>> {
+        let mut state = self.state.lock().await;
+        state.touch(nonce);
+        Ok(state.entries.get(nonce).map(|(_, s)| s.clone()))
+    }
+
+    async fn query(&self, query: &SettlementQuery) -> Result<Vec<SettleResponse>> {
+        let state = self.state.lock().await;
+        Ok(state
+            .order
+            .values()
+            .rev()
+            .filter_map(|nonce| state.entries.get(nonce))
+            .map(|(_, settlement)| settlement)
+            .filter(|settlement| query.matches(settlement))
+            .take(query.effective_limit())
+            .cloned()
+            .collect())
+    }
+}
+
+/// Redis-backed settlement store (requires `redis` feature)
+#[cfg(feature = "redis")]
+pub mod redis_store {
+    use super::{SettlementQuery, SettlementStore};
+    use crate::types::SettleResponse;
+    use crate::{Result, X402Error};
+    use redis::{AsyncCommands, Client};
+
+    /// Redis settlement store
+    ///
+    /// Each settlement is stored as JSON under `{prefix}{nonce}` and indexed in
+    /// sorted sets by payer, by network and overall, scored by record time.
+    /// Entries expire after `ttl_seconds`; stale index members are skipped.
+    #[derive(Debug, Clone)]
+    pub struct RedisSettlementStore {
+        client: Client,
+        key_prefix: String,
+        ttl_seconds: u64,
+    }
+
+    impl RedisSettlementStore {
+        /// Default retention of 30 days
+        pub const DEFAULT_TTL_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+        /// Create a new Redis settlement store
+        ///
+        /// * `key_prefix` - Optional prefix for Redis keys (default: "x402:settlement:")
+        pub async fn new(redis_url: &str, key_prefix: Option<&str>) -> Result<Self> {
+            let client = Client::open(redis_url)
+                .map_err(|e| X402Error::config(format!("Failed to connect to Redis: {}", e)))?;
+
+            Ok(Self {
+                client,
+                key_prefix: key_prefix.unwrap_or("x402:settlement:").to_string(),
+                ttl_seconds: Self::DEFAULT_TTL_SECONDS,
+            })
+        }
+
+        /// Set how long settlements are retained
+        pub fn with_ttl_seconds(mut self, ttl_seconds: u64) -> Self {
+            self.ttl_seconds = ttl_seconds;
+            self
+        }
+
+        fn record_key(&self, nonce: &str) -> String {
+            format!("{}{}", self.key_prefix, nonce)
+        }
+
+        fn payer_index(&self, payer: &str) -> String {
+            format!("{}index:payer:{}", self.key_prefix, payer.to_lowercase())
+        }
+
+        fn network_index(&self, network: &str) -> String {
+            format!("{}index:network:{}", self.key_prefix, network)
+        }
+
+        fn all_index(&self) -> String {
+            format!("{}index:all", self.key_prefix)
+        }
+
+        async fn connection(&self) -> Result<redis::aio::MultiplexedConnection> {
+            self.client
+                .get_multiplexed_async_connection()
+                .await
+                .map_err(|e| X402Error::config(format!("Failed to get Redis connection: {}", e)))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SettlementStore for RedisSettlementStore {
+        async fn record(&self, nonce: &str, settlement: &SettleResponse) -> Result<()> {
+            let mut conn = self.connection().await?;
+            let json = serde_json::to_string(settlement)?;
+            let score = chrono::Utc::now().timestamp_millis();
+
+            let mut indexes = vec![self.all_index(), self.network_index(&settlement.network)];
+            if let Some(payer) = &settlement.payer {
+                indexes.push(self.payer_index(payer));
+            }
+
+            let mut pipe = redis::pipe();
+            pipe.atomic()
+                .set_ex(self.record_key(nonce), json, self.ttl_seconds)
+                .ignore();
+            for index in &indexes {
+                pipe.zadd(index, nonce, score).ignore();
+                pipe.expire(index, self.ttl_seconds as i64).ignore();
+            }
+            pipe.query_async::<()>(&mut conn)
+                .await
+                .map_err(|e| X402Error::config(format!("Redis settlement write failed: {}", e)))
+        }
+
+        async fn get(&self, nonce: &str) -> Result<Option<SettleResponse>> {
+            let mut conn = self.connection().await?;
+            let json: Option<String> = conn
+                .get(self.record_key(nonce))
+                .await
+                .map_err(|e| X402Error::config(format!("Redis GET command failed: {}", e)))?;
+            json.map(|json| serde_json::from_str(&json).map_err(Into::into))
+                .transpose()
+        }
+
+        async fn query(&self, query: &SettlementQuery) -> Result<Vec<SettleResponse>> {
+            let mut conn = self.connection().await?;
+            let index = match (&query.payer, &query.network) {
+                (Some(payer), _) => self.payer_index(payer),
+                (None, Some(network)) => self.network_index(network),
+                (None, None) => self.all_index(),
+            };
+            let limit = query.effective_limit();
+
+            // Page through the index newest first, filtering on the other field
+            let mut results = Vec::new();
+            let mut start = 0isize;
+            while results.len() < limit {
+                let stop = start + limit as isize - 1;
+                let nonces: Vec<String> =
+                    conn.zrevrange(&index, start, stop).await.map_err(|e| {
+                        X402Error::config(format!("Redis ZREVRANGE command failed: {}", e))
+                    })?;
+                if nonces.is_empty() {
+                    break;
+                }
+                start = stop + 1;
+
+                let keys: Vec<String> = nonces.iter().map(|n| self.record_key(n)).collect();
+                let records: Vec<Option<String>> = redis::cmd("MGET")
+                    .arg(&keys)
+                    .query_async(&mut conn)
+                    .await
+                    .map_err(|e| X402Error::config(format!("Redis MGET command failed: {}", e)))?;
+
+                for json in records.into_iter().flatten() {
+                    let settlement: SettleResponse = serde_json::from_str(&json)?;
+                    if query.matches(&settlement) && results.len() < limit {
+                        results.push(settlement);
+                    }
+                }
+            }
+
+            Ok(results)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::env;
+
+        #[tokio::test]
+        async fn test_redis_settlement_store_query_by_payer() {
+            let redis_url =
+                env::var("REDIS_URL").unwrap_or_else(|_| "redis://localhost:6379".to_string());
+            let prefix = format!("test:{}:", uuid::Uuid::new_v4());
+            let store = RedisSettlementStore::new(&redis_url, Some(&prefix))
+                .await
+                .unwrap()
+                .with_ttl_seconds(60);
+
+            let settlement = super::super::tests::settlement("0xPayer", "base", "0x01");
+            if store.record("nonce-1", &settlement).await.is_err() {
+                println!("Skipping Redis test: Redis not available at {}", redis_url);
+                return;
+            }
+
+            assert_eq!(
+                store.get("nonce-1").await.unwrap(),
+                Some(settlement.clone())
+            );
+            let found = store
+                .query(&SettlementQuery::payer("0xpayer"))
+                .await
+                .unwrap();
+            assert_eq!(found, vec![settlement]);
+            assert!(store
+                .query(&SettlementQuery::payer("0xother"))
+                .await
+                .unwrap()
+                .is_empty());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(super) fn settlement(payer: &str, network: &str, transaction: &str) -> SettleResponse {
+        SettleResponse {
+            success: true,
+            error_reason: None,
+            transaction: transaction.to_string(),
+            network: network.to_string(),
+            payer: Some(payer.to_string()),
+            fee_amount: None,
+            net_amount: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_query_by_payer() {
+        let store = InMemorySettlementStore::default();
+        store
+            .record("n1", &settlement("0xAlice", "base", "0x01"))
+            .await
+            .unwrap();
+        store
+            .record("n2", &settlement("0xBob", "base", "0x02"))
+            .await
+            .unwrap();
+        store
+            .record("n3", &settlement("0xalice", "base-sepolia", "0x03"))
+            .await
+            .unwrap();
+
+        // Payer matching ignores case; results are newest first
+        let alice = store
+            .query(&SettlementQuery::payer("0xALICE"))
+            .await
+            .unwrap();
+        let transactions: Vec<_> = alice.iter().map(|s| s.transaction.as_str()).collect();
+        assert_eq!(transactions, ["0x03", "0x01"]);
+
+        let alice_on_base = store
+            .query(&SettlementQuery::payer("0xalice").with_network("base"))
+            .await
+            .unwrap();
+        assert_eq!(alice_on_base.len(), 1);
+        assert_eq!(alice_on_base[0].transaction, "0x01");
+
+        let limited = store
+            .query(&SettlementQuery::default().with_limit(1))
+            .await
+            .unwrap();
+        assert_eq!(limited[0].transaction, "0x03");
+
+        assert_eq!(store.get("n2").await.unwrap().unwrap().transaction, "0x02");
+        assert_eq!(store.get("missing").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_store_evicts_least_recently_used() {
+        let store = InMemorySettlementStore::new(2);
+        store
+            .record("n1", &settlement("0xAlice", "base", "0x01"))
+            .await
+            .unwrap();
+        store
+            .record("n2", &settlement("0xAlice", "base", "0x02"))
+            .await
+            .unwrap();
+
+        // Reading n1 makes n2 the eviction candidate
+        store.get("n1").await.unwrap();
+        store
+            .record("n3", &settlement("0xAlice", "base", "0x03"))
+            .await
+            .unwrap();
+
+        assert_eq!(store.len().await, 2);
+        assert!(store.get("n1").await.unwrap().is_some());
+        assert!(store.get("n2").await.unwrap().is_none());
+        assert!(store.get("n3").await.unwrap().is_some());
+    }
+}
@@ -0,0 +1,235 @@
+//! AWS KMS-backed signer
+//!
+//! Signs EIP-712 digests with an asymmetric `ECC_SECG_P256K1` KMS key. KMS
+//! returns DER-encoded signatures without a recovery id, so the signature is
+//! normalized to low-s compact form and the recovery id is found by trying
+//! both candidates against the key's known public key.
+
+use super::{Signature, Signer};
+use crate::{Result, X402Error};
+use async_trait::async_trait;
+use aws_sdk_kms::{
+    primitives::Blob,
+    types::{MessageType, SigningAlgorithmSpec},
+};
+use ethereum_types::{Address, H256};
+use k256::{
+    ecdsa::{RecoveryId, Signature as K256Signature, VerifyingKey},
+    pkcs8::DecodePublicKey,
+};
+
+/// Signer delegating to an AWS KMS secp256k1 key
+#[derive(Clone)]
+pub struct KmsSigner {
+    client: aws_sdk_kms::Client,
+    key_id: String,
+    public_key: VerifyingKey,
+    address: Address,
+}
+
+impl KmsSigner {
+    /// Create a signer for the given KMS key id or ARN in `region`
+    ///
+    /// Credentials are resolved from the default AWS provider chain. The key's
+    /// public key is fetched once to derive the signer's address.
+    pub async fn new(key_id: impl Into<String>, region: impl Into<String>) -> Result<Self> {
+        let config = aws_config::from_env()
+            .region(aws_config::Region::new(region.into()))
+            .load()
+            .await;
+        Self::with_client(aws_sdk_kms::Client::new(&config), key_id).await
+    }
+
+    /// Create a signer from a preconfigured KMS client
+    pub async fn with_client(
+        client: aws_sdk_kms::Client,
+        key_id: impl Into<String>,
+    ) -> Result<Self> {
+        let key_id = key_id.into();
+        let output = client
+            .get_public_key()
+            .key_id(&key_id)
+            .send()
+            .await
+            .map_err(|e| X402Error::config(format!("KMS GetPublicKey failed: {}", e)))?;
+
+        let der = output
+            .public_key()
+            .ok_or_else(|| X402Error::config("KMS GetPublicKey returned no public key"))?;
+        let public_key = public_key_from_der(der.as_ref())?;
+        let address = address_from_verifying_key(&public_key);
+
+        Ok(Self {
+            client,
+            key_id,
+            public_key,
+            address,
+        })
+    }
+
+    /// Get the KMS key id
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+}
+
+impl std::fmt::Debug for KmsSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KmsSigner")
+            .field("key_id", &self.key_id)
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Signer for KmsSigner {
+    async fn sign_hash(&self, hash: H256) -> Result<Signature> {
+        let output = self
+            .client
+            .sign()
+            .key_id(&self.key_id)
+            .message(Blob::new(hash.as_bytes().to_vec()))
+            .message_type(MessageType::Digest)
+            .signing_algorithm(SigningAlgorithmSpec::EcdsaSha256)
+            .send()
+            .await
+            .map_err(|e| X402Error::invalid_signature(format!("KMS Sign failed: {}", e)))?;
+
+        let der = output
+            .signature()
+            .ok_or_else(|| X402Error::invalid_signature("KMS Sign returned no signature"))?;
+
+        signature_from_der(der.as_ref(), hash, &self.public_key)
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+}
+
+/// Parse a DER `SubjectPublicKeyInfo` secp256k1 public key
+fn public_key_from_der(der: &[u8]) -> Result<VerifyingKey> {
+    VerifyingKey::from_public_key_der(der)
+        .map_err(|_| X402Error::config("KMS key is not a secp256k1 public key"))
+}
+
+/// Derive the Ethereum address of a public key
+fn address_from_verifying_key(public_key: &VerifyingKey) -> Address {
+    use sha3::{Digest, Keccak256};
+    let encoded = public_key.to_encoded_point(false);
+    let hash = Keccak256::digest(&encoded.as_bytes()[1..]);
+    Address::from_slice(&hash[12..])
+}
+
+/// Convert a DER ECDSA signature into a recoverable compact signature
+///
+/// High-s values are normalized (Ethereum rejects them) before the recovery id
+/// is resolved against `public_key`.
+fn signature_from_der(der: &[u8], hash: H256, public_key: &VerifyingKey) -> Result<Signature> {
+    let signature = K256Signature::from_der(der)
+        .map_err(|_| X402Error::invalid_signature("Invalid DER signature from KMS"))?;
+    let signature = signature.normalize_s().unwrap_or(signature);
+
+    for v in 0..2u8 {
+        let recovery_id = RecoveryId::try_from(v)
+            .map_err(|_| X402Error::invalid_signature("Invalid recovery ID"))?;
+        if let Ok(recovered) =
+            VerifyingKey::recover_from_prehash(hash.as_bytes(), &signature, recovery_id)
+        {
+            if &recovered == public_key {
+                let bytes = signature.to_bytes();
+                return Ok(Signature::new(
+                    H256::from_slice(&bytes[0..32]),
+                    H256::from_slice(&bytes[32..64]),
+                    v,
+                ));
+            }
+        }
+    }
+
+    Err(X402Error::invalid_signature(
+        "Could not determine recovery ID",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::signature::verify_eip712_signature;
+    use k256::ecdsa::{signature::hazmat::PrehashSigner, SigningKey};
+
+    fn test_key() -> SigningKey {
+        SigningKey::from_slice(
+            &hex::decode("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")
+                .unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_public_key_from_der() {
+        let key = test_key();
+        // SubjectPublicKeyInfo header for an uncompressed secp256k1 point, as returned by KMS
+        let mut der = hex::decode("3056301006072a8648ce3d020106052b8104000a034200").unwrap();
+        der.extend_from_slice(key.verifying_key().to_encoded_point(false).as_bytes());
+
+        let public_key = public_key_from_der(&der).unwrap();
+        assert_eq!(
+            format!("{:?}", address_from_verifying_key(&public_key)),
+            "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+    }
+
+    #[test]
+    fn test_signature_from_der_resolves_recovery_id() {
+        let key = test_key();
+        let address = address_from_verifying_key(key.verifying_key());
+
+        // Exercise both recovery ids across a handful of digests
+        let mut seen = [false; 2];
+        for i in 0..16u8 {
+            let hash = H256::repeat_byte(i);
+            let (signature, expected_id): (K256Signature, RecoveryId) =
+                key.sign_prehash_recoverable(hash.as_bytes()).unwrap();
+
+            // What KMS returns: a DER signature with no recovery id
+            let der = signature.to_der();
+            let resolved = signature_from_der(der.as_bytes(), hash, key.verifying_key()).unwrap();
+
+            assert_eq!(resolved.v, expected_id.to_byte());
+            assert!(verify_eip712_signature(&resolved.to_hex(), hash, address).unwrap());
+            seen[resolved.v as usize] = true;
+        }
+        assert!(seen[0] && seen[1]);
+    }
+
+    #[test]
+    fn test_signature_from_der_normalizes_high_s() {
+        let key = test_key();
+        let address = address_from_verifying_key(key.verifying_key());
+        let hash = H256::repeat_byte(0x42);
+
+        let signature: K256Signature = key.sign_prehash(hash.as_bytes()).unwrap();
+        // Flip s to its high counterpart, as KMS may return either form
+        let (r, s) = signature.split_scalars();
+        let high = K256Signature::from_scalars(r, -*s).unwrap();
+        assert!(high.normalize_s().is_some());
+
+        let resolved =
+            signature_from_der(high.to_der().as_bytes(), hash, key.verifying_key()).unwrap();
+        assert_eq!(resolved.s.as_bytes(), &signature.s().to_bytes()[..]);
+        assert!(verify_eip712_signature(&resolved.to_hex(), hash, address).unwrap());
+    }
+
+    #[test]
+    fn test_signature_from_der_rejects_foreign_key() {
+        let key = test_key();
+        let other = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let hash = H256::repeat_byte(0x01);
+
+        let signature: K256Signature = other.sign_prehash(hash.as_bytes()).unwrap();
+        let result = signature_from_der(signature.to_der().as_bytes(), hash, key.verifying_key());
+        assert!(matches!(result, Err(X402Error::InvalidSignature { .. })));
+    }
+}
@@ -0,0 +1,155 @@
+//! Signer abstraction for x402 payment authorizations
+//!
+//! A [`Signer`] produces recoverable secp256k1 signatures over EIP-712 digests
+//! without exposing how the key is held. [`LocalSigner`] keeps the private key
+//! in process memory; other implementations can delegate to hardware wallets,
+//! KMS services or remote HSMs.
+
+use crate::{
+    crypto::signature::{address_from_private_key, sign_message_hash},
+    Result, X402Error,
+};
+use async_trait::async_trait;
+use ethereum_types::{Address, H256};
+
+#[cfg(feature = "kms")]
+pub mod kms;
+
+#[cfg(feature = "kms")]
+pub use kms::KmsSigner;
+
+/// Recoverable secp256k1 signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature {
+    /// R component
+    pub r: H256,
+    /// S component
+    pub s: H256,
+    /// Recovery id (0 or 1)
+    pub v: u8,
+}
+
+impl Signature {
+    /// Create a signature from its components
+    pub fn new(r: H256, s: H256, v: u8) -> Self {
+        Self { r, s, v }
+    }
+
+    /// Parse a 65-byte `r || s || v` signature
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 65 {
+            return Err(X402Error::invalid_signature("Signature must be 65 bytes"));
+        }
+
+        Ok(Self {
+            r: H256::from_slice(&bytes[0..32]),
+            s: H256::from_slice(&bytes[32..64]),
+            v: bytes[64],
+        })
+    }
+
+    /// Parse a hex-encoded 65-byte signature
+    pub fn from_hex(signature: &str) -> Result<Self> {
+        let bytes = hex::decode(signature.trim_start_matches("0x"))
+            .map_err(|_| X402Error::invalid_signature("Invalid hex signature"))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Serialize as `r || s || v`
+    pub fn to_bytes(&self) -> [u8; 65] {
+        let mut bytes = [0u8; 65];
+        bytes[0..32].copy_from_slice(self.r.as_bytes());
+        bytes[32..64].copy_from_slice(self.s.as_bytes());
+        bytes[64] = self.v;
+        bytes
+    }
+
+    /// Serialize as a `0x`-prefixed hex string
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.to_bytes()))
+    }
+}
+
+/// Signs EIP-712 digests on behalf of a single Ethereum account
+#[async_trait]
+pub trait Signer: Send + Sync {
+    /// Sign a 32-byte message hash
+    async fn sign_hash(&self, hash: H256) -> Result<Signature>;
+
+    /// Address of the signing account
+    fn address(&self) -> Address;
+}
+
+/// Signer holding a raw secp256k1 private key in memory
+#[derive(Clone)]
+pub struct LocalSigner {
+    private_key: String,
+    address: Address,
+}
+
+impl LocalSigner {
+    /// Create a signer from a hex-encoded private key
+    pub fn new(private_key: impl Into<String>) -> Result<Self> {
+        let private_key = private_key.into();
+        let address = address_from_private_key(&private_key)?;
+        Ok(Self {
+            private_key,
+            address,
+        })
+    }
+
+    /// Sign a 32-byte message hash without going through the async [`Signer`] trait
+    pub fn sign_hash_now(&self, hash: H256) -> Result<Signature> {
+        Signature::from_hex(&sign_message_hash(hash, &self.private_key)?)
+    }
+}
+
+impl std::fmt::Debug for LocalSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LocalSigner")
+            .field("private_key", &"<redacted>")
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl Signer for LocalSigner {
+    async fn sign_hash(&self, hash: H256) -> Result<Signature> {
+        self.sign_hash_now(hash)
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::signature::verify_eip712_signature;
+
+    #[test]
+    fn test_signature_hex_round_trip() {
+        let signature = Signature::new(H256::repeat_byte(0x11), H256::repeat_byte(0x22), 1);
+        let parsed = Signature::from_hex(&signature.to_hex()).unwrap();
+        assert_eq!(parsed, signature);
+
+        assert!(Signature::from_bytes(&[0u8; 64]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_local_signer_produces_recoverable_signature() {
+        let signer =
+            LocalSigner::new("0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")
+                .unwrap();
+        assert_eq!(
+            format!("{:?}", signer.address()),
+            "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+
+        let hash = H256::repeat_byte(0x42);
+        let signature = signer.sign_hash(hash).await.unwrap();
+        assert!(verify_eip712_signature(&signature.to_hex(), hash, signer.address()).unwrap());
+    }
+}
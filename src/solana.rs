@@ -0,0 +1,508 @@
+//! Solana payment scheme for x402
+//!
+//! Payments on Solana are authorized with an ed25519 signature over a
+//! canonical SPL transfer authorization message. Keys, signatures and
+//! addresses use base58 encoding as is customary on Solana.
+//!
+//! Settlement is currently mocked; verification checks the signature, timing,
+//! mint, recipient and amount against the payment requirements.
+
+use crate::types::{
+    networks, Amount, PaymentRequirements, SettleResponse, VerifyResponse, X402_VERSION,
+};
+use crate::{Result, X402Error};
+use chrono::Utc;
+use ed25519_dalek::{Signature, Signer as _, SigningKey, Verifier as _, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+/// SPL token transfer authorization parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SplTransferAuthorization {
+    /// Payer's wallet address (base58 public key)
+    pub from: String,
+    /// Recipient's wallet address (base58 public key)
+    pub to: String,
+    /// SPL token mint address
+    pub mint: String,
+    /// Payment amount in atomic token units
+    pub amount: String,
+    /// Unix timestamp when authorization becomes valid
+    #[serde(rename = "validAfter")]
+    pub valid_after: String,
+    /// Unix timestamp when authorization expires
+    #[serde(rename = "validBefore")]
+    pub valid_before: String,
+    /// Random nonce to prevent replay attacks
+    pub nonce: String,
+}
+
+impl SplTransferAuthorization {
+    /// Create a new authorization
+    pub fn new(
+        from: impl Into<String>,
+        to: impl Into<String>,
+        mint: impl Into<String>,
+        amount: impl Into<String>,
+        valid_after: impl Into<String>,
+        valid_before: impl Into<String>,
+        nonce: impl Into<String>,
+    ) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            mint: mint.into(),
+            amount: amount.into(),
+            valid_after: valid_after.into(),
+            valid_before: valid_before.into(),
+            nonce: nonce.into(),
+        }
+    }
+
+    /// Canonical message signed by the payer
+    ///
+    /// The network name is included so an authorization cannot be replayed
+    /// across clusters.
+    pub fn message(&self, network: &str) -> Vec<u8> {
+        format!(
+            "x402 SPL transfer authorization\n\
+             network: {}\n\
+             mint: {}\n\
+             from: {}\n\
+             to: {}\n\
+             amount: {}\n\
+             validAfter: {}\n\
+             validBefore: {}\n\
+             nonce: {}",
+            network,
+            self.mint,
+            self.from,
+            self.to,
+            self.amount,
+            self.valid_after,
+            self.valid_before,
+            self.nonce
+        )
+        .into_bytes()
+    }
+
+    /// Check if the authorization is currently valid
+    pub fn is_valid_now(&self) -> Result<bool> {
+        let now = Utc::now().timestamp();
+        let valid_after: i64 = self
+            .valid_after
+            .parse()
+            .map_err(|_| X402Error::invalid_authorization("Invalid valid_after timestamp"))?;
+        let valid_before: i64 = self
+            .valid_before
+            .parse()
+            .map_err(|_| X402Error::invalid_authorization("Invalid valid_before timestamp"))?;
+
+        Ok(now >= valid_after && now <= valid_before)
+    }
+}
+
+/// Solana payment payload (ed25519-signed SPL transfer authorization)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolanaPayload {
+    /// Base58-encoded ed25519 signature over the authorization message
+    pub signature: String,
+    /// SPL transfer authorization parameters
+    pub authorization: SplTransferAuthorization,
+}
+
+/// Payment payload carrying a Solana authorization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolanaPaymentPayload {
+    /// Protocol version identifier
+    #[serde(rename = "x402Version")]
+    pub x402_version: u32,
+    /// Payment scheme identifier
+    pub scheme: String,
+    /// Solana network identifier (`solana` or `solana-devnet`)
+    pub network: String,
+    /// Payment data object
+    pub payload: SolanaPayload,
+}
+
+impl SolanaPaymentPayload {
+    /// Create a new Solana payment payload
+    pub fn new(
+        scheme: impl Into<String>,
+        network: impl Into<String>,
+        payload: SolanaPayload,
+    ) -> Self {
+        Self {
+            x402_version: X402_VERSION,
+            scheme: scheme.into(),
+            network: network.into(),
+            payload,
+        }
+    }
+
+    /// Decode a base64-encoded payment payload
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        use base64::{engine::general_purpose, Engine as _};
+        let decoded = general_purpose::STANDARD.decode(encoded)?;
+        Ok(serde_json::from_slice(&decoded)?)
+    }
+
+    /// Encode the payment payload to base64
+    pub fn to_base64(&self) -> Result<String> {
+        use base64::{engine::general_purpose, Engine as _};
+        let json = serde_json::to_string(self)?;
+        Ok(general_purpose::STANDARD.encode(json))
+    }
+}
+
+/// Ed25519 keypair for signing Solana payment authorizations
+pub struct SolanaKeypair {
+    signing_key: SigningKey,
+}
+
+impl SolanaKeypair {
+    /// Create a keypair from a 32-byte ed25519 secret key
+    pub fn from_secret_key(secret: &[u8; 32]) -> Self {
+        Self {
+            signing_key: SigningKey::from_bytes(secret),
+        }
+    }
+
+    /// Create a keypair from a base58 string
+    ///
+    /// Accepts either a 32-byte secret key or the 64-byte `secret || public`
+    /// format used by Solana CLI keypair files.
+    pub fn from_base58(encoded: &str) -> Result<Self> {
+        let bytes = bs58::decode(encoded)
+            .into_vec()
+            .map_err(|_| X402Error::invalid_authorization("Invalid base58 keypair"))?;
+
+        match bytes.len() {
+            32 | 64 => {
+                let mut secret = [0u8; 32];
+                secret.copy_from_slice(&bytes[..32]);
+                let keypair = Self::from_secret_key(&secret);
+                if bytes.len() == 64 && bytes[32..] != keypair.public_key_bytes() {
+                    return Err(X402Error::invalid_authorization(
+                        "Keypair public key does not match secret key",
+                    ));
+                }
+                Ok(keypair)
+            }
+            _ => Err(X402Error::invalid_authorization(
+                "Keypair must be 32 or 64 bytes",
+            )),
+        }
+    }
+
+    /// Get the raw public key bytes
+    pub fn public_key_bytes(&self) -> [u8; 32] {
+        self.signing_key.verifying_key().to_bytes()
+    }
+
+    /// Get the base58-encoded public key (the wallet address)
+    pub fn pubkey(&self) -> String {
+        bs58::encode(self.public_key_bytes()).into_string()
+    }
+
+    /// Sign a message, returning the base58-encoded signature
+    pub fn sign(&self, message: &[u8]) -> String {
+        bs58::encode(self.signing_key.sign(message).to_bytes()).into_string()
+    }
+
+    /// Build and sign a Solana payment payload for the given requirements
+    pub fn authorize_payment(
+        &self,
+        requirements: &PaymentRequirements,
+    ) -> Result<SolanaPaymentPayload> {
+        if !is_solana_network(&requirements.network) {
+            return Err(X402Error::NetworkNotSupported {
+                network: requirements.network.clone(),
+            });
+        }
+
+        let nonce = crate::crypto::signature::generate_nonce();
+        let now = Utc::now().timestamp();
+        let authorization = SplTransferAuthorization::new(
+            self.pubkey(),
+            &requirements.pay_to,
+            &requirements.asset,
+            &requirements.max_amount_required,
+            (now - 60).to_string(),
+            (now + i64::from(requirements.max_timeout_seconds)).to_string(),
+            format!("{:?}", nonce),
+        );
+
+        let signature = self.sign(&authorization.message(&requirements.network));
+
+        Ok(SolanaPaymentPayload::new(
+            &requirements.scheme,
+            &requirements.network,
+            SolanaPayload {
+                signature,
+                authorization,
+            },
+        ))
+    }
+}
+
+impl std::fmt::Debug for SolanaKeypair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SolanaKeypair")
+            .field("pubkey", &self.pubkey())
+            .field("secret_key", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Check whether a network name refers to a Solana cluster
+pub fn is_solana_network(network: &str) -> bool {
+    networks::solana().contains(&network)
+}
+
+/// Verify the ed25519 signature of a Solana payload
+pub fn verify_solana_payload(payload: &SolanaPayload, network: &str) -> Result<bool> {
+    let public_key = decode_fixed::<32>(&payload.authorization.from)
+        .map_err(|_| X402Error::invalid_signature("Invalid from address"))?;
+    let verifying_key = VerifyingKey::from_bytes(&public_key)
+        .map_err(|_| X402Error::invalid_signature("Invalid from address"))?;
+
+    let signature = decode_fixed::<64>(&payload.signature)
+        .map_err(|_| X402Error::invalid_signature("Invalid signature encoding"))?;
+    let signature = Signature::from_bytes(&signature);
+
+    Ok(verifying_key
+        .verify(&payload.authorization.message(network), &signature)
+        .is_ok())
+}
+
+/// Verify a Solana payment against the payment requirements
+pub fn verify_solana_payment(
+    payment: &SolanaPaymentPayload,
+    requirements: &PaymentRequirements,
+) -> Result<VerifyResponse> {
+    let authorization = &payment.payload.authorization;
+    let invalid = |reason: &str| VerifyResponse {
+        is_valid: false,
+        invalid_reason: Some(reason.to_string()),
+        payer: Some(authorization.from.clone()),
+    };
+
+    if !is_solana_network(&payment.network) || payment.network != requirements.network {
+        return Ok(invalid("network_mismatch"));
+    }
+
+    if payment.scheme != requirements.scheme {
+        return Ok(invalid("scheme_mismatch"));
+    }
+
+    if !verify_solana_payload(&payment.payload, &payment.network)? {
+        return Ok(invalid("invalid_signature"));
+    }
+
+    if !authorization.is_valid_now()? {
+        return Ok(invalid("authorization_expired"));
+    }
+
+    if authorization.mint != requirements.asset {
+        return Ok(invalid("wrong_asset"));
+    }
+
+    if authorization.to != requirements.pay_to {
+        return Ok(invalid("recipient_mismatch"));
+    }
+
+    let decimals = requirements.asset_decimals();
+    let payment_amount = Amount::from_atomic_str(&authorization.amount, decimals)?;
+    let required_amount = requirements.required_amount(decimals)?;
+
+    if payment_amount < required_amount {
+        return Ok(invalid("insufficient_amount"));
+    }
+
+    Ok(VerifyResponse {
+        is_valid: true,
+        invalid_reason: None,
+        payer: Some(authorization.from.clone()),
+    })
+}
+
+/// Settle a verified Solana payment
+///
+/// Note: This is a mock implementation that returns a simulated transaction
+/// signature; no SPL transfer is submitted.
+pub fn settle_solana_payment(payment: &SolanaPaymentPayload) -> Result<SettleResponse> {
+    use rand::RngCore;
+    let mut bytes = [0u8; 64];
+    rand::thread_rng().fill_bytes(&mut bytes);
+
+    Ok(SettleResponse {
+        success: true,
+        error_reason: None,
+        transaction: bs58::encode(bytes).into_string(),
+        network: payment.network.clone(),
+        payer: Some(payment.payload.authorization.from.clone()),
+        fee_amount: None,
+        net_amount: None,
+    })
+}
+
+/// Decode a base58 string into a fixed-size byte array
+fn decode_fixed<const N: usize>(encoded: &str) -> Result<[u8; N]> {
+    let bytes = bs58::decode(encoded)
+        .into_vec()
+        .map_err(|_| X402Error::invalid_signature("Invalid base58 encoding"))?;
+    bytes
+        .try_into()
+        .map_err(|_| X402Error::invalid_signature("Unexpected base58 length"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 8032 section 7.1, test 1
+    const SECRET_KEY: &str = "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60";
+    const PUBLIC_KEY: &str = "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a";
+    const EMPTY_MESSAGE_SIGNATURE: &str = "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b";
+
+    fn test_keypair() -> SolanaKeypair {
+        let secret: [u8; 32] = hex::decode(SECRET_KEY).unwrap().try_into().unwrap();
+        SolanaKeypair::from_secret_key(&secret)
+    }
+
+    fn test_requirements() -> PaymentRequirements {
+        PaymentRequirements::new(
+            "exact",
+            networks::SOLANA_DEVNET,
+            "1000000",
+            networks::get_usdc_address(networks::SOLANA_DEVNET).unwrap(),
+            "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+            "https://example.com/test",
+            "Test payment",
+        )
+    }
+
+    #[test]
+    fn test_keypair_matches_known_vector() {
+        let keypair = test_keypair();
+        assert_eq!(hex::encode(keypair.public_key_bytes()), PUBLIC_KEY);
+
+        let signature = bs58::decode(keypair.sign(b"")).into_vec().unwrap();
+        assert_eq!(hex::encode(signature), EMPTY_MESSAGE_SIGNATURE);
+
+        // The 64-byte Solana CLI format round-trips to the same key
+        let mut full = hex::decode(SECRET_KEY).unwrap();
+        full.extend_from_slice(&hex::decode(PUBLIC_KEY).unwrap());
+        let from_cli = SolanaKeypair::from_base58(&bs58::encode(full).into_string()).unwrap();
+        assert_eq!(from_cli.pubkey(), keypair.pubkey());
+    }
+
+    #[test]
+    fn test_signed_payload_verifies() {
+        let keypair = test_keypair();
+        let requirements = test_requirements();
+        let payment = keypair.authorize_payment(&requirements).unwrap();
+
+        assert_eq!(payment.network, "solana-devnet");
+        assert_eq!(payment.payload.authorization.from, keypair.pubkey());
+        assert!(verify_solana_payload(&payment.payload, &payment.network).unwrap());
+
+        let response = verify_solana_payment(&payment, &requirements).unwrap();
+        assert!(response.is_valid);
+        assert_eq!(response.payer, Some(keypair.pubkey()));
+
+        // Round-trips through the wire encoding
+        let decoded = SolanaPaymentPayload::from_base64(&payment.to_base64().unwrap()).unwrap();
+        assert!(verify_solana_payload(&decoded.payload, &decoded.network).unwrap());
+    }
+
+    #[test]
+    fn test_tampered_payload_fails_verification() {
+        let keypair = test_keypair();
+        let requirements = test_requirements();
+        let payment = keypair.authorize_payment(&requirements).unwrap();
+
+        let mut tampered = payment.clone();
+        tampered.payload.authorization.amount = "1".to_string();
+        assert!(!verify_solana_payload(&tampered.payload, &tampered.network).unwrap());
+
+        // Signatures are bound to the cluster they were made for
+        assert!(!verify_solana_payload(&payment.payload, networks::SOLANA_MAINNET).unwrap());
+
+        let mut mainnet_requirements = requirements.clone();
+        mainnet_requirements.network = networks::SOLANA_MAINNET.to_string();
+        let mut replayed = payment.clone();
+        replayed.network = networks::SOLANA_MAINNET.to_string();
+        let response = verify_solana_payment(&replayed, &mainnet_requirements).unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("invalid_signature")
+        );
+    }
+
+    #[test]
+    fn test_verify_checks_requirements() {
+        let keypair = test_keypair();
+        let requirements = test_requirements();
+        let payment = keypair.authorize_payment(&requirements).unwrap();
+
+        let mut other = requirements.clone();
+        other.pay_to = keypair.pubkey();
+        let response = verify_solana_payment(&payment, &other).unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("recipient_mismatch")
+        );
+
+        let mut other = requirements.clone();
+        other.max_amount_required = "2000000".to_string();
+        let response = verify_solana_payment(&payment, &other).unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("insufficient_amount")
+        );
+
+        let mut other = requirements.clone();
+        other.asset = networks::get_usdc_address(networks::SOLANA_MAINNET)
+            .unwrap()
+            .to_string();
+        let response = verify_solana_payment(&payment, &other).unwrap();
+        assert_eq!(response.invalid_reason.as_deref(), Some("wrong_asset"));
+    }
+
+    #[test]
+    fn test_authorize_rejects_evm_network() {
+        let mut requirements = test_requirements();
+        requirements.network = networks::BASE_SEPOLIA.to_string();
+        let result = test_keypair().authorize_payment(&requirements);
+        assert!(matches!(result, Err(X402Error::NetworkNotSupported { .. })));
+    }
+
+    #[test]
+    fn test_solana_networks_listed_separately() {
+        assert!(networks::solana()
+            .iter()
+            .all(|network| is_solana_network(network)));
+        assert!(!networks::all_supported()
+            .iter()
+            .any(|network| is_solana_network(network)));
+        assert!(!networks::is_supported(networks::SOLANA_MAINNET));
+    }
+
+    #[test]
+    fn test_mock_settlement() {
+        let keypair = test_keypair();
+        let payment = keypair.authorize_payment(&test_requirements()).unwrap();
+        let settlement = settle_solana_payment(&payment).unwrap();
+
+        assert!(settlement.success);
+        assert_eq!(settlement.network, "solana-devnet");
+        assert_eq!(
+            bs58::decode(&settlement.transaction)
+                .into_vec()
+                .unwrap()
+                .len(),
+            64
+        );
+    }
+}
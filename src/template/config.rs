@@ -12,6 +12,7 @@ pub struct PaywallConfigBuilder {
     app_logo: Option<String>,
     cdp_client_key: Option<String>,
     session_token_endpoint: Option<String>,
+    wallet_connect: bool,
 }
 
 impl PaywallConfigBuilder {
@@ -22,6 +23,7 @@ impl PaywallConfigBuilder {
             app_logo: None,
             cdp_client_key: None,
             session_token_endpoint: None,
+            wallet_connect: false,
         }
     }
 
@@ -49,6 +51,12 @@ impl PaywallConfigBuilder {
         self
     }
 
+    /// Enable or disable the wallet-connect payment button
+    pub fn wallet_connect(mut self, enabled: bool) -> Self {
+        self.wallet_connect = enabled;
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> PaywallConfig {
         PaywallConfig {
@@ -60,6 +68,7 @@ impl PaywallConfigBuilder {
             custom_js: None,
             theme: None,
             branding: None,
+            wallet_connect: self.wallet_connect,
         }
     }
 }
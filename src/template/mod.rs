@@ -6,15 +6,18 @@
 pub mod config;
 pub mod paywall;
 
-use crate::types::PaymentRequirements;
+use crate::crypto::eip712;
+use crate::types::{Network, PaymentRequirements};
+use ethereum_types::Address;
 use serde_json;
+use std::str::FromStr;
 
 /// Template configuration for paywall customization
 #[derive(Debug, Clone, Default)]
 pub struct PaywallConfig {
     /// App name displayed in the paywall
     pub app_name: Option<String>,
-    /// App logo URL
+    /// App logo: an `http(s)://` image URL, or text such as an emoji
     pub app_logo: Option<String>,
     /// CDP client key for enhanced RPC
     pub cdp_client_key: Option<String>,
@@ -28,6 +31,8 @@ pub struct PaywallConfig {
     pub theme: Option<ThemeConfig>,
     /// Branding configuration
     pub branding: Option<BrandingConfig>,
+    /// Show a "Connect Wallet & Pay" button that pays from a browser wallet
+    pub wallet_connect: bool,
 }
 
 /// Theme configuration for the paywall
@@ -87,6 +92,9 @@ impl PaywallConfig {
     }
 
     /// Set the app logo
+    ///
+    /// `http://` and `https://` logos are rendered as an image, anything else
+    /// is rendered as (escaped) text.
     pub fn with_app_logo(mut self, app_logo: impl Into<String>) -> Self {
         self.app_logo = Some(app_logo.into());
         self
@@ -127,6 +135,16 @@ impl PaywallConfig {
         self.branding = Some(branding);
         self
     }
+
+    /// Enable or disable the wallet-connect payment button
+    ///
+    /// The button signs an EIP-3009 authorization for the first payment
+    /// requirement with `window.ethereum` (e.g. MetaMask) and retries the
+    /// request with the `X-PAYMENT` header.
+    pub fn with_wallet_connect(mut self, enabled: bool) -> Self {
+        self.wallet_connect = enabled;
+        self
+    }
 }
 
 impl ThemeConfig {
@@ -229,6 +247,7 @@ fn inject_payment_data(
 ) -> String {
     let x402_config = create_x402_config(error, payment_requirements, paywall_config);
     let config_json = serde_json::to_string(&x402_config).unwrap_or_else(|_| "{}".to_string());
+    let config_json = escape_script_json(&config_json);
 
     // Create the configuration script
     let config_script = format!(
@@ -242,6 +261,14 @@ fn inject_payment_data(
     // Apply theme customizations if provided
     let mut html = html_content.to_string();
     if let Some(config) = paywall_config {
+        if let Some(logo) = &config.app_logo {
+            let alt = config.app_name.as_deref().unwrap_or("Logo");
+            html = html.replace(
+                DEFAULT_LOGO_HTML,
+                &format!(r#"<div class="logo">{}</div>"#, render_logo(logo, alt)),
+            );
+        }
+
         if let Some(theme) = &config.theme {
             html = apply_theme_customizations(&html, theme);
         }
@@ -254,6 +281,10 @@ fn inject_payment_data(
             html = inject_custom_css(&html, custom_css);
         }
 
+        if config.wallet_connect {
+            html = inject_wallet_connect(&html);
+        }
+
         if let Some(custom_js) = &config.custom_js {
             html = inject_custom_js(&html, custom_js);
         }
@@ -263,22 +294,78 @@ fn inject_payment_data(
     html.replace("</head>", &format!("{}\n</head>", config_script))
 }
 
+/// Logo element of the base template
+const DEFAULT_LOGO_HTML: &str = r#"<div class="logo">💰</div>"#;
+
+/// Render a logo as an image for `http(s)://` URLs, or as text otherwise
+fn render_logo(logo: &str, alt: &str) -> String {
+    let lowercase = logo.to_ascii_lowercase();
+    if lowercase.starts_with("http://") || lowercase.starts_with("https://") {
+        format!(
+            r#"<img src="{}" alt="{}" style="width: 80px; height: 80px; object-fit: contain;">"#,
+            escape_html(logo),
+            escape_html(alt)
+        )
+    } else {
+        escape_html(logo)
+    }
+}
+
+/// Escape text for use in HTML content or a quoted attribute
+fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escape a value interpolated into a CSS declaration
+///
+/// Anything beyond the characters of colors and lengths is written as a CSS
+/// escape, so a value cannot end its declaration or the `<style>` element.
+fn escape_css(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_ascii_alphanumeric() || " #%.,()-".contains(c) {
+            escaped.push(c);
+        } else {
+            escaped.push_str(&format!("\\{:x} ", u32::from(c)));
+        }
+    }
+    escaped
+}
+
+/// Escape JSON embedded in a `<script>` element, so no string can close it
+fn escape_script_json(json: &str) -> String {
+    json.replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026")
+}
+
 /// Apply theme customizations to HTML
 fn apply_theme_customizations(html: &str, theme: &ThemeConfig) -> String {
     let css_vars = format!(
-        r#"
+        r#"<style>
     :root {{
       --primary-color: {};
       --secondary-color: {};
       --background-color: {};
       --text-color: {};
       --border-radius: {};
-    }}"#,
-        theme.primary_color,
-        theme.secondary_color,
-        theme.background_color,
-        theme.text_color,
-        theme.border_radius
+    }}</style>"#,
+        escape_css(&theme.primary_color),
+        escape_css(&theme.secondary_color),
+        escape_css(&theme.background_color),
+        escape_css(&theme.text_color),
+        escape_css(&theme.border_radius)
     );
 
     html.replace("</head>", &format!("{}\n</head>", css_vars))
@@ -291,18 +378,17 @@ fn apply_branding_customizations(html: &str, branding: &BrandingConfig) -> Strin
     // Replace app name in title
     html = html.replace(
         "Payment Required",
-        &format!("{} - Payment Required", branding.company_name),
+        &format!("{} - Payment Required", escape_html(&branding.company_name)),
     );
 
     // Replace logo if provided
-    if let Some(logo_url) = &branding.company_logo {
-        let logo_html = format!(
-            r#"<img src="{}" alt="{}" style="width: 80px; height: 80px; object-fit: contain;">"#,
-            logo_url, branding.company_name
-        );
+    if let Some(logo) = &branding.company_logo {
         html = html.replace(
-            r#"<div class="logo">💰</div>"#,
-            &format!(r#"<div class="logo">{}</div>"#, logo_html),
+            DEFAULT_LOGO_HTML,
+            &format!(
+                r#"<div class="logo">{}</div>"#,
+                render_logo(logo, &branding.company_name)
+            ),
         );
     }
 
@@ -315,6 +401,53 @@ fn inject_custom_css(html: &str, css: &str) -> String {
     html.replace("</head>", &format!("{}\n</head>", css_tag))
 }
 
+/// Add the wallet-connect button and its script to HTML
+fn inject_wallet_connect(html: &str) -> String {
+    let button = r#"<button id="x402-connect" class="pay-button" type="button">Connect Wallet &amp; Pay</button>"#;
+    html.replace(
+        r#"<div id="error-message""#,
+        &format!("{}\n\n    <div id=\"error-message\"", button),
+    )
+    .replace(
+        "</body>",
+        &format!(
+            "<script>{}</script>\n</body>",
+            paywall::get_wallet_connect_script()
+        ),
+    )
+}
+
+/// EIP-712 typed data a browser wallet signs to pay `requirements`
+///
+/// The payer-specific message fields (`from`, `validAfter`, `validBefore`,
+/// `nonce`) are filled in by the wallet-connect script. `None` when the
+/// network's chain id is unknown.
+fn wallet_typed_data(requirements: &PaymentRequirements) -> Option<serde_json::Value> {
+    let chain_id = Network::from_name(&requirements.network)?.chain_id();
+    let (primary_type, domain) = if requirements.is_native() {
+        (
+            "NativeTransferAuthorization",
+            eip712::native_payment_domain(chain_id),
+        )
+    } else {
+        let asset = Address::from_str(&requirements.asset).ok()?;
+        (
+            "TransferWithAuthorization",
+            eip712::token_domain(chain_id, asset, requirements.extra.as_ref()),
+        )
+    };
+
+    Some(serde_json::json!({
+        "types": eip712::authorization_types(primary_type, &domain),
+        "primaryType": primary_type,
+        "domain": domain.to_json(),
+        "message": {
+            "to": requirements.pay_to,
+            "value": requirements.max_amount_required,
+        },
+    }))
+}
+
 /// Inject custom JavaScript into HTML
 fn inject_custom_js(html: &str, js: &str) -> String {
     let js_tag = format!(r#"<script>{}</script>"#, js);
@@ -329,6 +462,7 @@ fn create_x402_config(
 ) -> serde_json::Value {
     let requirements = payment_requirements.first();
     let mut display_amount = 0.0;
+    let mut formatted_amount = String::new();
     let mut current_url = String::new();
     let mut testnet = true;
 
@@ -337,6 +471,7 @@ fn create_x402_config(
         if let Ok(amount) = req.max_amount_required.parse::<f64>() {
             display_amount = amount / 1_000_000.0; // USDC has 6 decimals
         }
+        formatted_amount = req.display_amount(6, "USDC");
         current_url = req.resource.clone();
         testnet = req.network == "base-sepolia";
     }
@@ -346,6 +481,7 @@ fn create_x402_config(
 
     let mut config_json = serde_json::json!({
         "amount": display_amount,
+        "displayAmount": formatted_amount,
         "paymentRequirements": payment_requirements,
         "testnet": testnet,
         "currentUrl": current_url,
@@ -357,6 +493,12 @@ fn create_x402_config(
         "sessionTokenEndpoint": config.session_token_endpoint.as_deref().unwrap_or(""),
     });
 
+    if config.wallet_connect {
+        config_json["typedData"] = requirements
+            .and_then(wallet_typed_data)
+            .unwrap_or(serde_json::Value::Null);
+    }
+
     // Add theme configuration if provided
     if let Some(theme) = &config.theme {
         config_json["theme"] = serde_json::json!({
@@ -387,3 +529,119 @@ fn create_x402_config(
 pub fn is_browser_request(user_agent: &str, accept: &str) -> bool {
     accept.contains("text/html") && user_agent.contains("Mozilla")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_logo_rendered_as_image() {
+        let config = PaywallConfig::new()
+            .with_app_name("My App")
+            .with_app_logo("https://example.com/logo.png");
+        let html = generate_paywall_html("", &[], Some(&config));
+
+        assert!(html
+            .contains(r#"<div class="logo"><img src="https://example.com/logo.png" alt="My App""#));
+        assert!(!html.contains(DEFAULT_LOGO_HTML));
+    }
+
+    #[test]
+    fn test_emoji_logo_rendered_as_text() {
+        let config = PaywallConfig::new().with_app_logo("🚀");
+        let html = generate_paywall_html("", &[], Some(&config));
+
+        assert!(html.contains(r#"<div class="logo">🚀</div>"#));
+        assert!(!html.contains("<img"));
+    }
+
+    #[test]
+    fn test_app_name_escaped() {
+        let xss = "</script><script>alert(1)</script>";
+        let config = PaywallConfig::new()
+            .with_app_name(xss)
+            .with_app_logo("https://example.com/logo.png");
+        let html = generate_paywall_html("", &[], Some(&config));
+
+        assert!(!html.contains("<script>alert(1)"));
+        assert!(html.contains(r#"alt="&lt;/script&gt;&lt;script&gt;alert(1)&lt;/script&gt;""#));
+        assert!(html.contains(r#""appName":"\u003c/script\u003e\u003cscript\u003ealert(1)"#));
+    }
+
+    #[test]
+    fn test_dynamic_values_escaped() {
+        let xss = "<script>alert(1)</script>";
+        let mut requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            xss,
+        );
+        requirements.pay_to = format!("0x{}", xss);
+        let config = PaywallConfig::new()
+            .with_branding(BrandingConfig::new(xss).with_company_logo(xss))
+            .with_theme(ThemeConfig::new().with_primary_color(format!("red}}</style>{}", xss)));
+        let html = generate_paywall_html(xss, &[requirements], Some(&config));
+
+        assert!(!html.contains(xss));
+        assert!(!html.contains("</style><script>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt; - Payment Required"));
+        assert!(html.contains(r#""description":"\u003cscript\u003ealert(1)\u003c/script\u003e""#));
+    }
+
+    #[test]
+    fn test_wallet_connect_typed_data_and_button() {
+        let requirements = PaymentRequirements::new(
+            "exact",
+            "base-sepolia",
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+        let config = PaywallConfig::new().with_wallet_connect(true);
+        let html = generate_paywall_html("", std::slice::from_ref(&requirements), Some(&config));
+
+        assert!(html.contains(r#"<button id="x402-connect""#));
+        assert!(html.contains("Connect Wallet &amp; Pay"));
+        assert!(html.contains("eth_signTypedData_v4"));
+        let typed_data = serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "TransferWithAuthorization": [
+                    {"name": "from", "type": "address"},
+                    {"name": "to", "type": "address"},
+                    {"name": "value", "type": "uint256"},
+                    {"name": "validAfter", "type": "uint256"},
+                    {"name": "validBefore", "type": "uint256"},
+                    {"name": "nonce", "type": "bytes32"}
+                ]
+            },
+            "primaryType": "TransferWithAuthorization",
+            "domain": {
+                "name": "USD Coin",
+                "version": "2",
+                "chainId": 84532,
+                "verifyingContract": "0x036cbd53842c5426634e7929541ec2318f3dcf7e"
+            },
+            "message": {
+                "to": "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+                "value": "1000000"
+            }
+        });
+        assert!(html.contains(&format!(r#""typedData":{}"#, typed_data)));
+
+        let html = generate_paywall_html("", &[requirements], None);
+        assert!(!html.contains("x402-connect"));
+        assert!(!html.contains("typedData"));
+    }
+}
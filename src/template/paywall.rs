@@ -7,6 +7,14 @@ pub fn get_base_template() -> &'static str {
     include_str!("paywall.html")
 }
 
+/// Get the wallet-connect script embedded when `PaywallConfig::wallet_connect` is set
+///
+/// It signs the requirements' typed data (`window.x402.typedData`) with
+/// `window.ethereum` and retries the request with the `X-PAYMENT` header.
+pub fn get_wallet_connect_script() -> &'static str {
+    include_str!("wallet_connect.js")
+}
+
 /// Get a simple fallback HTML template
 pub fn get_simple_template() -> &'static str {
     r#"<!DOCTYPE html>
@@ -159,7 +167,7 @@ pub fn get_simple_template() -> &'static str {
             
             // Show payment details
             if (config.amount > 0) {
-                document.getElementById('amount').textContent = `$${config.amount} USDC`;
+                document.getElementById('amount').textContent = config.displayAmount || `$${config.amount} USDC`;
                 document.getElementById('network').textContent = config.testnet ? 'Base Sepolia' : 'Base';
                 document.getElementById('description').textContent = config.paymentRequirements[0]?.description || 'Payment required';
                 document.getElementById('payment-details').style.display = 'block';
@@ -0,0 +1,79 @@
+// x402 wallet-connect flow: signs the payment authorization with the
+// browser wallet (window.ethereum) and retries the request with X-PAYMENT.
+(function () {
+  const button = document.getElementById('x402-connect');
+  if (!button) {
+    return;
+  }
+
+  function randomNonce() {
+    const bytes = crypto.getRandomValues(new Uint8Array(32));
+    return '0x' + Array.from(bytes, (b) => b.toString(16).padStart(2, '0')).join('');
+  }
+
+  async function connectAndPay() {
+    const config = window.x402 || {};
+    const requirements = (config.paymentRequirements || [])[0];
+    if (!config.typedData || !requirements) {
+      showStatus('This payment cannot be made from the browser.', 'error');
+      return;
+    }
+    if (!window.ethereum) {
+      showStatus('No wallet found. Install MetaMask or another browser wallet.', 'error');
+      return;
+    }
+
+    button.disabled = true;
+    try {
+      showStatus('Connecting wallet...', 'loading');
+      const [from] = await window.ethereum.request({ method: 'eth_requestAccounts' });
+      const chainId = '0x' + config.typedData.domain.chainId.toString(16);
+      await window.ethereum.request({
+        method: 'wallet_switchEthereumChain',
+        params: [{ chainId }],
+      });
+
+      const now = Math.floor(Date.now() / 1000);
+      const authorization = {
+        from,
+        to: config.typedData.message.to,
+        value: config.typedData.message.value,
+        validAfter: String(now - 60),
+        validBefore: String(now + requirements.maxTimeoutSeconds),
+        nonce: randomNonce(),
+      };
+      const typedData = Object.assign({}, config.typedData, { message: authorization });
+
+      showStatus('Waiting for signature...', 'loading');
+      const signature = await window.ethereum.request({
+        method: 'eth_signTypedData_v4',
+        params: [from, JSON.stringify(typedData)],
+      });
+
+      const payment = {
+        x402Version: config.x402_version,
+        scheme: requirements.scheme,
+        network: requirements.network,
+        payload: { signature, authorization },
+      };
+
+      showStatus('Submitting payment...', 'loading');
+      const response = await fetch(config.currentUrl || window.location.href, {
+        headers: { 'X-PAYMENT': btoa(JSON.stringify(payment)) },
+      });
+      if (!response.ok) {
+        throw new Error(`Payment failed with status ${response.status}`);
+      }
+
+      const body = await response.text();
+      document.open();
+      document.write(body);
+      document.close();
+    } catch (error) {
+      showStatus(error.message || String(error), 'error');
+      button.disabled = false;
+    }
+  }
+
+  button.addEventListener('click', connectAndPay);
+})();
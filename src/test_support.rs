@@ -0,0 +1,313 @@
+//! Test helpers for code built on x402 (the `test-utils` feature)
+//!
+//! [`MockFacilitator`] runs a local HTTP server answering `/verify`,
+//! `/settle` and `/supported` with canned responses, so code using a
+//! [`FacilitatorClient`] can be tested without a real facilitator. For
+//! in-process settlement, [`TestSettler`] is a [`Settler`] with a fixed
+//! outcome that records what it settled:
+//!
+//! ```rust,no_run
+//! # async fn example() -> rust_x402::Result<()> {
+//! use rust_x402::middleware::PaymentMiddleware;
+//! use rust_x402::test_support::MockFacilitator;
+//!
+//! let facilitator = MockFacilitator::always_valid().await;
+//! let middleware = PaymentMiddleware::new(
+//!     rust_decimal::Decimal::new(1, 4),
+//!     "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+//! )
+//! .with_facilitator(facilitator.client()?);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::facilitator::{FacilitatorClient, Settler};
+use crate::types::{
+    networks, schemes, FacilitatorConfig, PaymentPayload, PaymentRequirements, SettleResponse,
+    SupportedKind, SupportedKinds, VerifyResponse, X402_VERSION,
+};
+use crate::Result;
+use async_trait::async_trait;
+use mockito::{Mock, ServerGuard};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+/// Transaction hash reported by [`MockFacilitator::always_valid`] and
+/// [`TestSettler::succeeding`] settlements
+pub const MOCK_TRANSACTION: &str =
+    "0x0000000000000000000000000000000000000000000000000000000000000001";
+
+/// Local facilitator returning configured responses
+///
+/// Endpoints without a configured response answer with mockito's default
+/// `501 Not Implemented`. The server shuts down when this is dropped.
+pub struct MockFacilitator {
+    server: ServerGuard,
+    verify: Option<Mock>,
+    settle: Option<Mock>,
+    supported: Option<Mock>,
+}
+
+impl std::fmt::Debug for MockFacilitator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockFacilitator")
+            .field("url", &self.url())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MockFacilitator {
+    /// Start a facilitator with no configured responses
+    pub async fn new() -> Self {
+        Self {
+            server: mockito::Server::new_async().await,
+            verify: None,
+            settle: None,
+            supported: None,
+        }
+    }
+
+    /// Start a facilitator that accepts and settles every payment
+    ///
+    /// `/supported` lists the `exact` scheme on Base and Base Sepolia.
+    pub async fn always_valid() -> Self {
+        Self::new()
+            .await
+            .with_verify_response(VerifyResponse {
+                is_valid: true,
+                invalid_reason: None,
+                payer: None,
+            })
+            .await
+            .with_settle_response(SettleResponse {
+                success: true,
+                error_reason: None,
+                transaction: MOCK_TRANSACTION.to_string(),
+                network: networks::BASE_SEPOLIA.to_string(),
+                payer: None,
+                fee_amount: None,
+                net_amount: None,
+            })
+            .await
+            .with_supported(SupportedKinds {
+                kinds: [networks::BASE_MAINNET, networks::BASE_SEPOLIA]
+                    .into_iter()
+                    .map(|network| SupportedKind {
+                        x402_version: X402_VERSION,
+                        scheme: schemes::EXACT.to_string(),
+                        network: network.to_string(),
+                        metadata: None,
+                    })
+                    .collect(),
+            })
+            .await
+    }
+
+    /// Start a facilitator that rejects every payment with `reason`
+    pub async fn always_invalid(reason: impl Into<String>) -> Self {
+        Self::new()
+            .await
+            .with_verify_response(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some(reason.into()),
+                payer: None,
+            })
+            .await
+    }
+
+    /// Answer `/verify` with `response`
+    pub async fn with_verify_response(mut self, response: VerifyResponse) -> Self {
+        let previous = self.verify.take();
+        self.verify = Some(self.replace(previous, "POST", "/verify", &response).await);
+        self
+    }
+
+    /// Answer `/settle` with `response`
+    pub async fn with_settle_response(mut self, response: SettleResponse) -> Self {
+        let previous = self.settle.take();
+        self.settle = Some(self.replace(previous, "POST", "/settle", &response).await);
+        self
+    }
+
+    /// Answer `/supported` with `kinds`
+    pub async fn with_supported(mut self, kinds: SupportedKinds) -> Self {
+        let previous = self.supported.take();
+        self.supported = Some(self.replace(previous, "GET", "/supported", &kinds).await);
+        self
+    }
+
+    /// Base URL of the facilitator
+    pub fn url(&self) -> String {
+        self.server.url()
+    }
+
+    /// Facilitator configuration pointing at this facilitator
+    pub fn config(&self) -> FacilitatorConfig {
+        FacilitatorConfig::new(self.url())
+    }
+
+    /// Client for this facilitator
+    pub fn client(&self) -> Result<FacilitatorClient> {
+        FacilitatorClient::new(self.config())
+    }
+
+    /// Swap the mock for an endpoint, so only the latest response is served
+    async fn replace(
+        &mut self,
+        previous: Option<Mock>,
+        method: &str,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Mock {
+        if let Some(previous) = previous {
+            previous.remove_async().await;
+        }
+        self.server
+            .mock(method, path)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(serde_json::to_string(body).unwrap_or_default())
+            .create_async()
+            .await
+    }
+}
+
+/// Settler with a fixed outcome, recording every settlement it reports
+///
+/// Clones share the record, so keep one to inspect after handing the other
+/// to a middleware or [`LocalFacilitator`](crate::facilitator::LocalFacilitator).
+#[derive(Debug, Clone)]
+pub struct TestSettler {
+    transaction: String,
+    error_reason: Option<String>,
+    settlements: Arc<Mutex<Vec<SettleResponse>>>,
+}
+
+impl TestSettler {
+    /// Settle every payment with [`MOCK_TRANSACTION`]
+    pub fn succeeding() -> Self {
+        Self {
+            transaction: MOCK_TRANSACTION.to_string(),
+            error_reason: None,
+            settlements: Arc::default(),
+        }
+    }
+
+    /// Fail every settlement with `reason`
+    pub fn failing(reason: impl Into<String>) -> Self {
+        Self {
+            transaction: String::new(),
+            error_reason: Some(reason.into()),
+            settlements: Arc::default(),
+        }
+    }
+
+    /// Report `transaction` as the hash of successful settlements
+    pub fn with_transaction(mut self, transaction: impl Into<String>) -> Self {
+        self.transaction = transaction.into();
+        self
+    }
+
+    /// Settlements reported so far, oldest first
+    pub fn settlements(&self) -> Vec<SettleResponse> {
+        self.settlements
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+#[async_trait]
+impl Settler for TestSettler {
+    async fn settle(
+        &self,
+        payment: &PaymentPayload,
+        _requirements: &PaymentRequirements,
+    ) -> Result<SettleResponse> {
+        let settlement = SettleResponse {
+            success: self.error_reason.is_none(),
+            error_reason: self.error_reason.clone(),
+            transaction: self.transaction.clone(),
+            network: payment.network.clone(),
+            payer: Some(payment.payload.authorization.from.clone()),
+            fee_amount: None,
+            net_amount: None,
+        };
+        self.settlements
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(settlement.clone());
+        Ok(settlement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ExactEvmPayload, ExactEvmPayloadAuthorization};
+
+    fn payment() -> (PaymentPayload, PaymentRequirements) {
+        let requirements = PaymentRequirements::new(
+            schemes::EXACT,
+            networks::BASE_SEPOLIA,
+            "1000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693bc6afc0c5328ba36faf03c514ef312287c",
+            "https://example.com/resource",
+            "Test resource",
+        );
+        let payment = PaymentPayload::new(
+            schemes::EXACT,
+            networks::BASE_SEPOLIA,
+            ExactEvmPayload {
+                signature: "0x00".to_string(),
+                authorization: ExactEvmPayloadAuthorization::new(
+                    "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+                    "0x209693bc6afc0c5328ba36faf03c514ef312287c",
+                    "1000",
+                    "1745323800",
+                    "1745323985",
+                    "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+                ),
+            },
+        );
+        (payment, requirements)
+    }
+
+    #[tokio::test]
+    async fn test_canned_responses() {
+        let (payment, requirements) = payment();
+        let facilitator = MockFacilitator::always_valid().await;
+        let client = facilitator.client().unwrap();
+
+        assert!(
+            client
+                .verify(&payment, &requirements)
+                .await
+                .unwrap()
+                .is_valid
+        );
+        let settlement = client.settle(&payment, &requirements).await.unwrap();
+        assert_eq!(settlement.transaction, MOCK_TRANSACTION);
+        assert_eq!(client.supported().await.unwrap().kinds.len(), 2);
+
+        // A later response replaces the earlier one
+        let facilitator = facilitator
+            .with_verify_response(VerifyResponse {
+                is_valid: false,
+                invalid_reason: Some("insufficient_funds".to_string()),
+                payer: None,
+            })
+            .await;
+        let response = facilitator
+            .client()
+            .unwrap()
+            .verify(&payment, &requirements)
+            .await
+            .unwrap();
+        assert_eq!(
+            response.invalid_reason.as_deref(),
+            Some("insufficient_funds")
+        );
+    }
+}
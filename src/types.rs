@@ -1,6 +1,10 @@
 //! Core types for the x402 protocol
+//!
+//! Reading the wall clock is gated behind the `std` feature. Without it,
+//! authorization windows are checked against a caller-supplied [`Clock`], so
+//! payloads can be built and checked where time comes from the host (for
+//! example `Date.now()` in a browser).
 
-use chrono::Utc;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -8,6 +12,18 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
+/// Request header carrying the base64-encoded payment payload
+pub const X_PAYMENT_HEADER: &str = "X-PAYMENT";
+
+/// Response header carrying the base64-encoded settlement response
+pub const X_PAYMENT_RESPONSE_HEADER: &str = "X-PAYMENT-RESPONSE";
+
+/// Request header overriding whether a 402 is rendered for a browser or an API client
+pub const X_PAYMENT_CLIENT_HEADER: &str = "X-Payment-Client";
+
+/// Path at which a resource server publishes the payments it accepts
+pub const WELL_KNOWN_X402_PATH: &str = "/.well-known/x402";
+
 /// Type alias for authentication headers function
 pub type AuthHeadersFn =
     dyn Fn() -> crate::Result<HashMap<String, HashMap<String, String>>> + Send + Sync;
@@ -21,11 +37,40 @@ pub type AuthHeadersFnBox = Box<AuthHeadersFn>;
 /// x402 protocol version
 pub const X402_VERSION: u32 = 1;
 
-/// Network configuration for x402 payments
+/// Oldest x402 protocol version accepted from clients
+pub const X402_MIN_VERSION: u32 = 1;
+
+/// Newest x402 protocol version accepted from clients
+pub const X402_MAX_VERSION: u32 = X402_VERSION;
+
+/// Protocol versions accepted from clients
+pub fn supported_versions() -> std::ops::RangeInclusive<u32> {
+    X402_MIN_VERSION..=X402_MAX_VERSION
+}
+
+/// Whether `version` is within [`X402_MIN_VERSION`]..=[`X402_MAX_VERSION`]
+pub fn is_supported_version(version: u32) -> bool {
+    supported_versions().contains(&version)
+}
+
+/// Number of decimals used by USDC on all supported networks
+pub const USDC_DECIMALS: u8 = 6;
+
+/// Asset sentinel for payments in the network's native gas token
+pub const NATIVE_ASSET: &str = "native";
+
+/// Number of decimals of the native gas token on supported EVM networks
+pub const NATIVE_DECIMALS: u8 = 18;
+
+/// EVM network with a built-in [`NetworkConfig`]
+///
+/// `Mainnet` and `Testnet` are Base and Base Sepolia.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Network {
     Mainnet,
     Testnet,
+    Avalanche,
+    AvalancheFuji,
 }
 
 /// Network configuration with chain-specific details
@@ -39,6 +84,40 @@ pub struct NetworkConfig {
     pub name: String,
     /// Whether this is a testnet
     pub is_testnet: bool,
+    /// Native gas token of the network
+    pub native_token: NativeToken,
+}
+
+/// Native gas token metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NativeToken {
+    /// Token name (e.g., "Ether")
+    pub name: String,
+    /// Ticker symbol (e.g., "ETH")
+    pub symbol: String,
+    /// Number of decimals in the atomic unit
+    pub decimals: u8,
+}
+
+impl NativeToken {
+    /// Create native token metadata
+    pub fn new(name: impl Into<String>, symbol: impl Into<String>, decimals: u8) -> Self {
+        Self {
+            name: name.into(),
+            symbol: symbol.into(),
+            decimals,
+        }
+    }
+
+    /// Ether, as used on Base
+    pub fn ether() -> Self {
+        Self::new("Ether", "ETH", 18)
+    }
+
+    /// Avalanche's AVAX
+    pub fn avax() -> Self {
+        Self::new("Avalanche", "AVAX", 18)
+    }
 }
 
 impl NetworkConfig {
@@ -49,6 +128,7 @@ impl NetworkConfig {
             usdc_contract: "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".to_string(),
             name: "base".to_string(),
             is_testnet: false,
+            native_token: NativeToken::ether(),
         }
     }
 
@@ -59,6 +139,67 @@ impl NetworkConfig {
             usdc_contract: "0x036CbD53842c5426634e7929541eC2318f3dCF7e".to_string(),
             name: "base-sepolia".to_string(),
             is_testnet: true,
+            native_token: NativeToken::ether(),
+        }
+    }
+
+    /// Avalanche C-Chain mainnet configuration
+    pub fn avalanche_mainnet() -> Self {
+        Self {
+            chain_id: 43114,
+            usdc_contract: "0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E".to_string(),
+            name: "avalanche".to_string(),
+            is_testnet: false,
+            native_token: NativeToken::avax(),
+        }
+    }
+
+    /// Avalanche Fuji testnet configuration
+    pub fn avalanche_fuji() -> Self {
+        Self {
+            chain_id: 43113,
+            usdc_contract: "0x5425890298aed601595a70AB815c96711a31Bc65".to_string(),
+            name: "avalanche-fuji".to_string(),
+            is_testnet: true,
+            native_token: NativeToken::avax(),
+        }
+    }
+
+    /// Solana mainnet configuration
+    ///
+    /// Solana has no EVM chain ID, so `chain_id` is 0 and `usdc_contract`
+    /// holds the USDC mint address.
+    #[cfg(feature = "solana")]
+    pub fn solana_mainnet() -> Self {
+        Self {
+            chain_id: 0,
+            usdc_contract: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            name: "solana".to_string(),
+            is_testnet: false,
+            native_token: NativeToken::new("Solana", "SOL", 9),
+        }
+    }
+
+    /// Solana devnet configuration
+    #[cfg(feature = "solana")]
+    pub fn solana_devnet() -> Self {
+        Self {
+            chain_id: 0,
+            usdc_contract: "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU".to_string(),
+            name: "solana-devnet".to_string(),
+            is_testnet: true,
+            native_token: NativeToken::new("Solana", "SOL", 9),
+        }
+    }
+
+    /// Decimals of `asset` on this network, if it is the USDC or native token
+    pub fn asset_decimals(&self, asset: &str) -> Option<u8> {
+        if asset == NATIVE_ASSET {
+            Some(self.native_token.decimals)
+        } else if asset.eq_ignore_ascii_case(&self.usdc_contract) {
+            Some(USDC_DECIMALS)
+        } else {
+            None
         }
     }
 
@@ -67,38 +208,80 @@ impl NetworkConfig {
         match name {
             "base" => Some(Self::base_mainnet()),
             "base-sepolia" => Some(Self::base_sepolia()),
+            "avalanche" => Some(Self::avalanche_mainnet()),
+            "avalanche-fuji" => Some(Self::avalanche_fuji()),
+            #[cfg(feature = "solana")]
+            "solana" => Some(Self::solana_mainnet()),
+            #[cfg(feature = "solana")]
+            "solana-devnet" => Some(Self::solana_devnet()),
             _ => None,
         }
     }
 }
 
 impl Network {
+    /// Every variant, mainnets first
+    pub const ALL: [Network; 4] = [
+        Network::Mainnet,
+        Network::Avalanche,
+        Network::Testnet,
+        Network::AvalancheFuji,
+    ];
+
+    /// The network with identifier `name`, if it is one of [`Network::ALL`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|network| network.as_str() == name)
+    }
+
     /// Get the network identifier string
     pub fn as_str(&self) -> &'static str {
         match self {
-            Network::Mainnet => "base",
-            Network::Testnet => "base-sepolia",
+            Network::Mainnet => networks::BASE_MAINNET,
+            Network::Testnet => networks::BASE_SEPOLIA,
+            Network::Avalanche => networks::AVALANCHE_MAINNET,
+            Network::AvalancheFuji => networks::AVALANCHE_FUJI,
         }
     }
 
-    /// Get the USDC contract address for this network
-    pub fn usdc_address(&self) -> &'static str {
+    /// Chain details, as [`NetworkConfig::from_name`] reports them
+    pub fn config(&self) -> NetworkConfig {
         match self {
-            Network::Mainnet => "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
-            Network::Testnet => "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            Network::Mainnet => NetworkConfig::base_mainnet(),
+            Network::Testnet => NetworkConfig::base_sepolia(),
+            Network::Avalanche => NetworkConfig::avalanche_mainnet(),
+            Network::AvalancheFuji => NetworkConfig::avalanche_fuji(),
         }
     }
 
+    /// EVM chain ID of this network
+    pub fn chain_id(&self) -> u64 {
+        self.config().chain_id
+    }
+
+    /// Whether this is a test network
+    pub fn is_testnet(&self) -> bool {
+        self.config().is_testnet
+    }
+
+    /// Get the USDC contract address for this network
+    pub fn usdc_address(&self) -> String {
+        self.config().usdc_contract
+    }
+
     /// Get the USDC token name for this network
     pub fn usdc_name(&self) -> &'static str {
         match self {
-            Network::Mainnet => "USD Coin",
             Network::Testnet => "USDC",
+            Network::Mainnet | Network::Avalanche | Network::AvalancheFuji => "USD Coin",
         }
     }
 }
 
 /// Payment requirements for a resource
+///
+/// Fields serialize in the order of the reference TypeScript and Python SDKs.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PaymentRequirements {
     /// Payment scheme identifier (e.g., "exact")
@@ -108,30 +291,119 @@ pub struct PaymentRequirements {
     /// Required payment amount in atomic token units
     #[serde(rename = "maxAmountRequired")]
     pub max_amount_required: String,
-    /// Token contract address
-    pub asset: String,
-    /// Recipient wallet address for the payment
-    #[serde(rename = "payTo")]
-    pub pay_to: String,
     /// URL of the protected resource
     pub resource: String,
     /// Human-readable description of the resource
     pub description: String,
     /// MIME type of the expected response
-    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    ///
+    /// Always sent, as `""` when unset, like the reference SDKs.
+    #[serde(rename = "mimeType", default, with = "empty_string_as_none")]
     pub mime_type: Option<String>,
     /// JSON schema describing the response format
     #[serde(rename = "outputSchema", skip_serializing_if = "Option::is_none")]
     pub output_schema: Option<Value>,
+    /// Recipient wallet address for the payment
+    #[serde(rename = "payTo")]
+    pub pay_to: String,
     /// Maximum time allowed for payment completion in seconds
     #[serde(rename = "maxTimeoutSeconds")]
     pub max_timeout_seconds: u32,
+    /// Token contract address
+    pub asset: String,
     /// Scheme-specific additional information
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extra: Option<Value>,
 }
 
+/// Serde for optional strings the wire format always carries, empty when unset
+mod empty_string_as_none {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<String>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(value.as_deref().unwrap_or(""))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<String>, D::Error> {
+        let value = Option::<String>::deserialize(deserializer)?;
+        Ok(value.filter(|value| !value.is_empty()))
+    }
+}
+
 impl PaymentRequirements {
+    /// Parse JSON payment requirements, rejecting unknown fields
+    pub fn from_json_strict(json: &[u8]) -> crate::Result<Self> {
+        let mut deserializer = serde_json::Deserializer::from_slice(json);
+        let requirements = strict::PaymentRequirementsDef::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(requirements)
+    }
+
+    /// Check that `output_schema` is a JSON Schema and `extra` has the scheme's shape
+    ///
+    /// `extra` must be an object. For the `exact` and `upto` schemes its
+    /// `name`, `version` and `salt` (a 32-byte hex string) EIP-712 domain
+    /// fields and its `decimals` must be well-typed when present. Fails with
+    /// [`X402Error::InvalidPaymentRequirements`](crate::X402Error::InvalidPaymentRequirements).
+    #[cfg(feature = "json-schema")]
+    pub fn validate_documents(&self) -> crate::Result<()> {
+        use crate::X402Error;
+
+        if let Some(schema) = &self.output_schema {
+            jsonschema::meta::validate(schema).map_err(|e| {
+                X402Error::invalid_payment_requirements(format!(
+                    "outputSchema is not a valid JSON Schema: {}",
+                    e
+                ))
+            })?;
+        }
+
+        let Some(extra) = &self.extra else {
+            return Ok(());
+        };
+        let extra = extra
+            .as_object()
+            .ok_or_else(|| X402Error::invalid_payment_requirements("extra must be an object"))?;
+        if !matches!(self.scheme.as_str(), schemes::EXACT | schemes::UPTO) {
+            return Ok(());
+        }
+        for key in ["name", "version"] {
+            if extra.get(key).is_some_and(|value| !value.is_string()) {
+                return Err(X402Error::invalid_payment_requirements(format!(
+                    "extra.{} must be a string",
+                    key
+                )));
+            }
+        }
+        if let Some(salt) = extra.get("salt") {
+            let is_hash = salt
+                .as_str()
+                .is_some_and(|salt| salt.parse::<ethereum_types::H256>().is_ok());
+            if !is_hash {
+                return Err(X402Error::invalid_payment_requirements(
+                    "extra.salt must be a 32-byte hex string",
+                ));
+            }
+        }
+        if let Some(decimals) = extra.get("decimals") {
+            if decimals
+                .as_u64()
+                .and_then(|d| u8::try_from(d).ok())
+                .is_none()
+            {
+                return Err(X402Error::invalid_payment_requirements(
+                    "extra.decimals must be an integer from 0 to 255",
+                ));
+            }
+        }
+        Ok(())
+    }
+
     /// Create a new payment requirements instance
     pub fn new(
         scheme: impl Into<String>,
@@ -157,6 +429,93 @@ impl PaymentRequirements {
         }
     }
 
+    /// Create requirements payable in the network's native gas token
+    ///
+    /// `max_amount_required` is in the native token's atomic unit (e.g., wei).
+    pub fn native(
+        network: impl Into<String>,
+        max_amount_required: impl Into<String>,
+        pay_to: impl Into<String>,
+        resource: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            schemes::EXACT,
+            network,
+            max_amount_required,
+            NATIVE_ASSET,
+            pay_to,
+            resource,
+            description,
+        )
+    }
+
+    /// Whether payment is requested in the native gas token
+    pub fn is_native(&self) -> bool {
+        self.asset == NATIVE_ASSET
+    }
+
+    /// Whether `payload` pays for this option
+    ///
+    /// Compares scheme and network, and checks that the authorization pays
+    /// `pay_to` at least the required amount. The asset is not carried in the
+    /// payload; the signature binds it through the EIP-712 domain, so it is
+    /// checked when the signature is verified.
+    pub fn matches(&self, payload: &PaymentPayload) -> bool {
+        if payload.scheme != self.scheme || payload.network != self.network {
+            return false;
+        }
+
+        let authorization = &payload.payload.authorization;
+        if !authorization.to.eq_ignore_ascii_case(&self.pay_to) {
+            return false;
+        }
+
+        let decimals = self.asset_decimals();
+        match (
+            authorization.amount(decimals),
+            self.required_amount(decimals),
+        ) {
+            (Ok(value), Ok(required)) => value >= required,
+            _ => false,
+        }
+    }
+
+    /// Decimals of the required asset: those declared in `extra`, otherwise
+    /// the native token's or USDC's
+    pub fn asset_decimals(&self) -> u8 {
+        if let Some(decimals) = self.declared_decimals() {
+            decimals
+        } else if self.asset == NATIVE_ASSET {
+            NATIVE_DECIMALS
+        } else {
+            USDC_DECIMALS
+        }
+    }
+
+    /// Decimals declared by a numeric `decimals` entry in `extra`
+    pub fn declared_decimals(&self) -> Option<u8> {
+        self.extra
+            .as_ref()?
+            .get("decimals")?
+            .as_u64()
+            .and_then(|decimals| u8::try_from(decimals).ok())
+    }
+
+    /// Whether `network` gives the asset the decimals this requirement is priced in
+    ///
+    /// Assets the network does not know are taken at their declared decimals.
+    pub fn decimals_match(&self, network: &NetworkConfig) -> bool {
+        network
+            .asset_decimals(&self.asset)
+            .is_none_or(|decimals| decimals == self.asset_decimals())
+    }
+
+    /// The required amount in atomic units of a token with `decimals`
+    pub fn required_amount(&self, decimals: u8) -> crate::Result<Amount> {
+        Amount::from_atomic_str(&self.max_amount_required, decimals)
+    }
+
     /// Set USDC token information in the extra field
     pub fn set_usdc_info(&mut self, network: Network) -> crate::Result<()> {
         let mut usdc_info = HashMap::new();
@@ -176,10 +535,392 @@ impl PaymentRequirements {
 
     /// Get the amount in decimal units (e.g., 0.01 for 1 cent)
     pub fn amount_in_decimal_units(&self, decimals: u8) -> crate::Result<Decimal> {
-        let amount = self.amount_as_decimal()?;
-        let divisor = Decimal::from(10u64.pow(decimals as u32));
-        Ok(amount / divisor)
+        self.required_amount(decimals)?.to_decimal()
+    }
+
+    /// Format the required amount for display, e.g. `"1,234.5 USDC"`
+    ///
+    /// The atomic amount is shifted by `decimals` exactly (no floating point or
+    /// scientific notation), trailing zeros are trimmed and the integer part is
+    /// grouped with commas. An amount that is not an unsigned integer is shown
+    /// as-is.
+    pub fn display_amount(&self, decimals: u8, symbol: &str) -> String {
+        let atomic = self.max_amount_required.trim();
+        let number = if !atomic.is_empty() && atomic.bytes().all(|b| b.is_ascii_digit()) {
+            format_atomic_amount(atomic, usize::from(decimals))
+        } else {
+            atomic.to_string()
+        };
+
+        if symbol.is_empty() {
+            number
+        } else {
+            format!("{} {}", number, symbol)
+        }
+    }
+
+    /// Set the required amount from a decimal token amount
+    ///
+    /// The amount is converted to atomic units (e.g., 0.0001 USDC with 6
+    /// decimals becomes "100"). Fails if the amount is negative or has more
+    /// precision than `decimals` allows.
+    pub fn with_decimal_amount(mut self, amount: Decimal, decimals: u8) -> crate::Result<Self> {
+        self.max_amount_required = Amount::from_decimal(amount, decimals)?.as_atomic_string();
+        Ok(self)
+    }
+
+    /// Get the required amount as a decimal token amount
+    pub fn decimal_amount(&self, decimals: u8) -> crate::Result<Decimal> {
+        self.amount_in_decimal_units(decimals)
+    }
+
+    /// Create a builder with named setters
+    pub fn builder() -> PaymentRequirementsBuilder {
+        PaymentRequirementsBuilder::default()
+    }
+}
+
+/// Shift an atomic digit string by `decimals` and group the integer part
+fn format_atomic_amount(digits: &str, decimals: usize) -> String {
+    let digits = digits.trim_start_matches('0');
+    // Left-pad so there is at least one integer digit
+    let padded = format!("{:0>width$}", digits, width = decimals + 1);
+    let (integer, fraction) = padded.split_at(padded.len() - decimals);
+
+    let mut grouped = String::with_capacity(integer.len() + integer.len() / 3);
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    match fraction.trim_end_matches('0') {
+        "" => grouped,
+        fraction => format!("{}.{}", grouped, fraction),
+    }
+}
+
+/// Largest accepted amount in atomic units (2^96 - 1)
+///
+/// Every amount up to this cap converts to a [`Decimal`] exactly; larger
+/// values are rejected as an overflow rather than wrapped or truncated.
+pub const MAX_ATOMIC_AMOUNT: u128 = (1 << 96) - 1;
+
+fn amount_overflow() -> crate::X402Error {
+    crate::X402Error::invalid_payment_requirements("amount overflow")
+}
+
+/// Token amount in atomic units, tagged with the token's decimals
+///
+/// Keeps the human-readable amount (e.g. 0.0001 USDC) and the on-chain
+/// integer (`100`) from being mixed up. Arithmetic and comparisons are only
+/// defined between amounts with the same decimals, and every operation is
+/// checked against [`MAX_ATOMIC_AMOUNT`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Amount {
+    atomic: u128,
+    decimals: u8,
+}
+
+impl Amount {
+    /// Wrap an amount already in atomic units
+    pub fn new(atomic: u128, decimals: u8) -> crate::Result<Self> {
+        if atomic > MAX_ATOMIC_AMOUNT {
+            return Err(amount_overflow());
+        }
+        Ok(Self { atomic, decimals })
+    }
+
+    /// Parse an atomic amount such as a `maxAmountRequired` or authorization value
+    pub fn from_atomic_str(atomic: &str, decimals: u8) -> crate::Result<Self> {
+        use std::num::IntErrorKind;
+
+        match atomic.parse::<u128>() {
+            Ok(value) => Self::new(value, decimals),
+            Err(e) if *e.kind() == IntErrorKind::PosOverflow => Err(amount_overflow()),
+            Err(_) => Err(crate::X402Error::invalid_payment_requirements(format!(
+                "Invalid atomic amount: {}",
+                atomic
+            ))),
+        }
+    }
+
+    /// Convert a decimal token amount to atomic units
+    ///
+    /// Fails if the amount is negative or has more precision than `decimals`
+    /// allows (e.g. 0.0001 USDC with 6 decimals is `100`), and with
+    /// `"amount overflow"` if the atomic amount exceeds [`MAX_ATOMIC_AMOUNT`].
+    pub fn from_decimal(amount: Decimal, decimals: u8) -> crate::Result<Self> {
+        use rust_decimal::prelude::ToPrimitive;
+
+        if amount.is_sign_negative() && !amount.is_zero() {
+            return Err(crate::X402Error::invalid_payment_requirements(format!(
+                "Amount must not be negative: {}",
+                amount
+            )));
+        }
+
+        let scale = 10i128
+            .checked_pow(u32::from(decimals))
+            .and_then(|scale| Decimal::try_from_i128_with_scale(scale, 0).ok())
+            .ok_or_else(amount_overflow)?;
+        let atomic = amount.checked_mul(scale).ok_or_else(amount_overflow)?;
+        if !atomic.fract().is_zero() {
+            return Err(crate::X402Error::invalid_payment_requirements(format!(
+                "Cannot express {} with {} decimals in atomic units",
+                amount, decimals
+            )));
+        }
+
+        let atomic = atomic.trunc().to_u128().ok_or_else(amount_overflow)?;
+        Self::new(atomic, decimals)
+    }
+
+    /// Like [`from_decimal`](Self::from_decimal), rounding excess precision up
+    ///
+    /// For floors: 0.0000001 USDC is 1 atomic unit rather than an error.
+    pub fn from_decimal_ceil(amount: Decimal, decimals: u8) -> crate::Result<Self> {
+        let rounded = amount.round_dp_with_strategy(
+            u32::from(decimals),
+            rust_decimal::RoundingStrategy::AwayFromZero,
+        );
+        Self::from_decimal(rounded, decimals)
+    }
+
+    /// The amount in whole tokens, e.g. `0.0001` for 100 atomic USDC
+    ///
+    /// Fails for tokens with more decimals than [`Decimal`]'s maximum scale of 28.
+    pub fn to_decimal(&self) -> crate::Result<Decimal> {
+        // The cap keeps the atomic amount within the 96-bit mantissa
+        Decimal::try_from_i128_with_scale(self.atomic as i128, u32::from(self.decimals))
+            .map(|amount| amount.normalize())
+            .ok()
+            .ok_or_else(|| {
+                crate::X402Error::invalid_payment_requirements(format!(
+                    "Cannot express {} atomic units with {} decimals as a decimal",
+                    self.atomic, self.decimals
+                ))
+            })
+    }
+
+    /// The amount in atomic units
+    pub const fn atomic(&self) -> u128 {
+        self.atomic
+    }
+
+    /// Decimals of the token
+    pub const fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// Atomic units as a string, as carried in requirements and payloads
+    pub fn as_atomic_string(&self) -> String {
+        self.atomic.to_string()
+    }
+
+    /// Sum of two amounts of the same token
+    pub fn checked_add(self, other: Self) -> crate::Result<Self> {
+        self.combine(other, u128::checked_add)
+    }
+
+    /// Difference of two amounts of the same token
+    pub fn checked_sub(self, other: Self) -> crate::Result<Self> {
+        self.combine(other, u128::checked_sub)
+    }
+
+    /// The amount multiplied by `factor`, e.g. a unit price times a count
+    pub fn checked_mul(self, factor: u128) -> crate::Result<Self> {
+        let atomic = self
+            .atomic
+            .checked_mul(factor)
+            .ok_or_else(amount_overflow)?;
+        Self::new(atomic, self.decimals)
+    }
+
+    fn combine(self, other: Self, op: fn(u128, u128) -> Option<u128>) -> crate::Result<Self> {
+        if self.decimals != other.decimals {
+            return Err(crate::X402Error::invalid_payment_requirements(format!(
+                "Cannot combine amounts with {} and {} decimals",
+                self.decimals, other.decimals
+            )));
+        }
+        let atomic = op(self.atomic, other.atomic).ok_or_else(amount_overflow)?;
+        Self::new(atomic, self.decimals)
+    }
+}
+
+impl PartialOrd for Amount {
+    /// Amounts with different decimals are not comparable
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        (self.decimals == other.decimals).then(|| self.atomic.cmp(&other.atomic))
+    }
+}
+
+impl std::fmt::Display for Amount {
+    /// Exact token amount as shown by [`PaymentRequirements::display_amount`], e.g. `1,234.5`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&format_atomic_amount(
+            &self.as_atomic_string(),
+            usize::from(self.decimals),
+        ))
+    }
+}
+
+/// Builder for [`PaymentRequirements`]
+///
+/// Unlike [`PaymentRequirements::new`], fields are set by name and
+/// [`build`](Self::build) validates that required fields are present and that
+/// the amount and addresses parse.
+#[derive(Debug, Clone, Default)]
+pub struct PaymentRequirementsBuilder {
+    scheme: Option<String>,
+    network: Option<String>,
+    max_amount_required: Option<String>,
+    asset: Option<String>,
+    pay_to: Option<String>,
+    resource: Option<String>,
+    description: Option<String>,
+    mime_type: Option<String>,
+    output_schema: Option<Value>,
+    max_timeout_seconds: Option<u32>,
+    extra: Option<Value>,
+}
+
+impl PaymentRequirementsBuilder {
+    /// Set the payment scheme (e.g., "exact")
+    pub fn scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.scheme = Some(scheme.into());
+        self
+    }
+
+    /// Set the network identifier (e.g., "base-sepolia")
+    pub fn network(mut self, network: impl Into<String>) -> Self {
+        self.network = Some(network.into());
+        self
+    }
+
+    /// Set the required amount in atomic token units
+    pub fn amount_atomic(mut self, amount: impl Into<String>) -> Self {
+        self.max_amount_required = Some(amount.into());
+        self
+    }
+
+    /// Set the token contract address
+    pub fn asset(mut self, asset: impl Into<String>) -> Self {
+        self.asset = Some(asset.into());
+        self
+    }
+
+    /// Set the recipient wallet address
+    pub fn pay_to(mut self, pay_to: impl Into<String>) -> Self {
+        self.pay_to = Some(pay_to.into());
+        self
+    }
+
+    /// Set the protected resource URL
+    pub fn resource(mut self, resource: impl Into<String>) -> Self {
+        self.resource = Some(resource.into());
+        self
+    }
+
+    /// Set the human-readable description
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the MIME type of the expected response
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    /// Set the JSON schema describing the response format
+    pub fn output_schema(mut self, output_schema: Value) -> Self {
+        self.output_schema = Some(output_schema);
+        self
+    }
+
+    /// Set the maximum time allowed for payment completion (default 60s)
+    pub fn max_timeout_seconds(mut self, max_timeout_seconds: u32) -> Self {
+        self.max_timeout_seconds = Some(max_timeout_seconds);
+        self
+    }
+
+    /// Set scheme-specific additional information
+    pub fn extra(mut self, extra: Value) -> Self {
+        self.extra = Some(extra);
+        self
     }
+
+    /// Validate the fields and build the requirements
+    pub fn build(self) -> crate::Result<PaymentRequirements> {
+        fn required(value: Option<String>, field: &str) -> crate::Result<String> {
+            value.ok_or_else(|| {
+                crate::X402Error::invalid_payment_requirements(format!(
+                    "Missing required field: {}",
+                    field
+                ))
+            })
+        }
+
+        let scheme = required(self.scheme, "scheme")?;
+        let network = required(self.network, "network")?;
+        let max_amount_required = required(self.max_amount_required, "amount")?;
+        let asset = required(self.asset, "asset")?;
+        let pay_to = required(self.pay_to, "pay_to")?;
+        let resource = required(self.resource, "resource")?;
+        let description = self.description.unwrap_or_default();
+
+        // Decimals do not matter here, only that the amount parses
+        Amount::from_atomic_str(&max_amount_required, USDC_DECIMALS)?;
+        if asset != NATIVE_ASSET {
+            validate_address(&network, "asset", &asset)?;
+        }
+        validate_address(&network, "pay_to", &pay_to)?;
+
+        Ok(PaymentRequirements {
+            scheme,
+            network,
+            max_amount_required,
+            asset,
+            pay_to,
+            resource,
+            description,
+            mime_type: self.mime_type,
+            output_schema: self.output_schema,
+            max_timeout_seconds: self.max_timeout_seconds.unwrap_or(60),
+            extra: self.extra,
+        })
+    }
+}
+
+/// Check that `address` is a valid address for `network`
+fn validate_address(network: &str, field: &str, address: &str) -> crate::Result<()> {
+    let invalid = || {
+        crate::X402Error::invalid_payment_requirements(format!(
+            "Invalid {} address: {}",
+            field, address
+        ))
+    };
+
+    #[cfg(feature = "solana")]
+    if crate::solana::is_solana_network(network) {
+        let bytes = bs58::decode(address).into_vec().map_err(|_| invalid())?;
+        return if bytes.len() == 32 {
+            Ok(())
+        } else {
+            Err(invalid())
+        };
+    }
+    #[cfg(not(feature = "solana"))]
+    let _ = network;
+
+    let hex = address.strip_prefix("0x").ok_or_else(invalid)?;
+    hex.parse::<ethereum_types::Address>()
+        .map(|_| ())
+        .map_err(|_| invalid())
 }
 
 /// Payment payload for client payment authorization
@@ -219,14 +960,58 @@ impl PaymentPayload {
         Ok(payload)
     }
 
+    /// Decode a base64-encoded payment payload, rejecting unknown fields
+    pub fn from_base64_strict(encoded: &str) -> crate::Result<Self> {
+        use base64::{engine::general_purpose, Engine as _};
+        let decoded = general_purpose::STANDARD.decode(encoded)?;
+        Self::from_json_strict(&decoded)
+    }
+
+    /// Parse a JSON payment payload, rejecting unknown fields
+    pub fn from_json_strict(json: &[u8]) -> crate::Result<Self> {
+        let mut deserializer = serde_json::Deserializer::from_slice(json);
+        let payload = strict::PaymentPayloadDef::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(payload)
+    }
+
     /// Encode the payment payload to base64
     pub fn to_base64(&self) -> crate::Result<String> {
         use base64::{engine::general_purpose, Engine as _};
         let json = serde_json::to_string(self)?;
         Ok(general_purpose::STANDARD.encode(json))
     }
+
+    /// Decode a payment payload from an `X-PAYMENT` header value
+    pub fn from_header_value(value: &http::HeaderValue) -> crate::Result<Self> {
+        let encoded = value.to_str().map_err(|e| {
+            crate::X402Error::invalid_payment_payload(format!("Invalid header value: {}", e))
+        })?;
+        Self::from_base64(encoded)
+    }
+
+    /// Encode the payment payload as an `X-PAYMENT` header value
+    pub fn to_header_value(&self) -> crate::Result<http::HeaderValue> {
+        http::HeaderValue::from_str(&self.to_base64()?).map_err(|e| {
+            crate::X402Error::invalid_payment_payload(format!("Invalid header value: {}", e))
+        })
+    }
+
+    /// Check that the signature recovers to the authorization's `from` address
+    ///
+    /// Use before sending to catch payloads signed by the wrong key.
+    pub fn verify_signature(&self) -> crate::Result<bool> {
+        crate::crypto::signature::verify_payment_payload(
+            &self.payload,
+            &self.payload.authorization.from,
+            &self.network,
+        )
+    }
 }
 
+/// Default clock skew tolerated when checking authorization validity windows
+pub const DEFAULT_CLOCK_SKEW: Duration = Duration::from_secs(5);
+
 /// Exact EVM payment payload (EIP-3009)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExactEvmPayload {
@@ -236,6 +1021,33 @@ pub struct ExactEvmPayload {
     pub authorization: ExactEvmPayloadAuthorization,
 }
 
+/// Source of the current Unix time
+pub trait Clock {
+    /// Seconds since the Unix epoch
+    fn unix_timestamp(&self) -> i64;
+}
+
+impl<F: Fn() -> i64> Clock for F {
+    fn unix_timestamp(&self) -> i64 {
+        self()
+    }
+}
+
+/// The system wall clock
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+#[cfg(feature = "std")]
+impl Clock for SystemClock {
+    fn unix_timestamp(&self) -> i64 {
+        let elapsed = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+    }
+}
+
 /// EIP-3009 authorization parameters
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExactEvmPayloadAuthorization {
@@ -275,9 +1087,34 @@ impl ExactEvmPayloadAuthorization {
         }
     }
 
+    /// The authorized value in atomic units of a token with `decimals`
+    pub fn amount(&self, decimals: u8) -> crate::Result<Amount> {
+        Amount::from_atomic_str(&self.value, decimals)
+    }
+
     /// Check if the authorization is currently valid
+    #[cfg(feature = "std")]
     pub fn is_valid_now(&self) -> crate::Result<bool> {
-        let now = Utc::now().timestamp();
+        self.is_valid_now_with_skew(Duration::ZERO)
+    }
+
+    /// Check if the authorization is currently valid, tolerating clock skew
+    ///
+    /// The validity window is widened by `skew` at both ends so that payloads
+    /// signed on a client whose clock is slightly off are still accepted.
+    #[cfg(feature = "std")]
+    pub fn is_valid_now_with_skew(&self, skew: Duration) -> crate::Result<bool> {
+        self.is_valid_with_clock(&SystemClock, skew)
+    }
+
+    /// Check validity against the time reported by `clock`
+    pub fn is_valid_with_clock(&self, clock: &impl Clock, skew: Duration) -> crate::Result<bool> {
+        self.is_valid_at(clock.unix_timestamp(), skew)
+    }
+
+    /// Check validity at Unix time `now`, tolerating `skew` at both ends
+    pub fn is_valid_at(&self, now: i64, skew: Duration) -> crate::Result<bool> {
+        let skew = i64::try_from(skew.as_secs()).unwrap_or(i64::MAX);
         let valid_after: i64 = self.valid_after.parse().map_err(|_| {
             crate::X402Error::invalid_authorization("Invalid valid_after timestamp")
         })?;
@@ -285,7 +1122,7 @@ impl ExactEvmPayloadAuthorization {
             crate::X402Error::invalid_authorization("Invalid valid_before timestamp")
         })?;
 
-        Ok(now >= valid_after && now <= valid_before)
+        Ok(now >= valid_after.saturating_sub(skew) && now <= valid_before.saturating_add(skew))
     }
 
     /// Get the validity duration
@@ -311,34 +1148,111 @@ pub struct VerifyResponse {
     #[serde(rename = "invalidReason", skip_serializing_if = "Option::is_none")]
     pub invalid_reason: Option<String>,
     /// Payer's address
+    ///
+    /// Authoritative only when the facilitator reported it. If a valid response
+    /// omits it, [`fill_payer`](Self::fill_payer) derives it from the payload's
+    /// `authorization.from`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payer: Option<String>,
 }
 
+impl VerifyResponse {
+    /// Fill a missing payer on a valid response from the payload's `authorization.from`
+    pub fn fill_payer(mut self, payload: &PaymentPayload) -> Self {
+        if self.is_valid && self.payer.is_none() {
+            self.payer = Some(payload.payload.authorization.from.clone());
+        }
+        self
+    }
+}
+
 /// Payment settlement response
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SettleResponse {
     /// Whether the settlement was successful
     pub success: bool,
     /// Error reason if settlement failed
     #[serde(rename = "errorReason", skip_serializing_if = "Option::is_none")]
     pub error_reason: Option<String>,
+    /// Payer address if applicable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
     /// Transaction hash or identifier
     pub transaction: String,
     /// Network where the transaction was executed
     pub network: String,
-    /// Payer address if applicable
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub payer: Option<String>,
+    /// Facilitator fee taken from the settled amount, in atomic units
+    #[serde(rename = "feeAmount", default, skip_serializing_if = "Option::is_none")]
+    pub fee_amount: Option<String>,
+    /// Amount forwarded to the resource owner after the fee, in atomic units
+    #[serde(rename = "netAmount", default, skip_serializing_if = "Option::is_none")]
+    pub net_amount: Option<String>,
+}
+
+/// Facilitator fee charged on top of the resource price
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeePolicy {
+    /// Fee in basis points (1/100th of a percent) of the price, rounded up
+    BasisPoints(u16),
+    /// Fixed fee in atomic units
+    Flat(u128),
+}
+
+impl FeePolicy {
+    /// Compute the fee owed on `price` (in atomic units)
+    pub fn fee_for(&self, price: u128) -> crate::Result<u128> {
+        match *self {
+            Self::BasisPoints(bps) => price
+                .checked_mul(u128::from(bps))
+                .map(|scaled| scaled.div_ceil(10_000))
+                .ok_or_else(|| crate::X402Error::invalid_payment_requirements("Fee overflow")),
+            Self::Flat(fee) => Ok(fee),
+        }
+    }
+
+    /// Total the payer must authorize: `price` plus the fee
+    pub fn total_for(&self, price: u128) -> crate::Result<u128> {
+        price
+            .checked_add(self.fee_for(price)?)
+            .ok_or_else(|| crate::X402Error::invalid_payment_requirements("Fee overflow"))
+    }
 }
 
 impl SettleResponse {
+    /// Report the fee taken from `gross` and the net forwarded to the payee
+    pub fn with_fee_breakdown(mut self, gross: u128, fee: u128) -> Self {
+        self.fee_amount = Some(fee.to_string());
+        self.net_amount = Some(gross.saturating_sub(fee).to_string());
+        self
+    }
+
     /// Encode the settle response to base64
     pub fn to_base64(&self) -> crate::Result<String> {
         use base64::{engine::general_purpose, Engine as _};
         let json = serde_json::to_string(self)?;
         Ok(general_purpose::STANDARD.encode(json))
     }
+
+    /// Decode a base64-encoded settle response
+    pub fn from_base64(encoded: &str) -> crate::Result<Self> {
+        use base64::{engine::general_purpose, Engine as _};
+        let decoded = general_purpose::STANDARD.decode(encoded)?;
+        Ok(serde_json::from_slice(&decoded)?)
+    }
+
+    /// Decode a settle response from an `X-PAYMENT-RESPONSE` header value
+    pub fn from_header_value(value: &http::HeaderValue) -> crate::Result<Self> {
+        let encoded = value
+            .to_str()
+            .map_err(|e| crate::X402Error::unexpected(format!("Invalid header value: {}", e)))?;
+        Self::from_base64(encoded)
+    }
+
+    /// Encode the settle response as an `X-PAYMENT-RESPONSE` header value
+    pub fn to_header_value(&self) -> crate::Result<http::HeaderValue> {
+        http::HeaderValue::from_str(&self.to_base64()?)
+            .map_err(|e| crate::X402Error::unexpected(format!("Invalid header value: {}", e)))
+    }
 }
 
 /// Facilitator configuration
@@ -348,8 +1262,84 @@ pub struct FacilitatorConfig {
     pub url: String,
     /// Request timeout
     pub timeout: Option<Duration>,
+    /// Timeout for verify requests, overriding `timeout`
+    pub verify_timeout: Option<Duration>,
+    /// Timeout for settle requests, overriding `timeout`
+    pub settle_timeout: Option<Duration>,
     /// Function to create authentication headers
     pub create_auth_headers: Option<AuthHeadersFnArc>,
+    /// Endpoint paths appended to the base URL
+    pub endpoints: FacilitatorEndpoints,
+    /// Shared HTTP client to reuse instead of building a new one
+    pub http_client: Option<reqwest::Client>,
+    /// How long a `/supported` response is reused; uncached when `None`
+    pub supported_cache_ttl: Option<Duration>,
+}
+
+/// Facilitator endpoint paths, relative to the facilitator base URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FacilitatorEndpoints {
+    /// Payment verification path
+    pub verify: String,
+    /// Payment settlement path
+    pub settle: String,
+    /// Supported kinds path
+    pub supported: String,
+    /// Discovery resources path
+    pub discovery: String,
+}
+
+impl FacilitatorEndpoints {
+    /// Create the standard endpoint paths
+    pub fn new() -> Self {
+        Self {
+            verify: "/verify".to_string(),
+            settle: "/settle".to_string(),
+            supported: "/supported".to_string(),
+            discovery: "/discovery/resources".to_string(),
+        }
+    }
+
+    /// Mount all endpoints under `prefix` (e.g., "/platform/v2/x402")
+    pub fn with_prefix(self, prefix: &str) -> Self {
+        let prefix = prefix.trim_end_matches('/');
+        Self {
+            verify: format!("{}{}", prefix, self.verify),
+            settle: format!("{}{}", prefix, self.settle),
+            supported: format!("{}{}", prefix, self.supported),
+            discovery: format!("{}{}", prefix, self.discovery),
+        }
+    }
+
+    /// Set the verification path
+    pub fn with_verify(mut self, path: impl Into<String>) -> Self {
+        self.verify = path.into();
+        self
+    }
+
+    /// Set the settlement path
+    pub fn with_settle(mut self, path: impl Into<String>) -> Self {
+        self.settle = path.into();
+        self
+    }
+
+    /// Set the supported kinds path
+    pub fn with_supported(mut self, path: impl Into<String>) -> Self {
+        self.supported = path.into();
+        self
+    }
+
+    /// Set the discovery resources path
+    pub fn with_discovery(mut self, path: impl Into<String>) -> Self {
+        self.discovery = path.into();
+        self
+    }
+}
+
+impl Default for FacilitatorEndpoints {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl std::fmt::Debug for FacilitatorConfig {
@@ -357,7 +1347,12 @@ impl std::fmt::Debug for FacilitatorConfig {
         f.debug_struct("FacilitatorConfig")
             .field("url", &self.url)
             .field("timeout", &self.timeout)
+            .field("verify_timeout", &self.verify_timeout)
+            .field("settle_timeout", &self.settle_timeout)
             .field("create_auth_headers", &"<function>")
+            .field("endpoints", &self.endpoints)
+            .field("http_client", &self.http_client.is_some())
+            .field("supported_cache_ttl", &self.supported_cache_ttl)
             .finish()
     }
 }
@@ -368,7 +1363,12 @@ impl FacilitatorConfig {
         Self {
             url: url.into(),
             timeout: None,
+            verify_timeout: None,
+            settle_timeout: None,
             create_auth_headers: None,
+            endpoints: FacilitatorEndpoints::new(),
+            http_client: None,
+            supported_cache_ttl: None,
         }
     }
 
@@ -393,11 +1393,46 @@ impl FacilitatorConfig {
         self
     }
 
+    /// Set the timeout for verify requests
+    pub fn with_verify_timeout(mut self, timeout: Duration) -> Self {
+        self.verify_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for settle requests, which may wait on-chain
+    pub fn with_settle_timeout(mut self, timeout: Duration) -> Self {
+        self.settle_timeout = Some(timeout);
+        self
+    }
+
     /// Set the auth headers creator
     pub fn with_auth_headers(mut self, creator: AuthHeadersFnBox) -> Self {
         self.create_auth_headers = Some(Arc::from(creator));
         self
     }
+
+    /// Override the endpoint paths
+    pub fn with_endpoints(mut self, endpoints: FacilitatorEndpoints) -> Self {
+        self.endpoints = endpoints;
+        self
+    }
+
+    /// Reuse a shared HTTP client and its connection pool
+    ///
+    /// The client is used as-is, so `timeout` is not applied to it.
+    pub fn with_http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Reuse a `/supported` response for `ttl` before fetching it again
+    ///
+    /// The kinds a facilitator supports rarely change, so callers checking
+    /// them per request need not reach the facilitator every time.
+    pub fn with_supported_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.supported_cache_ttl = Some(ttl);
+        self
+    }
 }
 
 impl Default for FacilitatorConfig {
@@ -416,6 +1451,15 @@ pub struct PaymentRequirementsResponse {
     pub error: String,
     /// Array of acceptable payment methods
     pub accepts: Vec<PaymentRequirements>,
+    /// Payer the response concerns, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
+    /// Unix timestamp until which the quoted requirements are valid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<i64>,
+    /// Server's hex ECDSA signature over [`signing_hash`](Self::signing_hash)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
 }
 
 impl PaymentRequirementsResponse {
@@ -425,6 +1469,102 @@ impl PaymentRequirementsResponse {
             x402_version: X402_VERSION,
             error: error.into(),
             accepts,
+            payer: None,
+            expires: None,
+            signature: None,
+        }
+    }
+
+    /// Hash covered by the response's signature
+    ///
+    /// Keccak-256 of the response's canonical JSON: every field except
+    /// `signature`, with object keys sorted and no whitespace.
+    pub fn signing_hash(&self) -> crate::Result<ethereum_types::H256> {
+        let mut value = serde_json::to_value(self)?;
+        if let Value::Object(fields) = &mut value {
+            fields.remove("signature");
+        }
+        let canonical = serde_json::to_vec(&canonical_json(value))?;
+        Ok(ethereum_types::H256::from(
+            crate::crypto::signature::keccak256(&canonical),
+        ))
+    }
+
+    /// Sign the response, so clients trusting `signer` can detect tampering
+    pub fn signed(mut self, signer: &crate::signer::LocalSigner) -> crate::Result<Self> {
+        self.signature = Some(signer.sign_hash_now(self.signing_hash()?)?.to_hex());
+        Ok(self)
+    }
+
+    /// Check that the response was signed by the account at `signer`
+    ///
+    /// Fails with [`X402Error::InvalidSignature`](crate::X402Error::InvalidSignature)
+    /// when the response is unsigned, or when its price, recipient or any other
+    /// field was altered after signing.
+    pub fn verify_signature(&self, signer: &str) -> crate::Result<()> {
+        let signature = self.signature.as_deref().ok_or_else(|| {
+            crate::X402Error::invalid_signature("Payment requirements are not signed")
+        })?;
+        let expected = signer.parse::<ethereum_types::Address>().map_err(|_| {
+            crate::X402Error::invalid_signature(format!("Invalid signer address: {}", signer))
+        })?;
+
+        let recovered = crate::crypto::signature::recover_signer(signature, self.signing_hash()?)?;
+        if recovered != expected {
+            return Err(crate::X402Error::invalid_signature(
+                "Payment requirements were not signed by the expected signer",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Set the payer the response concerns
+    pub fn with_payer(mut self, payer: impl Into<String>) -> Self {
+        self.payer = Some(payer.into());
+        self
+    }
+
+    /// Set when the quoted requirements expire
+    pub fn with_expires(mut self, expires: i64) -> Self {
+        self.expires = Some(expires);
+        self
+    }
+}
+
+/// `value` with the keys of every object in sorted order
+fn canonical_json(value: Value) -> Value {
+    match value {
+        Value::Object(fields) => {
+            let mut fields: Vec<_> = fields.into_iter().collect();
+            fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(key, value)| (key, canonical_json(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(canonical_json).collect()),
+        value => value,
+    }
+}
+
+/// Payment options a resource server publishes at [`WELL_KNOWN_X402_PATH`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentOptions {
+    /// Protocol version
+    #[serde(rename = "x402Version")]
+    pub x402_version: u32,
+    /// Payment requirements the server accepts, as in its 402 responses
+    pub accepts: Vec<PaymentRequirements>,
+}
+
+impl PaymentOptions {
+    /// Create a discovery document listing `accepts`
+    pub fn new(accepts: Vec<PaymentRequirements>) -> Self {
+        Self {
+            x402_version: X402_VERSION,
+            accepts,
         }
     }
 }
@@ -451,6 +1591,62 @@ pub struct SupportedKind {
     pub metadata: Option<Value>,
 }
 
+impl SupportedKind {
+    /// Facilitator fee in basis points, from `metadata.feeBps`
+    pub fn fee_bps(&self) -> Option<u32> {
+        self.metadata_field("feeBps")?
+            .as_u64()
+            .and_then(|bps| u32::try_from(bps).ok())
+    }
+
+    /// Smallest settled amount in atomic units, from `metadata.minAmount`
+    pub fn min_amount(&self) -> Option<u128> {
+        atomic_amount(self.metadata_field("minAmount")?)
+    }
+
+    /// Largest settled amount in atomic units, from `metadata.maxAmount`
+    pub fn max_amount(&self) -> Option<u128> {
+        atomic_amount(self.metadata_field("maxAmount")?)
+    }
+
+    /// Asset addresses settled for this kind, from `metadata.assets`
+    pub fn assets(&self) -> Vec<String> {
+        self.metadata_field("assets")
+            .and_then(Value::as_array)
+            .map(|assets| {
+                assets
+                    .iter()
+                    .filter_map(|asset| asset.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Deserialize the whole metadata object into `T`
+    ///
+    /// Returns `Ok(None)` when the facilitator sent no metadata.
+    pub fn metadata_as<T: serde::de::DeserializeOwned>(&self) -> crate::Result<Option<T>> {
+        self.metadata
+            .clone()
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    fn metadata_field(&self, key: &str) -> Option<&Value> {
+        self.metadata.as_ref()?.get(key)
+    }
+}
+
+/// Atomic amount given as a decimal string or a JSON number
+fn atomic_amount(value: &Value) -> Option<u128> {
+    match value {
+        Value::String(amount) => amount.parse().ok(),
+        Value::Number(amount) => amount.as_u64().map(u128::from),
+        _ => None,
+    }
+}
+
 /// Discovery API resource
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscoveryResource {
@@ -494,6 +1690,73 @@ pub struct PaginationInfo {
     pub total: u32,
 }
 
+/// Strict deserializers mirroring the wire types with unknown fields denied
+mod strict {
+    use super::{
+        ExactEvmPayload, ExactEvmPayloadAuthorization, PaymentPayload, PaymentRequirements,
+    };
+    use serde::Deserialize;
+    use serde_json::Value;
+
+    #[derive(Deserialize)]
+    #[serde(remote = "PaymentPayload", deny_unknown_fields)]
+    pub(super) struct PaymentPayloadDef {
+        #[serde(rename = "x402Version")]
+        x402_version: u32,
+        scheme: String,
+        network: String,
+        #[serde(with = "ExactEvmPayloadDef")]
+        payload: ExactEvmPayload,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(remote = "ExactEvmPayload", deny_unknown_fields)]
+    struct ExactEvmPayloadDef {
+        signature: String,
+        #[serde(with = "ExactEvmPayloadAuthorizationDef")]
+        authorization: ExactEvmPayloadAuthorization,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(remote = "ExactEvmPayloadAuthorization", deny_unknown_fields)]
+    struct ExactEvmPayloadAuthorizationDef {
+        from: String,
+        to: String,
+        value: String,
+        #[serde(rename = "validAfter")]
+        valid_after: String,
+        #[serde(rename = "validBefore")]
+        valid_before: String,
+        nonce: String,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(remote = "PaymentRequirements", deny_unknown_fields)]
+    pub(super) struct PaymentRequirementsDef {
+        scheme: String,
+        network: String,
+        #[serde(rename = "maxAmountRequired")]
+        max_amount_required: String,
+        asset: String,
+        #[serde(rename = "payTo")]
+        pay_to: String,
+        resource: String,
+        description: String,
+        #[serde(
+            rename = "mimeType",
+            default,
+            deserialize_with = "super::empty_string_as_none::deserialize"
+        )]
+        mime_type: Option<String>,
+        #[serde(rename = "outputSchema", default)]
+        output_schema: Option<Value>,
+        #[serde(rename = "maxTimeoutSeconds")]
+        max_timeout_seconds: u32,
+        #[serde(default)]
+        extra: Option<Value>,
+    }
+}
+
 /// Common network configurations
 pub mod networks {
     /// Base mainnet configuration
@@ -504,6 +1767,12 @@ pub mod networks {
     pub const AVALANCHE_MAINNET: &str = "avalanche";
     /// Avalanche Fuji testnet configuration
     pub const AVALANCHE_FUJI: &str = "avalanche-fuji";
+    /// Solana mainnet configuration
+    #[cfg(feature = "solana")]
+    pub const SOLANA_MAINNET: &str = "solana";
+    /// Solana devnet configuration
+    #[cfg(feature = "solana")]
+    pub const SOLANA_DEVNET: &str = "solana-devnet";
 
     /// Get USDC contract address for a network
     pub fn get_usdc_address(network: &str) -> Option<&'static str> {
@@ -512,19 +1781,22 @@ pub mod networks {
             BASE_SEPOLIA => Some("0x036CbD53842c5426634e7929541eC2318f3dCF7e"),
             AVALANCHE_MAINNET => Some("0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E"),
             AVALANCHE_FUJI => Some("0x5425890298aed601595a70AB815c96711a31Bc65"),
+            #[cfg(feature = "solana")]
+            SOLANA_MAINNET => Some("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+            #[cfg(feature = "solana")]
+            SOLANA_DEVNET => Some("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU"),
             _ => None,
         }
     }
 
-    /// Check if a network is supported
+    /// Check if an EVM network is supported
     pub fn is_supported(network: &str) -> bool {
-        matches!(
-            network,
-            BASE_MAINNET | BASE_SEPOLIA | AVALANCHE_MAINNET | AVALANCHE_FUJI
-        )
+        all_supported().contains(&network)
     }
 
-    /// Get all supported networks
+    /// Get all supported EVM networks
+    ///
+    /// Solana clusters are listed separately by [`solana`].
     pub fn all_supported() -> Vec<&'static str> {
         vec![
             BASE_MAINNET,
@@ -533,10 +1805,19 @@ pub mod networks {
             AVALANCHE_FUJI,
         ]
     }
+
+    /// Get all supported Solana clusters
+    #[cfg(feature = "solana")]
+    pub fn solana() -> Vec<&'static str> {
+        vec![SOLANA_MAINNET, SOLANA_DEVNET]
+    }
 }
 
 /// Common payment schemes
 pub mod schemes {
     /// Exact payment scheme (EIP-3009)
     pub const EXACT: &str = "exact";
+    /// Up-to payment scheme: the payer authorizes a ceiling and the server
+    /// settles the amount actually consumed
+    pub const UPTO: &str = "upto";
 }
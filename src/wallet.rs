@@ -1,58 +1,85 @@
 //! Real wallet integration for x402 payments
 //!
 //! This module provides real wallet implementations for creating and signing
-//! x402 payment payloads with actual private keys and EIP-712 signatures.
+//! x402 payment payloads with EIP-712 signatures. Signing is delegated to a
+//! [`Signer`], so keys can live in process memory or in external hardware.
 
 use crate::{
+    blockchain::BlockchainClient,
     crypto::{
-        eip712::{create_transfer_with_authorization_hash, Domain},
-        signature::{generate_nonce, sign_message_hash, verify_payment_payload},
+        eip712::{
+            create_native_transfer_authorization_hash, create_transfer_with_authorization_hash,
+            native_payment_domain, token_domain,
+        },
+        signature::{
+            verify_eip712_signature, verify_native_payment_payload,
+            verify_payment_payload_with_extra, NonceSource, RandomNonceSource,
+        },
     },
+    signer::{LocalSigner, Signer},
     types::{ExactEvmPayload, ExactEvmPayloadAuthorization, PaymentPayload, PaymentRequirements},
     Result, X402Error,
 };
-use ethereum_types::{Address, U256};
+use ethereum_types::{Address, H256, U256};
 use std::str::FromStr;
+use std::sync::Arc;
 
 /// Wallet implementation for x402 payments
-#[derive(Debug)]
+#[derive(Clone)]
 pub struct Wallet {
-    /// Private key for signing (in production, this should come from secure storage)
-    private_key: String,
+    /// Signer holding (or fronting) the account's key
+    signer: Arc<dyn Signer>,
     /// Network configuration
     network: String,
+    /// Source of authorization nonces
+    nonce_source: Arc<dyn NonceSource>,
 }
 
 impl Wallet {
-    /// Create a new wallet instance
+    /// Create a new wallet instance backed by the given signer
     ///
     /// # Security Note
-    /// In production, the private key should be loaded from:
+    /// In production, prefer a signer whose key lives in:
     /// - Hardware wallets (Ledger, Trezor)
     /// - Encrypted key stores
-    /// - Secure environment variables
     /// - Key management services (AWS KMS, Azure Key Vault)
-    pub fn new(private_key: String, network: String) -> Self {
+    pub fn new(signer: impl Signer + 'static, network: impl Into<String>) -> Self {
+        Self::from_signer(Arc::new(signer), network)
+    }
+
+    /// Create a new wallet instance from a shared signer
+    pub fn from_signer(signer: Arc<dyn Signer>, network: impl Into<String>) -> Self {
         Self {
-            private_key,
-            network,
+            signer,
+            network: network.into(),
+            nonce_source: Arc::new(RandomNonceSource),
         }
     }
 
+    /// Draw authorization nonces from `source` instead of the secure random default
+    ///
+    /// Intended for reproducible tests and deterministic agents; predictable
+    /// nonces let others pre-compute authorizations, so keep the default in
+    /// production.
+    pub fn with_nonce_source(mut self, source: impl NonceSource + 'static) -> Self {
+        self.nonce_source = Arc::new(source);
+        self
+    }
+
     /// Create a payment payload with real EIP-712 signature
     ///
     /// This is the production-ready implementation that:
     /// 1. Generates cryptographically secure random nonce
     /// 2. Creates proper EIP-712 message hash
-    /// 3. Signs with the user's private key
+    /// 3. Signs with the wallet's signer
     /// 4. Verifies the signature before returning
-    pub fn create_signed_payment_payload(
+    pub async fn create_signed_payment_payload(
         &self,
         requirements: &PaymentRequirements,
         from_address: &str,
     ) -> Result<PaymentPayload> {
-        // Step 1: Generate cryptographically secure nonce
-        let nonce = generate_nonce();
+        // Step 1: Generate a nonce (cryptographically secure by default)
+        let nonce = self.nonce_source.next_nonce();
 
         // Step 2: Set appropriate timestamps
         let now = chrono::Utc::now().timestamp();
@@ -71,30 +98,16 @@ impl Wallet {
 
         // Step 4: Create the EIP-712 message hash
         let network_config = self.get_network_config()?;
-        let domain = Domain {
-            name: "USD Coin".to_string(),
-            version: "2".to_string(),
-            chain_id: network_config.chain_id,
-            verifying_contract: network_config.usdc_contract,
-        };
-
-        let message_hash = create_transfer_with_authorization_hash(
-            &domain,
-            Address::from_str(from_address)
-                .map_err(|_| X402Error::invalid_authorization("Invalid from address format"))?,
-            Address::from_str(&requirements.pay_to)
-                .map_err(|_| X402Error::invalid_authorization("Invalid pay_to address format"))?,
-            U256::from_str_radix(&requirements.max_amount_required, 10)
-                .map_err(|_| X402Error::invalid_authorization("Invalid amount format"))?,
-            U256::from_str_radix(&authorization.valid_after, 10)
-                .map_err(|_| X402Error::invalid_authorization("Invalid valid_after format"))?,
-            U256::from_str_radix(&authorization.valid_before, 10)
-                .map_err(|_| X402Error::invalid_authorization("Invalid valid_before format"))?,
+        let message_hash = authorization_hash(
+            &authorization,
             nonce,
+            &network_config,
+            requirements.is_native(),
+            requirements.extra.as_ref(),
         )?;
 
-        // Step 5: Sign the message hash with the private key
-        let signature = sign_message_hash(message_hash, &self.private_key)?;
+        // Step 5: Sign the message hash through the signer
+        let signature = self.signer.sign_hash(message_hash).await?.to_hex();
 
         // Step 6: Create the payload
         let payload = ExactEvmPayload {
@@ -106,8 +119,16 @@ impl Wallet {
             PaymentPayload::new(&requirements.scheme, &requirements.network, payload);
 
         // Step 7: Verify the signature (production best practice)
-        let is_valid =
-            verify_payment_payload(&payment_payload.payload, from_address, &self.network)?;
+        let is_valid = if requirements.is_native() {
+            verify_native_payment_payload(&payment_payload.payload, from_address, &self.network)?
+        } else {
+            verify_payment_payload_with_extra(
+                &payment_payload.payload,
+                from_address,
+                &self.network,
+                requirements.extra.as_ref(),
+            )?
+        };
 
         if !is_valid {
             return Err(X402Error::invalid_signature(
@@ -118,9 +139,172 @@ impl Wallet {
         Ok(payment_payload)
     }
 
+    /// Get the address controlled by this wallet
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    /// Get the signer backing this wallet
+    pub fn signer(&self) -> &Arc<dyn Signer> {
+        &self.signer
+    }
+
+    /// Build and sign a payment payload for the given requirements
+    ///
+    /// The authorization is issued from the wallet's own address with a fresh
+    /// nonce, stays valid for `max_timeout_seconds`, and is signed against the
+    /// domain of the requirement's network rather than the wallet's default.
+    /// Native gas-token requirements are signed as a `NativeTransferAuthorization`.
+    pub async fn authorize_payment(
+        &self,
+        requirements: &PaymentRequirements,
+    ) -> Result<PaymentPayload> {
+        let from = self.address();
+        let network_config = WalletNetworkConfig::for_network(&requirements.network)?;
+        let nonce = self.nonce_source.next_nonce();
+
+        let now = chrono::Utc::now().timestamp();
+        let valid_after = (now - 60).to_string(); // Allow 1 minute leeway
+        let valid_before = (now + i64::from(requirements.max_timeout_seconds)).to_string();
+
+        let authorization = ExactEvmPayloadAuthorization::new(
+            format!("{:?}", from),
+            &requirements.pay_to,
+            &requirements.max_amount_required,
+            valid_after,
+            valid_before,
+            format!("{:?}", nonce),
+        );
+
+        let message_hash = authorization_hash(
+            &authorization,
+            nonce,
+            &network_config,
+            requirements.is_native(),
+            requirements.extra.as_ref(),
+        )?;
+        let signature = self.signer.sign_hash(message_hash).await?.to_hex();
+
+        if !verify_eip712_signature(&signature, message_hash, from)? {
+            return Err(X402Error::invalid_signature(
+                "Generated signature verification failed",
+            ));
+        }
+
+        let payload = ExactEvmPayload {
+            signature,
+            authorization,
+        };
+
+        Ok(PaymentPayload::new(
+            &requirements.scheme,
+            &requirements.network,
+            payload,
+        ))
+    }
+
+    /// Like [`Wallet::authorize_payment`], but first checks that the wallet's
+    /// USDC balance covers the required amount
+    pub async fn authorize_payment_with_balance_check(
+        &self,
+        requirements: &PaymentRequirements,
+        client: &BlockchainClient,
+    ) -> Result<PaymentPayload> {
+        let from = self.address();
+        let balance = client.get_usdc_balance(&format!("{:?}", from)).await?;
+
+        let available = match balance
+            .token_balance
+            .as_deref()
+            .map(|b| b.trim_start_matches("0x"))
+        {
+            Some(hex) if !hex.is_empty() => U256::from_str_radix(hex, 16)
+                .map_err(|_| X402Error::network_error("Invalid token balance in RPC response"))?,
+            _ => U256::zero(),
+        };
+        let required = U256::from_dec_str(&requirements.max_amount_required)
+            .map_err(|_| X402Error::invalid_payment_requirements("Invalid required amount"))?;
+
+        if available < required {
+            return Err(X402Error::InsufficientBalance {
+                needed: required.to_string(),
+                available: available.to_string(),
+            });
+        }
+
+        self.authorize_payment(requirements).await
+    }
+
     /// Get network configuration for the current network
     pub fn get_network_config(&self) -> Result<WalletNetworkConfig> {
-        match self.network.as_str() {
+        WalletNetworkConfig::for_network(&self.network)
+    }
+
+    /// Get the network name
+    pub fn network(&self) -> &str {
+        &self.network
+    }
+}
+
+impl std::fmt::Debug for Wallet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Wallet")
+            .field("address", &self.signer.address())
+            .field("network", &self.network)
+            .finish()
+    }
+}
+
+/// Compute the EIP-712 hash of a `TransferWithAuthorization` message, or of a
+/// `NativeTransferAuthorization` when paying in the native gas token
+///
+/// `extra` is the payment requirements' `extra`, naming the token's domain.
+pub(crate) fn authorization_hash(
+    authorization: &ExactEvmPayloadAuthorization,
+    nonce: H256,
+    network_config: &WalletNetworkConfig,
+    native: bool,
+    extra: Option<&serde_json::Value>,
+) -> Result<H256> {
+    let (domain, hash_fn): (_, fn(_, _, _, _, _, _, _) -> _) = if native {
+        (
+            native_payment_domain(network_config.chain_id),
+            create_native_transfer_authorization_hash,
+        )
+    } else {
+        (
+            token_domain(network_config.chain_id, network_config.usdc_contract, extra),
+            create_transfer_with_authorization_hash,
+        )
+    };
+
+    hash_fn(
+        &domain,
+        Address::from_str(&authorization.from)
+            .map_err(|_| X402Error::invalid_authorization("Invalid from address format"))?,
+        Address::from_str(&authorization.to)
+            .map_err(|_| X402Error::invalid_authorization("Invalid pay_to address format"))?,
+        U256::from_str_radix(&authorization.value, 10)
+            .map_err(|_| X402Error::invalid_authorization("Invalid amount format"))?,
+        U256::from_str_radix(&authorization.valid_after, 10)
+            .map_err(|_| X402Error::invalid_authorization("Invalid valid_after format"))?,
+        U256::from_str_radix(&authorization.valid_before, 10)
+            .map_err(|_| X402Error::invalid_authorization("Invalid valid_before format"))?,
+        nonce,
+    )
+}
+
+/// Wallet network configuration for different blockchains
+#[derive(Debug, Clone)]
+pub struct WalletNetworkConfig {
+    pub chain_id: u64,
+    pub usdc_contract: Address,
+}
+
+impl WalletNetworkConfig {
+    /// Get the configuration for a network by name
+    pub fn for_network(network: &str) -> Result<Self> {
+        match network {
             "base-sepolia" => Ok(WalletNetworkConfig {
                 chain_id: 84532,
                 usdc_contract: Address::from_str("0x036CbD53842c5426634e7929541eC2318f3dCF7e")
@@ -143,22 +327,71 @@ impl Wallet {
             }),
             _ => Err(X402Error::invalid_network(format!(
                 "Unsupported network: {}",
-                self.network
+                network
             ))),
         }
     }
+}
 
-    /// Get the network name
+/// Default BIP-44 derivation path prefix for Ethereum accounts
+pub const ETHEREUM_DERIVATION_PATH_PREFIX: &str = "m/44'/60'/0'/0";
+
+/// Hierarchical deterministic wallet backed by a BIP-39 mnemonic seed
+///
+/// Derives any number of payment wallets from a single mnemonic, e.g. one
+/// sub-account per agent.
+pub struct HdWallet {
+    /// BIP-39 seed derived from the mnemonic
+    seed: [u8; 64],
+    /// Network assigned to derived wallets
+    network: String,
+}
+
+impl HdWallet {
+    /// Create an HD wallet from a BIP-39 mnemonic phrase
+    ///
+    /// The mnemonic checksum is validated; an invalid phrase yields a config error.
+    pub fn from_mnemonic(phrase: &str, network: &str) -> Result<Self> {
+        let mnemonic = bip39::Mnemonic::parse_normalized(phrase.trim())
+            .map_err(|e| X402Error::config(format!("Invalid mnemonic phrase: {}", e)))?;
+
+        Ok(Self {
+            seed: mnemonic.to_seed(""),
+            network: network.to_string(),
+        })
+    }
+
+    /// Derive the wallet at `m/44'/60'/0'/0/{index}`
+    pub fn derive(&self, index: u32) -> Result<Wallet> {
+        self.derive_path(&format!("{}/{}", ETHEREUM_DERIVATION_PATH_PREFIX, index))
+    }
+
+    /// Derive the wallet at an arbitrary BIP-32 derivation path
+    pub fn derive_path(&self, path: &str) -> Result<Wallet> {
+        let path = path
+            .parse::<bip32::DerivationPath>()
+            .map_err(|e| X402Error::config(format!("Invalid derivation path: {}", e)))?;
+
+        let xprv = bip32::XPrv::derive_from_path(self.seed, &path)
+            .map_err(|e| X402Error::config(format!("Key derivation failed: {}", e)))?;
+
+        let signer = LocalSigner::new(format!("0x{}", hex::encode(xprv.to_bytes())))?;
+        Ok(Wallet::new(signer, self.network.clone()))
+    }
+
+    /// Get the network assigned to derived wallets
     pub fn network(&self) -> &str {
         &self.network
     }
 }
 
-/// Wallet network configuration for different blockchains
-#[derive(Debug, Clone)]
-pub struct WalletNetworkConfig {
-    pub chain_id: u64,
-    pub usdc_contract: Address,
+impl std::fmt::Debug for HdWallet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HdWallet")
+            .field("seed", &"<redacted>")
+            .field("network", &self.network)
+            .finish()
+    }
 }
 
 /// Wallet factory for creating wallets from different sources
@@ -178,7 +411,19 @@ impl WalletFactory {
         hex::decode(&private_key[2..])
             .map_err(|_| X402Error::invalid_authorization("Invalid hex in private key"))?;
 
-        Ok(Wallet::new(private_key.to_string(), network.to_string()))
+        let signer = LocalSigner::new(private_key)
+            .map_err(|_| X402Error::invalid_authorization("Invalid private key"))?;
+        Ok(Wallet::new(signer, network))
+    }
+
+    /// Create wallet from a BIP-39 mnemonic at the given derivation path
+    pub fn from_mnemonic(phrase: &str, path: &str, network: &str) -> Result<Wallet> {
+        HdWallet::from_mnemonic(phrase, network)?.derive_path(path)
+    }
+
+    /// Create an HD wallet from a BIP-39 mnemonic for deriving many accounts
+    pub fn hd_from_mnemonic(phrase: &str, network: &str) -> Result<HdWallet> {
+        HdWallet::from_mnemonic(phrase, network)
     }
 
     /// Create wallet from environment variable
@@ -213,13 +458,14 @@ impl WalletFactory {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::crypto::signature::verify_payment_payload;
 
     #[test]
     fn test_wallet_creation() {
-        let wallet = Wallet::new(
-            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
-            "base-sepolia".to_string(),
-        );
+        let signer =
+            LocalSigner::new("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
+                .unwrap();
+        let wallet = Wallet::new(signer, "base-sepolia");
         assert_eq!(wallet.network(), "base-sepolia");
     }
 
@@ -276,12 +522,274 @@ mod tests {
         assert!(wallet.is_err(), "Missing 0x prefix should fail");
     }
 
+    fn test_requirements(network: &str) -> PaymentRequirements {
+        let mut requirements = PaymentRequirements::new(
+            "exact",
+            network,
+            "1000000",
+            "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            "https://example.com/test",
+            "Test payment",
+        );
+        requirements.max_timeout_seconds = 120;
+        requirements
+    }
+
+    #[tokio::test]
+    async fn test_authorize_payment_passes_verification() {
+        let wallet = WalletFactory::from_private_key(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+            "base-sepolia",
+        )
+        .unwrap();
+        let from = format!("{:?}", wallet.address());
+
+        let requirements = test_requirements("base-sepolia");
+        let payload = wallet.authorize_payment(&requirements).await.unwrap();
+
+        assert_eq!(payload.network, "base-sepolia");
+        assert_eq!(payload.payload.authorization.from, from);
+        assert_eq!(payload.payload.authorization.to, requirements.pay_to);
+        assert_eq!(payload.payload.authorization.value, "1000000");
+        assert!(payload.payload.authorization.is_valid_now().unwrap());
+
+        let valid_before: i64 = payload.payload.authorization.valid_before.parse().unwrap();
+        let remaining = valid_before - chrono::Utc::now().timestamp();
+        assert!(remaining > 110 && remaining <= 120);
+
+        assert!(verify_payment_payload(&payload.payload, &from, "base-sepolia").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_payload_verify_signature() {
+        let wallet = WalletFactory::from_private_key(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+            "base-sepolia",
+        )
+        .unwrap();
+
+        let mut payload = wallet
+            .authorize_payment(&test_requirements("base-sepolia"))
+            .await
+            .unwrap();
+        assert!(payload.verify_signature().unwrap());
+
+        // Claiming a different signer invalidates the payload
+        payload.payload.authorization.from =
+            "0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string();
+        assert!(!payload.verify_signature().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_authorize_payment_uses_injected_nonce_source() {
+        use crate::crypto::signature::{CounterNonceSource, RngNonceSource};
+
+        let wallet = WalletFactory::from_private_key(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+            "base-sepolia",
+        )
+        .unwrap();
+        let requirements = test_requirements("base-sepolia");
+
+        let seeded = wallet.clone().with_nonce_source(RngNonceSource::seeded(42));
+        let payload = seeded.authorize_payment(&requirements).await.unwrap();
+        assert_eq!(
+            payload.payload.authorization.nonce,
+            "0xa22427226377cc867d51ad3f130af08ad13451de7160efa2b23076fd782de967"
+        );
+        assert!(payload.verify_signature().unwrap());
+
+        let counter = wallet.with_nonce_source(CounterNonceSource::new(1));
+        let payload = counter.authorize_payment(&requirements).await.unwrap();
+        assert_eq!(
+            payload.payload.authorization.nonce,
+            format!("{:?}", H256::from_low_u64_be(1))
+        );
+        let payload = counter
+            .create_signed_payment_payload(&requirements, &format!("{:?}", counter.address()))
+            .await
+            .unwrap();
+        assert_eq!(
+            payload.payload.authorization.nonce,
+            format!("{:?}", H256::from_low_u64_be(2))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authorize_payment_uses_requirement_network() {
+        // Wallet defaults to testnet, but the requirement asks for mainnet
+        let wallet = WalletFactory::from_private_key(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+            "base-sepolia",
+        )
+        .unwrap();
+        let from = format!("{:?}", wallet.address());
+
+        let payload = wallet
+            .authorize_payment(&test_requirements("base"))
+            .await
+            .unwrap();
+
+        assert_eq!(payload.network, "base");
+        assert!(verify_payment_payload(&payload.payload, &from, "base").unwrap());
+        assert!(!verify_payment_payload(&payload.payload, &from, "base-sepolia").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_authorize_payment_unsupported_network() {
+        let wallet = WalletFactory::from_private_key(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+            "base-sepolia",
+        )
+        .unwrap();
+
+        let result = wallet
+            .authorize_payment(&test_requirements("unknown-network"))
+            .await;
+        assert!(matches!(result, Err(X402Error::InvalidNetwork { .. })));
+    }
+
+    /// Signer returning a canned signature and recording the hashes it was asked to sign
+    struct MockSigner {
+        address: Address,
+        signature: crate::signer::Signature,
+        signed: std::sync::Mutex<Vec<H256>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Signer for MockSigner {
+        async fn sign_hash(&self, hash: H256) -> Result<crate::signer::Signature> {
+            self.signed.lock().unwrap().push(hash);
+            Ok(self.signature)
+        }
+
+        fn address(&self) -> Address {
+            self.address
+        }
+    }
+
+    #[tokio::test]
+    async fn test_authorize_payment_signs_through_signer() {
+        let address = Address::from_str("0x857b06519E91e3A54538791bDbb0E22373e36b66").unwrap();
+        let signer = Arc::new(MockSigner {
+            address,
+            signature: crate::signer::Signature::new(
+                H256::repeat_byte(0x11),
+                H256::repeat_byte(0x22),
+                0,
+            ),
+            signed: std::sync::Mutex::new(Vec::new()),
+        });
+        let wallet = Wallet::from_signer(signer.clone(), "base-sepolia");
+        assert_eq!(wallet.address(), address);
+
+        // The canned signature does not recover to the signer's address,
+        // so the wallet must refuse to hand it out
+        let result = wallet
+            .authorize_payment(&test_requirements("base-sepolia"))
+            .await;
+        assert!(matches!(result, Err(X402Error::InvalidSignature { .. })));
+        assert_eq!(signer.signed.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_authorize_payment_with_balance_check() {
+        let wallet = WalletFactory::from_private_key(
+            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef",
+            "base-sepolia",
+        )
+        .unwrap();
+        let requirements = test_requirements("base-sepolia");
+
+        let mut server = mockito::Server::new_async().await;
+        let low = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":"0x0f4240"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let client = BlockchainClient::new(server.url(), "base-sepolia".to_string());
+
+        // Exactly 1 USDC covers the 1 USDC requirement
+        assert!(wallet
+            .authorize_payment_with_balance_check(&requirements, &client)
+            .await
+            .is_ok());
+        low.assert_async().await;
+
+        server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","id":1,"result":"0x0f423f"}"#)
+            .create_async()
+            .await;
+
+        let result = wallet
+            .authorize_payment_with_balance_check(&requirements, &client)
+            .await;
+        match result {
+            Err(X402Error::InsufficientBalance { needed, available }) => {
+                assert_eq!(needed, "1000000");
+                assert_eq!(available, "999999");
+            }
+            other => panic!("expected InsufficientBalance, got {:?}", other),
+        }
+    }
+
+    const TEST_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
     #[test]
-    fn test_network_config() {
-        let wallet = Wallet::new(
-            "0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef".to_string(),
-            "base-sepolia".to_string(),
+    fn test_hd_wallet_derives_known_addresses() {
+        let hd = WalletFactory::hd_from_mnemonic(TEST_MNEMONIC, "base-sepolia").unwrap();
+
+        let expected = [
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+            "0x70997970C51812dc3A010C7d01b50e0d17dc79C8",
+            "0x3C44CdDdB6a900fa2b585dd299e03d12FA4293BC",
+        ];
+        for (index, address) in expected.iter().enumerate() {
+            let wallet = hd.derive(index as u32).unwrap();
+            assert_eq!(wallet.address(), Address::from_str(address).unwrap());
+            assert_eq!(wallet.network(), "base-sepolia");
+        }
+    }
+
+    #[test]
+    fn test_wallet_factory_from_mnemonic_path() {
+        let wallet =
+            WalletFactory::from_mnemonic(TEST_MNEMONIC, "m/44'/60'/0'/0/0", "base").unwrap();
+        assert_eq!(
+            wallet.address(),
+            Address::from_str("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266").unwrap()
+        );
+        let expected =
+            LocalSigner::new("0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")
+                .unwrap();
+        assert_eq!(wallet.address(), expected.address());
+    }
+
+    #[test]
+    fn test_wallet_factory_from_mnemonic_invalid() {
+        // Valid words, wrong checksum
+        let result = WalletFactory::from_mnemonic(
+            "test test test test test test test test test test test test",
+            "m/44'/60'/0'/0/0",
+            "base",
         );
+        assert!(matches!(result, Err(X402Error::Config { .. })));
+
+        let result = WalletFactory::from_mnemonic(TEST_MNEMONIC, "not/a/path", "base");
+        assert!(matches!(result, Err(X402Error::Config { .. })));
+    }
+
+    #[test]
+    fn test_network_config() {
+        let signer =
+            LocalSigner::new("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
+                .unwrap();
+        let wallet = Wallet::new(signer, "base-sepolia");
         let config = wallet.get_network_config().unwrap();
         assert_eq!(config.chain_id, 84532);
     }
@@ -113,28 +113,21 @@ fn create_payment_requirements_for_warp() -> crate::Result<PaymentRequirements>
     let pay_to = std::env::var("X402_PAY_TO")
         .unwrap_or_else(|_| "0x209693Bc6afc0C5328bA36FaF03C514EF312287C".to_string());
 
-    let asset = match network.as_str() {
-        "base-sepolia" => "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
-        "base" => "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",
-        _ => "0x036CbD53842c5426634e7929541eC2318f3dCF7e", // Default to testnet
-    };
+    // Unknown networks default to Base Sepolia
+    let network_type =
+        crate::types::Network::from_name(&network).unwrap_or(crate::types::Network::Testnet);
+    let asset = network_type.usdc_address();
 
     let mut requirements = PaymentRequirements::new(
         &scheme,
         &network,
         &amount,
-        asset,
+        &asset,
         &pay_to,
         "/",
         "Payment required for this resource",
     );
 
-    let network_type = match network.as_str() {
-        "base" => crate::types::Network::Mainnet,
-        "base-sepolia" => crate::types::Network::Testnet,
-        _ => crate::types::Network::Testnet, // Default to testnet
-    };
-
     requirements.set_usdc_info(network_type)?;
     Ok(requirements)
 }
@@ -0,0 +1,253 @@
+//! Browser-side payment signing (the `wasm` feature)
+//!
+//! Exposes payload construction to JavaScript through `wasm-bindgen` so a dApp
+//! can pay without a backend. Signing uses the pure-Rust `k256` backend; the
+//! EIP-712 hashing in [`crate::crypto::eip712`] has no platform dependencies.
+//!
+//! The caller supplies the validity window and nonce, since neither the clock
+//! nor a random source is assumed to be available:
+//!
+//! ```js
+//! const nonce = "0x" + [...crypto.getRandomValues(new Uint8Array(32))]
+//!   .map((b) => b.toString(16).padStart(2, "0")).join("");
+//! const now = Math.floor(Date.now() / 1000);
+//! const header = signAuthorization(key, "base-sepolia", payTo, asset, "1000",
+//!   String(now - 60), String(now + 60), nonce);
+//! fetch(url, { headers: { "X-PAYMENT": header } });
+//! ```
+
+use crate::crypto::signature::address_from_private_key;
+use crate::types::{ExactEvmPayload, ExactEvmPayloadAuthorization, PaymentPayload, NATIVE_ASSET};
+use crate::wallet::{authorization_hash, WalletNetworkConfig};
+use crate::{Result, X402Error};
+use ethereum_types::H256;
+use std::str::FromStr;
+use wasm_bindgen::prelude::*;
+
+/// Authorization fields shared by both signing paths
+struct AuthorizationFields<'a> {
+    network: &'a str,
+    pay_to: &'a str,
+    asset: &'a str,
+    value: &'a str,
+    valid_after: &'a str,
+    valid_before: &'a str,
+    nonce: &'a str,
+}
+
+impl AuthorizationFields<'_> {
+    fn authorization(&self, from: &str) -> ExactEvmPayloadAuthorization {
+        ExactEvmPayloadAuthorization::new(
+            from,
+            self.pay_to,
+            self.value,
+            self.valid_after,
+            self.valid_before,
+            self.nonce,
+        )
+    }
+
+    fn hash(&self, authorization: &ExactEvmPayloadAuthorization) -> Result<H256> {
+        let nonce = H256::from_str(self.nonce)
+            .map_err(|_| X402Error::invalid_authorization("Invalid nonce format"))?;
+        authorization_hash(
+            authorization,
+            nonce,
+            &WalletNetworkConfig::for_network(self.network)?,
+            self.asset == NATIVE_ASSET,
+            None,
+        )
+    }
+
+    fn payload(
+        &self,
+        authorization: ExactEvmPayloadAuthorization,
+        signature: String,
+    ) -> PaymentPayload {
+        PaymentPayload::new(
+            "exact",
+            self.network,
+            ExactEvmPayload {
+                signature,
+                authorization,
+            },
+        )
+    }
+}
+
+/// Sign `hash` with the pure-Rust `k256` backend, returning `r || s || v` hex
+fn sign_hash(hash: H256, private_key: &str) -> Result<String> {
+    let key_bytes = hex::decode(private_key.trim_start_matches("0x"))
+        .map_err(|_| X402Error::invalid_signature("Invalid hex private key"))?;
+    let signing_key = k256::ecdsa::SigningKey::from_slice(&key_bytes)
+        .map_err(|_| X402Error::invalid_signature("Invalid private key"))?;
+    let (signature, recovery_id) = signing_key
+        .sign_prehash_recoverable(hash.as_bytes())
+        .map_err(|_| X402Error::invalid_signature("Failed to sign authorization"))?;
+
+    let mut bytes = signature.to_bytes().to_vec();
+    bytes.push(recovery_id.to_byte());
+    Ok(format!("0x{}", hex::encode(bytes)))
+}
+
+fn sign_payment(private_key: &str, fields: &AuthorizationFields) -> Result<String> {
+    let from = address_from_private_key(private_key)?;
+    let authorization = fields.authorization(&format!("{:?}", from));
+    let signature = sign_hash(fields.hash(&authorization)?, private_key)?;
+    fields.payload(authorization, signature).to_base64()
+}
+
+fn js_error(error: X402Error) -> JsError {
+    JsError::new(&error.to_string())
+}
+
+/// Sign an authorization with a private key and return the `X-PAYMENT` header value
+///
+/// `asset` selects the EIP-712 domain: the network's USDC contract, or the
+/// native-payment domain for [`NATIVE_ASSET`].
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen(js_name = signAuthorization)]
+pub fn sign_authorization(
+    private_key: &str,
+    network: &str,
+    pay_to: &str,
+    asset: &str,
+    value: &str,
+    valid_after: &str,
+    valid_before: &str,
+    nonce: &str,
+) -> std::result::Result<String, JsError> {
+    let fields = AuthorizationFields {
+        network,
+        pay_to,
+        asset,
+        value,
+        valid_after,
+        valid_before,
+        nonce,
+    };
+    sign_payment(private_key, &fields).map_err(js_error)
+}
+
+/// EIP-712 digest of an authorization, for signing with an injected wallet
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen(js_name = authorizationDigest)]
+pub fn authorization_digest(
+    from: &str,
+    network: &str,
+    pay_to: &str,
+    asset: &str,
+    value: &str,
+    valid_after: &str,
+    valid_before: &str,
+    nonce: &str,
+) -> std::result::Result<String, JsError> {
+    let fields = AuthorizationFields {
+        network,
+        pay_to,
+        asset,
+        value,
+        valid_after,
+        valid_before,
+        nonce,
+    };
+    fields
+        .hash(&fields.authorization(from))
+        .map(|hash| format!("{:?}", hash))
+        .map_err(js_error)
+}
+
+/// Assemble the `X-PAYMENT` header value from a signature made by an injected wallet
+#[allow(clippy::too_many_arguments)]
+#[wasm_bindgen(js_name = paymentHeader)]
+pub fn payment_header(
+    from: &str,
+    network: &str,
+    pay_to: &str,
+    value: &str,
+    valid_after: &str,
+    valid_before: &str,
+    nonce: &str,
+    signature: &str,
+) -> std::result::Result<String, JsError> {
+    let fields = AuthorizationFields {
+        network,
+        pay_to,
+        asset: "",
+        value,
+        valid_after,
+        valid_before,
+        nonce,
+    };
+    fields
+        .payload(fields.authorization(from), signature.to_string())
+        .to_base64()
+        .map_err(js_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::signature::sign_message_hash;
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const EXPECTED_SIGNATURE: &str = "0xfe27aa0890baeba93ca323308ce9158b8c659c26bb4b9f6678c4b7fbff600829083d5f656bddec200e3d7636960c6d119fe6e556e692c8cf84764a09372e903b00";
+
+    fn fields() -> AuthorizationFields<'static> {
+        AuthorizationFields {
+            network: "base-sepolia",
+            pay_to: "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+            asset: "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+            value: "1000",
+            valid_after: "1700000000",
+            valid_before: "1700000600",
+            nonce: "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+        }
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn test_known_signing_vector() {
+        let header = sign_payment(PRIVATE_KEY, &fields()).unwrap();
+        let payment = PaymentPayload::from_base64(&header).unwrap();
+
+        assert_eq!(
+            payment.payload.authorization.from,
+            "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+        assert_eq!(payment.payload.signature, EXPECTED_SIGNATURE);
+        assert!(payment.verify_signature().unwrap());
+
+        // The k256 backend agrees with the native secp256k1 signer
+        let hash = fields().hash(&payment.payload.authorization).unwrap();
+        assert_eq!(
+            sign_message_hash(hash, PRIVATE_KEY).unwrap(),
+            EXPECTED_SIGNATURE
+        );
+    }
+
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+    #[cfg_attr(not(target_arch = "wasm32"), test)]
+    fn test_injected_signer_round_trip() {
+        let fields = fields();
+        let from = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+        let hash = fields.hash(&fields.authorization(from)).unwrap();
+
+        // Sign the digest as an injected wallet would, then assemble the header
+        let signature = sign_hash(hash, PRIVATE_KEY).unwrap();
+        let header = payment_header(
+            from,
+            fields.network,
+            fields.pay_to,
+            fields.value,
+            fields.valid_after,
+            fields.valid_before,
+            fields.nonce,
+            &signature,
+        )
+        .unwrap();
+        assert_eq!(header, sign_payment(PRIVATE_KEY, &fields).unwrap());
+    }
+}
@@ -0,0 +1,195 @@
+//! Signed facilitator webhooks
+//!
+//! A facilitator notifying a resource server (for example, that a payment
+//! settled) signs the request body with a shared secret using HMAC-SHA256.
+//! The signature is sent in the [`SIGNATURE_HEADER`] header as
+//! `t=<unix timestamp>,sha256=<hex digest>`, where the digest covers
+//! `"<timestamp>.<body>"`. Including the timestamp lets resource servers
+//! reject replays of old deliveries.
+
+use crate::{Result, X402Error};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::Duration;
+
+/// Header carrying the webhook signature
+pub const SIGNATURE_HEADER: &str = "X-X402-Signature";
+
+/// Default maximum age of a webhook signature
+pub const DEFAULT_WEBHOOK_TOLERANCE: Duration = Duration::from_secs(300);
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// HMAC over `"<timestamp>.<body>"`
+fn mac(body: &[u8], secret: &[u8], timestamp: i64) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+/// Sign a webhook body, returning the [`SIGNATURE_HEADER`] value
+pub fn sign_webhook(body: &[u8], secret: &[u8], timestamp: i64) -> String {
+    let digest = mac(body, secret, timestamp).finalize().into_bytes();
+    format!("t={},sha256={}", timestamp, hex::encode(digest))
+}
+
+/// Verify a webhook signature against the current time
+///
+/// Returns `false` for a malformed header, a signature that does not match the
+/// body, or a timestamp more than [`DEFAULT_WEBHOOK_TOLERANCE`] from now.
+pub fn verify_webhook_signature(body: &[u8], header: &str, secret: &[u8]) -> bool {
+    verify_webhook_signature_at(
+        body,
+        header,
+        secret,
+        chrono::Utc::now().timestamp(),
+        DEFAULT_WEBHOOK_TOLERANCE,
+    )
+}
+
+/// Verify a webhook signature as of `now`, accepting timestamps within `tolerance`
+pub fn verify_webhook_signature_at(
+    body: &[u8],
+    header: &str,
+    secret: &[u8],
+    now: i64,
+    tolerance: Duration,
+) -> bool {
+    let mut timestamp = None;
+    let mut signature = None;
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("sha256", value)) => signature = hex::decode(value).ok(),
+            _ => {}
+        }
+    }
+    let (Some(timestamp), Some(signature)) = (timestamp, signature) else {
+        return false;
+    };
+
+    if now.abs_diff(timestamp) > tolerance.as_secs() {
+        return false;
+    }
+
+    // Constant-time comparison
+    mac(body, secret, timestamp)
+        .verify_slice(&signature)
+        .is_ok()
+}
+
+/// POST a signed JSON webhook to `url`
+pub async fn send_webhook<T: serde::Serialize>(
+    client: &reqwest::Client,
+    url: &str,
+    payload: &T,
+    secret: &[u8],
+) -> Result<reqwest::Response> {
+    let body = serde_json::to_vec(payload)?;
+    let signature = sign_webhook(&body, secret, chrono::Utc::now().timestamp());
+
+    client
+        .post(url)
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .header(SIGNATURE_HEADER, signature)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| X402Error::network_error(format!("Webhook delivery failed: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"whsec_test";
+    const BODY: &[u8] = br#"{"success":true,"transaction":"0xabc","network":"base-sepolia"}"#;
+    const NOW: i64 = 1_700_000_000;
+
+    #[test]
+    fn test_valid_signature() {
+        let header = sign_webhook(BODY, SECRET, NOW);
+        assert!(header.starts_with(&format!("t={},sha256=", NOW)));
+        assert!(verify_webhook_signature_at(
+            BODY,
+            &header,
+            SECRET,
+            NOW + 10,
+            DEFAULT_WEBHOOK_TOLERANCE
+        ));
+
+        // Signed with the current time, as a facilitator would
+        let header = sign_webhook(BODY, SECRET, chrono::Utc::now().timestamp());
+        assert!(verify_webhook_signature(BODY, &header, SECRET));
+    }
+
+    #[test]
+    fn test_tampered_body_or_secret() {
+        let header = sign_webhook(BODY, SECRET, NOW);
+        let tampered = br#"{"success":true,"transaction":"0xdef","network":"base-sepolia"}"#;
+        assert!(!verify_webhook_signature_at(
+            tampered,
+            &header,
+            SECRET,
+            NOW,
+            DEFAULT_WEBHOOK_TOLERANCE
+        ));
+        assert!(!verify_webhook_signature_at(
+            BODY,
+            &header,
+            b"other",
+            NOW,
+            DEFAULT_WEBHOOK_TOLERANCE
+        ));
+
+        // Moving the timestamp invalidates the signature
+        let forged = header.replace(&NOW.to_string(), &(NOW + 1).to_string());
+        assert!(!verify_webhook_signature_at(
+            BODY,
+            &forged,
+            SECRET,
+            NOW,
+            DEFAULT_WEBHOOK_TOLERANCE
+        ));
+
+        for malformed in ["", "sha256=00", "t=abc,sha256=00", "t=1700000000"] {
+            assert!(!verify_webhook_signature_at(
+                BODY,
+                malformed,
+                SECRET,
+                NOW,
+                DEFAULT_WEBHOOK_TOLERANCE
+            ));
+        }
+    }
+
+    #[test]
+    fn test_stale_timestamp() {
+        let header = sign_webhook(BODY, SECRET, NOW);
+        let tolerance = Duration::from_secs(300);
+        assert!(verify_webhook_signature_at(
+            BODY,
+            &header,
+            SECRET,
+            NOW + 300,
+            tolerance
+        ));
+        assert!(!verify_webhook_signature_at(
+            BODY,
+            &header,
+            SECRET,
+            NOW + 301,
+            tolerance
+        ));
+        // Timestamps too far in the future are rejected as well
+        assert!(!verify_webhook_signature_at(
+            BODY,
+            &header,
+            SECRET,
+            NOW - 301,
+            tolerance
+        ));
+    }
+}
@@ -208,3 +208,73 @@ fn test_error_display_format() {
         display_str
     );
 }
+
+#[test]
+fn test_structured_error_variants() {
+    let cases = [
+        (
+            X402Error::PaymentExceedsLimit {
+                limit: "1000000".to_string(),
+                requested: "5000000".to_string(),
+            },
+            "Payment of 5000000 exceeds the limit of 1000000",
+            "payment_exceeds_limit",
+        ),
+        (
+            X402Error::InsufficientBalance {
+                needed: "1000000".to_string(),
+                available: "999999".to_string(),
+            },
+            "Insufficient balance: need 1000000, have 999999",
+            "insufficient_balance",
+        ),
+        (
+            X402Error::SimulationReverted {
+                reason: "FiatTokenV2: invalid signature".to_string(),
+            },
+            "Simulation reverted: FiatTokenV2: invalid signature",
+            "simulation_reverted",
+        ),
+        (
+            X402Error::UnsupportedVersion {
+                got: 7,
+                expected: 1,
+            },
+            "Unsupported x402 version 7: expected 1",
+            "unsupported_version",
+        ),
+        (
+            X402Error::InvalidAddress("0x1234".to_string()),
+            "Invalid address: 0x1234",
+            "invalid_address",
+        ),
+    ];
+
+    for (error, message, error_type) in cases {
+        assert_eq!(error.to_string(), message);
+        assert_eq!(error.error_type(), error_type);
+        assert!(error.details().is_some(), "{} has no details", error_type);
+    }
+}
+
+#[test]
+fn test_structured_error_response_details() {
+    let error = X402Error::InsufficientBalance {
+        needed: "1000000".to_string(),
+        available: "0".to_string(),
+    };
+    let response =
+        serde_json::to_value(rust_x402::error::ErrorResponse::from_x402_error(&error)).unwrap();
+
+    assert_eq!(response["type"], "insufficient_balance");
+    assert_eq!(response["status_code"], 402);
+    assert_eq!(response["details"]["needed"], "1000000");
+    assert_eq!(response["details"]["available"], "0");
+
+    // Errors without structured fields omit details
+    let response = serde_json::to_value(rust_x402::error::ErrorResponse::from_x402_error(
+        &X402Error::Timeout,
+    ))
+    .unwrap();
+    assert!(response.get("details").is_none());
+}
@@ -0,0 +1,127 @@
+//! Wire-format compatibility with the reference TypeScript and Python SDKs
+//!
+//! Each file in `tests/golden` is a payload as the reference SDKs serialize
+//! it. Our types must produce the same bytes and read them back unchanged.
+
+use rust_x402::types::*;
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Debug;
+
+const PAYER: &str = "0x857b06519E91e3A54538791bDbb0E22373e36b66";
+
+fn golden(name: &str) -> String {
+    let path = format!("{}/tests/golden/{}.json", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {}", path, e))
+        .trim_end()
+        .to_string()
+}
+
+/// `value` serializes to the golden bytes, which deserialize back to it
+fn assert_golden<T: Serialize + DeserializeOwned + Debug>(name: &str, value: &T) {
+    let expected = golden(name);
+    assert_eq!(serde_json::to_string(value).unwrap(), expected, "{}", name);
+
+    let parsed: T = serde_json::from_str(&expected).unwrap();
+    assert_eq!(
+        serde_json::to_string(&parsed).unwrap(),
+        expected,
+        "{}",
+        name
+    );
+}
+
+fn requirements() -> PaymentRequirements {
+    PaymentRequirements::new(
+        "exact",
+        "base-sepolia",
+        "10000",
+        "0x036CbD53842c5426634e7929541eC2318f3dCF7e",
+        "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+        "https://api.example.com/premium",
+        "",
+    )
+}
+
+#[test]
+fn test_verify_response_golden() {
+    assert_golden(
+        "verify_response_valid",
+        &VerifyResponse {
+            is_valid: true,
+            invalid_reason: None,
+            payer: Some(PAYER.to_string()),
+        },
+    );
+    assert_golden(
+        "verify_response_invalid",
+        &VerifyResponse {
+            is_valid: false,
+            invalid_reason: Some("insufficient_funds".to_string()),
+            payer: Some(PAYER.to_string()),
+        },
+    );
+}
+
+#[test]
+fn test_settle_response_golden() {
+    assert_golden(
+        "settle_response_success",
+        &SettleResponse {
+            success: true,
+            error_reason: None,
+            payer: Some(PAYER.to_string()),
+            transaction: "0x7b4c1f6c2a1b9e3d5f8a0c4e6b2d9f1a3c5e7b9d0f2a4c6e8b1d3f5a7c9e0b2d"
+                .to_string(),
+            network: "base-sepolia".to_string(),
+            fee_amount: None,
+            net_amount: None,
+        },
+    );
+    assert_golden(
+        "settle_response_failed",
+        &SettleResponse {
+            success: false,
+            error_reason: Some("invalid_exact_evm_payload_signature".to_string()),
+            payer: Some(PAYER.to_string()),
+            transaction: String::new(),
+            network: "base-sepolia".to_string(),
+            fee_amount: None,
+            net_amount: None,
+        },
+    );
+}
+
+#[test]
+fn test_payment_required_golden() {
+    let mut premium = requirements();
+    premium.description = "Premium data".to_string();
+    premium.mime_type = Some("application/json".to_string());
+    premium.extra = Some(serde_json::json!({ "name": "USDC", "version": "2" }));
+    assert_golden(
+        "payment_required",
+        &PaymentRequirementsResponse::new("X-PAYMENT header is required", vec![premium]),
+    );
+
+    // An unset MIME type is sent empty, and the payer follows the requirements
+    assert_golden(
+        "payment_required_with_payer",
+        &PaymentRequirementsResponse::new("insufficient_funds", vec![requirements()])
+            .with_payer(PAYER),
+    );
+}
+
+#[test]
+fn test_empty_mime_type_reads_as_unset() {
+    let parsed: PaymentRequirementsResponse =
+        serde_json::from_str(&golden("payment_required_with_payer")).unwrap();
+    assert_eq!(parsed.accepts[0].mime_type, None);
+
+    let strict = PaymentRequirements::from_json_strict(
+        serde_json::to_string(&parsed.accepts[0])
+            .unwrap()
+            .as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(strict.mime_type, None);
+}
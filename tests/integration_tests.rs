@@ -415,6 +415,8 @@ async fn test_settle_response_serialization() {
             .to_string(),
         network: "base-sepolia".to_string(),
         payer: Some("0x857b06519E91e3A54538791bDbb0E22373e36b66".to_string()),
+        fee_amount: None,
+        net_amount: None,
     };
 
     let encoded = settle_response
@@ -554,3 +556,84 @@ fn create_test_payment_payload() -> PaymentPayload {
 
     PaymentPayload::new("exact", "base-sepolia", payload)
 }
+
+#[tokio::test]
+async fn test_client_pays_on_funded_network() {
+    use rust_x402::{
+        axum::payment_middleware_handler, blockchain::BlockchainClient, facilitator::MockSettler,
+        facilitator_storage::InMemoryStorage, middleware::PaymentMiddleware, LocalSigner,
+        PaymentSelector, Wallet,
+    };
+
+    // Resource server accepting USDC on Base Sepolia, then on Avalanche Fuji
+    let middleware = PaymentMiddleware::new(
+        rust_decimal::Decimal::from_str("0.01").unwrap(),
+        "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+    )
+    .with_testnet(true)
+    .with_additional_network(Network::AvalancheFuji)
+    .with_local_verification(InMemoryStorage::new())
+    .with_settler(MockSettler);
+    let app = axum::Router::new()
+        .route("/premium", axum::routing::get(|| async { "paid content" }))
+        .layer(axum::middleware::from_fn_with_state(
+            middleware,
+            payment_middleware_handler,
+        ));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    // The payer holds no USDC on Base Sepolia and 5 USDC on Avalanche Fuji
+    let balance =
+        |amount: u64| json!({"jsonrpc": "2.0", "id": 1, "result": format!("0x{:064x}", amount)});
+    let mut base_rpc = Server::new_async().await;
+    let base_balance = base_rpc
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(json!({"method": "eth_call"})))
+        .with_body(balance(0).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+    let mut fuji_rpc = Server::new_async().await;
+    let fuji_balance = fuji_rpc
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(json!({"method": "eth_call"})))
+        .with_body(balance(5_000_000).to_string())
+        .expect(1)
+        .create_async()
+        .await;
+
+    let wallet = Wallet::new(
+        LocalSigner::new("0x1234567890abcdef1234567890abcdef1234567890abcdef1234567890abcdef")
+            .unwrap(),
+        "base-sepolia",
+    );
+    let selector = PaymentSelector::new(format!("{:?}", wallet.address()))
+        .with_client(BlockchainClient::new(
+            base_rpc.url(),
+            "base-sepolia".to_string(),
+        ))
+        .with_client(BlockchainClient::new(
+            fuji_rpc.url(),
+            "avalanche-fuji".to_string(),
+        ));
+
+    let paid = X402Client::new()
+        .unwrap()
+        .get(&format!("http://{}/premium", address))
+        .send_with_wallet(&wallet, &selector)
+        .await
+        .unwrap();
+
+    assert_eq!(paid.status(), 200);
+    let receipt = paid.receipt.expect("settled payment has a receipt");
+    assert_eq!(receipt.network, "avalanche-fuji");
+    assert_eq!(receipt.amount, "10000");
+    assert_eq!(
+        receipt.requirement.unwrap().asset,
+        "0x5425890298aed601595a70AB815c96711a31Bc65"
+    );
+    base_balance.assert_async().await;
+    fuji_balance.assert_async().await;
+}
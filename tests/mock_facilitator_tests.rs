@@ -0,0 +1,182 @@
+//! Payment middleware tests against the `test_support` helpers
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+    routing::get,
+    Router,
+};
+use base64::Engine;
+use rust_decimal::Decimal;
+use rust_x402::{
+    axum::payment_middleware_handler,
+    facilitator::{FacilitatorSettler, MockSettler, Settler},
+    middleware::{PaymentMiddleware, PaymentResult},
+    test_support::{MockFacilitator, TestSettler, MOCK_TRANSACTION},
+    types::*,
+};
+use std::str::FromStr;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+fn middleware(facilitator: &MockFacilitator) -> PaymentMiddleware {
+    PaymentMiddleware::new(
+        Decimal::from_str("0.0001").unwrap(),
+        "0x209693Bc6afc0C5328bA36FaF03C514EF312287C",
+    )
+    .with_testnet(true)
+    .with_facilitator(facilitator.client().unwrap())
+}
+
+fn app(facilitator: &MockFacilitator) -> Router {
+    let middleware = middleware(facilitator);
+
+    Router::new()
+        .route("/paid", get(|| async { "paid content" }))
+        .layer(axum::middleware::from_fn_with_state(
+            middleware,
+            payment_middleware_handler,
+        ))
+}
+
+fn paid_request() -> Request {
+    let payload = PaymentPayload::new(
+        "exact",
+        "base-sepolia",
+        ExactEvmPayload {
+            signature: "0x00".to_string(),
+            authorization: ExactEvmPayloadAuthorization::new(
+                "0x857b06519E91e3A54538791bDbb0E22373e36b66",
+                "0x209693bc6afc0c5328ba36faf03c514ef312287c",
+                "100",
+                "1745323800",
+                "1745323985",
+                "0xf3746613c2d920b5fdabc0856f2aeb2d4f88ee6037b8cc5d04a71a4462f13480",
+            ),
+        },
+    );
+
+    Request::builder()
+        .uri("/paid")
+        .header(X_PAYMENT_HEADER, payload.to_header_value().unwrap())
+        .body(Body::empty())
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_paid_request_with_always_valid_facilitator() {
+    let facilitator = MockFacilitator::always_valid().await;
+
+    let response = app(&facilitator).oneshot(paid_request()).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+}
+
+#[tokio::test]
+async fn test_paid_request_with_rejecting_facilitator() {
+    let facilitator = MockFacilitator::always_invalid("insufficient_funds").await;
+
+    let response = app(&facilitator).oneshot(paid_request()).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+}
+
+/// Layer running `process_payment`, requiring a settlement failure
+async fn expect_settlement_failed(
+    State(middleware): State<PaymentMiddleware>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match middleware.process_payment(request, next).await.unwrap() {
+        PaymentResult::SettlementFailed { response } => response,
+        other => panic!("unexpected payment result: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_test_settler_settles_deterministically() {
+    let facilitator = MockFacilitator::always_valid().await;
+    let settler = TestSettler::succeeding();
+    let app = Router::new()
+        .route("/paid", get(|| async { "paid content" }))
+        .layer(axum::middleware::from_fn_with_state(
+            middleware(&facilitator).with_settler(settler.clone()),
+            payment_middleware_handler,
+        ));
+
+    let response = app.oneshot(paid_request()).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let settlements = settler.settlements();
+    assert_eq!(settlements.len(), 1);
+    assert_eq!(settlements[0].transaction, MOCK_TRANSACTION);
+    assert_eq!(
+        settlements[0].payer.as_deref(),
+        Some("0x857b06519E91e3A54538791bDbb0E22373e36b66")
+    );
+}
+
+#[tokio::test]
+async fn test_settlement_failure_through_middleware() {
+    let facilitator = MockFacilitator::always_valid().await;
+    let settler = TestSettler::failing("insufficient_funds");
+    let app = Router::new()
+        .route("/paid", get(|| async { "paid content" }))
+        .layer(axum::middleware::from_fn_with_state(
+            middleware(&facilitator).with_settler(settler.clone()),
+            expect_settlement_failed,
+        ));
+
+    let response = app.oneshot(paid_request()).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+    assert!(!response.headers().contains_key(X_PAYMENT_RESPONSE_HEADER));
+    assert_eq!(
+        settler.settlements()[0].error_reason.as_deref(),
+        Some("insufficient_funds")
+    );
+}
+
+#[tokio::test]
+async fn test_settlers_swap_behind_the_same_middleware() {
+    let facilitator = MockFacilitator::always_valid().await;
+    let base = middleware(&facilitator);
+    let test_settler = TestSettler::succeeding().with_transaction("0xfeed");
+
+    let settlers: Vec<Arc<dyn Settler>> = vec![
+        Arc::new(FacilitatorSettler::new(facilitator.client().unwrap())),
+        Arc::new(MockSettler),
+        Arc::new(test_settler.clone()),
+    ];
+    let mut transactions = Vec::new();
+    for settler in settlers {
+        let app = Router::new()
+            .route("/paid", get(|| async { "paid content" }))
+            .layer(axum::middleware::from_fn_with_state(
+                base.clone().with_shared_settler(settler),
+                payment_middleware_handler,
+            ));
+
+        let response = app.oneshot(paid_request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let header = response.headers()[X_PAYMENT_RESPONSE_HEADER].as_bytes();
+        let settlement: SettleResponse = serde_json::from_slice(
+            &base64::engine::general_purpose::STANDARD
+                .decode(header)
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(settlement.success);
+        transactions.push(settlement.transaction);
+    }
+
+    // The facilitator's canned hash, a random one, then the test settler's
+    assert_eq!(transactions[0], MOCK_TRANSACTION);
+    assert_ne!(transactions[1], MOCK_TRANSACTION);
+    assert_eq!(transactions[2], "0xfeed");
+    assert_eq!(test_settler.settlements().len(), 1);
+}
@@ -169,11 +169,15 @@ async fn test_real_facilitator_factory() {
     // Test custom facilitator configuration - MUST succeed with valid config
     let config = BlockchainFacilitatorConfig {
         rpc_url: Some("https://custom.facilitator.com".to_string()),
+        rpc_registry: Default::default(),
         network: "custom".to_string(),
         verification_timeout: std::time::Duration::from_secs(60),
         confirmation_blocks: 2,
         max_retries: 5,
         retry_delay: std::time::Duration::from_secs(2),
+        clock_skew_tolerance: std::time::Duration::from_secs(10),
+        fee: None,
+        simulate_before_settle: false,
     };
 
     let facilitator = BlockchainFacilitatorFactory::custom(config);